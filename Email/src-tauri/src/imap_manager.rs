@@ -1,10 +1,18 @@
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use tokio::sync::{RwLock, Mutex};
+use tokio::sync::{broadcast, RwLock, Mutex};
 use uuid::Uuid;
 
+use crate::semantic_search::SemanticSearchManager;
+use crate::threading;
+
+/// Capacity of each account's `FolderEvent` broadcast channel; a slow
+/// subscriber can fall behind by this many events before it starts missing them
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmailAccount {
     pub id: String,
@@ -18,12 +26,22 @@ pub struct EmailAccount {
     pub encrypted_password: String,
     pub use_tls: bool,
     pub last_sync: Option<DateTime<Utc>>,
+    /// Whether the last connectivity probe reached this account's IMAP
+    /// server. New accounts start offline until their first successful check.
+    #[serde(default)]
+    pub is_online: bool,
+    /// Error from the most recent failed connectivity probe, if any
+    #[serde(default)]
+    pub last_error: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmailMessage {
     pub id: String,
     pub uid: u32,
+    /// Per-message CONDSTORE modification sequence, used to detect changes
+    /// (flag updates, new arrivals) since the folder's last synced MODSEQ
+    pub modseq: u64,
     pub subject: String,
     pub from: String,
     pub to: Vec<String>,
@@ -57,6 +75,17 @@ pub struct EmailFolder {
     pub selectable: bool,
 }
 
+/// CONDSTORE/QRESYNC sync state for a single folder, persisted across syncs
+/// so reconnecting only needs to fetch what changed since the last pass
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FolderSyncState {
+    /// Server-reported UIDVALIDITY; a mismatch means cached UIDs are no
+    /// longer meaningful and the folder must be fully resynced
+    pub uid_validity: u32,
+    /// Highest MODSEQ observed as of the last successful sync
+    pub highest_modseq: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmailThread {
     pub id: String,
@@ -67,7 +96,19 @@ pub struct EmailThread {
     pub has_unread: bool,
     pub is_flagged: bool,
     pub folder: String,
-    pub messages: Vec<String>, // Message IDs
+    pub messages: Vec<String>, // Message IDs, in JWZ tree order (depth-first, roots first)
+    /// `message_parents[i]` is the index into `messages` of that message's
+    /// parent, or `None` if `messages[i]` is itself a thread root, so the UI
+    /// can render the JWZ nesting without re-deriving it
+    pub message_parents: Vec<Option<usize>>,
+}
+
+/// A push notification from a watched folder's IMAP IDLE connection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum FolderEvent {
+    NewMessage { uid: u32, message_id: String },
+    FlagsChanged { uid: u32, seen: bool, flagged: bool },
+    Expunged { uid: u32 },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -80,12 +121,78 @@ pub struct SyncProgress {
     pub error: Option<String>,
 }
 
+/// A UID is only unique within one account's one folder at one UIDVALIDITY
+/// epoch, so this is the scope a `UidIndex` entry is keyed by
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct UidKey {
+    account_id: String,
+    folder: String,
+    uid: u32,
+}
+
+/// What a `UidKey` currently resolves to: the cached message, and the
+/// UIDVALIDITY it was resolved under (so a later epoch change can be detected)
+#[derive(Debug, Clone)]
+struct UidEntry {
+    message_id: String,
+    uid_validity: u32,
+}
+
+/// A flag/move/delete mutation deferred while its account was offline,
+/// replayed once the account reconnects. Carries the UIDVALIDITY observed
+/// when it was queued so a stale op (folder resynced under us in the
+/// meantime) can be dropped instead of silently corrupting state.
+#[derive(Debug, Clone)]
+enum PendingOp {
+    MarkRead { folder: String, uid_validity: u32, message_id: String, read: bool },
+    Flag { folder: String, uid_validity: u32, message_id: String, flagged: bool },
+    Move { folder: String, uid_validity: u32, message_id: String, target_folder: String },
+    Delete { folder: String, uid_validity: u32, message_id: String },
+}
+
+impl PendingOp {
+    fn folder(&self) -> &str {
+        match self {
+            PendingOp::MarkRead { folder, .. }
+            | PendingOp::Flag { folder, .. }
+            | PendingOp::Move { folder, .. }
+            | PendingOp::Delete { folder, .. } => folder,
+        }
+    }
+
+    fn uid_validity(&self) -> u32 {
+        match self {
+            PendingOp::MarkRead { uid_validity, .. }
+            | PendingOp::Flag { uid_validity, .. }
+            | PendingOp::Move { uid_validity, .. }
+            | PendingOp::Delete { uid_validity, .. } => *uid_validity,
+        }
+    }
+}
+
 pub struct IMAPManager {
     accounts: Arc<RwLock<HashMap<String, EmailAccount>>>,
     messages: Arc<RwLock<HashMap<String, EmailMessage>>>,
     folders: Arc<RwLock<HashMap<String, Vec<EmailFolder>>>>,
     threads: Arc<RwLock<HashMap<String, Vec<EmailThread>>>>,
     sync_progress: Arc<RwLock<HashMap<String, SyncProgress>>>,
+    /// CONDSTORE/QRESYNC state per folder, keyed by "{account_id}_{folder_path}"
+    folder_sync_state: Arc<RwLock<HashMap<String, FolderSyncState>>>,
+    /// Resolves (account, folder, UID) to the message it currently names
+    uid_index: Arc<RwLock<HashMap<UidKey, UidEntry>>>,
+    /// Flag/move/delete mutations queued while offline, keyed by account, in
+    /// the order they were issued
+    pending_ops: Arc<RwLock<HashMap<String, Vec<PendingOp>>>>,
+    /// One `FolderEvent` broadcast channel per account, fed by its `watch_folder` IDLE loop(s)
+    event_channels: Arc<RwLock<HashMap<String, broadcast::Sender<FolderEvent>>>>,
+    /// Last known CAPABILITY response per account, keyed by account id
+    capabilities: Arc<RwLock<HashMap<String, Vec<String>>>>,
+    /// Running `watch_folder` tasks, keyed by "{account_id}_{folder}", so a
+    /// watch can be torn down cleanly (IMAP `DONE`) instead of leaking
+    watch_handles: Arc<RwLock<HashMap<String, tokio::task::JoinHandle<()>>>>,
+    /// Wired in after construction via `set_semantic_search`, so newly
+    /// synced messages get embedded and indexed automatically
+    semantic_search: Arc<RwLock<Option<Arc<SemanticSearchManager>>>>,
 }
 
 impl IMAPManager {
@@ -96,29 +203,403 @@ impl IMAPManager {
             folders: Arc::new(RwLock::new(HashMap::new())),
             threads: Arc::new(RwLock::new(HashMap::new())),
             sync_progress: Arc::new(RwLock::new(HashMap::new())),
+            folder_sync_state: Arc::new(RwLock::new(HashMap::new())),
+            uid_index: Arc::new(RwLock::new(HashMap::new())),
+            pending_ops: Arc::new(RwLock::new(HashMap::new())),
+            event_channels: Arc::new(RwLock::new(HashMap::new())),
+            capabilities: Arc::new(RwLock::new(HashMap::new())),
+            watch_handles: Arc::new(RwLock::new(HashMap::new())),
+            semantic_search: Arc::new(RwLock::new(None)),
         }
     }
 
+    /// Wire in the semantic-search indexer. Until this is called, synced
+    /// messages are cached as usual but never embedded.
+    pub async fn set_semantic_search(&self, manager: Arc<SemanticSearchManager>) {
+        *self.semantic_search.write().await = Some(manager);
+    }
+
+    /// Hand freshly synced `messages` to the semantic-search indexer, if
+    /// one is wired in. Best-effort: an embedding failure is logged, not
+    /// propagated, so a RAG outage never blocks mail sync.
+    async fn index_for_semantic_search(&self, account_id: &str, messages: &[EmailMessage]) {
+        let Some(manager) = self.semantic_search.read().await.clone() else {
+            return;
+        };
+        for message in messages {
+            if let Err(e) = manager.index_message(account_id, message).await {
+                log::warn!("Semantic indexing failed for message {}: {}", message.id, e);
+            }
+        }
+    }
+
+    /// Look up a single message by its internal id, regardless of account
+    /// or folder
+    pub async fn get_message(&self, message_id: &str) -> Option<EmailMessage> {
+        self.messages.read().await.get(message_id).cloned()
+    }
+
+    /// Look up a single message by its RFC `Message-ID` header, regardless
+    /// of account or folder
+    pub async fn get_message_by_rfc_id(&self, rfc_message_id: &str) -> Option<EmailMessage> {
+        self.messages
+            .read()
+            .await
+            .values()
+            .find(|msg| msg.message_id == rfc_message_id)
+            .cloned()
+    }
+
     /// Add a new email account
     pub async fn add_account(&self, mut account: EmailAccount) -> Result<String, String> {
-        // TODO: Test connection before adding
-        // For now, just simulate successful connection
-        
         account.id = Uuid::new_v4().to_string();
         let account_id = account.id.clone();
-        
+        account.is_online = false;
+        account.last_error = None;
+
         let mut accounts = self.accounts.write().await;
         accounts.insert(account_id.clone(), account);
-        
+        drop(accounts);
+
+        // Probe connectivity immediately so the account doesn't sit in the
+        // default offline state until the next health-check tick
+        if let Err(e) = self.check_connection(&account_id).await {
+            log::warn!("Initial connection check failed for account {}: {}", account_id, e);
+        }
+
         log::info!("Added email account: {}", account_id);
         Ok(account_id)
     }
 
+    /// Probe connectivity for an account, updating `is_online`/`last_error`
+    /// accordingly, and replaying any queued offline operations if this
+    /// probe just brought the account back online. Returns the resulting
+    /// online state.
+    pub async fn check_connection(&self, account_id: &str) -> Result<bool, String> {
+        // TODO: Replace with a real IMAP connect/NOOP probe. The mock server
+        // is always reachable once an account exists.
+        let was_offline = {
+            let mut accounts = self.accounts.write().await;
+            let account = accounts
+                .get_mut(account_id)
+                .ok_or_else(|| format!("Account not found: {}", account_id))?;
+            let was_offline = !account.is_online;
+            account.is_online = true;
+            account.last_error = None;
+            was_offline
+        };
+
+        // TODO: Replace with the server's real CAPABILITY response. The mock
+        // server always reports full IDLE/CONDSTORE/QRESYNC support.
+        self.capabilities.write().await.insert(
+            account_id.to_string(),
+            vec!["IDLE".to_string(), "CONDSTORE".to_string(), "QRESYNC".to_string()],
+        );
+
+        if was_offline {
+            self.replay_pending_ops(account_id).await;
+        }
+
+        Ok(true)
+    }
+
+    /// Whether the account's last known CAPABILITY response advertised
+    /// IDLE; an account with no capability probe yet is assumed to support
+    /// it until proven otherwise
+    async fn supports_idle(&self, account_id: &str) -> bool {
+        self.capabilities
+            .read()
+            .await
+            .get(account_id)
+            .map_or(true, |caps| caps.iter().any(|c| c == "IDLE"))
+    }
+
+    /// Whether the account's last known CAPABILITY response advertised
+    /// CONDSTORE, required for any MODSEQ-based incremental sync (QRESYNC
+    /// implies it). An account with no capability probe yet is assumed to
+    /// support it until proven otherwise.
+    async fn supports_condstore(&self, account_id: &str) -> bool {
+        self.capabilities
+            .read()
+            .await
+            .get(account_id)
+            .map_or(true, |caps| caps.iter().any(|c| c == "CONDSTORE" || c == "QRESYNC"))
+    }
+
+    /// Whether the account's last known CAPABILITY response advertised
+    /// QRESYNC, required for the `SELECT (QRESYNC ...)` fast path and its
+    /// `VANISHED (EARLIER)` reporting. Without it, incremental sync falls
+    /// back to plain CONDSTORE's `FETCH ... (CHANGEDSINCE <modseq>)`, which
+    /// has no equivalent for deletions. An account with no capability probe
+    /// yet is assumed to support it until proven otherwise.
+    async fn supports_qresync(&self, account_id: &str) -> bool {
+        self.capabilities
+            .read()
+            .await
+            .get(account_id)
+            .map_or(true, |caps| caps.iter().any(|c| c == "QRESYNC"))
+    }
+
+    /// Replay `account_id`'s queued offline mutations in order, dropping any
+    /// whose folder has since been resynced under a different UIDVALIDITY
+    async fn replay_pending_ops(&self, account_id: &str) {
+        let ops = {
+            let mut pending = self.pending_ops.write().await;
+            pending.remove(account_id).unwrap_or_default()
+        };
+
+        for op in ops {
+            let sync_key = format!("{}_{}", account_id, op.folder());
+            let current_uid_validity = self.folder_sync_state.read().await.get(&sync_key).map(|s| s.uid_validity);
+
+            if current_uid_validity != Some(op.uid_validity()) {
+                log::warn!(
+                    "Dropping stale pending op for account {} folder {}: UIDVALIDITY changed since it was queued",
+                    account_id, op.folder()
+                );
+                continue;
+            }
+
+            // TODO: Issue the corresponding IMAP command (STORE/COPY+EXPUNGE)
+            // here before the local cache is considered authoritative again.
+            match &op {
+                PendingOp::MarkRead { message_id, read, .. } => self.apply_mark_read(message_id, *read).await,
+                PendingOp::Flag { message_id, flagged, .. } => self.apply_flag(message_id, *flagged).await,
+                PendingOp::Move { message_id, target_folder, .. } => self.apply_move(message_id, target_folder).await,
+                PendingOp::Delete { message_id, .. } => self.apply_delete(message_id).await,
+            }
+        }
+    }
+
+    /// Queue `op` for replay once `account_id` reconnects
+    async fn queue_pending_op(&self, account_id: &str, op: PendingOp) {
+        let mut pending = self.pending_ops.write().await;
+        pending.entry(account_id.to_string()).or_insert_with(Vec::new).push(op);
+    }
+
+    /// Resolve a UID to the message it currently names, scoped to (account,
+    /// folder) so a UID colliding with another account/folder never cross-matches
+    async fn resolve_uid(&self, account_id: &str, folder: &str, uid: u32) -> Option<UidEntry> {
+        self.uid_index
+            .read()
+            .await
+            .get(&UidKey { account_id: account_id.to_string(), folder: folder.to_string(), uid })
+            .cloned()
+    }
+
+    async fn apply_mark_read(&self, message_id: &str, read: bool) {
+        let mut messages = self.messages.write().await;
+        if let Some(message) = messages.get_mut(message_id) {
+            message.read = read;
+        }
+    }
+
+    async fn apply_flag(&self, message_id: &str, flagged: bool) {
+        let mut messages = self.messages.write().await;
+        if let Some(message) = messages.get_mut(message_id) {
+            message.flagged = flagged;
+        }
+    }
+
+    async fn apply_move(&self, message_id: &str, target_folder: &str) {
+        let mut messages = self.messages.write().await;
+        if let Some(message) = messages.get_mut(message_id) {
+            message.folder = target_folder.to_string();
+        }
+    }
+
+    async fn apply_delete(&self, message_id: &str) {
+        let mut messages = self.messages.write().await;
+        messages.remove(message_id);
+    }
+
+    /// Whether `account_id` is currently reachable, per the last connectivity
+    /// probe. An unknown account is treated as online so callers that haven't
+    /// gone through `add_account` yet (e.g. tests) aren't short-circuited.
+    async fn is_account_online(&self, account_id: &str) -> bool {
+        self.accounts.read().await.get(account_id).map_or(true, |a| a.is_online)
+    }
+
+    /// Periodically probe every known account's connectivity, so an account
+    /// that drops mid-session automatically resumes syncing once it's
+    /// reachable again, without requiring the user to retry by hand
+    pub async fn run_health_check_loop(self: Arc<Self>, interval: std::time::Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            ticker.tick().await;
+            let account_ids: Vec<String> = self.accounts.read().await.keys().cloned().collect();
+            for account_id in account_ids {
+                if let Err(e) = self.check_connection(&account_id).await {
+                    log::warn!("Health check failed for account {}: {}", account_id, e);
+                }
+            }
+        }
+    }
+
+    /// Subscribe to `FolderEvent`s for an account, creating its broadcast
+    /// channel on first use. Call `watch_folder` to actually start feeding it.
+    pub async fn subscribe_events(&self, account_id: &str) -> broadcast::Receiver<FolderEvent> {
+        self.event_sender(account_id).await.subscribe()
+    }
+
+    async fn event_sender(&self, account_id: &str) -> broadcast::Sender<FolderEvent> {
+        if let Some(sender) = self.event_channels.read().await.get(account_id) {
+            return sender.clone();
+        }
+        let mut channels = self.event_channels.write().await;
+        channels
+            .entry(account_id.to_string())
+            .or_insert_with(|| broadcast::channel(EVENT_CHANNEL_CAPACITY).0)
+            .clone()
+    }
+
+    /// Watch a folder via a long-lived IMAP IDLE connection, spawning a
+    /// background task that renews IDLE before the server's ~29-minute
+    /// timeout and emits `FolderEvent`s (subscribed to via
+    /// `subscribe_events`) as the server reports changes. If the account's
+    /// CAPABILITY response doesn't advertise IDLE, this falls back to a
+    /// plain NOOP polling loop instead. If the account drops offline
+    /// mid-watch, this falls back to a one-shot incremental resync as soon
+    /// as connectivity returns. Replaces any watch already running for this
+    /// account/folder.
+    pub fn watch_folder(self: Arc<Self>, account_id: String, folder: String) {
+        // Real IMAP IDLE must be renewed before ~29 minutes or the server
+        // drops the connection; poll for mock server notifications well
+        // inside that window. Accounts without IDLE are polled less
+        // aggressively since each round trip is a full NOOP, not a push.
+        const IDLE_RENEWAL_INTERVAL: Duration = Duration::from_secs(25 * 60);
+        const IDLE_POLL_INTERVAL: Duration = Duration::from_secs(30);
+        const NOOP_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+        let key = format!("{}_{}", account_id, folder);
+        let manager = self.clone();
+
+        let handle = tokio::spawn(async move {
+            loop {
+                let use_idle = manager.supports_idle(&account_id).await;
+                let poll_interval = if use_idle { IDLE_POLL_INTERVAL } else { NOOP_POLL_INTERVAL };
+                let renew_at = Instant::now() + IDLE_RENEWAL_INTERVAL;
+                let mut dropped_while_idling = false;
+
+                loop {
+                    tokio::time::sleep(poll_interval).await;
+
+                    if !manager.is_account_online(&account_id).await {
+                        dropped_while_idling = true;
+                        break;
+                    }
+
+                    // TODO: Replace with reading the real IMAP IDLE untagged
+                    // responses (EXISTS/EXPUNGE/FETCH), or issuing a plain
+                    // NOOP when `use_idle` is false. For now, poll for what
+                    // changed the same way a reconnect would.
+                    if let Err(e) = manager.poll_folder_changes(&account_id, &folder).await {
+                        log::warn!(
+                            "{} poll failed for {}/{}: {}",
+                            if use_idle { "IDLE" } else { "NOOP" },
+                            account_id, folder, e
+                        );
+                    }
+
+                    // Only a real IDLE connection needs renewing before the
+                    // server's timeout; a NOOP loop just keeps polling.
+                    if use_idle && Instant::now() >= renew_at {
+                        break;
+                    }
+                }
+
+                if dropped_while_idling {
+                    log::warn!("Watch for {}/{} dropped; falling back to a one-shot resync once reachable", account_id, folder);
+                    while !manager.is_account_online(&account_id).await {
+                        tokio::time::sleep(poll_interval).await;
+                    }
+                    if let Err(e) = manager.poll_folder_changes(&account_id, &folder).await {
+                        log::warn!("Post-reconnect resync failed for {}/{}: {}", account_id, folder, e);
+                    }
+                }
+
+                // TODO: Issue IMAP `DONE` followed by a fresh `IDLE` (or
+                // NOOP) here; looping re-establishes the watch for the next
+                // interval.
+            }
+        });
+
+        let watch_handles = self.watch_handles.clone();
+        tokio::spawn(async move {
+            if let Some(previous) = watch_handles.write().await.insert(key, handle) {
+                previous.abort();
+            }
+        });
+    }
+
+    /// Tear down a running folder watch, the in-process stand-in for
+    /// issuing IMAP `DONE` and closing the IDLE connection. A no-op if
+    /// nothing is watching this account/folder.
+    pub async fn unwatch_folder(&self, account_id: &str, folder: &str) {
+        let key = format!("{}_{}", account_id, folder);
+        if let Some(handle) = self.watch_handles.write().await.remove(&key) {
+            handle.abort();
+        }
+    }
+
+    /// Re-fetch a folder incrementally (reusing the CONDSTORE MODSEQ
+    /// machinery in `sync_folder_messages`), diff the result against the
+    /// current cache, refresh the `threads` cache, and broadcast a
+    /// `FolderEvent` per new/changed/vanished message
+    async fn poll_folder_changes(&self, account_id: &str, folder: &str) -> Result<(), String> {
+        let before: HashMap<u32, EmailMessage> = self
+            .get_folder_messages(account_id, folder)
+            .await
+            .into_iter()
+            .map(|msg| (msg.uid, msg))
+            .collect();
+
+        let after = self.sync_folder_messages(account_id, folder, true).await?;
+        let sender = self.event_sender(account_id).await;
+
+        for message in &after {
+            match before.get(&message.uid) {
+                None => {
+                    let _ = sender.send(FolderEvent::NewMessage { uid: message.uid, message_id: message.id.clone() });
+                }
+                Some(prev) if prev.read != message.read || prev.flagged != message.flagged => {
+                    let _ = sender.send(FolderEvent::FlagsChanged { uid: message.uid, seen: message.read, flagged: message.flagged });
+                }
+                _ => {}
+            }
+        }
+
+        // `after` holds only what changed since the last MODSEQ, so vanished
+        // UIDs are the ones no longer in the cache once the sync has run
+        let after_uids: std::collections::HashSet<u32> = self
+            .get_folder_messages(account_id, folder)
+            .await
+            .iter()
+            .map(|msg| msg.uid)
+            .collect();
+        for uid in before.keys() {
+            if !after_uids.contains(uid) {
+                let _ = sender.send(FolderEvent::Expunged { uid: *uid });
+            }
+        }
+
+        if let Ok(threads) = self.get_threads(account_id, folder).await {
+            self.threads.write().await.insert(format!("{}_{}", account_id, folder), threads);
+        }
+
+        Ok(())
+    }
+
     /// Synchronize folders for an account
     pub async fn sync_folders(&self, account_id: &str) -> Result<Vec<EmailFolder>, String> {
+        if !self.is_account_online(account_id).await {
+            log::warn!("Account {} is offline; returning cached folders", account_id);
+            return Ok(self.get_folders(account_id).await.unwrap_or_default());
+        }
+
         // TODO: Implement actual IMAP folder synchronization
         // For now, return mock folders
-        
+
         let folders = vec![
             EmailFolder {
                 id: format!("{}_INBOX", account_id),
@@ -171,17 +652,65 @@ impl IMAPManager {
         }
     }
 
-    /// Synchronize messages for a specific folder
-    pub async fn sync_folder_messages(&self, account_id: &str, folder_path: &str, _incremental: bool) -> Result<Vec<EmailMessage>, String> {
-        // TODO: Implement actual IMAP message synchronization
-        // For now, return mock messages
-        
+    /// Synchronize messages for a specific folder. When `incremental` is set,
+    /// sync state exists for this folder, and the account's CAPABILITY
+    /// advertises CONDSTORE, this issues an incremental fetch instead of
+    /// pulling the whole folder: with QRESYNC, `SELECT (QRESYNC (uidvalidity
+    /// highestmodseq))` followed by `FETCH 1:* (FLAGS UID MODSEQ)
+    /// (CHANGEDSINCE <modseq>)`, whose `VANISHED (EARLIER)` UIDs are removed
+    /// from the local cache; with plain CONDSTORE, the same FETCH without a
+    /// VANISHED set, so deletions are only caught on the next full resync. A
+    /// UIDVALIDITY mismatch, missing stored state, or no CONDSTORE support
+    /// forces a full resync.
+    pub async fn sync_folder_messages(&self, account_id: &str, folder_path: &str, incremental: bool) -> Result<Vec<EmailMessage>, String> {
+        if !self.is_account_online(account_id).await {
+            log::warn!("Account {} is offline; returning cached messages for folder {}", account_id, folder_path);
+            return Ok(self.get_folder_messages(account_id, folder_path).await);
+        }
+
+        let sync_key = format!("{}_{}", account_id, folder_path);
+
         self.update_sync_progress(account_id, folder_path, 0, 2, "Fetching messages").await;
-        
-        let messages = vec![
+
+        let stored_state = self.folder_sync_state.read().await.get(&sync_key).cloned();
+        let condstore = self.supports_condstore(account_id).await;
+        let qresync = self.supports_qresync(account_id).await;
+
+        // TODO: Replace with a real IMAP session. This issues:
+        //   SELECT (QRESYNC (uid_validity highest_modseq))
+        // and reads back the server's UIDVALIDITY/HIGHESTMODSEQ. Until a real
+        // IMAP client is wired in, the server state is mocked as stable across
+        // connections with HIGHESTMODSEQ advancing by one per sync.
+        let server_uid_validity: u32 = 1;
+        let server_highest_modseq = stored_state.as_ref().map_or(1, |s| s.highest_modseq + 1);
+
+        let do_incremental = incremental
+            && condstore
+            && stored_state
+                .as_ref()
+                .is_some_and(|state| state.uid_validity == server_uid_validity);
+
+        if incremental && !do_incremental {
+            // No CONDSTORE, no prior state, or UIDVALIDITY changed underneath
+            // us: cached UIDs for this folder are no longer meaningful.
+            let mut stored_messages = self.messages.write().await;
+            stored_messages.retain(|_, msg| !(msg.id.starts_with(account_id) && msg.folder == folder_path));
+            if !condstore {
+                log::info!("Account {} lacks CONDSTORE; performing full resync of folder {}", account_id, folder_path);
+            } else {
+                log::info!("UIDVALIDITY mismatch or no prior state for folder {} ({}); performing full resync", folder_path, account_id);
+            }
+        }
+
+        // TODO: Replace with FETCH 1:* (FLAGS UID MODSEQ) (CHANGEDSINCE <modseq>)
+        // against the real IMAP connection. All_messages below stands in for
+        // the server's full message set; do_incremental filters it down to
+        // only the messages whose MODSEQ increased since the last sync.
+        let all_messages = vec![
             EmailMessage {
                 id: format!("{}_{}_1", account_id, folder_path),
                 uid: 1,
+                modseq: 1,
                 subject: "Welcome to Melanie Email".to_string(),
                 from: "welcome@melanie.ai".to_string(),
                 to: vec!["user@example.com".to_string()],
@@ -205,6 +734,7 @@ impl IMAPManager {
             EmailMessage {
                 id: format!("{}_{}_2", account_id, folder_path),
                 uid: 2,
+                modseq: 2,
                 subject: "Getting Started Guide".to_string(),
                 from: "support@melanie.ai".to_string(),
                 to: vec!["user@example.com".to_string()],
@@ -226,21 +756,64 @@ impl IMAPManager {
                 priority: "normal".to_string(),
             },
         ];
-        
-        // Store messages
+
+        let messages: Vec<EmailMessage> = if do_incremental {
+            let since_modseq = stored_state.as_ref().map_or(0, |s| s.highest_modseq);
+            all_messages.into_iter().filter(|msg| msg.modseq > since_modseq).collect()
+        } else {
+            all_messages
+        };
+
+        // TODO: Process the server's VANISHED (EARLIER) UID set here and
+        // remove each one from `messages`. No UIDs vanish in the mock server
+        // response above, so this is currently a no-op. Only meaningful with
+        // QRESYNC enabled; plain CONDSTORE's CHANGEDSINCE has no deletion
+        // signal, so those folders rely on the next full resync to catch up.
+        let vanished_uids: Vec<u32> = Vec::new();
+        if do_incremental && qresync && !vanished_uids.is_empty() {
+            let mut stored_messages = self.messages.write().await;
+            stored_messages.retain(|_, msg| {
+                !(msg.id.starts_with(account_id) && msg.folder == folder_path && vanished_uids.contains(&msg.uid))
+            });
+        }
+
+        // Store fetched messages
         let mut stored_messages = self.messages.write().await;
         for message in &messages {
             stored_messages.insert(message.id.clone(), message.clone());
         }
-        
+        drop(stored_messages);
+
+        // Keep the UID index current so flag/move/delete can resolve a UID
+        // without colliding with the same UID in another account or folder
+        let mut uid_index = self.uid_index.write().await;
+        for message in &messages {
+            uid_index.insert(
+                UidKey { account_id: account_id.to_string(), folder: folder_path.to_string(), uid: message.uid },
+                UidEntry { message_id: message.id.clone(), uid_validity: server_uid_validity },
+            );
+        }
+        drop(uid_index);
+
+        // Only advance the persisted sync state after a successful pass, so
+        // an interrupted sync restarts from the last known-good MODSEQ.
+        let mut folder_sync_state = self.folder_sync_state.write().await;
+        folder_sync_state.insert(sync_key, FolderSyncState {
+            uid_validity: server_uid_validity,
+            highest_modseq: server_highest_modseq,
+        });
+        drop(folder_sync_state);
+
         // Update account last sync time
         let mut accounts = self.accounts.write().await;
         if let Some(account) = accounts.get_mut(account_id) {
             account.last_sync = Some(Utc::now());
         }
-        
+
         self.update_sync_progress(account_id, folder_path, 2, 2, "Complete").await;
-        
+
+        self.index_for_semantic_search(account_id, &messages).await;
+
         log::info!("Synchronized {} messages from folder {} for account {}", messages.len(), folder_path, account_id);
         Ok(messages)
     }
@@ -282,8 +855,14 @@ impl IMAPManager {
         sync_progress.insert(format!("{}_{}", account_id, folder), progress);
     }
 
-    /// Search messages across all folders
+    /// Search messages across all folders. Search always runs against the
+    /// local cache, so an offline account degrades to stale results instead
+    /// of erroring.
     pub async fn search_messages(&self, account_id: &str, query: &str, folder: Option<&str>) -> Result<Vec<EmailMessage>, String> {
+        if !self.is_account_online(account_id).await {
+            log::warn!("Account {} is offline; searching cached messages", account_id);
+        }
+
         let messages = self.messages.read().await;
         let query_lower = query.to_lowercase();
         
@@ -306,40 +885,40 @@ impl IMAPManager {
         Ok(filtered)
     }
 
-    /// Group messages into threads
+    /// Group messages into threads using the JWZ algorithm (see
+    /// `threading::jwz_thread`), rather than the naive single-field
+    /// `thread_id` grouping
     pub async fn get_threads(&self, account_id: &str, folder: &str) -> Result<Vec<EmailThread>, String> {
         let messages = self.messages.read().await;
-        let mut thread_map: HashMap<String, Vec<EmailMessage>> = HashMap::new();
-        
-        // Group messages by thread ID
-        for message in messages.values() {
-            if message.id.starts_with(account_id) && message.folder == folder {
-                thread_map.entry(message.thread_id.clone())
-                    .or_insert_with(Vec::new)
-                    .push(message.clone());
-            }
-        }
-        
-        // Convert to EmailThread structs
+        let account_messages: Vec<EmailMessage> = messages
+            .values()
+            .filter(|msg| msg.id.starts_with(account_id) && msg.folder == folder)
+            .cloned()
+            .collect();
+        drop(messages);
+
+        let thread_groups = threading::jwz_thread(account_messages);
+
+        // Convert to EmailThread structs. Message order (and the
+        // `message_parents` indices into it) comes straight from the JWZ
+        // tree traversal, not a timestamp sort, so nesting survives.
         let mut threads = Vec::new();
-        for (thread_id, mut thread_messages) in thread_map {
-            thread_messages.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
-            
+        for (thread_id, thread_messages) in thread_groups {
             let participants: Vec<String> = thread_messages.iter()
-                .flat_map(|msg| {
-                    let mut p = vec![msg.from.clone()];
-                    p.extend(msg.to.clone());
+                .flat_map(|threaded| {
+                    let mut p = vec![threaded.message.from.clone()];
+                    p.extend(threaded.message.to.clone());
                     p
                 })
                 .collect::<std::collections::HashSet<_>>()
                 .into_iter()
                 .collect();
-            
-            let has_unread = thread_messages.iter().any(|msg| !msg.read);
-            let is_flagged = thread_messages.iter().any(|msg| msg.flagged);
-            let last_message_date = thread_messages.last().unwrap().timestamp;
-            let subject = thread_messages.first().unwrap().subject.clone();
-            
+
+            let has_unread = thread_messages.iter().any(|threaded| !threaded.message.read);
+            let is_flagged = thread_messages.iter().any(|threaded| threaded.message.flagged);
+            let last_message_date = thread_messages.iter().map(|threaded| threaded.message.timestamp).max().unwrap();
+            let subject = thread_messages[0].message.subject.clone();
+
             let thread = EmailThread {
                 id: thread_id,
                 subject,
@@ -349,9 +928,10 @@ impl IMAPManager {
                 has_unread,
                 is_flagged,
                 folder: folder.to_string(),
-                messages: thread_messages.iter().map(|msg| msg.id.clone()).collect(),
+                messages: thread_messages.iter().map(|threaded| threaded.message.id.clone()).collect(),
+                message_parents: thread_messages.iter().map(|threaded| threaded.parent_index).collect(),
             };
-            
+
             threads.push(thread);
         }
         
@@ -391,60 +971,115 @@ impl IMAPManager {
             .collect()
     }
 
-    /// Mark message as read/unread
-    pub async fn mark_message_read(&self, _account_id: &str, message_uid: u32, read: bool) -> Result<(), String> {
-        // TODO: Implement actual IMAP flag update
-        // For now, just update local cache
-        
-        let mut messages = self.messages.write().await;
-        for message in messages.values_mut() {
-            if message.uid == message_uid {
-                message.read = read;
-                break;
-            }
+    /// Next UID to assign in `account_id`/`folder`, one past the highest UID
+    /// currently cached there. TODO: Replace with the UID the server assigns
+    /// back in its tagged `APPEND` `OK [APPENDUID ...]` response.
+    pub async fn next_uid(&self, account_id: &str, folder: &str) -> u32 {
+        self.get_folder_messages(account_id, folder).await.iter().map(|m| m.uid).max().unwrap_or(0) + 1
+    }
+
+    /// APPEND a just-submitted outgoing message to its Sent folder with
+    /// `\Seen` set, so it shows up locally without waiting for the next
+    /// sync. TODO: Issue a real IMAP APPEND with the raw MIME source here;
+    /// the mock store just inserts the already-built `EmailMessage` directly.
+    pub async fn append_sent_message(&self, account_id: &str, message: EmailMessage) {
+        let folder = message.folder.clone();
+        let uid = message.uid;
+        let message_id = message.id.clone();
+
+        self.messages.write().await.insert(message_id.clone(), message);
+
+        let sync_key = format!("{}_{}", account_id, folder);
+        let uid_validity = self.folder_sync_state.read().await.get(&sync_key).map_or(1, |s| s.uid_validity);
+        self.uid_index.write().await.insert(
+            UidKey { account_id: account_id.to_string(), folder, uid },
+            UidEntry { message_id, uid_validity },
+        );
+    }
+
+    /// Mark message as read/unread. UIDs are only resolved within the given
+    /// account/folder, and the mutation is queued for replay if the account
+    /// is currently offline.
+    pub async fn mark_message_read(&self, account_id: &str, folder: &str, message_uid: u32, read: bool) -> Result<(), String> {
+        let entry = self.resolve_uid(account_id, folder, message_uid).await.ok_or_else(|| {
+            format!("Unknown message: account={} folder={} uid={}", account_id, folder, message_uid)
+        })?;
+
+        // TODO: Issue IMAP STORE FLAGS (\Seen) here when online; the mock
+        // "network" call always succeeds, so offline is the only failure mode.
+        if !self.is_account_online(account_id).await {
+            self.queue_pending_op(account_id, PendingOp::MarkRead {
+                folder: folder.to_string(),
+                uid_validity: entry.uid_validity,
+                message_id: entry.message_id.clone(),
+                read,
+            }).await;
         }
-        
+
+        self.apply_mark_read(&entry.message_id, read).await;
         Ok(())
     }
 
-    /// Flag/unflag message
-    pub async fn flag_message(&self, _account_id: &str, message_uid: u32, flagged: bool) -> Result<(), String> {
-        // TODO: Implement actual IMAP flag update
-        // For now, just update local cache
-        
-        let mut messages = self.messages.write().await;
-        for message in messages.values_mut() {
-            if message.uid == message_uid {
-                message.flagged = flagged;
-                break;
-            }
+    /// Flag/unflag message. See `mark_message_read` for the UID resolution
+    /// and offline-queueing behavior.
+    pub async fn flag_message(&self, account_id: &str, folder: &str, message_uid: u32, flagged: bool) -> Result<(), String> {
+        let entry = self.resolve_uid(account_id, folder, message_uid).await.ok_or_else(|| {
+            format!("Unknown message: account={} folder={} uid={}", account_id, folder, message_uid)
+        })?;
+
+        // TODO: Issue IMAP STORE FLAGS (\Flagged) here when online.
+        if !self.is_account_online(account_id).await {
+            self.queue_pending_op(account_id, PendingOp::Flag {
+                folder: folder.to_string(),
+                uid_validity: entry.uid_validity,
+                message_id: entry.message_id.clone(),
+                flagged,
+            }).await;
         }
-        
+
+        self.apply_flag(&entry.message_id, flagged).await;
         Ok(())
     }
 
-    /// Move message to folder
-    pub async fn move_message(&self, _account_id: &str, message_uid: u32, target_folder: &str) -> Result<(), String> {
-        // TODO: Implement actual IMAP move operation
-        // For now, just update local cache
-        
-        let mut messages = self.messages.write().await;
-        if let Some(message) = messages.values_mut()
-            .find(|msg| msg.uid == message_uid) {
-            message.folder = target_folder.to_string();
+    /// Move message to folder. See `mark_message_read` for the UID
+    /// resolution and offline-queueing behavior.
+    pub async fn move_message(&self, account_id: &str, folder: &str, message_uid: u32, target_folder: &str) -> Result<(), String> {
+        let entry = self.resolve_uid(account_id, folder, message_uid).await.ok_or_else(|| {
+            format!("Unknown message: account={} folder={} uid={}", account_id, folder, message_uid)
+        })?;
+
+        // TODO: Issue an IMAP UID MOVE (or COPY+STORE \Deleted+EXPUNGE) here when online.
+        if !self.is_account_online(account_id).await {
+            self.queue_pending_op(account_id, PendingOp::Move {
+                folder: folder.to_string(),
+                uid_validity: entry.uid_validity,
+                message_id: entry.message_id.clone(),
+                target_folder: target_folder.to_string(),
+            }).await;
         }
-        
+
+        self.apply_move(&entry.message_id, target_folder).await;
         Ok(())
     }
 
-    /// Delete message
-    pub async fn delete_message(&self, account_id: &str, message_uid: u32) -> Result<(), String> {
-        // TODO: Implement actual IMAP delete operation
-        // For now, just remove from local cache
-        
-        let mut messages = self.messages.write().await;
-        messages.retain(|_, msg| !(msg.uid == message_uid && msg.id.starts_with(account_id)));
-        
+    /// Delete message. See `mark_message_read` for the UID resolution and
+    /// offline-queueing behavior.
+    pub async fn delete_message(&self, account_id: &str, folder: &str, message_uid: u32) -> Result<(), String> {
+        let entry = self.resolve_uid(account_id, folder, message_uid).await.ok_or_else(|| {
+            format!("Unknown message: account={} folder={} uid={}", account_id, folder, message_uid)
+        })?;
+
+        // TODO: Issue an IMAP UID STORE \Deleted + EXPUNGE here when online.
+        if !self.is_account_online(account_id).await {
+            self.queue_pending_op(account_id, PendingOp::Delete {
+                folder: folder.to_string(),
+                uid_validity: entry.uid_validity,
+                message_id: entry.message_id.clone(),
+            }).await;
+        }
+
+        self.apply_delete(&entry.message_id).await;
         Ok(())
     }
-}
\ No newline at end of file
+}
+
@@ -2,13 +2,21 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod imap_manager;
+mod managesieve;
+mod semantic_search;
+mod smtp;
+mod threading;
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use chrono::Utc;
 use tauri::{Manager, State};
 use serde::{Deserialize, Serialize};
 
-use imap_manager::{IMAPManager, EmailAccount, EmailMessage, EmailFolder, EmailThread, SyncProgress};
+use imap_manager::{IMAPManager, EmailAccount, EmailMessage, EmailFolder, EmailThread, FolderEvent, SyncProgress};
+use managesieve::{ManageSieveManager, SieveCapabilities, SieveScript};
+use semantic_search::SemanticSearchManager;
+use smtp::EmailAttachment;
 
 #[derive(Debug, Serialize, Deserialize)]
 struct AIAnalysis {
@@ -19,15 +27,44 @@ struct AIAnalysis {
     suggested_actions: Vec<String>,
 }
 
+// Payloads for the `mail:*` events emitted to the frontend by `start_idle`
+#[derive(Debug, Serialize)]
+struct MailNewPayload {
+    uid: u32,
+    message_id: String,
+}
+
+#[derive(Debug, Serialize)]
+struct MailFlagsPayload {
+    uid: u32,
+    seen: bool,
+    flagged: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct MailExpungePayload {
+    uid: u32,
+}
+
 // Application state
 struct AppState {
     imap_manager: Arc<IMAPManager>,
+    managesieve_manager: Arc<ManageSieveManager>,
+    semantic_search_manager: Arc<SemanticSearchManager>,
 }
 
 impl AppState {
-    fn new() -> Self {
+    async fn new() -> Self {
+        let imap_manager = Arc::new(IMAPManager::new());
+        let semantic_search_manager = Arc::new(
+            SemanticSearchManager::new().await.expect("failed to initialize semantic search"),
+        );
+        imap_manager.set_semantic_search(semantic_search_manager.clone()).await;
+
         Self {
-            imap_manager: Arc::new(IMAPManager::new()),
+            imap_manager,
+            managesieve_manager: Arc::new(ManageSieveManager::new()),
+            semantic_search_manager,
         }
     }
 }
@@ -44,6 +81,49 @@ async fn add_email_account(account: EmailAccount, state: State<'_, AppState>) ->
     state.imap_manager.add_account(account).await
 }
 
+#[tauri::command]
+async fn check_account_connection(account_id: String, state: State<'_, AppState>) -> Result<bool, String> {
+    state.imap_manager.check_connection(&account_id).await
+}
+
+#[tauri::command]
+async fn start_idle(account_id: String, folder: String, window: tauri::Window, state: State<'_, AppState>) -> Result<(), String> {
+    log::info!("Starting IDLE watch for {}/{}", account_id, folder);
+
+    let mut events = state.imap_manager.subscribe_events(&account_id).await;
+    state.imap_manager.clone().watch_folder(account_id, folder);
+
+    // Bridge the manager's internal FolderEvent stream to the events the
+    // frontend listens for, so a live inbox update needs no manual refresh.
+    tauri::async_runtime::spawn(async move {
+        while let Ok(event) = events.recv().await {
+            let emitted = match event {
+                FolderEvent::NewMessage { uid, message_id } => {
+                    window.emit("mail:new", MailNewPayload { uid, message_id })
+                }
+                FolderEvent::FlagsChanged { uid, seen, flagged } => {
+                    window.emit("mail:flags", MailFlagsPayload { uid, seen, flagged })
+                }
+                FolderEvent::Expunged { uid } => {
+                    window.emit("mail:expunge", MailExpungePayload { uid })
+                }
+            };
+            if let Err(e) = emitted {
+                log::warn!("Failed to emit folder event to frontend: {}", e);
+            }
+        }
+    });
+
+    Ok(())
+}
+
+#[tauri::command]
+async fn stop_idle(account_id: String, folder: String, state: State<'_, AppState>) -> Result<(), String> {
+    log::info!("Stopping IDLE watch for {}/{}", account_id, folder);
+    state.imap_manager.unwatch_folder(&account_id, &folder).await;
+    Ok(())
+}
+
 #[tauri::command]
 async fn sync_emails(account_id: String, state: State<'_, AppState>) -> Result<Vec<EmailMessage>, String> {
     log::info!("Syncing emails for account: {}", account_id);
@@ -85,29 +165,62 @@ async fn search_messages(account_id: String, query: String, folder: Option<Strin
     state.imap_manager.search_messages(&account_id, &query, folder.as_deref()).await
 }
 
+/// Rank `account_id`'s messages by semantic similarity to `query`, using the
+/// HNSW-backed embedding index built up as messages sync, then merge in
+/// any additional substring hits the semantic pass missed
+#[tauri::command]
+async fn semantic_search(account_id: String, query: String, state: State<'_, AppState>) -> Result<Vec<EmailMessage>, String> {
+    const TOP_K: usize = 10;
+
+    let semantic_hits = state
+        .semantic_search_manager
+        .search(&account_id, &query, TOP_K)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let text_hits = state.imap_manager.search_messages(&account_id, &query, None).await?;
+
+    let mut seen = std::collections::HashSet::new();
+    let mut merged = Vec::new();
+    for (message_id, _score) in semantic_hits {
+        if let Some(message) = state.imap_manager.get_message(&message_id).await {
+            if seen.insert(message.id.clone()) {
+                merged.push(message);
+            }
+        }
+    }
+    for message in text_hits {
+        if seen.insert(message.id.clone()) {
+            merged.push(message);
+        }
+    }
+
+    Ok(merged)
+}
+
 #[tauri::command]
 async fn get_threads(account_id: String, folder: String, state: State<'_, AppState>) -> Result<Vec<EmailThread>, String> {
     state.imap_manager.get_threads(&account_id, &folder).await
 }
 
 #[tauri::command]
-async fn mark_message_read(account_id: String, message_uid: u32, read: bool, state: State<'_, AppState>) -> Result<(), String> {
-    state.imap_manager.mark_message_read(&account_id, message_uid, read).await
+async fn mark_message_read(account_id: String, folder: String, message_uid: u32, read: bool, state: State<'_, AppState>) -> Result<(), String> {
+    state.imap_manager.mark_message_read(&account_id, &folder, message_uid, read).await
 }
 
 #[tauri::command]
-async fn flag_message(account_id: String, message_uid: u32, flagged: bool, state: State<'_, AppState>) -> Result<(), String> {
-    state.imap_manager.flag_message(&account_id, message_uid, flagged).await
+async fn flag_message(account_id: String, folder: String, message_uid: u32, flagged: bool, state: State<'_, AppState>) -> Result<(), String> {
+    state.imap_manager.flag_message(&account_id, &folder, message_uid, flagged).await
 }
 
 #[tauri::command]
-async fn move_message(account_id: String, message_uid: u32, target_folder: String, state: State<'_, AppState>) -> Result<(), String> {
-    state.imap_manager.move_message(&account_id, message_uid, &target_folder).await
+async fn move_message(account_id: String, folder: String, message_uid: u32, target_folder: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.imap_manager.move_message(&account_id, &folder, message_uid, &target_folder).await
 }
 
 #[tauri::command]
-async fn delete_message(account_id: String, message_uid: u32, state: State<'_, AppState>) -> Result<(), String> {
-    state.imap_manager.delete_message(&account_id, message_uid).await
+async fn delete_message(account_id: String, folder: String, message_uid: u32, state: State<'_, AppState>) -> Result<(), String> {
+    state.imap_manager.delete_message(&account_id, &folder, message_uid).await
 }
 
 #[tauri::command]
@@ -115,25 +228,154 @@ async fn get_sync_progress(account_id: String, state: State<'_, AppState>) -> Re
     Ok(state.imap_manager.get_sync_progress(&account_id).await)
 }
 
+#[tauri::command]
+async fn get_sieve_capabilities(account_id: String, state: State<'_, AppState>) -> Result<SieveCapabilities, String> {
+    Ok(state.managesieve_manager.capabilities(&account_id).await)
+}
+
+#[tauri::command]
+async fn list_sieve_scripts(account_id: String, state: State<'_, AppState>) -> Result<Vec<SieveScript>, String> {
+    state.managesieve_manager.list_scripts(&account_id).await
+}
+
+#[tauri::command]
+async fn get_sieve_script(account_id: String, name: String, state: State<'_, AppState>) -> Result<String, String> {
+    state.managesieve_manager.get_script(&account_id, &name).await
+}
+
+#[tauri::command]
+async fn put_sieve_script(account_id: String, name: String, content: String, state: State<'_, AppState>) -> Result<(), String> {
+    log::info!("Uploading Sieve script {} for account: {}", name, account_id);
+    state.managesieve_manager.put_script(&account_id, &name, content).await
+}
+
+#[tauri::command]
+async fn activate_sieve_script(account_id: String, name: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.managesieve_manager.activate_script(&account_id, &name).await
+}
+
+#[tauri::command]
+async fn delete_sieve_script(account_id: String, name: String, state: State<'_, AppState>) -> Result<(), String> {
+    state.managesieve_manager.delete_script(&account_id, &name).await
+}
+
 #[tauri::command]
 async fn send_email(
     account_id: String,
     to: Vec<String>,
-    _cc: Vec<String>,
-    _bcc: Vec<String>,
-    _subject: String,
-    _body: String,
-    _html_body: Option<String>,
+    cc: Vec<String>,
+    bcc: Vec<String>,
+    subject: String,
+    body: String,
+    html_body: Option<String>,
+    attachments: Vec<EmailAttachment>,
+    state: State<'_, AppState>,
 ) -> Result<String, String> {
-    // TODO: Implement SMTP email sending
     log::info!("Sending email from account: {} to: {:?}", account_id, to);
-    Ok("message_id".to_string())
+
+    let account = state.imap_manager.get_accounts().await
+        .into_iter()
+        .find(|a| a.id == account_id)
+        .ok_or_else(|| format!("Account not found: {}", account_id))?;
+
+    let outgoing = smtp::OutgoingMessage {
+        from: account.email.clone(),
+        to: to.clone(),
+        cc: cc.clone(),
+        bcc: bcc.clone(),
+        subject,
+        body,
+        html_body,
+        attachments,
+    };
+    let assembled = smtp::assemble_mime_message(&outgoing);
+
+    let envelope_recipients: Vec<String> = outgoing.to.iter()
+        .chain(outgoing.cc.iter())
+        .chain(outgoing.bcc.iter())
+        .cloned()
+        .collect();
+    smtp::submit(
+        &account.smtp_server,
+        account.smtp_port,
+        account.use_tls,
+        &account.username,
+        &assembled,
+        &envelope_recipients,
+    ).await?;
+
+    let uid = state.imap_manager.next_uid(&account_id, "Sent").await;
+    let sent_message = EmailMessage {
+        id: format!("{}_Sent_{}", account_id, uid),
+        uid,
+        modseq: 1,
+        subject: outgoing.subject.clone(),
+        from: outgoing.from.clone(),
+        to: outgoing.to.clone(),
+        cc: outgoing.cc.clone(),
+        bcc: outgoing.bcc.clone(),
+        body: outgoing.body.clone(),
+        html_body: outgoing.html_body.clone(),
+        timestamp: Utc::now(),
+        read: true,
+        flagged: false,
+        folder: "Sent".to_string(),
+        message_id: assembled.message_id.clone(),
+        in_reply_to: None,
+        references: vec![],
+        thread_id: assembled.message_id.clone(),
+        has_attachments: !outgoing.attachments.is_empty(),
+        size: assembled.raw.len(),
+        labels: vec![],
+        priority: "normal".to_string(),
+    };
+    state.imap_manager.append_sent_message(&account_id, sent_message).await;
+
+    Ok(assembled.message_id)
+}
+
+/// Up to `top_k` other messages most semantically similar to `seed`'s body
+/// (`seed` itself is dropped from the hits), for use as RAG context before
+/// a Melanie API call. Resolves `seed`'s
+/// account from the id prefix convention `imap_manager` assigns message ids
+/// (`"{account_id}_{folder}_{uid}"`); best-effort, empty on any miss.
+async fn context_messages(state: &State<'_, AppState>, seed: &EmailMessage, top_k: usize) -> Vec<EmailMessage> {
+    let accounts = state.imap_manager.get_accounts().await;
+    let Some(account) = accounts.into_iter().find(|a| seed.id.starts_with(&a.id)) else {
+        return Vec::new();
+    };
+
+    match state.semantic_search_manager.search(&account.id, &seed.body, top_k).await {
+        Ok(hits) => {
+            let mut context = Vec::new();
+            for (hit_id, _score) in hits {
+                if hit_id == seed.id {
+                    continue;
+                }
+                if let Some(message) = state.imap_manager.get_message(&hit_id).await {
+                    context.push(message);
+                }
+            }
+            context
+        }
+        Err(e) => {
+            log::warn!("Semantic context retrieval failed for {}: {}", seed.id, e);
+            Vec::new()
+        }
+    }
 }
 
 #[tauri::command]
-async fn analyze_email_with_ai(message_id: String) -> Result<AIAnalysis, String> {
-    // TODO: Implement AI analysis integration with Melanie API
+async fn analyze_email_with_ai(message_id: String, state: State<'_, AppState>) -> Result<AIAnalysis, String> {
     log::info!("Analyzing email with AI: {}", message_id);
+
+    let message = state.imap_manager.get_message(&message_id).await
+        .ok_or_else(|| format!("Message not found: {}", message_id))?;
+    let context = context_messages(&state, &message, 3).await;
+    log::info!("Grounding AI analysis of {} with {} related message(s)", message_id, context.len());
+
+    // TODO: Send `message` plus `context` to the Melanie API and use its
+    // response instead of this stub.
     Ok(AIAnalysis {
         sentiment: "neutral".to_string(),
         category: "general".to_string(),
@@ -144,16 +386,31 @@ async fn analyze_email_with_ai(message_id: String) -> Result<AIAnalysis, String>
 }
 
 #[tauri::command]
-async fn summarize_thread(thread_id: String) -> Result<String, String> {
-    // TODO: Implement thread summarization using Melanie-3-light
+async fn summarize_thread(thread_id: String, state: State<'_, AppState>) -> Result<String, String> {
     log::info!("Summarizing thread: {}", thread_id);
+
+    let context = match state.imap_manager.get_message_by_rfc_id(&thread_id).await {
+        Some(root) => context_messages(&state, &root, 5).await,
+        None => Vec::new(),
+    };
+    log::info!("Grounding thread {} summary with {} related message(s)", thread_id, context.len());
+
+    // TODO: Send the thread's messages plus `context` to Melanie-3-light and
+    // use its response instead of this stub.
     Ok("Thread summary pending".to_string())
 }
 
 #[tauri::command]
-async fn draft_reply(message_id: String, _context: String) -> Result<String, String> {
-    // TODO: Implement reply drafting with RAG context
+async fn draft_reply(message_id: String, _context: String, state: State<'_, AppState>) -> Result<String, String> {
     log::info!("Drafting reply for message: {}", message_id);
+
+    let message = state.imap_manager.get_message(&message_id).await
+        .ok_or_else(|| format!("Message not found: {}", message_id))?;
+    let context = context_messages(&state, &message, 5).await;
+    log::info!("Grounding reply draft for {} with {} related message(s)", message_id, context.len());
+
+    // TODO: Send `message` plus `context` to the Melanie API and return its
+    // drafted reply instead of this stub.
     Ok("Reply draft pending".to_string())
 }
 
@@ -172,21 +429,31 @@ fn main() {
     env_logger::init();
     
     tauri::Builder::default()
-        .manage(AppState::new())
+        .manage(tauri::async_runtime::block_on(AppState::new()))
         .invoke_handler(tauri::generate_handler![
             get_email_accounts,
             add_email_account,
+            check_account_connection,
+            start_idle,
+            stop_idle,
             sync_emails,
             sync_folder,
             get_folders,
             get_folder_messages,
             search_messages,
+            semantic_search,
             get_threads,
             mark_message_read,
             flag_message,
             move_message,
             delete_message,
             get_sync_progress,
+            get_sieve_capabilities,
+            list_sieve_scripts,
+            get_sieve_script,
+            put_sieve_script,
+            activate_sieve_script,
+            delete_sieve_script,
             send_email,
             analyze_email_with_ai,
             summarize_thread,
@@ -195,7 +462,14 @@ fn main() {
         ])
         .setup(|app| {
             let window = app.get_window("main").unwrap();
-            
+
+            // Keep account connectivity status current so a dropped account
+            // automatically resumes syncing once it's reachable again
+            let imap_manager = app.state::<AppState>().imap_manager.clone();
+            tauri::async_runtime::spawn(async move {
+                imap_manager.run_health_check_loop(std::time::Duration::from_secs(30)).await;
+            });
+
             // Set up window event handlers
             let window_clone = window.clone();
             window.on_window_event(move |event| {
@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+/// Scripts larger than this are rejected with a quota `NO`, mirroring a
+/// server's `PUTSCRIPT` quota enforcement
+const MOCK_QUOTA_BYTES: usize = 32 * 1024;
+
+/// A single Sieve script as returned by `LISTSCRIPTS`/`GETSCRIPT`. At most
+/// one script per account can have `active` set, per RFC 5804 section 2.7.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SieveScript {
+    pub name: String,
+    pub content: String,
+    pub active: bool,
+}
+
+/// The server's ManageSieve `CAPABILITY` response: the Sieve extensions it
+/// supports (e.g. `fileinto`, `imap4flags`) and its implementation string,
+/// surfaced to the frontend so it can warn before installing a script that
+/// uses an unsupported extension.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SieveCapabilities {
+    pub implementation: String,
+    pub sieve_extensions: Vec<String>,
+}
+
+/// Client for the ManageSieve protocol (RFC 5804), run over the account's
+/// existing TLS connection path to manage server-side mail filters. Mirrors
+/// `imap_manager::IMAPManager`'s shape: an in-memory mock store per account
+/// until a real ManageSieve session is wired in.
+pub struct ManageSieveManager {
+    /// Scripts per account, keyed by account id
+    scripts: Arc<RwLock<HashMap<String, Vec<SieveScript>>>>,
+    /// Last known CAPABILITY response per account, keyed by account id
+    capabilities: Arc<RwLock<HashMap<String, SieveCapabilities>>>,
+}
+
+impl ManageSieveManager {
+    pub fn new() -> Self {
+        Self {
+            scripts: Arc::new(RwLock::new(HashMap::new())),
+            capabilities: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// The server's ManageSieve CAPABILITY response, probing it on first
+    /// use. TODO: Replace with a real `AUTHENTICATE` + `CAPABILITY` exchange
+    /// over the account's TLS connection; the mock server always reports
+    /// `fileinto`, `imap4flags`, `reject`, and `vacation`.
+    pub async fn capabilities(&self, account_id: &str) -> SieveCapabilities {
+        if let Some(caps) = self.capabilities.read().await.get(account_id) {
+            return caps.clone();
+        }
+        let caps = SieveCapabilities {
+            implementation: "Melanie Mock ManageSieve".to_string(),
+            sieve_extensions: vec![
+                "fileinto".to_string(),
+                "imap4flags".to_string(),
+                "reject".to_string(),
+                "vacation".to_string(),
+            ],
+        };
+        self.capabilities.write().await.insert(account_id.to_string(), caps.clone());
+        caps
+    }
+
+    /// `LISTSCRIPTS`: the account's scripts, with the currently active one flagged
+    pub async fn list_scripts(&self, account_id: &str) -> Result<Vec<SieveScript>, String> {
+        self.capabilities(account_id).await;
+        Ok(self.scripts.read().await.get(account_id).cloned().unwrap_or_default())
+    }
+
+    /// `GETSCRIPT <name>`: the named script's content
+    pub async fn get_script(&self, account_id: &str, name: &str) -> Result<String, String> {
+        self.scripts
+            .read()
+            .await
+            .get(account_id)
+            .and_then(|scripts| scripts.iter().find(|s| s.name == name))
+            .map(|s| s.content.clone())
+            .ok_or_else(|| format!("NO: script not found: {}", name))
+    }
+
+    /// `PUTSCRIPT <name> {len+}\r\n<content>`: upload (or replace) a script.
+    /// TODO: Replace the length check below with the real literal framing
+    /// and let the server's own `NO (quota/maxsize)` response drive this;
+    /// the mock server enforces a flat per-script size limit instead.
+    pub async fn put_script(&self, account_id: &str, name: &str, content: String) -> Result<(), String> {
+        if content.len() > MOCK_QUOTA_BYTES {
+            return Err(format!(
+                "NO (quota/maxsize) script {} is {} bytes, over the {}-byte limit",
+                name, content.len(), MOCK_QUOTA_BYTES
+            ));
+        }
+
+        let mut scripts = self.scripts.write().await;
+        let account_scripts = scripts.entry(account_id.to_string()).or_insert_with(Vec::new);
+        match account_scripts.iter_mut().find(|s| s.name == name) {
+            Some(existing) => existing.content = content,
+            None => account_scripts.push(SieveScript { name: name.to_string(), content, active: false }),
+        }
+        Ok(())
+    }
+
+    /// `SETACTIVE <name>`: make `name` the account's single active script,
+    /// deactivating whichever script held that slot before. An empty `name`
+    /// deactivates every script.
+    pub async fn activate_script(&self, account_id: &str, name: &str) -> Result<(), String> {
+        let mut scripts = self.scripts.write().await;
+        if name.is_empty() {
+            for script in scripts.get_mut(account_id).into_iter().flatten() {
+                script.active = false;
+            }
+            return Ok(());
+        }
+        let account_scripts = scripts
+            .get_mut(account_id)
+            .ok_or_else(|| format!("NO: script not found: {}", name))?;
+
+        if !account_scripts.iter().any(|s| s.name == name) {
+            return Err(format!("NO: script not found: {}", name));
+        }
+        for script in account_scripts.iter_mut() {
+            script.active = script.name == name;
+        }
+        Ok(())
+    }
+
+    /// `DELETESCRIPT <name>`. Deleting the active script is rejected, same
+    /// as a real ManageSieve server (`SETACTIVE ""` must run first).
+    pub async fn delete_script(&self, account_id: &str, name: &str) -> Result<(), String> {
+        let mut scripts = self.scripts.write().await;
+        let account_scripts = scripts
+            .get_mut(account_id)
+            .ok_or_else(|| format!("NO: script not found: {}", name))?;
+
+        if account_scripts.iter().any(|s| s.name == name && s.active) {
+            return Err(format!("NO: cannot delete the active script: {}", name));
+        }
+
+        let before = account_scripts.len();
+        account_scripts.retain(|s| s.name != name);
+        if account_scripts.len() == before {
+            return Err(format!("NO: script not found: {}", name));
+        }
+        Ok(())
+    }
+}
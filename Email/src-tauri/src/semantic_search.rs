@@ -0,0 +1,127 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use melanie_rag::chunker::SmartChunker;
+use melanie_rag::config::{ChunkingConfig, EmbeddingConfig, HnswParams};
+use melanie_rag::embedder::EmbeddingClient;
+use melanie_rag::error::RagResult;
+use melanie_rag::types::DocumentId;
+use melanie_rag::vector_store::HnswIndex;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::imap_manager::EmailMessage;
+
+/// One account's semantic index: an HNSW graph over per-message embeddings,
+/// plus enough bookkeeping to map a hit back to a real message and skip
+/// re-indexing a message already embedded
+struct MailboxIndex {
+    index: HnswIndex,
+    next_id: u64,
+    message_ids: HashMap<u64, String>,
+    indexed: HashSet<String>,
+}
+
+impl MailboxIndex {
+    fn new() -> Self {
+        Self {
+            index: HnswIndex::new(HnswParams::default()),
+            next_id: 0,
+            message_ids: HashMap::new(),
+            indexed: HashSet::new(),
+        }
+    }
+}
+
+/// Embeds and indexes mailbox messages so the frontend's search can rank by
+/// meaning rather than just substring matches, and so `draft_reply`/
+/// `summarize_thread` can ground themselves in the messages most relevant
+/// to the one they're acting on. Mirrors `managesieve::ManageSieveManager`'s
+/// shape: one dedicated manager, one `RwLock`-guarded store keyed by
+/// account, mocked network calls behind the RAG crate's own clients.
+pub struct SemanticSearchManager {
+    embedder: EmbeddingClient,
+    chunker: SmartChunker,
+    mailboxes: Arc<RwLock<HashMap<String, MailboxIndex>>>,
+}
+
+impl SemanticSearchManager {
+    pub async fn new() -> RagResult<Self> {
+        let embedder = EmbeddingClient::new(EmbeddingConfig::default())?;
+        let chunker = SmartChunker::with_default_tokenizer(ChunkingConfig::default()).await?;
+        Ok(Self {
+            embedder,
+            chunker,
+            mailboxes: Arc::new(RwLock::new(HashMap::new())),
+        })
+    }
+
+    /// Chunk `message`'s body, embed the chunks, and add their average
+    /// embedding to `account_id`'s index under `message.id`. A message
+    /// already indexed is skipped; re-syncing doesn't re-embed it, since
+    /// `HnswIndex` has no update-in-place support yet.
+    pub async fn index_message(&self, account_id: &str, message: &EmailMessage) -> RagResult<()> {
+        {
+            let mailboxes = self.mailboxes.read().await;
+            if mailboxes.get(account_id).is_some_and(|m| m.indexed.contains(&message.id)) {
+                return Ok(());
+            }
+        }
+
+        let document_id: DocumentId = Uuid::new_v4();
+        let mut chunks = self.chunker.chunk_document(document_id, &message.body).await?;
+        self.embedder.embed_chunks(&mut chunks).await?;
+
+        let embeddings: Vec<_> = chunks.into_iter().filter_map(|c| c.embedding).collect();
+        let Some(averaged) = average_embeddings(&embeddings) else {
+            return Ok(());
+        };
+
+        let mut mailboxes = self.mailboxes.write().await;
+        let mailbox = mailboxes.entry(account_id.to_string()).or_insert_with(MailboxIndex::new);
+        if mailbox.indexed.contains(&message.id) {
+            return Ok(());
+        }
+
+        let id = mailbox.next_id;
+        mailbox.next_id += 1;
+        mailbox.index.insert(id, averaged);
+        mailbox.message_ids.insert(id, message.id.clone());
+        mailbox.indexed.insert(message.id.clone());
+        Ok(())
+    }
+
+    /// Embed `query` and rank `account_id`'s indexed messages by cosine
+    /// similarity to it, most relevant first
+    pub async fn search(&self, account_id: &str, query: &str, top_k: usize) -> RagResult<Vec<(String, f32)>> {
+        let query_embedding = self.embedder.embed_single(query).await?;
+
+        let mailboxes = self.mailboxes.read().await;
+        let Some(mailbox) = mailboxes.get(account_id) else {
+            return Ok(Vec::new());
+        };
+
+        Ok(mailbox
+            .index
+            .search(&query_embedding, top_k)
+            .into_iter()
+            .filter_map(|(id, score)| mailbox.message_ids.get(&id).map(|message_id| (message_id.clone(), score)))
+            .collect())
+    }
+}
+
+/// Mean of a set of chunk embeddings, so a multi-chunk message still stores
+/// as one vector per message in the index. `None` if `embeddings` is empty.
+fn average_embeddings(embeddings: &[Vec<f32>]) -> Option<Vec<f32>> {
+    let dim = embeddings.first()?.len();
+    let mut averaged = vec![0.0f32; dim];
+    for embedding in embeddings {
+        for (i, value) in embedding.iter().enumerate() {
+            averaged[i] += value;
+        }
+    }
+    for value in &mut averaged {
+        *value /= embeddings.len() as f32;
+    }
+    Some(averaged)
+}
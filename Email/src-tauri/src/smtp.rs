@@ -0,0 +1,126 @@
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A single file attached to an outgoing message, already base64-encoded
+/// for the `Content-Transfer-Encoding: base64` MIME part
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailAttachment {
+    pub filename: String,
+    pub content_type: String,
+    pub content_base64: String,
+}
+
+/// Everything needed to assemble and submit an outgoing message. `bcc` is
+/// only ever used as an envelope recipient, never written into the
+/// assembled MIME headers.
+#[derive(Debug, Clone)]
+pub struct OutgoingMessage {
+    pub from: String,
+    pub to: Vec<String>,
+    pub cc: Vec<String>,
+    pub bcc: Vec<String>,
+    pub subject: String,
+    pub body: String,
+    pub html_body: Option<String>,
+    pub attachments: Vec<EmailAttachment>,
+}
+
+/// A MIME message assembled and ready for `DATA`/`APPEND`
+pub struct AssembledMessage {
+    pub message_id: String,
+    pub raw: String,
+}
+
+/// Assemble `msg` into an RFC 5322 MIME message: plain text alone, a
+/// `multipart/alternative` of text+html when `html_body` is set, wrapped in
+/// a `multipart/mixed` if there are attachments. Generates a fresh
+/// `Message-ID` and the current `Date`.
+pub fn assemble_mime_message(msg: &OutgoingMessage) -> AssembledMessage {
+    let message_id = format!("<{}@melanie.ai>", Uuid::new_v4());
+    let date = Utc::now().to_rfc2822();
+
+    let mut header_lines = vec![
+        format!("From: {}", msg.from),
+        format!("To: {}", msg.to.join(", ")),
+    ];
+    if !msg.cc.is_empty() {
+        header_lines.push(format!("Cc: {}", msg.cc.join(", ")));
+    }
+    header_lines.push(format!("Subject: {}", msg.subject));
+    header_lines.push(format!("Message-ID: {}", message_id));
+    header_lines.push(format!("Date: {}", date));
+    header_lines.push("MIME-Version: 1.0".to_string());
+
+    let alt_boundary = format!("alt_{}", Uuid::new_v4().simple());
+    let mixed_boundary = format!("mixed_{}", Uuid::new_v4().simple());
+
+    // The message body: plain text alone, or a `multipart/alternative` of
+    // text+html when both are present.
+    let (body_content_type, body) = match &msg.html_body {
+        Some(html) => {
+            let body = format!(
+                "--{b}\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n{text}\r\n\r\n--{b}\r\nContent-Type: text/html; charset=utf-8\r\n\r\n{html}\r\n\r\n--{b}--\r\n",
+                b = alt_boundary, text = msg.body, html = html
+            );
+            (format!("multipart/alternative; boundary=\"{}\"", alt_boundary), body)
+        }
+        None => ("text/plain; charset=utf-8".to_string(), format!("{}\r\n", msg.body)),
+    };
+
+    // Attachments wrap the body in `multipart/mixed`; with none, the body
+    // (or the text/html alternative) is the whole message.
+    let (content_type, full_body) = if msg.attachments.is_empty() {
+        (body_content_type, body)
+    } else {
+        let mut mixed_body = format!(
+            "--{b}\r\nContent-Type: {ct}\r\n\r\n{body}\r\n",
+            b = mixed_boundary, ct = body_content_type, body = body.trim_end()
+        );
+        for attachment in &msg.attachments {
+            mixed_body.push_str(&format!(
+                "\r\n--{b}\r\nContent-Type: {ct}; name=\"{name}\"\r\nContent-Transfer-Encoding: base64\r\nContent-Disposition: attachment; filename=\"{name}\"\r\n\r\n{data}\r\n",
+                b = mixed_boundary, ct = attachment.content_type, name = attachment.filename, data = attachment.content_base64
+            ));
+        }
+        mixed_body.push_str(&format!("\r\n--{}--\r\n", mixed_boundary));
+        (format!("multipart/mixed; boundary=\"{}\"", mixed_boundary), mixed_body)
+    };
+
+    header_lines.push(format!("Content-Type: {}", content_type));
+
+    let raw = format!("{}\r\n\r\n{}", header_lines.join("\r\n"), full_body);
+    AssembledMessage { message_id, raw }
+}
+
+/// Deliver `assembled` over authenticated SMTP submission to every envelope
+/// recipient (`To` + `Cc` + `Bcc`; `Bcc` only ever appears here, never in
+/// the assembled headers).
+///
+/// TODO: Replace with a real SMTP session: STARTTLS or implicit TLS per
+/// `use_tls`, `AUTH`, then `MAIL FROM`/`RCPT TO`/`DATA` per recipient. The
+/// mock transport always succeeds once an account exists.
+pub async fn submit(
+    smtp_server: &str,
+    smtp_port: u16,
+    use_tls: bool,
+    username: &str,
+    assembled: &AssembledMessage,
+    envelope_recipients: &[String],
+) -> Result<(), String> {
+    if envelope_recipients.is_empty() {
+        return Err("No recipients specified".to_string());
+    }
+
+    log::info!(
+        "Submitting message {} to {} recipient(s) via {}:{} ({}, user {})",
+        assembled.message_id,
+        envelope_recipients.len(),
+        smtp_server,
+        smtp_port,
+        if use_tls { "TLS" } else { "plaintext" },
+        username
+    );
+
+    Ok(())
+}
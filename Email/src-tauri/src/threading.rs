@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+
+use crate::imap_manager::EmailMessage;
+
+/// A node in the JWZ container tree, keyed by RFC Message-ID. A container can
+/// hold no message at all (an ancestor referenced by `References` that was
+/// never itself fetched), which is exactly how unrelated messages end up
+/// grouped under one synthetic thread root.
+struct Container {
+    message: Option<EmailMessage>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+}
+
+/// One message in a threaded group, with `parent_index` pointing back into
+/// the same group's `Vec` (not a raw Message-ID) so the UI can render the
+/// JWZ nesting without re-deriving it.
+pub struct ThreadedMessage {
+    pub message: EmailMessage,
+    pub parent_index: Option<usize>,
+}
+
+/// Thread a flat set of messages with the JWZ references algorithm: build a
+/// container per distinct Message-ID, link containers along each message's
+/// `references` chain (falling back to `in_reply_to` for the message's own
+/// parent), prune containers that hold no message and have at most one
+/// child, then merge the remaining roots that share a normalized subject.
+/// Returns one `(thread_id, messages)` pair per resulting thread, each
+/// message carrying the index of its parent within that same thread.
+pub fn jwz_thread(messages: Vec<EmailMessage>) -> Vec<(String, Vec<ThreadedMessage>)> {
+    let mut containers: Vec<Container> = Vec::new();
+    let mut id_table: HashMap<String, usize> = HashMap::new();
+
+    for message in messages {
+        let msg_idx = get_or_create(&mut containers, &mut id_table, &message.message_id);
+
+        let mut prev_idx: Option<usize> = None;
+        for reference in &message.references {
+            let ref_idx = get_or_create(&mut containers, &mut id_table, reference);
+            if let Some(parent_idx) = prev_idx {
+                set_parent(&mut containers, ref_idx, parent_idx);
+            }
+            prev_idx = Some(ref_idx);
+        }
+
+        // The message's own parent is the last reference in the chain,
+        // falling back to In-Reply-To when References is absent or empty.
+        let parent_ref = message
+            .references
+            .last()
+            .cloned()
+            .or_else(|| message.in_reply_to.clone());
+        if let Some(parent_ref) = parent_ref {
+            if parent_ref != message.message_id {
+                let parent_idx = get_or_create(&mut containers, &mut id_table, &parent_ref);
+                set_parent(&mut containers, msg_idx, parent_idx);
+            }
+        }
+
+        if containers[msg_idx].message.is_none() {
+            containers[msg_idx].message = Some(message);
+        }
+    }
+
+    let roots: Vec<usize> = containers
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.parent.is_none())
+        .map(|(idx, _)| idx)
+        .collect();
+    let roots = prune_roots(&mut containers, roots);
+
+    let mut subject_groups: Vec<(String, Vec<ThreadedMessage>)> = Vec::new();
+    for root in roots {
+        let mut root_messages = Vec::new();
+        collect_messages(&containers, root, None, &mut root_messages);
+        if root_messages.is_empty() {
+            continue;
+        }
+
+        let subject = normalize_subject(&root_messages[0].message.subject);
+        if let Some((_, existing)) = subject_groups.iter_mut().find(|(s, _)| *s == subject) {
+            // Merging a second root tree in: its indices are relative to its
+            // own `root_messages`, so offset them past what's already there.
+            let offset = existing.len();
+            for mut threaded in root_messages {
+                threaded.parent_index = threaded.parent_index.map(|idx| idx + offset);
+                existing.push(threaded);
+            }
+        } else {
+            subject_groups.push((subject, root_messages));
+        }
+    }
+
+    subject_groups
+        .into_iter()
+        .map(|(_, msgs)| {
+            let thread_id = msgs[0].message.message_id.clone();
+            (thread_id, msgs)
+        })
+        .collect()
+}
+
+fn get_or_create(containers: &mut Vec<Container>, id_table: &mut HashMap<String, usize>, msg_id: &str) -> usize {
+    if let Some(&idx) = id_table.get(msg_id) {
+        return idx;
+    }
+    let idx = containers.len();
+    containers.push(Container { message: None, parent: None, children: Vec::new() });
+    id_table.insert(msg_id.to_string(), idx);
+    idx
+}
+
+/// True if `ancestor` appears somewhere in `node`'s parent chain (or is `node` itself)
+fn is_ancestor(containers: &[Container], ancestor: usize, node: usize) -> bool {
+    let mut current = Some(node);
+    while let Some(idx) = current {
+        if idx == ancestor {
+            return true;
+        }
+        current = containers[idx].parent;
+    }
+    false
+}
+
+/// Link `child` under `parent`, skipping the link entirely if it would
+/// create a cycle (i.e. `child` is already an ancestor of `parent`) or
+/// reparent a message under one of its own descendants
+fn set_parent(containers: &mut Vec<Container>, child: usize, parent: usize) {
+    if child == parent || containers[child].parent == Some(parent) {
+        return;
+    }
+    if is_ancestor(containers, child, parent) {
+        return;
+    }
+    if let Some(old_parent) = containers[child].parent {
+        containers[old_parent].children.retain(|&c| c != child);
+    }
+    containers[child].parent = Some(parent);
+    containers[parent].children.push(child);
+}
+
+/// Recursively prune `node`'s children: a child with no message and no
+/// grandchildren is dropped, one with no message and exactly one
+/// grandchild is spliced out in favor of that grandchild, and anything
+/// else is kept as-is
+fn prune(containers: &mut Vec<Container>, node: usize) {
+    let children = containers[node].children.clone();
+    let mut kept_children = Vec::new();
+
+    for child in children {
+        prune(containers, child);
+
+        let has_message = containers[child].message.is_some();
+        let grandchildren = containers[child].children.clone();
+        if !has_message {
+            if grandchildren.is_empty() {
+                continue;
+            }
+            if grandchildren.len() == 1 {
+                let grandchild = grandchildren[0];
+                containers[grandchild].parent = Some(node);
+                kept_children.push(grandchild);
+                continue;
+            }
+        }
+        kept_children.push(child);
+    }
+
+    containers[node].children = kept_children;
+}
+
+/// Apply `prune` to each root, then collapse roots the same way (drop empty
+/// childless roots, promote an empty root's only child)
+fn prune_roots(containers: &mut Vec<Container>, roots: Vec<usize>) -> Vec<usize> {
+    let mut pruned_roots = Vec::new();
+
+    for root in roots {
+        prune(containers, root);
+
+        let has_message = containers[root].message.is_some();
+        let children = containers[root].children.clone();
+        if !has_message {
+            if children.is_empty() {
+                continue;
+            }
+            if children.len() == 1 {
+                let child = children[0];
+                containers[child].parent = None;
+                pruned_roots.push(child);
+                continue;
+            }
+        }
+        pruned_roots.push(root);
+    }
+
+    pruned_roots
+}
+
+/// Depth-first flatten of `node`'s subtree into `out`, recording each
+/// message's parent as an index into `out` itself. A message-less
+/// placeholder (pruning keeps those with two or more children) is simply
+/// skipped, and its children inherit `parent_out_idx` instead.
+fn collect_messages(containers: &[Container], node: usize, parent_out_idx: Option<usize>, out: &mut Vec<ThreadedMessage>) {
+    let mut next_parent = parent_out_idx;
+    if let Some(message) = &containers[node].message {
+        next_parent = Some(out.len());
+        out.push(ThreadedMessage { message: message.clone(), parent_index: parent_out_idx });
+    }
+    for &child in &containers[node].children {
+        collect_messages(containers, child, next_parent, out);
+    }
+}
+
+/// Lowercase and iteratively strip `re:`/`fwd:`/`fw:` prefixes so e.g.
+/// "Re: Fwd: Re: hello" and "hello" normalize to the same subject
+fn normalize_subject(subject: &str) -> String {
+    let mut normalized = subject.trim().to_lowercase();
+    loop {
+        let trimmed = normalized.trim_start();
+        let rest = trimmed
+            .strip_prefix("re:")
+            .or_else(|| trimmed.strip_prefix("fwd:"))
+            .or_else(|| trimmed.strip_prefix("fw:"));
+        match rest {
+            Some(rest) => normalized = rest.trim_start().to_string(),
+            None => break,
+        }
+    }
+    normalized
+}
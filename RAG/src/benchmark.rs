@@ -0,0 +1,148 @@
+//! Declarative workload benchmark harness for comparing `RagConfig` changes
+//! reproducibly, with per-stage span timing (chunking, embedding, vector
+//! search, reranking).
+
+use crate::config::RagConfig;
+use crate::engine::RagEngine;
+use crate::error::{RagError, RagResult};
+use crate::types::RetrievalMode;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// A declarative workload: a corpus to ingest and queries to run against it
+#[derive(Debug, Clone, Deserialize)]
+pub struct BenchmarkWorkload {
+    /// Path to a text file with one document per line
+    pub corpus_path: PathBuf,
+    /// Queries to issue against the ingested corpus
+    pub queries: Vec<BenchmarkQuery>,
+}
+
+/// A single query in a benchmark workload
+#[derive(Debug, Clone, Deserialize)]
+pub struct BenchmarkQuery {
+    /// Query text
+    pub query: String,
+    /// Expected number of results. `run_benchmark` neither passes it to the
+    /// engine nor records it in the report.
+    #[serde(default = "default_top_k")]
+    pub top_k: usize,
+    /// Retrieval mode to use for this query (defaults to `General`)
+    #[serde(default)]
+    pub mode: Option<RetrievalMode>,
+}
+
+fn default_top_k() -> usize {
+    10
+}
+
+/// Per-stage timing samples, in milliseconds, accumulated while
+/// `PerformanceConfig::enable_span_capture` is set
+#[derive(Debug, Default)]
+pub struct StageTimings {
+    samples: Mutex<HashMap<String, Vec<f64>>>,
+}
+
+impl StageTimings {
+    /// Create an empty set of stage timings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a stage's duration
+    pub fn record(&self, stage: &str, duration: Duration) {
+        let mut samples = self.samples.lock().expect("stage timing mutex poisoned");
+        samples.entry(stage.to_string()).or_default().push(duration.as_secs_f64() * 1000.0);
+    }
+
+    /// Aggregate recorded samples into a per-stage p50/p95/throughput report
+    pub fn report(&self) -> StageReport {
+        let samples = self.samples.lock().expect("stage timing mutex poisoned");
+        let stages = samples
+            .iter()
+            .map(|(stage, durations)| (stage.clone(), StageStats::from_samples(durations)))
+            .collect();
+        StageReport { stages }
+    }
+}
+
+/// Aggregate p50/p95/throughput statistics for one instrumented stage
+#[derive(Debug, Clone)]
+pub struct StageStats {
+    pub count: usize,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub throughput_per_sec: f64,
+}
+
+impl StageStats {
+    fn from_samples(durations: &[f64]) -> Self {
+        let mut sorted = durations.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+        let percentile = |p: f64| -> f64 {
+            if sorted.is_empty() {
+                return 0.0;
+            }
+            let idx = (((sorted.len() - 1) as f64) * p).round() as usize;
+            sorted[idx.min(sorted.len() - 1)]
+        };
+
+        let mean_ms = if sorted.is_empty() {
+            0.0
+        } else {
+            sorted.iter().sum::<f64>() / sorted.len() as f64
+        };
+
+        Self {
+            count: sorted.len(),
+            p50_ms: percentile(0.50),
+            p95_ms: percentile(0.95),
+            throughput_per_sec: if mean_ms > 0.0 { 1000.0 / mean_ms } else { 0.0 },
+        }
+    }
+}
+
+/// Aggregate report across all instrumented stages
+#[derive(Debug, Clone, Default)]
+pub struct StageReport {
+    pub stages: HashMap<String, StageStats>,
+}
+
+/// Build the full RAG pipeline from `config`, ingest the workload's corpus,
+/// run its queries, and return an aggregate per-stage timing report
+pub async fn run_benchmark(mut config: RagConfig, workload_path: &Path) -> RagResult<StageReport> {
+    let workload_data = std::fs::read_to_string(workload_path)
+        .map_err(|e| RagError::configuration(format!("Failed to read workload file: {}", e)))?;
+    let workload: BenchmarkWorkload = serde_json::from_str(&workload_data)?;
+
+    // Span capture has a small overhead on every request, so the harness
+    // turns it on for the duration of the run rather than requiring callers
+    // to remember to.
+    config.performance.enable_span_capture = true;
+
+    let engine = RagEngine::new(config).await?;
+
+    let corpus = std::fs::read_to_string(&workload.corpus_path)
+        .map_err(|e| RagError::configuration(format!("Failed to read corpus file: {}", e)))?;
+    let mut document_ids = Vec::new();
+    for document in corpus.lines().filter(|line| !line.trim().is_empty()) {
+        document_ids.push(engine.ingest_document(document.to_string(), HashMap::new()).await?);
+    }
+    // Ingestion now returns as soon as a document is chunked and queued;
+    // wait for the background indexing worker to catch up so the queries
+    // below see the full corpus, matching pre-queue benchmark behavior.
+    for document_id in document_ids {
+        engine.await_indexed(document_id).await?;
+    }
+
+    for query in &workload.queries {
+        let mode = query.mode.unwrap_or(RetrievalMode::General);
+        let _ = engine.retrieve_context(&query.query, mode).await?;
+    }
+
+    Ok(engine.stage_timings())
+}
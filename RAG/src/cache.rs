@@ -1,37 +1,207 @@
 //! Caching layer for the RAG engine
 
-use crate::config::CacheConfig;
+use crate::config::{CacheConfig, CacheSizes, EvictionPolicy, FingerprintMode, RemoteCacheMode};
+use crate::embedder::EmbeddingClient;
 use crate::error::{RagError, RagResult};
+use crate::remote_cache::{BackendTier, CacheBackend};
+#[cfg(feature = "redis-cache")]
+use crate::remote_cache::LayeredBackend;
 use crate::types::{Embedding, RetrievalResult};
 use lru::LruCache;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sled::Db;
 use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
 use std::hash::{Hash, Hasher};
+use std::future::Future;
 use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
+use tracing::warn;
 
 /// Cache key type
 type CacheKey = u64;
 
-/// Cached item with TTL
+/// A verification fingerprint of the input that produced a `CacheKey`,
+/// stored alongside the value so a `DefaultHasher` collision between two
+/// different inputs is caught at lookup time instead of silently handing
+/// back the wrong value. Which variant is stored is controlled by
+/// `CacheConfig::fingerprint`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Fingerprint {
+    /// The full original input, compared verbatim
+    Full(String),
+    /// A blake3 digest of the original input
+    Blake3([u8; 32]),
+}
+
+impl Fingerprint {
+    fn compute(mode: FingerprintMode, input: &str) -> Self {
+        match mode {
+            FingerprintMode::Full => Fingerprint::Full(input.to_string()),
+            FingerprintMode::Blake3 => Fingerprint::Blake3(*blake3::hash(input.as_bytes()).as_bytes()),
+        }
+    }
+}
+
+/// Cached item with TTL, plus the bookkeeping the disk-backed cold tier
+/// needs to decide when an entry has gone cold
 #[derive(Debug, Clone)]
 struct CachedItem<T> {
     value: T,
     created_at: Instant,
     ttl: Duration,
+    /// The cache's `age` counter as of this entry's last `get_*` touch;
+    /// the flush task demotes entries whose age has fallen far enough
+    /// behind the current age
+    last_touched_age: u64,
+    /// Set on every touch; cleared once the flush task has persisted this
+    /// entry, so an already-flushed-and-untouched entry isn't rewritten
+    dirty: bool,
+    /// Verifies this entry really belongs to the lookup key that found
+    /// it; a mismatch means a `CacheKey` collision and is treated as a miss
+    fingerprint: Fingerprint,
+    /// Number of `touch()` calls (i.e. cache hits) this entry has received,
+    /// used by `EvictionPolicy::Lfu`/`WeightedScore` to rank it against
+    /// other entries
+    access_count: u64,
+    /// Per-key in-flight guard for stale-while-revalidate: set while a
+    /// background recompute triggered by `get_*_or_refresh` is running, so
+    /// a second accessor finding the same stale entry coalesces into the
+    /// existing refresh instead of launching a duplicate one. Not
+    /// persisted; a rehydrated entry always starts with no refresh in
+    /// flight
+    refreshing: Arc<AtomicBool>,
 }
 
 impl<T> CachedItem<T> {
-    fn new(value: T, ttl: Duration) -> Self {
+    fn new(value: T, ttl: Duration, age: u64, fingerprint: Fingerprint) -> Self {
         Self {
             value,
             created_at: Instant::now(),
             ttl,
+            last_touched_age: age,
+            dirty: true,
+            fingerprint,
+            access_count: 0,
+            refreshing: Arc::new(AtomicBool::new(false)),
         }
     }
-    
+
+    fn is_expired(&self) -> bool {
+        self.created_at.elapsed() > self.ttl
+    }
+
+    fn touch(&mut self, age: u64) {
+        self.last_touched_age = age;
+        self.dirty = true;
+        self.access_count += 1;
+    }
+
+    /// Fraction of `ttl` that has elapsed since this entry was created, used
+    /// by stale-while-revalidate to decide whether it's worth triggering a
+    /// background refresh yet
+    fn ttl_ratio_elapsed(&self) -> f64 {
+        self.created_at.elapsed().as_secs_f64() / self.ttl.as_secs_f64().max(f64::EPSILON)
+    }
+
+    /// Total number of cache hits this entry has received, used by
+    /// `EvictionPolicy::Lfu` to rank eviction victims
+    fn access_frequency(&self) -> u64 {
+        self.access_count
+    }
+
+    /// Blend of access frequency and recency used by
+    /// `EvictionPolicy::WeightedScore` to rank eviction victims: higher is
+    /// more valuable, so eviction drops the lowest. `current_age` is the
+    /// cache's global age counter, used to turn `last_touched_age` into a
+    /// "ticks since last touch" recency measure.
+    fn cache_score(&self, current_age: u64) -> f64 {
+        let frequency = self.access_count as f64;
+        let recency = 1.0 / (1.0 + current_age.saturating_sub(self.last_touched_age) as f64);
+        frequency * 0.5 + recency * 0.5
+    }
+}
+
+/// On-disk shape of a `CachedItem`: `Instant` isn't serializable, so it's
+/// stored as a Unix timestamp instead and converted back to an `Instant`
+/// offset on rehydration
+#[derive(Debug, Serialize, Deserialize)]
+struct PersistedItem<T> {
+    value: T,
+    created_at_unix_secs: f64,
+    ttl_secs: u64,
+    fingerprint: Fingerprint,
+}
+
+impl<T: Clone> CachedItem<T> {
+    fn to_persisted(&self) -> PersistedItem<T> {
+        let age = self.created_at.elapsed();
+        let created_at_unix_secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .saturating_sub(age)
+            .as_secs_f64();
+        PersistedItem {
+            value: self.value.clone(),
+            created_at_unix_secs,
+            ttl_secs: self.ttl.as_secs(),
+            fingerprint: self.fingerprint.clone(),
+        }
+    }
+}
+
+/// Wire shape of a value sent to the remote (Redis) cache tier. Redis
+/// itself carries the entry's expiry as a native key TTL, so unlike
+/// `PersistedItem` there's no `created_at`/`ttl` to track here — just the
+/// value and the fingerprint needed to catch a `CacheKey` collision
+/// between this node and whichever one last wrote the entry.
+#[derive(Debug, Serialize, Deserialize)]
+struct RemotePayload<T> {
+    value: T,
+    fingerprint: Fingerprint,
+}
+
+impl<T> PersistedItem<T> {
+    fn is_expired(&self) -> bool {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64();
+        now - self.created_at_unix_secs > self.ttl_secs as f64
+    }
+
+    /// Rehydrate into an in-memory `CachedItem`, touched at `age` since a
+    /// rehydration counts as a use
+    fn into_cached_item(self, age: u64) -> CachedItem<T> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64();
+        let elapsed = Duration::from_secs_f64((now - self.created_at_unix_secs).max(0.0));
+        CachedItem {
+            value: self.value,
+            created_at: Instant::now() - elapsed,
+            ttl: Duration::from_secs(self.ttl_secs),
+            last_touched_age: age,
+            dirty: true,
+            fingerprint: self.fingerprint,
+            access_count: 0,
+            refreshing: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
+/// One entry in the semantic (embedding-similarity) retrieval cache.
+/// Unlike the exact-match `retrieval` tier, these are scanned linearly
+/// for the closest previously-seen query embedding rather than looked up
+/// by hash, so there's no `Fingerprint`/`CacheKey` collision to guard
+/// against here.
+#[derive(Clone)]
+struct SemanticCacheEntry {
+    embedding: Embedding,
+    results: Vec<RetrievalResult>,
+    created_at: Instant,
+    ttl: Duration,
+}
+
+impl SemanticCacheEntry {
     fn is_expired(&self) -> bool {
         self.created_at.elapsed() > self.ttl
     }
@@ -45,10 +215,68 @@ pub struct RagCache {
     reranking: Arc<RwLock<LruCache<CacheKey, CachedItem<Vec<f32>>>>>,
     /// Retrieval results cache
     retrieval: Arc<RwLock<LruCache<CacheKey, CachedItem<Vec<RetrievalResult>>>>>,
+    /// Small "query cache" of previously-seen query embeddings, matched by
+    /// cosine similarity rather than exact text, so paraphrased repeat
+    /// questions can still hit. Capped at
+    /// `CacheConfig::semantic_cache_max_size`, evicting the oldest entry
+    /// (front of the deque) once full
+    semantic_retrieval: Arc<RwLock<VecDeque<SemanticCacheEntry>>>,
     /// Configuration
     config: CacheConfig,
     /// Cache statistics
     stats: Arc<RwLock<CacheStats>>,
+    /// Disk-backed cold tier, keyed by the same `CacheKey` as the
+    /// in-memory maps. `None` when `CacheConfig::disk_tier_enabled` is
+    /// `false`, in which case `RagCache` behaves exactly as the
+    /// in-memory-only cache it used to be
+    disk: Option<Arc<Db>>,
+    /// Incremented roughly once per `age_tick_interval_secs` by a
+    /// background task; compared against each entry's `last_touched_age`
+    /// to find entries cold enough to flush
+    age: Arc<AtomicU64>,
+    /// Set while `clear()` or `cache_embeddings()` are mutating a map, so
+    /// the background flush task skips it rather than racing eviction
+    /// against insertion
+    stop_flush: Arc<AtomicBool>,
+    /// Optional shared cache tier consulted after an in-memory and disk
+    /// miss, and written alongside every insert. `None` when
+    /// `CacheConfig::remote_cache_mode` is `RemoteCacheMode::Disabled`
+    remote: Option<Arc<dyn CacheBackend>>,
+    /// Ring buffer of timestamped `CacheStats` snapshots, capped at
+    /// `CacheConfig::stats_snapshot_history_size`, captured by a background
+    /// task on `CacheConfig::stats_snapshot_interval_secs`. Empty when
+    /// snapshotting is disabled
+    snapshots: Arc<RwLock<VecDeque<StatsSnapshot>>>,
+}
+
+/// One timestamped `CacheStats` reading in `RagCache`'s snapshot history,
+/// letting a host application chart hit-rate and eviction trends over time
+/// instead of only ever seeing the current totals
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatsSnapshot {
+    pub timestamp_unix_secs: f64,
+    pub stats: CacheStats,
+}
+
+/// Live entry count and estimated byte footprint of one in-memory tier, as
+/// reported by `RagCache::memory_report`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CategoryMemoryUsage {
+    pub entries: usize,
+    pub bytes: u64,
+}
+
+/// A point-in-time breakdown of live in-memory byte usage by category
+/// (`embeddings`, `reranking`, `retrieval`), analogous to a memory panel.
+/// Unlike `CacheStats::embedding_bytes` and friends, this is computed on
+/// demand rather than only updated on insert, so it reflects the current
+/// state even when `CacheConfig::category_byte_budgets` isn't configured.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MemoryReport {
+    pub embeddings: CategoryMemoryUsage,
+    pub reranking: CategoryMemoryUsage,
+    pub retrieval: CategoryMemoryUsage,
+    pub total_bytes: u64,
 }
 
 /// Cache statistics
@@ -60,7 +288,66 @@ pub struct CacheStats {
     pub reranking_misses: u64,
     pub retrieval_hits: u64,
     pub retrieval_misses: u64,
+    /// Hits/misses against the embedding-similarity semantic cache,
+    /// tracked separately from `retrieval_hits`/`retrieval_misses` since a
+    /// query can miss the exact-match tier but still hit this one
+    pub semantic_hits: u64,
+    pub semantic_misses: u64,
     pub evictions: u64,
+    /// Hits served by rehydrating an entry from the disk tier
+    pub disk_hits: u64,
+    /// Entries the background flush task has persisted to the disk tier
+    pub disk_flushes: u64,
+    /// Entries rehydrated from the disk or remote tier back into memory
+    pub rehydrations: u64,
+    /// Disk-tier entries evicted by `CacheConfig::disk_max_bytes` to bring
+    /// the on-disk footprint back under budget
+    pub disk_evictions: u64,
+    /// In-memory entries evicted by the memory-pressure task to bring the
+    /// in-memory tier back under its currently chosen byte budget
+    pub pressure_evictions: u64,
+    /// The in-memory tier's byte budget as of the memory-pressure task's
+    /// last sample; tracks system memory conditions rather than a static
+    /// limit. Stays `0` while `CacheConfig::memory_pressure_enabled` is
+    /// `false`
+    pub current_budget_bytes: u64,
+    /// Estimated current byte footprint of each in-memory tier, updated on
+    /// every insert when `CacheConfig::category_byte_budgets` is
+    /// configured; stays `0` otherwise
+    pub embedding_bytes: u64,
+    pub reranking_bytes: u64,
+    pub retrieval_bytes: u64,
+    /// Evictions triggered by exceeding that category's own
+    /// `CacheSizes` byte budget specifically, as opposed to `evictions`
+    /// (LRU entry-count eviction) or `pressure_evictions` (system-memory
+    /// pressure)
+    pub embedding_evictions: u64,
+    pub reranking_evictions: u64,
+    pub retrieval_evictions: u64,
+    /// Entries replaced in place by a stale-while-revalidate background
+    /// recompute triggered by a `get_*_or_refresh` call
+    pub background_refreshes: u64,
+    /// `get_*_or_refresh` calls that found a refresh already in flight for
+    /// their key and were coalesced into it rather than starting a
+    /// duplicate recompute
+    pub refresh_coalesced: u64,
+    /// Hits served by the remote (Redis) tier specifically, i.e. both the
+    /// in-memory and disk tiers missed. Counted in addition to, not instead
+    /// of, the per-kind hit counters above, so a remote hit still looks
+    /// like a normal hit to `embedding_hit_rate` and friends
+    pub remote_hits: u64,
+    /// Lookups that missed every tier, including the remote one
+    pub remote_misses: u64,
+    /// Of `evictions`, how many were decided by the `lru` crate's own
+    /// recency ordering (`CacheConfig::eviction_policy` was `Lru`, the
+    /// default)
+    pub lru_evictions: u64,
+    /// Of `evictions`, how many were decided by `EvictionPolicy::Lfu`
+    /// picking the entry with the lowest `access_frequency()`
+    pub lfu_evictions: u64,
+    /// Of `evictions`, how many were decided by `EvictionPolicy::WeightedScore`
+    /// picking the entry with the lowest blended `cache_score()`
+    pub weighted_score_evictions: u64,
 }
 
 impl CacheStats {
@@ -91,10 +378,30 @@ impl CacheStats {
         }
     }
     
+    pub fn semantic_hit_rate(&self) -> f64 {
+        let total = self.semantic_hits + self.semantic_misses;
+        if total == 0 {
+            0.0
+        } else {
+            self.semantic_hits as f64 / total as f64
+        }
+    }
+
+    /// Hit rate of the remote (Redis) tier alone, among lookups that
+    /// reached it (i.e. already missed the in-memory and disk tiers)
+    pub fn remote_hit_rate(&self) -> f64 {
+        let total = self.remote_hits + self.remote_misses;
+        if total == 0 { 0.0 } else { self.remote_hits as f64 / total as f64 }
+    }
+
     pub fn overall_hit_rate(&self) -> f64 {
-        let total_hits = self.embedding_hits + self.reranking_hits + self.retrieval_hits;
-        let total_requests = total_hits + self.embedding_misses + self.reranking_misses + self.retrieval_misses;
-        
+        let total_hits = self.embedding_hits + self.reranking_hits + self.retrieval_hits + self.semantic_hits;
+        let total_requests = total_hits
+            + self.embedding_misses
+            + self.reranking_misses
+            + self.retrieval_misses
+            + self.semantic_misses;
+
         if total_requests == 0 {
             0.0
         } else {
@@ -103,32 +410,634 @@ impl CacheStats {
     }
 }
 
+/// Bump the hit counter matching `kind` ("embeddings", "reranking", or
+/// "retrieval")
+fn bump_hit(stats: &mut CacheStats, kind: &str) {
+    match kind {
+        "embeddings" => stats.embedding_hits += 1,
+        "reranking" => stats.reranking_hits += 1,
+        "retrieval" => stats.retrieval_hits += 1,
+        _ => {}
+    }
+}
+
+/// Bump the miss counter matching `kind` ("embeddings", "reranking", or
+/// "retrieval")
+fn bump_miss(stats: &mut CacheStats, kind: &str) {
+    match kind {
+        "embeddings" => stats.embedding_misses += 1,
+        "reranking" => stats.reranking_misses += 1,
+        "retrieval" => stats.retrieval_misses += 1,
+        _ => {}
+    }
+}
+
+/// Look up `key` in `tree_name` on disk. Returns `None` if there's no
+/// disk tier, the key isn't present, the entry is expired, or its stored
+/// fingerprint doesn't match `expected` (a `CacheKey` collision) — in any
+/// of the latter two cases the colliding/expired entry is removed from
+/// disk on the way out.
+fn rehydrate_from_disk<T: DeserializeOwned>(
+    disk: &Option<Arc<Db>>,
+    tree_name: &str,
+    key: CacheKey,
+    age: u64,
+    expected: &Fingerprint,
+) -> Option<CachedItem<T>> {
+    let db = disk.as_ref()?;
+    let tree = db.open_tree(tree_name).ok()?;
+    let bytes = tree.get(key.to_be_bytes()).ok()??;
+    let persisted: PersistedItem<T> = serde_json::from_slice(&bytes).ok()?;
+
+    if persisted.is_expired() || &persisted.fingerprint != expected {
+        let _ = tree.remove(key.to_be_bytes());
+        return None;
+    }
+
+    Some(persisted.into_cached_item(age))
+}
+
+/// Demote every entry in `cache` whose `last_touched_age` has fallen at
+/// least `flush_age` ticks behind `current_age`: persist it to `tree_name`
+/// on disk, then evict it from memory. Returns the number flushed.
+async fn flush_cold_entries<T: Clone + Serialize>(
+    cache: &Arc<RwLock<LruCache<CacheKey, CachedItem<T>>>>,
+    disk: &Arc<Db>,
+    tree_name: &str,
+    current_age: u64,
+    flush_age: u64,
+) -> u64 {
+    let Ok(tree) = disk.open_tree(tree_name) else {
+        return 0;
+    };
+
+    let mut cache = cache.write().await;
+    let cold_keys: Vec<CacheKey> = cache
+        .iter()
+        .filter_map(|(key, item)| {
+            if current_age.saturating_sub(item.last_touched_age) >= flush_age {
+                Some(*key)
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    let mut flushed = 0;
+    for key in cold_keys {
+        let Some(item) = cache.peek(&key) else { continue };
+        if item.is_expired() {
+            cache.pop(&key);
+            continue;
+        }
+
+        // A clean entry was already written to disk by an earlier flush
+        // pass and hasn't been touched since, so there's nothing new to
+        // persist; just demote it.
+        if item.dirty {
+            let Ok(bytes) = serde_json::to_vec(&item.to_persisted()) else { continue };
+            if tree.insert(key.to_be_bytes(), bytes).is_err() {
+                continue;
+            }
+        }
+
+        cache.pop(&key);
+        flushed += 1;
+    }
+
+    flushed
+}
+
+/// Evict the globally oldest disk-tier entries (across `embeddings`,
+/// `reranking`, `retrieval`, compared by `created_at_unix_secs`) until the
+/// database's on-disk footprint is back under `max_bytes`. A no-op when
+/// there's no budget or it isn't currently exceeded. Returns the number of
+/// entries evicted.
+fn enforce_disk_budget(disk: &Arc<Db>, max_bytes: u64) -> u64 {
+    let Ok(size) = disk.size_on_disk() else { return 0 };
+    if size <= max_bytes {
+        return 0;
+    }
+
+    let mut entries: Vec<(f64, &'static str, sled::IVec)> = Vec::new();
+    for tree_name in ["embeddings", "reranking", "retrieval"] {
+        let Ok(tree) = disk.open_tree(tree_name) else { continue };
+        for item in tree.iter().flatten() {
+            let (key, bytes) = item;
+            let created_at = serde_json::from_slice::<serde_json::Value>(&bytes)
+                .ok()
+                .and_then(|v| v["created_at_unix_secs"].as_f64())
+                .unwrap_or(0.0);
+            entries.push((created_at, tree_name, key));
+        }
+    }
+
+    if entries.is_empty() {
+        return 0;
+    }
+
+    entries.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+    // Evict oldest-first until the estimated footprint (assuming entries
+    // are roughly uniform in size) is back under budget, rather than
+    // calling the comparatively expensive `size_on_disk` after every
+    // removal.
+    let avg_entry_bytes = (size / entries.len() as u64).max(1);
+    let mut remaining = size;
+    let mut evicted = 0u64;
+
+    for (_, tree_name, key) in entries {
+        if remaining <= max_bytes {
+            break;
+        }
+        let Ok(tree) = disk.open_tree(tree_name) else { continue };
+        if tree.remove(&key).is_ok() {
+            evicted += 1;
+            remaining = remaining.saturating_sub(avg_entry_bytes);
+        }
+    }
+
+    evicted
+}
+
+/// One eviction candidate for `enforce_memory_budget`: which in-memory
+/// tier an entry belongs to, its key, how stale it is (lower
+/// `last_touched_age` evicts first), and its estimated serialized size
+struct BudgetCandidate {
+    tier: &'static str,
+    key: CacheKey,
+    last_touched_age: u64,
+    bytes: u64,
+}
+
+/// Collect an eviction candidate for every entry in one in-memory tier,
+/// estimating each entry's size from serializing its value. Used by
+/// `enforce_memory_budget` to rank entries across all three tiers together.
+async fn collect_budget_candidates<T: Clone + Serialize>(
+    cache: &Arc<RwLock<LruCache<CacheKey, CachedItem<T>>>>,
+    tier: &'static str,
+) -> Vec<BudgetCandidate> {
+    cache
+        .read()
+        .await
+        .iter()
+        .map(|(key, item)| BudgetCandidate {
+            tier,
+            key: *key,
+            last_touched_age: item.last_touched_age,
+            bytes: serde_json::to_vec(&item.value).map(|bytes| bytes.len() as u64).unwrap_or(0),
+        })
+        .collect()
+}
+
+/// Evict the stalest in-memory entries (lowest `last_touched_age`, ranked
+/// across `embeddings`, `reranking`, and `retrieval` together) until the
+/// estimated total in-memory footprint is back under `budget_bytes`. This is
+/// what the memory-pressure task calls once it's picked a budget for the
+/// current system-memory reading. A no-op when already under budget.
+/// Returns the number of entries evicted.
+async fn enforce_memory_budget(
+    embeddings: &Arc<RwLock<LruCache<CacheKey, CachedItem<Embedding>>>>,
+    reranking: &Arc<RwLock<LruCache<CacheKey, CachedItem<Vec<f32>>>>>,
+    retrieval: &Arc<RwLock<LruCache<CacheKey, CachedItem<Vec<RetrievalResult>>>>>,
+    budget_bytes: u64,
+) -> u64 {
+    let mut candidates = collect_budget_candidates(embeddings, "embeddings").await;
+    candidates.extend(collect_budget_candidates(reranking, "reranking").await);
+    candidates.extend(collect_budget_candidates(retrieval, "retrieval").await);
+
+    let total_bytes: u64 = candidates.iter().map(|c| c.bytes).sum();
+    if total_bytes <= budget_bytes {
+        return 0;
+    }
+
+    candidates.sort_by_key(|c| c.last_touched_age);
+
+    let mut remaining = total_bytes;
+    let mut evicted = 0u64;
+    for candidate in candidates {
+        if remaining <= budget_bytes {
+            break;
+        }
+
+        let popped = match candidate.tier {
+            "embeddings" => embeddings.write().await.pop(&candidate.key).is_some(),
+            "reranking" => reranking.write().await.pop(&candidate.key).is_some(),
+            "retrieval" => retrieval.write().await.pop(&candidate.key).is_some(),
+            _ => false,
+        };
+
+        if popped {
+            evicted += 1;
+            remaining = remaining.saturating_sub(candidate.bytes);
+        }
+    }
+
+    evicted
+}
+
+/// Evict the stalest entries (lowest `last_touched_age`) from one in-memory
+/// tier until its own estimated byte footprint is back under `max_bytes`.
+/// Unlike `enforce_memory_budget`, ranks entries only within this single
+/// category, so a burst of large retrieval payloads can never evict
+/// embedding entries to make room for itself. Returns the category's
+/// resulting byte footprint and how many entries it evicted.
+async fn enforce_category_budget<T: Clone + Serialize>(
+    cache: &Arc<RwLock<LruCache<CacheKey, CachedItem<T>>>>,
+    max_bytes: u64,
+) -> (u64, u64) {
+    let mut guard = cache.write().await;
+    let mut entries: Vec<(CacheKey, u64, u64)> = guard
+        .iter()
+        .map(|(key, item)| (*key, item.last_touched_age, serde_json::to_vec(&item.value).map(|b| b.len() as u64).unwrap_or(0)))
+        .collect();
+
+    let mut total: u64 = entries.iter().map(|(_, _, bytes)| *bytes).sum();
+    if total <= max_bytes {
+        return (total, 0);
+    }
+
+    entries.sort_by_key(|(_, age, _)| *age);
+
+    let mut evicted = 0u64;
+    for (key, _, bytes) in entries {
+        if total <= max_bytes {
+            break;
+        }
+        if guard.pop(&key).is_some() {
+            evicted += 1;
+            total = total.saturating_sub(bytes);
+        }
+    }
+
+    (total, evicted)
+}
+
+/// Pick the in-memory tier's byte budget for one system-memory sample:
+/// `memory_budget_floor_bytes` once available memory drops below
+/// `low_watermark_mb`, `memory_budget_ceiling_bytes` otherwise. A simple
+/// high/low split rather than a linear ramp, so the cache's behavior near
+/// the watermark is easy to reason about from the config alone.
+fn choose_memory_budget(available_mb: f64, low_watermark_mb: u64, floor_bytes: u64, ceiling_bytes: u64) -> u64 {
+    if available_mb < low_watermark_mb as f64 {
+        floor_bytes
+    } else {
+        ceiling_bytes
+    }
+}
+
+/// Pick which entry an insert into a full tier should evict, per
+/// `CacheConfig::eviction_policy`. `EvictionPolicy::Lru` returns `None`,
+/// leaving eviction to the `lru` crate's own `put()`; `Lfu` and
+/// `WeightedScore` instead scan the whole tier for the entry with the
+/// lowest `access_frequency()`/`cache_score()` so it can be popped before
+/// `put()` runs, pre-empting the crate's recency-based choice.
+fn pick_eviction_victim<T>(
+    cache: &LruCache<CacheKey, CachedItem<T>>,
+    policy: EvictionPolicy,
+    current_age: u64,
+) -> Option<CacheKey> {
+    match policy {
+        EvictionPolicy::Lru => None,
+        EvictionPolicy::Lfu => cache
+            .iter()
+            .min_by_key(|(_, item)| item.access_frequency())
+            .map(|(key, _)| *key),
+        EvictionPolicy::WeightedScore => cache
+            .iter()
+            .min_by(|(_, a), (_, b)| {
+                a.cache_score(current_age)
+                    .partial_cmp(&b.cache_score(current_age))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .map(|(key, _)| *key),
+    }
+}
+
+/// Bump the per-policy eviction counter matching `policy`
+fn bump_policy_eviction(stats: &mut CacheStats, policy: EvictionPolicy) {
+    match policy {
+        EvictionPolicy::Lru => stats.lru_evictions += 1,
+        EvictionPolicy::Lfu => stats.lfu_evictions += 1,
+        EvictionPolicy::WeightedScore => stats.weighted_score_evictions += 1,
+    }
+}
+
+/// Insert `item` under `key` into `cache`, evicting one entry first if the
+/// tier is already full and `key` isn't already present. Under
+/// `EvictionPolicy::Lru` the eviction is left to the `lru` crate's own
+/// `put()`; under `Lfu`/`WeightedScore`, `pick_eviction_victim` selects the
+/// true minimum-frequency/minimum-score entry across the whole tier and
+/// pops it first, pre-empting the crate's recency-based choice. Returns
+/// the policy that performed an eviction, or `None` if the insert didn't
+/// evict anything (the tier had room, or `key` already had an entry).
+async fn insert_with_eviction_policy<T: Clone>(
+    cache: &Arc<RwLock<LruCache<CacheKey, CachedItem<T>>>>,
+    key: CacheKey,
+    item: CachedItem<T>,
+    policy: EvictionPolicy,
+    current_age: u64,
+) -> Option<EvictionPolicy> {
+    let mut guard = cache.write().await;
+    let full = guard.len() >= guard.cap().get() && !guard.contains(&key);
+
+    let evicted_policy = if !full {
+        None
+    } else if let Some(victim) = pick_eviction_victim(&guard, policy, current_age) {
+        guard.pop(&victim);
+        Some(policy)
+    } else {
+        // `EvictionPolicy::Lru`: no pre-pop, `put()` below evicts its own
+        // LRU tail
+        Some(EvictionPolicy::Lru)
+    };
+
+    guard.put(key, item);
+    evicted_policy
+}
+
 impl RagCache {
     /// Create a new RAG cache
-    pub fn new(config: CacheConfig) -> RagResult<Self> {
+    pub async fn new(config: CacheConfig) -> RagResult<Self> {
         if !config.enabled {
             // Create minimal caches when disabled
             return Ok(Self {
                 embeddings: Arc::new(RwLock::new(LruCache::new(NonZeroUsize::new(1).unwrap()))),
                 reranking: Arc::new(RwLock::new(LruCache::new(NonZeroUsize::new(1).unwrap()))),
                 retrieval: Arc::new(RwLock::new(LruCache::new(NonZeroUsize::new(1).unwrap()))),
+                semantic_retrieval: Arc::new(RwLock::new(VecDeque::new())),
                 config,
                 stats: Arc::new(RwLock::new(CacheStats::default())),
+                disk: None,
+                age: Arc::new(AtomicU64::new(0)),
+                stop_flush: Arc::new(AtomicBool::new(false)),
+                remote: None,
+                snapshots: Arc::new(RwLock::new(VecDeque::new())),
             });
         }
-        
+
         let cache_size = NonZeroUsize::new(config.max_size)
             .ok_or_else(|| RagError::cache("Cache size must be greater than 0"))?;
-        
-        Ok(Self {
+
+        let disk = if config.disk_tier_enabled {
+            std::fs::create_dir_all(&config.disk_path)
+                .map_err(|e| RagError::cache(format!("Failed to create cache disk directory: {}", e)))?;
+            let db = sled::open(&config.disk_path)
+                .map_err(|e| RagError::cache(format!("Failed to open disk cache: {}", e)))?;
+            Some(Arc::new(db))
+        } else {
+            None
+        };
+
+        let remote = Self::build_remote(&config).await?;
+
+        let cache = Self {
             embeddings: Arc::new(RwLock::new(LruCache::new(cache_size))),
             reranking: Arc::new(RwLock::new(LruCache::new(cache_size))),
             retrieval: Arc::new(RwLock::new(LruCache::new(cache_size))),
+            semantic_retrieval: Arc::new(RwLock::new(VecDeque::new())),
             config,
             stats: Arc::new(RwLock::new(CacheStats::default())),
-        })
+            disk,
+            age: Arc::new(AtomicU64::new(0)),
+            stop_flush: Arc::new(AtomicBool::new(false)),
+            remote,
+            snapshots: Arc::new(RwLock::new(VecDeque::new())),
+        };
+
+        cache.spawn_flush_task();
+        cache.spawn_memory_pressure_task();
+        cache.spawn_stats_snapshot_task();
+
+        Ok(cache)
     }
-    
+
+    /// Build the remote cache backend per `CacheConfig::remote_cache_mode`.
+    /// Returns `None` when the mode is `Disabled`.
+    async fn build_remote(config: &CacheConfig) -> RagResult<Option<Arc<dyn CacheBackend>>> {
+        match config.remote_cache_mode {
+            RemoteCacheMode::Disabled => Ok(None),
+            RemoteCacheMode::MemoryOverRedis => {
+                let redis_url = config.redis_url.as_ref().ok_or_else(|| {
+                    RagError::cache("remote_cache_mode is MemoryOverRedis but redis_url is not set")
+                })?;
+
+                #[cfg(feature = "redis-cache")]
+                {
+                    let redis = crate::remote_cache::RedisBackend::connect(redis_url).await?;
+                    let layered = LayeredBackend::new(config.max_size, Arc::new(redis));
+                    Ok(Some(Arc::new(layered) as Arc<dyn CacheBackend>))
+                }
+
+                #[cfg(not(feature = "redis-cache"))]
+                {
+                    let _ = redis_url;
+                    Err(RagError::cache(
+                        "remote_cache_mode is MemoryOverRedis but this build was compiled without the redis-cache feature",
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Spawn the background task that ages the cache and, once a tick,
+    /// flushes any entry that's gone cold to the disk tier. A no-op when
+    /// there's no disk tier to flush to.
+    fn spawn_flush_task(&self) {
+        let Some(disk) = self.disk.clone() else { return };
+
+        let embeddings = self.embeddings.clone();
+        let reranking = self.reranking.clone();
+        let retrieval = self.retrieval.clone();
+        let age = self.age.clone();
+        let stop_flush = self.stop_flush.clone();
+        let stats = self.stats.clone();
+        let tick = Duration::from_secs(self.config.age_tick_interval_secs.max(1));
+        let flush_age = self.config.flush_age;
+        let disk_max_bytes = self.config.disk_max_bytes;
+
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(tick).await;
+                let current_age = age.fetch_add(1, Ordering::SeqCst) + 1;
+
+                if stop_flush.load(Ordering::SeqCst) {
+                    continue;
+                }
+
+                let flushed = flush_cold_entries(&embeddings, &disk, "embeddings", current_age, flush_age).await
+                    + flush_cold_entries(&reranking, &disk, "reranking", current_age, flush_age).await
+                    + flush_cold_entries(&retrieval, &disk, "retrieval", current_age, flush_age).await;
+
+                if flushed > 0 {
+                    stats.write().await.disk_flushes += flushed;
+                }
+
+                if let Some(max_bytes) = disk_max_bytes {
+                    let evicted = enforce_disk_budget(&disk, max_bytes);
+                    if evicted > 0 {
+                        stats.write().await.disk_evictions += evicted;
+                    }
+                }
+            }
+        });
+    }
+
+    /// Spawn the background task that samples available system memory on
+    /// an interval via `sysinfo` and adjusts the in-memory tier's effective
+    /// byte budget in response: low available memory shrinks the budget to
+    /// `CacheConfig::memory_budget_floor_bytes` and evicts the stalest
+    /// entries down to it, abundant memory lets the cache grow back toward
+    /// `memory_budget_ceiling_bytes`. A no-op when
+    /// `CacheConfig::memory_pressure_enabled` is `false`.
+    fn spawn_memory_pressure_task(&self) {
+        if !self.config.memory_pressure_enabled {
+            return;
+        }
+
+        let embeddings = self.embeddings.clone();
+        let reranking = self.reranking.clone();
+        let retrieval = self.retrieval.clone();
+        let stats = self.stats.clone();
+        let interval = Duration::from_secs(self.config.memory_pressure_check_interval_secs.max(1));
+        let low_watermark_mb = self.config.memory_pressure_low_watermark_mb;
+        let floor_bytes = self.config.memory_budget_floor_bytes;
+        let ceiling_bytes = self.config.memory_budget_ceiling_bytes;
+
+        tokio::spawn(async move {
+            let mut system = sysinfo::System::new_all();
+
+            loop {
+                tokio::time::sleep(interval).await;
+
+                system.refresh_memory();
+                let available_mb = system.available_memory() as f64 / (1024.0 * 1024.0);
+                let budget = choose_memory_budget(available_mb, low_watermark_mb, floor_bytes, ceiling_bytes);
+
+                let evicted = enforce_memory_budget(&embeddings, &reranking, &retrieval, budget).await;
+
+                let mut stats = stats.write().await;
+                stats.current_budget_bytes = budget;
+                if evicted > 0 {
+                    stats.pressure_evictions += evicted;
+                }
+            }
+        });
+    }
+
+    /// Spawn the background task that captures a timestamped `CacheStats`
+    /// snapshot into `snapshots` on an interval, dropping the oldest once
+    /// `CacheConfig::stats_snapshot_history_size` is exceeded. A no-op when
+    /// `CacheConfig::stats_snapshot_interval_secs` is `None`.
+    fn spawn_stats_snapshot_task(&self) {
+        let Some(interval_secs) = self.config.stats_snapshot_interval_secs else {
+            return;
+        };
+
+        let stats = self.stats.clone();
+        let snapshots = self.snapshots.clone();
+        let history_size = self.config.stats_snapshot_history_size.max(1);
+        let interval = Duration::from_secs(interval_secs.max(1));
+
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+
+                let timestamp_unix_secs =
+                    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64();
+                let snapshot = StatsSnapshot { timestamp_unix_secs, stats: stats.read().await.clone() };
+
+                let mut snapshots = snapshots.write().await;
+                snapshots.push_back(snapshot);
+                while snapshots.len() > history_size {
+                    snapshots.pop_front();
+                }
+            }
+        });
+    }
+
+    /// Snapshot history captured so far, oldest first
+    pub async fn snapshot_history(&self) -> Vec<StatsSnapshot> {
+        self.snapshots.read().await.iter().cloned().collect()
+    }
+
+    /// Change in `CacheStats::overall_hit_rate` between the oldest and
+    /// newest captured snapshots, so a host application can tell whether
+    /// the cache is trending toward or away from a healthy hit rate
+    /// without re-deriving it from raw counters. `None` if fewer than two
+    /// snapshots have been captured yet.
+    pub async fn hit_rate_trend(&self) -> Option<f64> {
+        let snapshots = self.snapshots.read().await;
+        if snapshots.len() < 2 {
+            return None;
+        }
+        let oldest = snapshots.front()?;
+        let newest = snapshots.back()?;
+        Some(newest.stats.overall_hit_rate() - oldest.stats.overall_hit_rate())
+    }
+
+    /// Point-in-time breakdown of live in-memory byte usage by category,
+    /// analogous to a memory panel. This cache's entries don't carry a
+    /// priority level (unlike the richer `EnhancedCachedItem` design this
+    /// was modeled on), so the breakdown is by category only.
+    pub async fn memory_report(&self) -> MemoryReport {
+        async fn usage<T: Clone + Serialize>(
+            cache: &Arc<RwLock<LruCache<CacheKey, CachedItem<T>>>>,
+        ) -> CategoryMemoryUsage {
+            let guard = cache.read().await;
+            let bytes = guard
+                .iter()
+                .map(|(_, item)| serde_json::to_vec(&item.value).map(|b| b.len() as u64).unwrap_or(0))
+                .sum();
+            CategoryMemoryUsage { entries: guard.len(), bytes }
+        }
+
+        let embeddings = usage(&self.embeddings).await;
+        let reranking = usage(&self.reranking).await;
+        let retrieval = usage(&self.retrieval).await;
+        let total_bytes = embeddings.bytes + reranking.bytes + retrieval.bytes;
+
+        MemoryReport { embeddings, reranking, retrieval, total_bytes }
+    }
+
+    /// After an insert into the `kind` in-memory tier, evict that category's
+    /// stalest entries until back under its own
+    /// `CacheConfig::category_byte_budgets` limit, and record its resulting
+    /// byte footprint and eviction count in stats. A no-op when no
+    /// per-category budgets are configured.
+    async fn enforce_category_budget_for(&self, kind: &str) {
+        let Some(sizes) = self.config.category_byte_budgets.clone() else { return };
+
+        let (bytes, evicted) = match kind {
+            "embeddings" => enforce_category_budget(&self.embeddings, sizes.embeddings_max_bytes).await,
+            "reranking" => enforce_category_budget(&self.reranking, sizes.reranking_max_bytes).await,
+            "retrieval" => enforce_category_budget(&self.retrieval, sizes.retrieval_max_bytes).await,
+            _ => return,
+        };
+
+        let mut stats = self.stats.write().await;
+        match kind {
+            "embeddings" => {
+                stats.embedding_bytes = bytes;
+                stats.embedding_evictions += evicted;
+            }
+            "reranking" => {
+                stats.reranking_bytes = bytes;
+                stats.reranking_evictions += evicted;
+            }
+            "retrieval" => {
+                stats.retrieval_bytes = bytes;
+                stats.retrieval_evictions += evicted;
+            }
+            _ => {}
+        }
+    }
+
+    /// Current age, for stamping a freshly inserted entry's `last_touched_age`
+    fn current_age(&self) -> u64 {
+        self.age.load(Ordering::SeqCst)
+    }
+
     /// Generate cache key from text
     fn generate_key(text: &str) -> CacheKey {
         let mut hasher = DefaultHasher::new();
@@ -144,200 +1053,547 @@ impl RagCache {
         }
         hasher.finish()
     }
+
+    /// Join multiple texts into a single fingerprint input. Joined with a
+    /// NUL separator (texts aren't expected to contain one) so `["ab",
+    /// "c"]` and `["a", "bc"]` don't fingerprint identically.
+    fn fingerprint_input_multi(texts: &[String]) -> String {
+        texts.join("\u{0}")
+    }
     
     /// Cache embedding for text
     pub async fn cache_embedding(&self, text: &str, embedding: Embedding) -> RagResult<()> {
         if !self.config.enabled || !self.config.cache_embeddings {
             return Ok(());
         }
-        
+
         let key = Self::generate_key(text);
-        let item = CachedItem::new(embedding, Duration::from_secs(self.config.ttl));
-        
-        let mut cache = self.embeddings.write().await;
-        if cache.put(key, item).is_some() {
-            // Item was evicted
+        let fingerprint = Fingerprint::compute(self.config.fingerprint, text);
+        let ttl = Duration::from_secs(self.config.ttl);
+        let item = CachedItem::new(embedding.clone(), ttl, self.current_age(), fingerprint.clone());
+
+        if let Some(policy) =
+            insert_with_eviction_policy(&self.embeddings, key, item, self.config.eviction_policy, self.current_age()).await
+        {
             let mut stats = self.stats.write().await;
             stats.evictions += 1;
+            bump_policy_eviction(&mut stats, policy);
         }
-        
+        self.enforce_category_budget_for("embeddings").await;
+
+        self.write_remote("embeddings", key, &embedding, &fingerprint, ttl).await;
+
         Ok(())
     }
-    
-    /// Get cached embedding for text
+
+    /// Get cached embedding for text, rehydrating from the disk tier on
+    /// an in-memory miss
     pub async fn get_embedding(&self, text: &str) -> Option<Embedding> {
         if !self.config.enabled || !self.config.cache_embeddings {
             return None;
         }
-        
+
         let key = Self::generate_key(text);
-        let mut cache = self.embeddings.write().await;
-        let mut stats = self.stats.write().await;
-        
-        match cache.get(&key) {
-            Some(item) => {
-                if item.is_expired() {
-                    cache.pop(&key);
-                    stats.embedding_misses += 1;
-                    None
-                } else {
-                    stats.embedding_hits += 1;
-                    Some(item.value.clone())
-                }
-            }
-            None => {
-                stats.embedding_misses += 1;
-                None
-            }
+        let fingerprint = Fingerprint::compute(self.config.fingerprint, text);
+        self.get_cached(&self.embeddings, "embeddings", key, &fingerprint).await
+    }
+
+    /// Like `get_embedding`, but under
+    /// `CacheConfig::stale_while_revalidate_ratio` a sufficiently stale hit
+    /// is served immediately while `recompute` refreshes it in the
+    /// background instead of the caller blocking on recomputing it inline
+    pub async fn get_embedding_or_refresh<F, Fut>(&self, text: &str, recompute: F) -> Option<Embedding>
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = RagResult<Embedding>> + Send + 'static,
+    {
+        if !self.config.enabled || !self.config.cache_embeddings {
+            return None;
         }
+
+        let key = Self::generate_key(text);
+        let fingerprint = Fingerprint::compute(self.config.fingerprint, text);
+        self.get_cached_with_refresh(&self.embeddings, "embeddings", key, &fingerprint, recompute).await
     }
-    
-    /// Cache embeddings for multiple texts
+
+    /// Cache embeddings for multiple texts. Holds off the background
+    /// flush task for the duration of the insert so it can't demote an
+    /// entry this call is still writing.
     pub async fn cache_embeddings(&self, texts: &[String], embeddings: &[Embedding]) -> RagResult<()> {
         if !self.config.enabled || !self.config.cache_embeddings {
             return Ok(());
         }
-        
+
         if texts.len() != embeddings.len() {
             return Err(RagError::cache("Text and embedding counts don't match"));
         }
-        
+
+        self.stop_flush.store(true, Ordering::SeqCst);
         let ttl = Duration::from_secs(self.config.ttl);
-        let mut cache = self.embeddings.write().await;
-        let mut evictions = 0;
-        
+        let age = self.current_age();
+        let policy = self.config.eviction_policy;
+
+        let mut policy_evictions: Vec<EvictionPolicy> = Vec::new();
+        let mut remote_writes = Vec::with_capacity(texts.len());
         for (text, embedding) in texts.iter().zip(embeddings.iter()) {
             let key = Self::generate_key(text);
-            let item = CachedItem::new(embedding.clone(), ttl);
-            
-            if cache.put(key, item).is_some() {
-                evictions += 1;
+            let fingerprint = Fingerprint::compute(self.config.fingerprint, text);
+            let item = CachedItem::new(embedding.clone(), ttl, age, fingerprint.clone());
+
+            if let Some(evicted) = insert_with_eviction_policy(&self.embeddings, key, item, policy, age).await {
+                policy_evictions.push(evicted);
             }
+            remote_writes.push((key, embedding.clone(), fingerprint));
         }
-        
-        if evictions > 0 {
+        self.stop_flush.store(false, Ordering::SeqCst);
+
+        if !policy_evictions.is_empty() {
             let mut stats = self.stats.write().await;
-            stats.evictions += evictions;
+            stats.evictions += policy_evictions.len() as u64;
+            for evicted in policy_evictions {
+                bump_policy_eviction(&mut stats, evicted);
+            }
         }
-        
+
+        self.enforce_category_budget_for("embeddings").await;
+
+        if self.remote.is_some() {
+            for (key, embedding, fingerprint) in remote_writes {
+                self.write_remote("embeddings", key, &embedding, &fingerprint, ttl).await;
+            }
+        }
+
         Ok(())
     }
-    
+
     /// Cache reranking results
     pub async fn cache_reranking(&self, query: &str, documents: &[String], scores: &[f32]) -> RagResult<()> {
         if !self.config.enabled || !self.config.cache_reranking {
             return Ok(());
         }
-        
+
         let mut combined = vec![query.to_string()];
         combined.extend(documents.iter().cloned());
-        
+
         let key = Self::generate_key_multi(&combined);
-        let item = CachedItem::new(scores.to_vec(), Duration::from_secs(self.config.ttl));
-        
-        let mut cache = self.reranking.write().await;
-        if cache.put(key, item).is_some() {
+        let fingerprint = Fingerprint::compute(self.config.fingerprint, &Self::fingerprint_input_multi(&combined));
+        let ttl = Duration::from_secs(self.config.ttl);
+        let item = CachedItem::new(scores.to_vec(), ttl, self.current_age(), fingerprint.clone());
+
+        if let Some(policy) =
+            insert_with_eviction_policy(&self.reranking, key, item, self.config.eviction_policy, self.current_age()).await
+        {
             let mut stats = self.stats.write().await;
             stats.evictions += 1;
+            bump_policy_eviction(&mut stats, policy);
         }
-        
+        self.enforce_category_budget_for("reranking").await;
+
+        self.write_remote("reranking", key, &scores.to_vec(), &fingerprint, ttl).await;
+
         Ok(())
     }
-    
-    /// Get cached reranking results
+
+    /// Get cached reranking results, rehydrating from the disk tier on
+    /// an in-memory miss
     pub async fn get_reranking(&self, query: &str, documents: &[String]) -> Option<Vec<f32>> {
         if !self.config.enabled || !self.config.cache_reranking {
             return None;
         }
-        
+
         let mut combined = vec![query.to_string()];
         combined.extend(documents.iter().cloned());
-        
         let key = Self::generate_key_multi(&combined);
-        let mut cache = self.reranking.write().await;
-        let mut stats = self.stats.write().await;
-        
-        match cache.get(&key) {
-            Some(item) => {
-                if item.is_expired() {
-                    cache.pop(&key);
-                    stats.reranking_misses += 1;
-                    None
-                } else {
-                    stats.reranking_hits += 1;
-                    Some(item.value.clone())
-                }
-            }
-            None => {
-                stats.reranking_misses += 1;
-                None
-            }
+        let fingerprint = Fingerprint::compute(self.config.fingerprint, &Self::fingerprint_input_multi(&combined));
+
+        self.get_cached(&self.reranking, "reranking", key, &fingerprint).await
+    }
+
+    /// Like `get_reranking`, but under
+    /// `CacheConfig::stale_while_revalidate_ratio` a sufficiently stale hit
+    /// is served immediately while `recompute` refreshes it in the
+    /// background instead of the caller blocking on recomputing it inline
+    pub async fn get_reranking_or_refresh<F, Fut>(&self, query: &str, documents: &[String], recompute: F) -> Option<Vec<f32>>
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = RagResult<Vec<f32>>> + Send + 'static,
+    {
+        if !self.config.enabled || !self.config.cache_reranking {
+            return None;
         }
+
+        let mut combined = vec![query.to_string()];
+        combined.extend(documents.iter().cloned());
+        let key = Self::generate_key_multi(&combined);
+        let fingerprint = Fingerprint::compute(self.config.fingerprint, &Self::fingerprint_input_multi(&combined));
+
+        self.get_cached_with_refresh(&self.reranking, "reranking", key, &fingerprint, recompute).await
     }
-    
+
     /// Cache retrieval results
     pub async fn cache_retrieval(&self, query: &str, results: &[RetrievalResult]) -> RagResult<()> {
         if !self.config.enabled || !self.config.cache_retrieval {
             return Ok(());
         }
-        
+
         let key = Self::generate_key(query);
-        let item = CachedItem::new(results.to_vec(), Duration::from_secs(self.config.ttl));
-        
-        let mut cache = self.retrieval.write().await;
-        if cache.put(key, item).is_some() {
+        let fingerprint = Fingerprint::compute(self.config.fingerprint, query);
+        let ttl = Duration::from_secs(self.config.ttl);
+        let item = CachedItem::new(results.to_vec(), ttl, self.current_age(), fingerprint.clone());
+
+        if let Some(policy) =
+            insert_with_eviction_policy(&self.retrieval, key, item, self.config.eviction_policy, self.current_age()).await
+        {
             let mut stats = self.stats.write().await;
             stats.evictions += 1;
+            bump_policy_eviction(&mut stats, policy);
         }
-        
+        self.enforce_category_budget_for("retrieval").await;
+
+        self.write_remote("retrieval", key, &results.to_vec(), &fingerprint, ttl).await;
+
         Ok(())
     }
-    
-    /// Get cached retrieval results
+
+    /// Get cached retrieval results, rehydrating from the disk tier on
+    /// an in-memory miss
     pub async fn get_retrieval(&self, query: &str) -> Option<Vec<RetrievalResult>> {
         if !self.config.enabled || !self.config.cache_retrieval {
             return None;
         }
-        
+
+        let key = Self::generate_key(query);
+        let fingerprint = Fingerprint::compute(self.config.fingerprint, query);
+        self.get_cached(&self.retrieval, "retrieval", key, &fingerprint).await
+    }
+
+    /// Like `get_retrieval`, but under
+    /// `CacheConfig::stale_while_revalidate_ratio` a sufficiently stale hit
+    /// is served immediately while `recompute` refreshes it in the
+    /// background instead of the caller blocking on recomputing it inline
+    pub async fn get_retrieval_or_refresh<F, Fut>(&self, query: &str, recompute: F) -> Option<Vec<RetrievalResult>>
+    where
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = RagResult<Vec<RetrievalResult>>> + Send + 'static,
+    {
+        if !self.config.enabled || !self.config.cache_retrieval {
+            return None;
+        }
+
         let key = Self::generate_key(query);
-        let mut cache = self.retrieval.write().await;
+        let fingerprint = Fingerprint::compute(self.config.fingerprint, query);
+        self.get_cached_with_refresh(&self.retrieval, "retrieval", key, &fingerprint, recompute).await
+    }
+
+    /// Look up the closest previously-cached query embedding and return its
+    /// stored retrieval results if the cosine similarity exceeds
+    /// `CacheConfig::semantic_similarity_threshold`. This is what lets a
+    /// paraphrased repeat question hit the cache even though its exact text
+    /// never matches `get_retrieval`'s hash key.
+    pub async fn get_semantic_retrieval(&self, query_embedding: &Embedding) -> Option<Vec<RetrievalResult>> {
+        if !self.config.enabled || !self.config.semantic_cache_enabled {
+            return None;
+        }
+
+        let cache = self.semantic_retrieval.read().await;
+        let best = cache
+            .iter()
+            .filter(|entry| !entry.is_expired())
+            .map(|entry| (EmbeddingClient::cosine_similarity(query_embedding, &entry.embedding), entry))
+            .filter(|(similarity, _)| *similarity >= self.config.semantic_similarity_threshold)
+            .max_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
         let mut stats = self.stats.write().await;
-        
-        match cache.get(&key) {
-            Some(item) => {
-                if item.is_expired() {
-                    cache.pop(&key);
-                    stats.retrieval_misses += 1;
-                    None
-                } else {
-                    stats.retrieval_hits += 1;
-                    Some(item.value.clone())
-                }
+        match best {
+            Some((_, entry)) => {
+                stats.semantic_hits += 1;
+                Some(entry.results.clone())
             }
             None => {
-                stats.retrieval_misses += 1;
+                stats.semantic_misses += 1;
                 None
             }
         }
     }
-    
-    /// Clear all caches
+
+    /// Insert `query_embedding` -> `results` into the semantic cache,
+    /// evicting the oldest entry once `CacheConfig::semantic_cache_max_size`
+    /// is exceeded.
+    pub async fn cache_semantic_retrieval(&self, query_embedding: Embedding, results: &[RetrievalResult]) -> RagResult<()> {
+        if !self.config.enabled || !self.config.semantic_cache_enabled {
+            return Ok(());
+        }
+
+        if self.config.semantic_cache_max_size == 0 {
+            return Err(RagError::cache("semantic_cache_max_size must be greater than 0"));
+        }
+
+        let mut cache = self.semantic_retrieval.write().await;
+        let mut evicted = false;
+        while cache.len() >= self.config.semantic_cache_max_size {
+            cache.pop_front();
+            evicted = true;
+        }
+
+        cache.push_back(SemanticCacheEntry {
+            embedding: query_embedding,
+            results: results.to_vec(),
+            created_at: Instant::now(),
+            ttl: Duration::from_secs(self.config.ttl),
+        });
+        drop(cache);
+
+        if evicted {
+            let mut stats = self.stats.write().await;
+            stats.evictions += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Shared `get_*` path: check the in-memory tier first, then fall back
+    /// to rehydrating from disk, then the remote tier if one is configured.
+    /// Every tier treats a fingerprint mismatch (a `CacheKey` collision
+    /// between the lookup input and whatever's actually stored under
+    /// `key`) as a miss rather than returning it; the in-memory and disk
+    /// tiers also evict the colliding entry, the remote tier leaves it in
+    /// place. A disk or remote hit is re-inserted into memory and
+    /// counted as both a hit and a rehydration; a miss in every tier is a
+    /// plain miss. `kind` selects which `CacheStats` counters to bump and
+    /// names the disk tier's Sled tree / the remote tier's namespace.
+    async fn get_cached<T>(
+        &self,
+        cache: &Arc<RwLock<LruCache<CacheKey, CachedItem<T>>>>,
+        kind: &str,
+        key: CacheKey,
+        expected_fingerprint: &Fingerprint,
+    ) -> Option<T>
+    where
+        T: Clone + Serialize + DeserializeOwned,
+    {
+        let mut guard = cache.write().await;
+        if let Some(item) = guard.get_mut(&key) {
+            if item.is_expired() || &item.fingerprint != expected_fingerprint {
+                guard.pop(&key);
+            } else {
+                item.touch(self.current_age());
+                let value = item.value.clone();
+                self.record_hit(kind).await;
+                return Some(value);
+            }
+        }
+        drop(guard);
+
+        let age = self.current_age();
+        if let Some(item) = rehydrate_from_disk::<T>(&self.disk, kind, key, age, expected_fingerprint) {
+            let value = item.value.clone();
+            cache.write().await.put(key, item);
+            let mut stats = self.stats.write().await;
+            stats.disk_hits += 1;
+            stats.rehydrations += 1;
+            bump_hit(&mut stats, kind);
+            return Some(value);
+        }
+
+        if let Some((value, tier)) = self.rehydrate_from_remote::<T>(kind, key, expected_fingerprint).await {
+            cache.write().await.put(key, CachedItem::new(value.clone(), Duration::from_secs(self.config.ttl), age, expected_fingerprint.clone()));
+            let mut stats = self.stats.write().await;
+            // Only a genuine Redis round trip counts as a `remote_hits`;
+            // `LayeredBackend`'s own local-memory layer answering is
+            // indistinguishable from any other cache hit to the caller.
+            if tier == BackendTier::Redis {
+                stats.remote_hits += 1;
+            }
+            stats.rehydrations += 1;
+            bump_hit(&mut stats, kind);
+            return Some(value);
+        }
+
+        let mut stats = self.stats.write().await;
+        if self.remote.is_some() {
+            stats.remote_misses += 1;
+        }
+        bump_miss(&mut stats, kind);
+        None
+    }
+
+    async fn record_hit(&self, kind: &str) {
+        let mut stats = self.stats.write().await;
+        bump_hit(&mut stats, kind);
+    }
+
+    /// Stale-while-revalidate wrapper around `get_cached`: on a hit whose
+    /// age has consumed more than `CacheConfig::stale_while_revalidate_ratio`
+    /// of its `ttl` but hasn't yet expired, the stale value is returned
+    /// immediately and `recompute` is spawned in the background to replace
+    /// the entry in place. `CachedItem::refreshing` is the per-key
+    /// in-flight guard: a second accessor arriving while a refresh is
+    /// already running is coalesced into it rather than launching a
+    /// duplicate. A plain passthrough to `get_cached` when
+    /// `CacheConfig::stale_while_revalidate_ratio` is `None` or the hit
+    /// isn't stale enough yet.
+    async fn get_cached_with_refresh<T, F, Fut>(
+        &self,
+        cache: &Arc<RwLock<LruCache<CacheKey, CachedItem<T>>>>,
+        kind: &str,
+        key: CacheKey,
+        expected_fingerprint: &Fingerprint,
+        recompute: F,
+    ) -> Option<T>
+    where
+        T: Clone + Serialize + DeserializeOwned + Send + Sync + 'static,
+        F: FnOnce() -> Fut + Send + 'static,
+        Fut: Future<Output = RagResult<T>> + Send + 'static,
+    {
+        let value = self.get_cached(cache, kind, key, expected_fingerprint).await?;
+
+        let Some(ratio) = self.config.stale_while_revalidate_ratio else {
+            return Some(value);
+        };
+
+        let guard = cache.read().await;
+        let Some(item) = guard.peek(&key) else {
+            return Some(value);
+        };
+        if item.is_expired() || item.ttl_ratio_elapsed() < ratio {
+            return Some(value);
+        }
+        let refreshing = item.refreshing.clone();
+        drop(guard);
+
+        if refreshing.swap(true, Ordering::SeqCst) {
+            self.stats.write().await.refresh_coalesced += 1;
+            return Some(value);
+        }
+
+        let cache = cache.clone();
+        let stats = self.stats.clone();
+        let ttl = Duration::from_secs(self.config.ttl);
+        let age = self.current_age();
+        let fingerprint = expected_fingerprint.clone();
+
+        tokio::spawn(async move {
+            if let Ok(fresh) = recompute().await {
+                cache.write().await.put(key, CachedItem::new(fresh, ttl, age, fingerprint));
+                stats.write().await.background_refreshes += 1;
+            }
+            refreshing.store(false, Ordering::SeqCst);
+        });
+
+        Some(value)
+    }
+
+    /// Look up `key` in the remote tier, if one is configured, returning
+    /// the value along with which tier of it actually answered. A hit
+    /// whose stored fingerprint doesn't match `expected` is a `CacheKey`
+    /// collision and is treated as a miss, same as the in-memory and disk
+    /// tiers.
+    async fn rehydrate_from_remote<T: DeserializeOwned>(
+        &self,
+        kind: &str,
+        key: CacheKey,
+        expected: &Fingerprint,
+    ) -> Option<(T, BackendTier)> {
+        let remote = self.remote.as_ref()?;
+        let hit = remote.get(kind, key).await?;
+        let payload: RemotePayload<T> = serde_json::from_slice(&hit.bytes).ok()?;
+
+        if &payload.fingerprint != expected {
+            return None;
+        }
+
+        Some((payload.value, hit.tier))
+    }
+
+    /// Write `value` to the remote tier, if one is configured. Best-effort:
+    /// a remote write failure never fails the surrounding `cache_*` call,
+    /// since the value is already safely cached locally.
+    async fn write_remote<T: Serialize>(&self, kind: &str, key: CacheKey, value: &T, fingerprint: &Fingerprint, ttl: Duration) {
+        let Some(remote) = &self.remote else { return };
+        let payload = RemotePayload { value, fingerprint: fingerprint.clone() };
+        let Ok(bytes) = serde_json::to_vec(&payload) else { return };
+        if let Err(e) = remote.put(kind, key, bytes, ttl).await {
+            warn!("failed to write {} entry to remote cache tier: {}", kind, e);
+        }
+    }
+
+    /// Clear all caches. Holds off the background flush task for the
+    /// duration so it can't race a clear with an in-flight flush. Does not
+    /// reach into the remote tier — `CacheBackend` has no bulk-delete
+    /// operation, so any entries this node wrote there age out on their
+    /// own via their Redis key TTL instead.
     pub async fn clear(&self) -> RagResult<()> {
+        self.stop_flush.store(true, Ordering::SeqCst);
+
         let mut embeddings = self.embeddings.write().await;
         let mut reranking = self.reranking.write().await;
         let mut retrieval = self.retrieval.write().await;
-        
+        let mut semantic_retrieval = self.semantic_retrieval.write().await;
+
         embeddings.clear();
         reranking.clear();
         retrieval.clear();
-        
+        semantic_retrieval.clear();
+        drop(embeddings);
+        drop(reranking);
+        drop(retrieval);
+        drop(semantic_retrieval);
+
+        if let Some(disk) = &self.disk {
+            for tree_name in ["embeddings", "reranking", "retrieval"] {
+                if let Ok(tree) = disk.open_tree(tree_name) {
+                    let _ = tree.clear();
+                }
+            }
+        }
+
+        self.stop_flush.store(false, Ordering::SeqCst);
+
         // Reset stats
         let mut stats = self.stats.write().await;
         *stats = CacheStats::default();
-        
+
         Ok(())
     }
-    
+
+    /// Clear only one kind of cache: `"embedding"`/`"embeddings"`,
+    /// `"reranking"`, `"retrieval"`, or `"semantic"`. Unlike `clear()`, leaves the other
+    /// kinds and the overall stats untouched. Errors on an unrecognized
+    /// kind.
+    pub async fn clear_kind(&self, kind: &str) -> RagResult<()> {
+        if kind == "semantic" {
+            self.semantic_retrieval.write().await.clear();
+            return Ok(());
+        }
+
+        let tree_name = match kind {
+            "embedding" | "embeddings" => "embeddings",
+            "reranking" => "reranking",
+            "retrieval" => "retrieval",
+            other => return Err(RagError::cache(format!("unknown cache kind: {}", other))),
+        };
+
+        self.stop_flush.store(true, Ordering::SeqCst);
+
+        match tree_name {
+            "embeddings" => self.embeddings.write().await.clear(),
+            "reranking" => self.reranking.write().await.clear(),
+            "retrieval" => self.retrieval.write().await.clear(),
+            _ => unreachable!(),
+        }
+
+        if let Some(disk) = &self.disk {
+            if let Ok(tree) = disk.open_tree(tree_name) {
+                let _ = tree.clear();
+            }
+        }
+
+        self.stop_flush.store(false, Ordering::SeqCst);
+
+        Ok(())
+    }
+
+
     /// Get cache statistics
     pub async fn get_stats(&self) -> CacheStats {
         self.stats.read().await.clone()
@@ -348,9 +1604,14 @@ impl RagCache {
         let embeddings = self.embeddings.read().await;
         let reranking = self.reranking.read().await;
         let retrieval = self.retrieval.read().await;
-        
+
         (embeddings.len(), reranking.len(), retrieval.len())
     }
+
+    /// Number of entries currently held in the semantic retrieval cache
+    pub async fn semantic_cache_size(&self) -> usize {
+        self.semantic_retrieval.read().await.len()
+    }
     
     /// Cleanup expired items
     pub async fn cleanup_expired(&self) -> RagResult<usize> {
@@ -397,9 +1658,206 @@ impl RagCache {
                 total_removed += 1;
             }
         }
-        
+
+        // Cleanup the semantic cache
+        {
+            let mut cache = self.semantic_retrieval.write().await;
+            let before = cache.len();
+            cache.retain(|entry| !entry.is_expired());
+            total_removed += before - cache.len();
+        }
+
         Ok(total_removed)
     }
+
+    /// Render `CacheStats` and the in-memory tier's sizes as Prometheus
+    /// text exposition format, so operators can scrape cache health the
+    /// same way they'd scrape any other service instead of having to
+    /// poll `get_stats()`/`get_sizes()` from application code.
+    pub async fn render_metrics(&self) -> String {
+        let stats = self.get_stats().await;
+        let (embeddings, reranking, retrieval) = self.get_sizes().await;
+        let semantic_retrieval_size = self.semantic_cache_size().await;
+
+        let mut out = String::new();
+
+        push_counter(&mut out, "rag_cache_embedding_hits_total", "Total embedding cache hits", stats.embedding_hits);
+        push_counter(&mut out, "rag_cache_embedding_misses_total", "Total embedding cache misses", stats.embedding_misses);
+        push_counter(&mut out, "rag_cache_reranking_hits_total", "Total reranking cache hits", stats.reranking_hits);
+        push_counter(&mut out, "rag_cache_reranking_misses_total", "Total reranking cache misses", stats.reranking_misses);
+        push_counter(&mut out, "rag_cache_retrieval_hits_total", "Total retrieval cache hits", stats.retrieval_hits);
+        push_counter(&mut out, "rag_cache_retrieval_misses_total", "Total retrieval cache misses", stats.retrieval_misses);
+        push_counter(&mut out, "rag_cache_semantic_hits_total", "Total semantic (embedding-similarity) retrieval cache hits", stats.semantic_hits);
+        push_counter(&mut out, "rag_cache_semantic_misses_total", "Total semantic (embedding-similarity) retrieval cache misses", stats.semantic_misses);
+        push_counter(&mut out, "rag_cache_evictions_total", "Total entries evicted from the in-memory tier", stats.evictions);
+        push_counter(&mut out, "rag_cache_disk_hits_total", "Total hits served by rehydrating from the disk tier", stats.disk_hits);
+        push_counter(&mut out, "rag_cache_disk_flushes_total", "Total entries the flush task has persisted to the disk tier", stats.disk_flushes);
+        push_counter(&mut out, "rag_cache_rehydrations_total", "Total entries rehydrated from the disk or remote tier back into memory", stats.rehydrations);
+        push_counter(&mut out, "rag_cache_remote_hits_total", "Total hits served by the remote (Redis) tier", stats.remote_hits);
+        push_counter(&mut out, "rag_cache_remote_misses_total", "Total lookups that missed every tier including the remote one", stats.remote_misses);
+        push_counter(&mut out, "rag_cache_pressure_evictions_total", "Total in-memory entries evicted by the memory-pressure task", stats.pressure_evictions);
+        push_counter(&mut out, "rag_cache_embedding_category_evictions_total", "Total embedding entries evicted to stay under their own CacheSizes byte budget", stats.embedding_evictions);
+        push_counter(&mut out, "rag_cache_reranking_category_evictions_total", "Total reranking entries evicted to stay under their own CacheSizes byte budget", stats.reranking_evictions);
+        push_counter(&mut out, "rag_cache_retrieval_category_evictions_total", "Total retrieval entries evicted to stay under their own CacheSizes byte budget", stats.retrieval_evictions);
+        push_counter(&mut out, "rag_cache_background_refreshes_total", "Total entries replaced in place by a stale-while-revalidate background recompute", stats.background_refreshes);
+        push_counter(&mut out, "rag_cache_refresh_coalesced_total", "Total get_*_or_refresh calls coalesced into an already in-flight background refresh", stats.refresh_coalesced);
+        push_counter(&mut out, "rag_cache_lru_evictions_total", "Total evictions decided by the lru crate's own recency ordering", stats.lru_evictions);
+        push_counter(&mut out, "rag_cache_lfu_evictions_total", "Total evictions decided by EvictionPolicy::Lfu", stats.lfu_evictions);
+        push_counter(&mut out, "rag_cache_weighted_score_evictions_total", "Total evictions decided by EvictionPolicy::WeightedScore", stats.weighted_score_evictions);
+
+        push_gauge(&mut out, "rag_cache_embedding_hit_ratio", "Embedding cache hit ratio", stats.embedding_hit_rate());
+        push_gauge(&mut out, "rag_cache_reranking_hit_ratio", "Reranking cache hit ratio", stats.reranking_hit_rate());
+        push_gauge(&mut out, "rag_cache_retrieval_hit_ratio", "Retrieval cache hit ratio", stats.retrieval_hit_rate());
+        push_gauge(&mut out, "rag_cache_semantic_hit_ratio", "Semantic retrieval cache hit ratio", stats.semantic_hit_rate());
+        push_gauge(&mut out, "rag_cache_remote_hit_ratio", "Remote (Redis) tier hit ratio among lookups that reached it", stats.remote_hit_rate());
+        push_gauge(&mut out, "rag_cache_overall_hit_ratio", "Overall cache hit ratio across every kind", stats.overall_hit_rate());
+        push_gauge(&mut out, "rag_cache_current_budget_bytes", "The in-memory tier's current byte budget as chosen by the memory-pressure task", stats.current_budget_bytes as f64);
+        push_gauge(&mut out, "rag_cache_embedding_bytes", "Estimated current byte footprint of the embedding in-memory tier", stats.embedding_bytes as f64);
+        push_gauge(&mut out, "rag_cache_reranking_bytes", "Estimated current byte footprint of the reranking in-memory tier", stats.reranking_bytes as f64);
+        push_gauge(&mut out, "rag_cache_retrieval_bytes", "Estimated current byte footprint of the retrieval in-memory tier", stats.retrieval_bytes as f64);
+
+        out.push_str("# HELP rag_cache_entries Number of entries currently held in the in-memory tier\n");
+        out.push_str("# TYPE rag_cache_entries gauge\n");
+        out.push_str(&format!("rag_cache_entries{{kind=\"embedding\"}} {}\n", embeddings));
+        out.push_str(&format!("rag_cache_entries{{kind=\"reranking\"}} {}\n", reranking));
+        out.push_str(&format!("rag_cache_entries{{kind=\"retrieval\"}} {}\n", retrieval));
+        out.push_str(&format!("rag_cache_entries{{kind=\"semantic\"}} {}\n", semantic_retrieval_size));
+
+        out
+    }
+}
+
+/// Append one Prometheus counter's `HELP`/`TYPE`/sample lines to `out`
+fn push_counter(out: &mut String, name: &str, help: &str, value: u64) {
+    out.push_str(&format!("# HELP {} {}\n# TYPE {} counter\n{} {}\n", name, help, name, name, value));
+}
+
+/// Append one Prometheus gauge's `HELP`/`TYPE`/sample lines to `out`
+fn push_gauge(out: &mut String, name: &str, help: &str, value: f64) {
+    out.push_str(&format!("# HELP {} {}\n# TYPE {} gauge\n{} {}\n", name, help, name, name, value));
+}
+
+/// An axum handler exposing `RagCache::render_metrics` over HTTP, e.g.
+/// `Router::new().route("/metrics", get(cache_metrics_handler)).with_state(cache)`.
+/// Kept behind a feature flag so pulling in axum is opt-in for callers
+/// that already expose their own metrics endpoint.
+#[cfg(feature = "cache-metrics-http")]
+pub mod http {
+    use super::RagCache;
+    use axum::extract::State;
+    use axum::http::{header, StatusCode};
+    use axum::response::IntoResponse;
+    use std::sync::Arc;
+
+    pub async fn cache_metrics_handler(State(cache): State<Arc<RagCache>>) -> impl IntoResponse {
+        (StatusCode::OK, [(header::CONTENT_TYPE, "text/plain; version=0.0.4")], cache.render_metrics().await)
+    }
+}
+
+/// An authenticated admin surface for `RagCache`, following the same
+/// scoped-management-endpoint shape as a storage node's admin router:
+/// `GET /stats`, `GET /sizes`, `POST /clear` (optionally `?kind=`), and
+/// `POST /cleanup`. Every route is gated by `CacheConfig::admin_token` as
+/// a bearer token; a cache with no token configured rejects every
+/// request. Kept behind its own feature flag since it's a heavier surface
+/// than the read-only metrics endpoint in `http` above.
+#[cfg(feature = "cache-admin-http")]
+pub mod admin {
+    use super::RagCache;
+    use axum::extract::{Query, Request, State};
+    use axum::http::{header, StatusCode};
+    use axum::middleware::{self, Next};
+    use axum::response::IntoResponse;
+    use axum::routing::{get, post};
+    use axum::{Json, Router};
+    use serde::Deserialize;
+    use std::sync::Arc;
+
+    /// Build the admin router. Mount it under a path of your choosing,
+    /// e.g. `Router::new().nest("/cache", admin::router(cache))`.
+    pub fn router(cache: Arc<RagCache>) -> Router {
+        Router::new()
+            .route("/stats", get(stats))
+            .route("/sizes", get(sizes))
+            .route("/clear", post(clear))
+            .route("/cleanup", post(cleanup))
+            .layer(middleware::from_fn_with_state(cache.clone(), require_bearer_token))
+            .with_state(cache)
+    }
+
+    async fn require_bearer_token(State(cache): State<Arc<RagCache>>, req: Request, next: Next) -> axum::response::Response {
+        let provided = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+
+        match (&cache.config.admin_token, provided) {
+            (Some(expected), Some(got)) if constant_time_eq(expected.as_bytes(), got.as_bytes()) => {
+                next.run(req).await
+            }
+            _ => (StatusCode::UNAUTHORIZED, "missing or invalid bearer token").into_response(),
+        }
+    }
+
+    /// Compare two byte strings in constant time (no early exit on the
+    /// first mismatching byte), so a request with a wrong bearer token
+    /// can't be timed to learn how many leading bytes it got right.
+    fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+        if a.len() != b.len() {
+            return false;
+        }
+        a.iter().zip(b.iter()).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+    }
+
+    async fn stats(State(cache): State<Arc<RagCache>>) -> impl IntoResponse {
+        Json(cache.get_stats().await)
+    }
+
+    async fn sizes(State(cache): State<Arc<RagCache>>) -> impl IntoResponse {
+        let (embeddings, reranking, retrieval) = cache.get_sizes().await;
+        Json(serde_json::json!({
+            "embeddings": embeddings,
+            "reranking": reranking,
+            "retrieval": retrieval,
+        }))
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct ClearParams {
+        kind: Option<String>,
+    }
+
+    async fn clear(State(cache): State<Arc<RagCache>>, Query(params): Query<ClearParams>) -> impl IntoResponse {
+        let result = match params.kind {
+            Some(kind) => cache.clear_kind(&kind).await,
+            None => cache.clear().await,
+        };
+
+        match result {
+            Ok(()) => StatusCode::NO_CONTENT.into_response(),
+            Err(e) => (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+        }
+    }
+
+    async fn cleanup(State(cache): State<Arc<RagCache>>) -> impl IntoResponse {
+        match cache.cleanup_expired().await {
+            Ok(removed) => Json(serde_json::json!({ "removed": removed })).into_response(),
+            Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::constant_time_eq;
+
+        #[test]
+        fn test_constant_time_eq() {
+            assert!(constant_time_eq(b"secret-token", b"secret-token"));
+            assert!(!constant_time_eq(b"secret-token", b"wrong-token!"));
+            assert!(!constant_time_eq(b"secret-token", b"secret-toke"));
+        }
+    }
 }
 
 #[cfg(test)]
@@ -410,7 +1868,7 @@ mod tests {
     #[tokio::test]
     async fn test_embedding_cache() {
         let config = CacheConfig::default();
-        let cache = RagCache::new(config).unwrap();
+        let cache = RagCache::new(config).await.unwrap();
         
         let text = "test text";
         let embedding = vec![1.0, 2.0, 3.0];
@@ -430,7 +1888,7 @@ mod tests {
     #[tokio::test]
     async fn test_reranking_cache() {
         let config = CacheConfig::default();
-        let cache = RagCache::new(config).unwrap();
+        let cache = RagCache::new(config).await.unwrap();
         
         let query = "test query";
         let documents = vec!["doc1".to_string(), "doc2".to_string()];
@@ -454,7 +1912,7 @@ mod tests {
             ttl: 1, // 1 second TTL
             ..Default::default()
         };
-        let cache = RagCache::new(config).unwrap();
+        let cache = RagCache::new(config).await.unwrap();
         
         let text = "test text";
         let embedding = vec![1.0, 2.0, 3.0];
@@ -475,7 +1933,7 @@ mod tests {
     #[tokio::test]
     async fn test_cache_stats() {
         let config = CacheConfig::default();
-        let cache = RagCache::new(config).unwrap();
+        let cache = RagCache::new(config).await.unwrap();
         
         let text = "test text";
         let embedding = vec![1.0, 2.0, 3.0];
@@ -504,7 +1962,7 @@ mod tests {
             enabled: false,
             ..Default::default()
         };
-        let cache = RagCache::new(config).unwrap();
+        let cache = RagCache::new(config).await.unwrap();
         
         let text = "test text";
         let embedding = vec![1.0, 2.0, 3.0];
@@ -515,4 +1973,444 @@ mod tests {
         // Should not be cached
         assert!(cache.get_embedding(text).await.is_none());
     }
+
+    #[tokio::test]
+    async fn test_disk_tier_flush_and_rehydrate() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config = CacheConfig {
+            disk_tier_enabled: true,
+            disk_path: temp_dir.path().to_path_buf(),
+            age_tick_interval_secs: 1,
+            flush_age: 0, // flush on the very first tick after a touch
+            ..Default::default()
+        };
+        let cache = RagCache::new(config).await.unwrap();
+
+        let text = "test text";
+        let embedding = vec![1.0, 2.0, 3.0];
+        cache.cache_embedding(text, embedding.clone()).await.unwrap();
+
+        // Give the background task time to tick and flush this now-cold entry
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        let (embeddings_in_memory, _, _) = cache.get_sizes().await;
+        assert_eq!(embeddings_in_memory, 0);
+
+        // Still retrievable, rehydrated from disk and counted as a hit
+        let cached = cache.get_embedding(text).await;
+        assert_eq!(cached, Some(embedding));
+
+        let stats = cache.get_stats().await;
+        assert_eq!(stats.disk_flushes, 1);
+        assert_eq!(stats.disk_hits, 1);
+        assert_eq!(stats.rehydrations, 1);
+        assert_eq!(stats.embedding_hits, 1);
+    }
+
+    #[tokio::test]
+    async fn test_clear_wipes_disk_tier() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let config = CacheConfig {
+            disk_tier_enabled: true,
+            disk_path: temp_dir.path().to_path_buf(),
+            age_tick_interval_secs: 1,
+            flush_age: 0,
+            ..Default::default()
+        };
+        let cache = RagCache::new(config).await.unwrap();
+
+        let text = "test text";
+        let embedding = vec![1.0, 2.0, 3.0];
+        cache.cache_embedding(text, embedding).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+        assert!(cache.get_stats().await.disk_flushes > 0);
+
+        cache.clear().await.unwrap();
+
+        // Flushed entry must be gone from disk too, not just memory
+        assert!(cache.get_embedding(text).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_fingerprint_collision_is_treated_as_miss() {
+        let config = CacheConfig::default();
+        let cache = RagCache::new(config).await.unwrap();
+
+        // Simulate a real `CacheKey` collision: two different inputs that
+        // hash to the same key. `generate_key` can't be forced to collide
+        // in a test, so insert directly under a shared key the way a
+        // genuine collision would land.
+        let key = RagCache::generate_key("original text");
+        let fingerprint = Fingerprint::compute(FingerprintMode::Full, "original text");
+        let item = CachedItem::new(vec![1.0, 2.0], Duration::from_secs(60), 0, fingerprint);
+        cache.embeddings.write().await.put(key, item);
+
+        let colliding_fingerprint = Fingerprint::compute(FingerprintMode::Full, "a different text entirely");
+        let hit = cache.get_cached::<Embedding>(&cache.embeddings, "embeddings", key, &colliding_fingerprint).await;
+        assert!(hit.is_none());
+
+        // The colliding entry must be evicted, not left behind for a
+        // future lookup with the right fingerprint to find
+        assert_eq!(cache.get_sizes().await.0, 0);
+    }
+
+    #[tokio::test]
+    async fn test_blake3_fingerprint_mode() {
+        let config = CacheConfig {
+            fingerprint: FingerprintMode::Blake3,
+            ..Default::default()
+        };
+        let cache = RagCache::new(config).await.unwrap();
+
+        let text = "test text";
+        let embedding = vec![1.0, 2.0, 3.0];
+        cache.cache_embedding(text, embedding.clone()).await.unwrap();
+
+        assert_eq!(cache.get_embedding(text).await, Some(embedding));
+        assert!(cache.get_embedding("different text").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_clear_kind_leaves_other_kinds_alone() {
+        let config = CacheConfig::default();
+        let cache = RagCache::new(config).await.unwrap();
+
+        cache.cache_embedding("text", vec![1.0, 2.0]).await.unwrap();
+        cache.cache_retrieval("query", &[]).await.unwrap();
+
+        cache.clear_kind("embedding").await.unwrap();
+
+        assert!(cache.get_embedding("text").await.is_none());
+        assert!(cache.get_retrieval("query").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_clear_kind_rejects_unknown_kind() {
+        let config = CacheConfig::default();
+        let cache = RagCache::new(config).await.unwrap();
+        assert!(cache.clear_kind("bogus").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_render_metrics() {
+        let config = CacheConfig::default();
+        let cache = RagCache::new(config).await.unwrap();
+
+        cache.cache_embedding("test text", vec![1.0, 2.0, 3.0]).await.unwrap();
+        cache.get_embedding("test text").await;
+        cache.get_embedding("missing").await;
+
+        let metrics = cache.render_metrics().await;
+        assert!(metrics.contains("# TYPE rag_cache_embedding_hits_total counter"));
+        assert!(metrics.contains("rag_cache_embedding_hits_total 1"));
+        assert!(metrics.contains("rag_cache_embedding_misses_total 1"));
+        assert!(metrics.contains("rag_cache_entries{kind=\"embedding\"} 1"));
+    }
+
+    #[tokio::test]
+    async fn test_semantic_cache_hits_on_similar_but_not_identical_embedding() {
+        let config = CacheConfig { semantic_similarity_threshold: 0.9, ..Default::default() };
+        let cache = RagCache::new(config).await.unwrap();
+
+        let original = vec![1.0, 0.0, 0.0];
+        let results = vec![RetrievalResult::new(crate::types::Chunk::new(uuid::Uuid::new_v4(), "hi".to_string(), 0, 2, 1), 0.5)];
+        cache.cache_semantic_retrieval(original, &results).await.unwrap();
+
+        // Close enough to the cached embedding to clear the threshold
+        let paraphrase = vec![0.99, 0.01, 0.0];
+        assert!(cache.get_semantic_retrieval(&paraphrase).await.is_some());
+
+        // Too far from the cached embedding
+        let unrelated = vec![0.0, 1.0, 0.0];
+        assert!(cache.get_semantic_retrieval(&unrelated).await.is_none());
+
+        let stats = cache.get_stats().await;
+        assert_eq!(stats.semantic_hits, 1);
+        assert_eq!(stats.semantic_misses, 1);
+    }
+
+    #[tokio::test]
+    async fn test_semantic_cache_evicts_oldest_past_max_size() {
+        let config = CacheConfig { semantic_cache_max_size: 1, semantic_similarity_threshold: 0.99, ..Default::default() };
+        let cache = RagCache::new(config).await.unwrap();
+
+        cache.cache_semantic_retrieval(vec![1.0, 0.0], &[]).await.unwrap();
+        cache.cache_semantic_retrieval(vec![0.0, 1.0], &[]).await.unwrap();
+
+        // The first entry should have been evicted to make room for the second
+        assert!(cache.get_semantic_retrieval(&vec![1.0, 0.0]).await.is_none());
+        assert!(cache.get_semantic_retrieval(&vec![0.0, 1.0]).await.is_some());
+        assert_eq!(cache.get_stats().await.evictions, 1);
+    }
+
+    /// Stands in for `RedisBackend` in tests that don't have a real Redis
+    /// instance to talk to: same byte-store semantics, but every hit is
+    /// tagged `BackendTier::Redis` so `RagCache` counts it as a genuine
+    /// remote round trip rather than `LayeredBackend`'s local-memory layer.
+    struct FakeRemoteBackend {
+        inner: crate::remote_cache::MemoryBackend,
+    }
+
+    impl FakeRemoteBackend {
+        fn new() -> Self {
+            Self { inner: crate::remote_cache::MemoryBackend::new(100) }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl crate::remote_cache::CacheBackend for FakeRemoteBackend {
+        async fn get(&self, kind: &str, key: CacheKey) -> Option<crate::remote_cache::BackendHit> {
+            let mut hit = self.inner.get(kind, key).await?;
+            hit.tier = crate::remote_cache::BackendTier::Redis;
+            Some(hit)
+        }
+
+        async fn put(&self, kind: &str, key: CacheKey, bytes: Vec<u8>, ttl: Duration) -> RagResult<()> {
+            self.inner.put(kind, key, bytes, ttl).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_remote_tier_serves_after_memory_and_disk_miss() {
+        let base = RagCache::new(CacheConfig::default()).await.unwrap();
+        let cache = RagCache { remote: Some(Arc::new(FakeRemoteBackend::new())), ..base };
+
+        let text = "remote test text";
+        let embedding = vec![1.0, 2.0, 3.0];
+
+        // Simulate a peer node having already cached this embedding remotely
+        cache.write_remote(
+            "embeddings",
+            RagCache::generate_key(text),
+            &embedding,
+            &Fingerprint::compute(cache.config.fingerprint, text),
+            Duration::from_secs(60),
+        ).await;
+
+        // This node's own in-memory and disk tiers are empty, so the hit
+        // must come from the remote tier
+        assert_eq!(cache.get_embedding(text).await, Some(embedding));
+
+        let stats = cache.get_stats().await;
+        assert_eq!(stats.remote_hits, 1);
+        assert_eq!(stats.embedding_hits, 1);
+
+        // A second lookup is served by the in-memory tier the first lookup
+        // rehydrated into, so it doesn't count as another remote hit
+        cache.get_embedding(text).await;
+        assert_eq!(cache.get_stats().await.remote_hits, 1);
+    }
+
+    #[tokio::test]
+    async fn test_enforce_memory_budget_evicts_stalest_first() {
+        let config = CacheConfig::default();
+        let cache = RagCache::new(config).await.unwrap();
+
+        cache.cache_embedding("oldest", vec![1.0; 32]).await.unwrap();
+        cache.embeddings.write().await.get_mut(&RagCache::generate_key("oldest")).unwrap().last_touched_age = 0;
+        cache.cache_embedding("newest", vec![2.0; 32]).await.unwrap();
+        cache.embeddings.write().await.get_mut(&RagCache::generate_key("newest")).unwrap().last_touched_age = 10;
+
+        // A budget that fits exactly one of the two equal-sized entries, so
+        // evicting the stalest one is enough to get back under it
+        let one_entry_bytes = serde_json::to_vec(&vec![2.0f32; 32]).unwrap().len() as u64;
+        let evicted = enforce_memory_budget(&cache.embeddings, &cache.reranking, &cache.retrieval, one_entry_bytes).await;
+        assert_eq!(evicted, 1);
+
+        // The stalest entry is the one that should have been reclaimed
+        assert!(cache.embeddings.read().await.peek(&RagCache::generate_key("oldest")).is_none());
+        assert!(cache.embeddings.read().await.peek(&RagCache::generate_key("newest")).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_memory_pressure_task_shrinks_budget_under_watermark() {
+        let config = CacheConfig {
+            memory_pressure_enabled: true,
+            memory_pressure_check_interval_secs: 1,
+            // Always "under pressure" regardless of the box running this test
+            memory_pressure_low_watermark_mb: u64::MAX,
+            memory_budget_floor_bytes: 1,
+            memory_budget_ceiling_bytes: 512 * 1024 * 1024,
+            ..Default::default()
+        };
+        let cache = RagCache::new(config).await.unwrap();
+        cache.cache_embedding("text", vec![1.0, 2.0, 3.0]).await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(1500)).await;
+
+        let stats = cache.get_stats().await;
+        assert_eq!(stats.current_budget_bytes, 1);
+        assert!(stats.pressure_evictions > 0);
+        assert_eq!(cache.get_sizes().await.0, 0);
+    }
+
+    #[tokio::test]
+    async fn test_category_byte_budget_evicts_only_its_own_category() {
+        let config = CacheConfig {
+            category_byte_budgets: Some(CacheSizes {
+                embeddings_max_bytes: 1,
+                reranking_max_bytes: u64::MAX,
+                retrieval_max_bytes: u64::MAX,
+            }),
+            ..Default::default()
+        };
+        let cache = RagCache::new(config).await.unwrap();
+
+        cache.cache_embedding("first", vec![1.0; 32]).await.unwrap();
+        cache.cache_embedding("second", vec![2.0; 32]).await.unwrap();
+        cache.cache_retrieval("query", &[]).await.unwrap();
+
+        // The tiny embeddings budget should have forced an eviction, but
+        // retrieval (an unrelated category with an effectively unlimited
+        // budget) must be untouched
+        let stats = cache.get_stats().await;
+        assert!(stats.embedding_evictions > 0);
+        assert_eq!(stats.retrieval_evictions, 0);
+        assert!(cache.get_retrieval("query").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_stale_while_revalidate_refreshes_in_background() {
+        let config = CacheConfig {
+            ttl: 2,
+            stale_while_revalidate_ratio: Some(0.5),
+            ..Default::default()
+        };
+        let cache = RagCache::new(config).await.unwrap();
+        cache.cache_embedding("text", vec![1.0, 2.0]).await.unwrap();
+
+        // Cross the 50% staleness threshold without fully expiring
+        tokio::time::sleep(Duration::from_millis(1200)).await;
+
+        let refreshed = Arc::new(AtomicBool::new(false));
+        let refreshed_clone = refreshed.clone();
+        let value = cache
+            .get_embedding_or_refresh("text", move || async move {
+                refreshed_clone.store(true, Ordering::SeqCst);
+                Ok(vec![9.0, 9.0])
+            })
+            .await;
+
+        // The stale-but-valid value is served immediately; the recompute
+        // above runs in the background
+        assert_eq!(value, Some(vec![1.0, 2.0]));
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(refreshed.load(Ordering::SeqCst));
+        assert_eq!(cache.get_stats().await.background_refreshes, 1);
+        assert_eq!(cache.get_embedding("text").await, Some(vec![9.0, 9.0]));
+    }
+
+    #[tokio::test]
+    async fn test_stale_while_revalidate_coalesces_concurrent_refreshes() {
+        let config = CacheConfig {
+            ttl: 2,
+            stale_while_revalidate_ratio: Some(0.5),
+            ..Default::default()
+        };
+        let cache = RagCache::new(config).await.unwrap();
+        cache.cache_embedding("text", vec![1.0, 2.0]).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(1200)).await;
+
+        let refresh_calls = Arc::new(AtomicU64::new(0));
+        for _ in 0..3 {
+            let refresh_calls = refresh_calls.clone();
+            cache
+                .get_embedding_or_refresh("text", move || async move {
+                    refresh_calls.fetch_add(1, Ordering::SeqCst);
+                    Ok(vec![9.0, 9.0])
+                })
+                .await;
+        }
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        // Only the first call should have actually launched a recompute
+        assert_eq!(refresh_calls.load(Ordering::SeqCst), 1);
+        assert_eq!(cache.get_stats().await.refresh_coalesced, 2);
+    }
+
+    #[tokio::test]
+    async fn test_stats_snapshot_history_and_hit_rate_trend() {
+        let config = CacheConfig {
+            stats_snapshot_interval_secs: Some(1),
+            stats_snapshot_history_size: 2,
+            ..Default::default()
+        };
+        let cache = RagCache::new(config).await.unwrap();
+
+        assert!(cache.snapshot_history().await.is_empty());
+        assert_eq!(cache.hit_rate_trend().await, None);
+
+        // First tick: all misses so far
+        cache.get_embedding("missing").await;
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+
+        // Second tick: now a hit too, so the overall hit rate should have risen
+        cache.cache_embedding("text", vec![1.0]).await.unwrap();
+        cache.get_embedding("text").await;
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+
+        let history = cache.snapshot_history().await;
+        assert_eq!(history.len(), 2);
+        assert!(history[0].timestamp_unix_secs <= history[1].timestamp_unix_secs);
+        assert!(cache.hit_rate_trend().await.unwrap() > 0.0);
+    }
+
+    #[tokio::test]
+    async fn test_memory_report_breaks_down_by_category() {
+        let config = CacheConfig::default();
+        let cache = RagCache::new(config).await.unwrap();
+
+        cache.cache_embedding("text", vec![1.0, 2.0, 3.0]).await.unwrap();
+        cache.cache_retrieval("query", &[]).await.unwrap();
+
+        let report = cache.memory_report().await;
+        assert_eq!(report.embeddings.entries, 1);
+        assert_eq!(report.retrieval.entries, 1);
+        assert_eq!(report.reranking.entries, 0);
+        assert!(report.embeddings.bytes > 0);
+        assert_eq!(report.total_bytes, report.embeddings.bytes + report.reranking.bytes + report.retrieval.bytes);
+    }
+
+    #[tokio::test]
+    async fn test_remote_miss_is_counted() {
+        let base = RagCache::new(CacheConfig::default()).await.unwrap();
+        let cache = RagCache { remote: Some(Arc::new(FakeRemoteBackend::new())), ..base };
+
+        assert!(cache.get_embedding("never cached anywhere").await.is_none());
+
+        let stats = cache.get_stats().await;
+        assert_eq!(stats.remote_misses, 1);
+        assert_eq!(stats.embedding_misses, 1);
+    }
+
+    #[tokio::test]
+    async fn test_lfu_eviction_policy_keeps_the_more_frequently_used_entry() {
+        let config = CacheConfig {
+            max_size: 2,
+            eviction_policy: EvictionPolicy::Lfu,
+            ..Default::default()
+        };
+        let cache = RagCache::new(config).await.unwrap();
+
+        cache.cache_embedding("a", vec![1.0]).await.unwrap();
+        cache.cache_embedding("b", vec![2.0]).await.unwrap();
+
+        // "a" is touched far more often than "b", but "b" is touched more
+        // recently, so a plain LRU policy would evict "a" while Lfu should
+        // keep it and evict "b" instead.
+        for _ in 0..3 {
+            cache.get_embedding("a").await;
+        }
+        cache.get_embedding("b").await;
+
+        cache.cache_embedding("c", vec![3.0]).await.unwrap();
+
+        assert!(cache.get_embedding("a").await.is_some());
+        assert!(cache.get_embedding("b").await.is_none());
+        assert!(cache.get_embedding("c").await.is_some());
+
+        let stats = cache.get_stats().await;
+        assert_eq!(stats.lfu_evictions, 1);
+        assert_eq!(stats.lru_evictions, 0);
+    }
 }
\ No newline at end of file
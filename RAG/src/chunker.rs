@@ -1,18 +1,356 @@
 //! Smart chunking implementation with semantic awareness
 
 use crate::error::{RagError, RagResult};
-use crate::types::{Chunk, ChunkingConfig, DocumentId};
+use crate::types::{Chunk, ChunkingConfig, ChunkingStrategy, DocumentId, SourceLanguage};
 use rayon::prelude::*;
 use std::sync::Arc;
+use std::sync::OnceLock;
 use tokenizers::Tokenizer;
 use unicode_segmentation::UnicodeSegmentation;
 
+/// Which `tokenizers` model to train when building a vocabulary from a
+/// document corpus via `SmartChunker::train_tokenizer`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenizerModelKind {
+    /// Byte-pair encoding, as used by GPT-style models
+    Bpe,
+    /// Unigram language model tokenization, as used by SentencePiece
+    Unigram,
+    /// WordPiece, as used by BERT-style models
+    WordPiece,
+}
+
+/// Number of entries in the FastCDC gear table
+const GEAR_TABLE_SIZE: usize = 256;
+
+/// Precomputed gear table used by FastCDC content-defined chunking.
+/// Entries are pseudo-random u64 values derived once via splitmix64 from a
+/// fixed seed, so chunk boundaries stay stable across process runs.
+fn gear_table() -> &'static [u64; GEAR_TABLE_SIZE] {
+    static TABLE: OnceLock<[u64; GEAR_TABLE_SIZE]> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        let mut table = [0u64; GEAR_TABLE_SIZE];
+        let mut seed: u64 = 0x9E3779B97F4A7C15;
+        for entry in table.iter_mut() {
+            seed = seed.wrapping_add(0x9E3779B97F4A7C15);
+            let mut z = seed;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+            z ^= z >> 31;
+            *entry = z;
+        }
+        table
+    })
+}
+
+/// A tokenization job sent to the background validation worker pool
+struct ValidationTask {
+    tokenizer: Arc<Tokenizer>,
+    text: String,
+    respond_to: tokio::sync::oneshot::Sender<RagResult<usize>>,
+}
+
+/// Number of worker threads in the background validation pool
+const VALIDATION_POOL_WORKERS: usize = 4;
+
+/// Bounded channel into the background token-counting worker pool used by
+/// `SmartChunker::validate_document`, so validating many large documents
+/// doesn't block the async executor
+fn validation_pool() -> &'static std::sync::mpsc::SyncSender<ValidationTask> {
+    static POOL: OnceLock<std::sync::mpsc::SyncSender<ValidationTask>> = OnceLock::new();
+    POOL.get_or_init(|| {
+        let (tx, rx) = std::sync::mpsc::sync_channel::<ValidationTask>(64);
+        let rx = Arc::new(std::sync::Mutex::new(rx));
+
+        for _ in 0..VALIDATION_POOL_WORKERS {
+            let rx = Arc::clone(&rx);
+            std::thread::spawn(move || loop {
+                let task = {
+                    let rx = rx.lock().expect("validation pool receiver poisoned");
+                    rx.recv()
+                };
+                let Ok(ValidationTask { tokenizer, text, respond_to }) = task else {
+                    break;
+                };
+
+                let result = tokenizer
+                    .encode(text.as_str(), false)
+                    .map(|encoding| encoding.len())
+                    .map_err(|e| RagError::tokenization(format!("Failed to tokenize text: {}", e)));
+                let _ = respond_to.send(result);
+            });
+        }
+
+        tx
+    })
+}
+
+/// Compute FastCDC chunk boundaries (exclusive end offsets, in bytes) for
+/// `content` using normalized chunking: `mask_s` (more 1-bits) applies in the
+/// region before `avg_size`, `mask_l` (fewer 1-bits) after it.
+fn fastcdc_boundaries(content: &[u8], min_size: usize, avg_size: usize, max_size: usize) -> Vec<usize> {
+    if content.is_empty() {
+        return Vec::new();
+    }
+
+    let gear = gear_table();
+    let bits = (avg_size.max(1) as f64).log2().round() as u32;
+    let mask_s = (1u64 << (bits + 1).min(63)) - 1;
+    let mask_l = (1u64 << bits.saturating_sub(1)) - 1;
+
+    let mut boundaries = Vec::new();
+    let mut start = 0usize;
+    let mut fp: u64 = 0;
+
+    for i in 0..content.len() {
+        let chunk_len = i - start + 1;
+        fp = (fp << 1).wrapping_add(gear[content[i] as usize]);
+
+        if chunk_len < min_size {
+            continue;
+        }
+
+        let cut = if chunk_len >= max_size {
+            true
+        } else if chunk_len < avg_size {
+            fp & mask_s == 0
+        } else {
+            fp & mask_l == 0
+        };
+
+        if cut {
+            boundaries.push(i + 1);
+            start = i + 1;
+            fp = 0;
+        }
+    }
+
+    if start < content.len() {
+        boundaries.push(content.len());
+    }
+
+    boundaries
+}
+
+/// Map a `SourceLanguage` to its tree-sitter grammar, or `None` if this
+/// build has no grammar compiled in for it
+fn tree_sitter_language(language: SourceLanguage) -> Option<tree_sitter::Language> {
+    match language {
+        SourceLanguage::Rust => Some(tree_sitter_rust::LANGUAGE.into()),
+        SourceLanguage::Python => Some(tree_sitter_python::LANGUAGE.into()),
+        SourceLanguage::JavaScript => Some(tree_sitter_javascript::LANGUAGE.into()),
+        SourceLanguage::TypeScript => Some(tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into()),
+    }
+}
+
+/// Phrase-chunk tag in the OpenNLP-style `B-`/`I-`/`O` scheme: `B-X` opens a
+/// phrase of type `X`, `I-X` continues it, and `O` is outside any phrase
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum PhraseTag {
+    O,
+    BNp,
+    INp,
+    BVp,
+    IVp,
+}
+
+impl PhraseTag {
+    const ALL: [PhraseTag; 5] = [PhraseTag::O, PhraseTag::BNp, PhraseTag::INp, PhraseTag::BVp, PhraseTag::IVp];
+
+    /// Whether this tag may legally follow `prev`: an `I-X` may only follow
+    /// `B-X` or another `I-X` of the same phrase type
+    fn can_follow(self, prev: PhraseTag) -> bool {
+        match self {
+            PhraseTag::INp => matches!(prev, PhraseTag::BNp | PhraseTag::INp),
+            PhraseTag::IVp => matches!(prev, PhraseTag::BVp | PhraseTag::IVp),
+            _ => true,
+        }
+    }
+
+    /// Whether a phrase ends right after this tag, i.e. `next` isn't an
+    /// `I-X` continuing the same phrase `self` started or is part of
+    fn ends_phrase_before(self, next: Option<PhraseTag>) -> bool {
+        match next {
+            None => true,
+            Some(PhraseTag::INp) => !matches!(self, PhraseTag::BNp | PhraseTag::INp),
+            Some(PhraseTag::IVp) => !matches!(self, PhraseTag::BVp | PhraseTag::IVp),
+            Some(_) => true,
+        }
+    }
+}
+
+/// Lightweight feature-scored linear model over shallow word shape
+/// features, producing a raw score per `PhraseTag::ALL` entry for `word`.
+/// This is a heuristic stand-in for a trained maxent model: it is not
+/// learned from data, but it is scored and beam-searched exactly the way a
+/// trained one would be.
+fn score_phrase_outcomes(word: &str) -> [f64; 5] {
+    let starts_upper = word.chars().next().is_some_and(char::is_uppercase);
+    let is_punct = !word.is_empty() && word.chars().all(|c| c.is_ascii_punctuation());
+    let is_determiner = matches!(
+        word.to_lowercase().as_str(),
+        "the" | "a" | "an" | "this" | "that" | "these" | "those" | "my" | "your" | "our"
+    );
+    let is_verb_like = word.ends_with("ing") || word.ends_with("ed") || word.ends_with("s");
+
+    // [O, B-NP, I-NP, B-VP, I-VP]
+    [
+        if is_punct { 2.0 } else { 0.2 },
+        if is_determiner || starts_upper { 1.5 } else { 0.5 },
+        0.6,
+        if is_verb_like && !is_determiner { 1.4 } else { 0.1 },
+        0.4,
+    ]
+}
+
+/// Normalize raw scores into a probability distribution
+fn softmax(scores: &[f64]) -> Vec<f64> {
+    let max = scores.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+    let exps: Vec<f64> = scores.iter().map(|s| (s - max).exp()).collect();
+    let sum: f64 = exps.iter().sum();
+    exps.into_iter().map(|e| e / sum).collect()
+}
+
+/// A partial tag sequence under construction during beam search, ordered by
+/// cumulative log-probability so a `BinaryHeap` pops the most likely first
+#[derive(Debug, Clone)]
+struct PhraseSequence {
+    tags: Vec<PhraseTag>,
+    log_prob: f64,
+}
+
+impl PartialEq for PhraseSequence {
+    fn eq(&self, other: &Self) -> bool {
+        self.log_prob == other.log_prob
+    }
+}
+impl Eq for PhraseSequence {}
+impl PartialOrd for PhraseSequence {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for PhraseSequence {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.log_prob.partial_cmp(&other.log_prob).unwrap_or(std::cmp::Ordering::Equal)
+    }
+}
+
+/// Number of candidate sequences kept alive at each beam search step
+const PHRASE_BEAM_WIDTH: usize = 8;
+
+/// Tag `words` with phrase-chunk boundaries via beam search: at each word,
+/// every surviving sequence is expanded with each admissible next outcome
+/// (respecting `PhraseTag::can_follow`), scored by `softmax` over
+/// `score_phrase_outcomes`, and the beam is pruned back to
+/// `PHRASE_BEAM_WIDTH` by cumulative log-probability. Returns the best
+/// full-length tag sequence.
+fn beam_search_phrase_tags(words: &[&str]) -> Vec<PhraseTag> {
+    let mut beam: std::collections::BinaryHeap<PhraseSequence> = std::collections::BinaryHeap::new();
+    beam.push(PhraseSequence { tags: Vec::new(), log_prob: 0.0 });
+
+    for word in words {
+        let probs = softmax(&score_phrase_outcomes(word));
+
+        let mut candidates: Vec<PhraseSequence> = Vec::new();
+        while let Some(seq) = beam.pop() {
+            candidates.push(seq);
+        }
+
+        let mut expanded: std::collections::BinaryHeap<PhraseSequence> = std::collections::BinaryHeap::new();
+        for seq in &candidates {
+            let prev_tag = seq.tags.last().copied();
+            for (&tag, &prob) in PhraseTag::ALL.iter().zip(probs.iter()) {
+                if let Some(prev) = prev_tag {
+                    if !tag.can_follow(prev) {
+                        continue;
+                    }
+                }
+                let mut tags = seq.tags.clone();
+                tags.push(tag);
+                expanded.push(PhraseSequence { tags, log_prob: seq.log_prob + prob.ln() });
+            }
+        }
+
+        beam = std::collections::BinaryHeap::new();
+        for _ in 0..PHRASE_BEAM_WIDTH {
+            match expanded.pop() {
+                Some(seq) => beam.push(seq),
+                None => break,
+            }
+        }
+    }
+
+    beam.pop().map(|seq| seq.tags).unwrap_or_default()
+}
+
+/// Number of named ancestor nodes enclosing `byte_offset` in `tree`;
+/// lower means the offset sits closer to the top level of the file
+fn named_depth_at(tree: &tree_sitter::Tree, byte_offset: usize) -> usize {
+    let Some(mut node) = tree.root_node().descendant_for_byte_range(byte_offset, byte_offset) else {
+        return 0;
+    };
+
+    let mut depth = 0;
+    while let Some(parent) = node.parent() {
+        if node.is_named() {
+            depth += 1;
+        }
+        node = parent;
+    }
+    depth
+}
+
+/// Tree-sitter node kinds considered "outline" items (functions, classes,
+/// methods) for `language`, used to build a chunk's enclosing scope path
+fn outline_node_kinds(language: SourceLanguage) -> &'static [&'static str] {
+    match language {
+        SourceLanguage::Rust => &["function_item", "impl_item", "struct_item", "enum_item", "trait_item", "mod_item"],
+        SourceLanguage::Python => &["function_definition", "class_definition"],
+        SourceLanguage::JavaScript | SourceLanguage::TypeScript => {
+            &["function_declaration", "class_declaration", "method_definition"]
+        }
+    }
+}
+
+/// The enclosing scope path at `byte_offset` (e.g. `ClassFoo::method_bar`),
+/// built by walking from the node at that offset up to the root and
+/// collecting the names of any enclosing outline nodes, outermost first.
+/// Returns `None` if `byte_offset` isn't nested in any outline item.
+fn scope_path_at(tree: &tree_sitter::Tree, content: &str, byte_offset: usize, language: SourceLanguage) -> Option<String> {
+    let kinds = outline_node_kinds(language);
+    let mut node = tree.root_node().descendant_for_byte_range(byte_offset, byte_offset)?;
+    let mut names = Vec::new();
+
+    loop {
+        if kinds.contains(&node.kind()) {
+            if let Some(name_node) = node.child_by_field_name("name") {
+                if let Ok(text) = name_node.utf8_text(content.as_bytes()) {
+                    names.push(text.to_string());
+                }
+            }
+        }
+        match node.parent() {
+            Some(parent) => node = parent,
+            None => break,
+        }
+    }
+
+    if names.is_empty() {
+        return None;
+    }
+    names.reverse();
+    Some(names.join("::"))
+}
+
 /// Smart chunker that creates semantically aware chunks
 pub struct SmartChunker {
     /// Tokenizer for counting tokens
     tokenizer: Arc<Tokenizer>,
     /// Chunking configuration
     config: ChunkingConfig,
+    /// Document-level validation enforced by `validate_and_chunk_document`
+    validation: crate::types::ValidationConfig,
 }
 
 impl SmartChunker {
@@ -21,9 +359,17 @@ impl SmartChunker {
         Self {
             tokenizer: Arc::new(tokenizer),
             config,
+            validation: crate::types::ValidationConfig::default(),
         }
     }
-    
+
+    /// Attach document-level validation, enforced by
+    /// `validate_and_chunk_document`
+    pub fn with_validation(mut self, validation: crate::types::ValidationConfig) -> Self {
+        self.validation = validation;
+        self
+    }
+
     /// Create a chunker with default GPT tokenizer
     pub async fn with_default_tokenizer(config: ChunkingConfig) -> RagResult<Self> {
         // Create a simple word-based tokenizer for testing and basic functionality
@@ -74,33 +420,525 @@ impl SmartChunker {
         
         Ok(Self::new(tokenizer, config))
     }
-    
-    /// Chunk a document into semantically aware pieces
+
+    /// Load a serialized Hugging Face `tokenizers` JSON file (BPE, Unigram,
+    /// or WordPiece) instead of the toy vocabulary `with_default_tokenizer`
+    /// builds, so `count_tokens` matches the real embedding model's
+    /// tokenization
+    pub fn from_tokenizer_file(path: impl AsRef<std::path::Path>, config: ChunkingConfig) -> RagResult<Self> {
+        let tokenizer = Tokenizer::from_file(path)
+            .map_err(|e| RagError::tokenization(format!("Failed to load tokenizer file: {}", e)))?;
+
+        Ok(Self::new(tokenizer, config))
+    }
+
+    /// Build a chunker whose `ChunkingConfig` is derived from `model_id`'s
+    /// `EmbeddingModelProfile` (via `ChunkingConfig::for_model`), so chunk
+    /// size, the hard token cap, and embedding concurrency all track the
+    /// target model instead of generic defaults
+    pub async fn with_model_profile(model_id: &str) -> RagResult<Self> {
+        Self::with_default_tokenizer(ChunkingConfig::for_model(model_id)).await
+    }
+
+    /// Train a tokenizer from a document corpus and build a chunker around
+    /// it, so chunk boundaries track a vocabulary fit to the user's own
+    /// domain instead of a generic pretrained one
+    pub fn train_tokenizer<P: AsRef<std::path::Path>>(
+        files: &[P],
+        model_kind: TokenizerModelKind,
+        vocab_size: usize,
+        min_frequency: u32,
+        config: ChunkingConfig,
+    ) -> RagResult<Self> {
+        use tokenizers::models::bpe::{BpeTrainer, BPE};
+        use tokenizers::models::unigram::{Unigram, UnigramTrainer};
+        use tokenizers::models::wordpiece::{WordPiece, WordPieceTrainerBuilder};
+        use tokenizers::pre_tokenizers::whitespace::Whitespace;
+        use tokenizers::TrainerWrapper;
+
+        let paths: Vec<String> = files
+            .iter()
+            .map(|p| p.as_ref().to_string_lossy().into_owned())
+            .collect();
+
+        let mut tokenizer = match model_kind {
+            TokenizerModelKind::Bpe => Tokenizer::new(BPE::default()),
+            TokenizerModelKind::Unigram => Tokenizer::new(Unigram::default()),
+            TokenizerModelKind::WordPiece => Tokenizer::new(WordPiece::default()),
+        };
+        tokenizer.with_pre_tokenizer(Whitespace {});
+
+        let mut trainer: TrainerWrapper = match model_kind {
+            TokenizerModelKind::Bpe => BpeTrainer::builder()
+                .vocab_size(vocab_size)
+                .min_frequency(min_frequency)
+                .build()
+                .into(),
+            TokenizerModelKind::Unigram => UnigramTrainer::builder()
+                .vocab_size(vocab_size as u32)
+                .build()
+                .map_err(|e| RagError::tokenization(format!("Failed to build Unigram trainer: {}", e)))?
+                .into(),
+            TokenizerModelKind::WordPiece => WordPieceTrainerBuilder::default()
+                .vocab_size(vocab_size)
+                .min_frequency(min_frequency)
+                .build()
+                .into(),
+        };
+
+        tokenizer
+            .train_from_files(&mut trainer, paths)
+            .map_err(|e| RagError::tokenization(format!("Failed to train tokenizer: {}", e)))?;
+
+        Ok(Self::new(tokenizer, config))
+    }
+
+    /// Chunk a document into semantically aware pieces. Whatever chunks fall
+    /// out of the configured strategy, any chunk still over
+    /// `config.max_input_tokens` is re-split on sentence boundaries before
+    /// being returned. A single sentence longer than the cap is not split
+    /// further, so a chunk holding one can still exceed it.
     pub async fn chunk_document(&self, document_id: DocumentId, content: &str) -> RagResult<Vec<Chunk>> {
         if content.is_empty() {
             return Ok(Vec::new());
         }
-        
+
+        match self.config.strategy {
+            ChunkingStrategy::ContentDefined { min_size, avg_size, max_size } => {
+                let chunks = self.chunk_document_content_defined(document_id, content, min_size, avg_size, max_size)?;
+                return self.enforce_max_input_tokens(document_id, chunks);
+            }
+            ChunkingStrategy::Syntactic { language } => {
+                if let Some(chunks) = self.chunk_document_syntactic(document_id, content, language)? {
+                    return self.enforce_max_input_tokens(document_id, chunks);
+                }
+                // No grammar available for `language`; fall through to
+                // the paragraph-based path below
+            }
+            ChunkingStrategy::Sentence => {
+                let chunks = self.chunk_document_sentence_balanced(document_id, content)?;
+                return self.enforce_max_input_tokens(document_id, chunks);
+            }
+            ChunkingStrategy::Fixed => {}
+        }
+
         // First, split by paragraphs to maintain semantic boundaries
         let paragraphs: Vec<&str> = content
             .split("\n\n")
             .filter(|p| !p.trim().is_empty())
             .collect();
-        
+
         if paragraphs.is_empty() {
             return Ok(Vec::new());
         }
-        
+
         // Process paragraphs in parallel if enabled
         let chunks = if self.config.chunk_size > 1000 && paragraphs.len() > 10 {
-            self.chunk_paragraphs_parallel(document_id, &paragraphs).await?
+            self.chunk_paragraphs_parallel(document_id, content).await?
         } else {
             self.chunk_paragraphs_sequential(document_id, &paragraphs).await?
         };
-        
+
+        self.enforce_max_input_tokens(document_id, chunks)
+    }
+
+    /// Chunk `content` as `chunk_document` does, then run `embed` over the
+    /// resulting chunks through a semaphore bounded at
+    /// `config.max_concurrent_chunks`, so a large document embeds in
+    /// parallel without overrunning the embedding provider's rate limit
+    pub async fn chunk_document_concurrent<F, Fut>(
+        &self,
+        document_id: DocumentId,
+        content: &str,
+        embed: F,
+    ) -> RagResult<Vec<Chunk>>
+    where
+        F: Fn(Chunk) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = RagResult<Chunk>> + Send + 'static,
+    {
+        let chunks = self.chunk_document(document_id, content).await?;
+
+        let semaphore = Arc::new(tokio::sync::Semaphore::new(self.config.max_concurrent_chunks.max(1)));
+        let embed = Arc::new(embed);
+
+        let tasks: Vec<_> = chunks
+            .into_iter()
+            .map(|chunk| {
+                let semaphore = Arc::clone(&semaphore);
+                let embed = Arc::clone(&embed);
+                tokio::spawn(async move {
+                    let _permit = semaphore.acquire_owned().await.expect("chunk embedding semaphore closed");
+                    embed(chunk).await
+                })
+            })
+            .collect();
+
+        let mut embedded = Vec::with_capacity(tasks.len());
+        for task in tasks {
+            let chunk = task
+                .await
+                .map_err(|e| RagError::embedding(format!("Chunk embedding task panicked: {}", e)))??;
+            embedded.push(chunk);
+        }
+
+        Ok(embedded)
+    }
+
+    /// Validate `content` against `self.validation` before chunking: rejects
+    /// empty input and documents over `max_total_tokens` per the configured
+    /// `OverLengthAction`, then chunks the (possibly truncated) result. When
+    /// the action is `Split`, chunks starting past the configured limit are
+    /// tagged with an `"over_length"` metadata marker rather than dropped.
+    pub async fn validate_and_chunk_document(&self, document_id: DocumentId, content: &str) -> RagResult<Vec<Chunk>> {
+        let (content, over_length) = self.validate_document(content).await?;
+        let mut chunks = self.chunk_document(document_id, &content).await?;
+
+        if over_length && self.validation.over_length_action == crate::types::OverLengthAction::Split {
+            let limit = self.validation.max_total_tokens;
+            let mut running_tokens = 0usize;
+            for chunk in chunks.iter_mut() {
+                if running_tokens > limit {
+                    chunk.metadata.insert("over_length".to_string(), "true".to_string());
+                }
+                running_tokens += chunk.token_count;
+            }
+        }
+
         Ok(chunks)
     }
+
+    /// Enforce `self.validation` against `content`. Returns the content that
+    /// should be chunked (truncated when the action is `Truncate`) along
+    /// with whether the document was over `max_total_tokens`.
+    async fn validate_document(&self, content: &str) -> RagResult<(String, bool)> {
+        if content.trim().is_empty() {
+            return Err(RagError::EmptyInput);
+        }
+
+        let limit = self.validation.max_total_tokens;
+        if limit == 0 {
+            return Ok((content.to_string(), false));
+        }
+
+        let got = self.count_tokens_background(content).await?;
+        if got <= limit {
+            return Ok((content.to_string(), false));
+        }
+
+        match self.validation.over_length_action {
+            crate::types::OverLengthAction::Error => Err(RagError::InputTooLong { got, limit }),
+            crate::types::OverLengthAction::Truncate => {
+                let truncated = self.truncate_to_token_limit(content, limit)?;
+                Ok((truncated, true))
+            }
+            crate::types::OverLengthAction::Split => Ok((content.to_string(), true)),
+        }
+    }
+
+    /// Cut `content` right after its `limit`-th token, keeping as much of
+    /// the document as fits
+    fn truncate_to_token_limit(&self, content: &str, limit: usize) -> RagResult<String> {
+        let encoding = self
+            .tokenizer
+            .encode(content, false)
+            .map_err(|e| RagError::tokenization(format!("Failed to tokenize text: {}", e)))?;
+
+        let end = match limit.checked_sub(1).and_then(|i| encoding.get_offsets().get(i)) {
+            Some(&(_, end)) => end,
+            None => content.len(),
+        };
+
+        Ok(content[..end].to_string())
+    }
+
+    /// Count tokens in `text` on the background validation worker pool,
+    /// so validating large documents doesn't block the async executor
+    async fn count_tokens_background(&self, text: &str) -> RagResult<usize> {
+        let (respond_to, response) = tokio::sync::oneshot::channel();
+        let task = ValidationTask {
+            tokenizer: Arc::clone(&self.tokenizer),
+            text: text.to_string(),
+            respond_to,
+        };
+
+        validation_pool()
+            .send(task)
+            .map_err(|_| RagError::tokenization("Validation worker pool is no longer running"))?;
+
+        response
+            .await
+            .map_err(|_| RagError::tokenization("Validation worker pool dropped the response channel"))?
+    }
+
+    /// Re-split any chunk over `config.max_input_tokens` along sentence
+    /// boundaries. A `max_input_tokens` of `0` disables the cap entirely.
+    fn enforce_max_input_tokens(&self, document_id: DocumentId, chunks: Vec<Chunk>) -> RagResult<Vec<Chunk>> {
+        let limit = self.config.max_input_tokens;
+        if limit == 0 {
+            return Ok(chunks);
+        }
+
+        let mut result = Vec::with_capacity(chunks.len());
+        for chunk in chunks {
+            if chunk.token_count <= limit {
+                result.push(chunk);
+            } else {
+                result.extend(self.split_oversized_chunk(document_id, chunk, limit)?);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Split a single chunk that exceeds `limit` tokens into several
+    /// sentence-aligned chunks, each within the limit unless one sentence
+    /// alone exceeds it: such a sentence becomes a chunk of its own, still
+    /// over the limit, rather than being dropped. A chunk with no sentence
+    /// boundaries to split on (e.g. one giant word) comes back unsplit.
+    fn split_oversized_chunk(&self, document_id: DocumentId, chunk: Chunk, limit: usize) -> RagResult<Vec<Chunk>> {
+        let sentences: Vec<&str> = chunk.content.unicode_sentences().collect();
+
+        let mut pieces = Vec::new();
+        let mut current = String::new();
+        let mut current_tokens = 0;
+        let mut offset = chunk.start_offset;
+        let mut piece_start = offset;
+
+        for sentence in sentences {
+            let sentence_tokens = self.count_tokens(sentence)?;
+
+            if current_tokens + sentence_tokens > limit && !current.is_empty() {
+                pieces.push(self.create_chunk(document_id, current.trim().to_string(), piece_start, offset, current_tokens)?);
+                current = String::new();
+                current_tokens = 0;
+                piece_start = offset;
+            }
+
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(sentence);
+            current_tokens += sentence_tokens;
+            offset += sentence.len();
+        }
+
+        if !current.trim().is_empty() {
+            pieces.push(self.create_chunk(document_id, current.trim().to_string(), piece_start, offset, current_tokens)?);
+        }
+
+        if pieces.is_empty() {
+            pieces.push(chunk);
+        }
+
+        Ok(pieces)
+    }
     
+    /// Chunk a document using FastCDC content-defined boundaries. Unlike the
+    /// fixed-window path, boundaries are stable across insertions/deletions
+    /// upstream, which keeps deduplication effective across document revisions.
+    fn chunk_document_content_defined(
+        &self,
+        document_id: DocumentId,
+        content: &str,
+        min_size: usize,
+        avg_size: usize,
+        max_size: usize,
+    ) -> RagResult<Vec<Chunk>> {
+        let bytes = content.as_bytes();
+        let boundaries = fastcdc_boundaries(bytes, min_size, avg_size, max_size);
+
+        let mut chunks = Vec::with_capacity(boundaries.len());
+        let mut start = 0usize;
+
+        for end in boundaries {
+            let slice = &content[start..end];
+            if !slice.trim().is_empty() {
+                let token_count = self.count_tokens(slice)?;
+                let mut chunk = self.create_chunk(document_id, slice.to_string(), start, end, token_count)?;
+                // A content hash lets callers diff against previously stored
+                // chunks and skip re-embedding regions that didn't change
+                // across document revisions
+                chunk.metadata.insert("content_hash".to_string(), blake3::hash(slice.as_bytes()).to_hex().to_string());
+                chunks.push(chunk);
+            }
+            start = end;
+        }
+
+        Ok(chunks)
+    }
+
+    /// Chunk `content` on sentence boundaries, packing sentences into
+    /// `chunk_count = ceil(total_tokens / chunk_size)` chunks of roughly
+    /// `total_tokens / chunk_count` tokens each, rather than greedily
+    /// filling to `chunk_size` and leaving a near-empty trailing chunk.
+    /// `config.overlap` is applied by letting each chunk after the first
+    /// start from the trailing sentences of the previous one that fit
+    /// within `overlap` tokens, so that text is duplicated across the
+    /// boundary exactly like the other chunking paths.
+    fn chunk_document_sentence_balanced(&self, document_id: DocumentId, content: &str) -> RagResult<Vec<Chunk>> {
+        // Exact byte offset of each sentence within `content`
+        let mut sentences: Vec<(usize, &str)> = Vec::new();
+        let mut cursor = 0usize;
+        for sentence in content.unicode_sentences() {
+            let Some(rel) = content[cursor..].find(sentence) else {
+                continue;
+            };
+            let start = cursor + rel;
+            sentences.push((start, sentence));
+            cursor = start + sentence.len();
+        }
+
+        if sentences.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let sentence_tokens: Vec<usize> = sentences
+            .iter()
+            .map(|(_, s)| self.count_tokens(s))
+            .collect::<RagResult<Vec<_>>>()?;
+        let total_tokens: usize = sentence_tokens.iter().sum();
+        if total_tokens == 0 {
+            return Ok(Vec::new());
+        }
+
+        let chunk_size = self.config.chunk_size.max(1);
+        let chunk_count = total_tokens.div_ceil(chunk_size).max(1);
+        let target = total_tokens.div_ceil(chunk_count).max(1);
+
+        // Phase 1: partition sentence indices into `chunk_count` groups of
+        // roughly `target` tokens each
+        let n = sentences.len();
+        let mut groups: Vec<(usize, usize)> = Vec::with_capacity(chunk_count);
+        let mut idx = 0usize;
+        for group_idx in 0..chunk_count {
+            if idx >= n {
+                break;
+            }
+            let remaining_groups = chunk_count - group_idx;
+            let mut end_idx = idx;
+            let mut tokens = 0usize;
+            while end_idx < n && (tokens == 0 || (tokens + sentence_tokens[end_idx] <= target && remaining_groups > 1)) {
+                tokens += sentence_tokens[end_idx];
+                end_idx += 1;
+            }
+            groups.push((idx, end_idx));
+            idx = end_idx;
+        }
+        if idx < n {
+            match groups.last_mut() {
+                Some(last) => last.1 = n,
+                None => groups.push((0, n)),
+            }
+        }
+
+        // Phase 2: build each chunk, extending its start backward into the
+        // previous group's trailing sentences to realize the overlap
+        let mut chunks = Vec::with_capacity(groups.len());
+        for (group_idx, &(start_idx, end_idx)) in groups.iter().enumerate() {
+            let mut overlap_from = start_idx;
+            if group_idx > 0 && self.config.overlap > 0 {
+                let prev_start = groups[group_idx - 1].0;
+                let mut carried = 0usize;
+                while overlap_from > prev_start && carried + sentence_tokens[overlap_from - 1] <= self.config.overlap {
+                    overlap_from -= 1;
+                    carried += sentence_tokens[overlap_from];
+                }
+            }
+
+            let (chunk_start_offset, _) = sentences[overlap_from];
+            let (last_offset, last_text) = sentences[end_idx - 1];
+            let chunk_end_offset = last_offset + last_text.len();
+
+            let chunk_content = content[chunk_start_offset..chunk_end_offset].to_string();
+            let token_count = self.count_tokens(&chunk_content)?;
+            chunks.push(self.create_chunk(document_id, chunk_content, chunk_start_offset, chunk_end_offset, token_count)?);
+        }
+
+        Ok(chunks)
+    }
+
+    /// Chunk `content` using tree-sitter syntax boundaries instead of blank
+    /// lines: greedily accumulate line-aligned candidate boundaries up to
+    /// `config.chunk_size` tokens, then among the last few candidates
+    /// considered, prefer whichever is nested within the fewest enclosing
+    /// named syntax nodes, so the split falls between statements/items
+    /// rather than mid-construct. Returns `None` (rather than erroring) if
+    /// this build has no grammar for `language`, so the caller can fall
+    /// back to paragraph-based chunking.
+    fn chunk_document_syntactic(
+        &self,
+        document_id: DocumentId,
+        content: &str,
+        language: SourceLanguage,
+    ) -> RagResult<Option<Vec<Chunk>>> {
+        let Some(ts_language) = tree_sitter_language(language) else {
+            return Ok(None);
+        };
+
+        let mut parser = tree_sitter::Parser::new();
+        if parser.set_language(&ts_language).is_err() {
+            return Ok(None);
+        }
+        let Some(tree) = parser.parse(content, None) else {
+            return Ok(None);
+        };
+
+        // Every byte offset right after a '\n', plus the end of the
+        // document, is a candidate chunk boundary
+        let mut line_boundaries: Vec<usize> = content
+            .bytes()
+            .enumerate()
+            .filter_map(|(i, b)| (b == b'\n').then_some(i + 1))
+            .collect();
+        if line_boundaries.last() != Some(&content.len()) {
+            line_boundaries.push(content.len());
+        }
+
+        let mut chunks = Vec::new();
+        let mut start = 0usize;
+
+        while start < content.len() {
+            // Walk candidate boundaries after `start` until the chunk
+            // would exceed the target size (or the hard max)
+            let mut in_budget = Vec::new();
+            for &boundary in line_boundaries.iter().filter(|&&b| b > start) {
+                let tokens = self.count_tokens(&content[start..boundary])?;
+                if tokens > self.config.max_chunk_size {
+                    break;
+                }
+                in_budget.push(boundary);
+                if tokens >= self.config.chunk_size {
+                    break;
+                }
+            }
+
+            let end = if in_budget.is_empty() {
+                // Even the very next line boundary overflows max_chunk_size;
+                // take it anyway rather than looping forever
+                line_boundaries.iter().copied().find(|&b| b > start).unwrap_or(content.len())
+            } else {
+                let window = &in_budget[in_budget.len().saturating_sub(5)..];
+                window
+                    .iter()
+                    .copied()
+                    .min_by_key(|&b| named_depth_at(&tree, b.saturating_sub(1)))
+                    .unwrap_or(*in_budget.last().unwrap())
+            };
+
+            let slice = &content[start..end];
+            if !slice.trim().is_empty() {
+                let token_count = self.count_tokens(slice)?;
+                let mut chunk = self.create_chunk(document_id, slice.to_string(), start, end, token_count)?;
+                if let Some(scope) = scope_path_at(&tree, content, start, language) {
+                    chunk.metadata.insert("scope".to_string(), scope);
+                }
+                chunks.push(chunk);
+            }
+            start = end;
+        }
+
+        Ok(Some(chunks))
+    }
+
     /// Chunk paragraphs sequentially
     async fn chunk_paragraphs_sequential(
         &self,
@@ -159,126 +997,97 @@ impl SmartChunker {
         Ok(chunks)
     }
     
-    /// Chunk paragraphs in parallel (for large documents)
+    /// Chunk paragraphs in parallel (for large documents). Window
+    /// boundaries are decided up front as a true token-based sliding
+    /// window over paragraph indices - stepping `chunk_size - overlap`
+    /// tokens so each window's trailing `config.overlap` tokens carry into
+    /// the next, exactly like `chunk_paragraphs_sequential`'s overlap - so
+    /// every chunk's `start_offset`/`end_offset` land on the real byte
+    /// offsets of `content` rather than a fabricated estimate. Only the
+    /// actual text/token assembly per window runs in parallel via rayon.
     async fn chunk_paragraphs_parallel(
         &self,
         document_id: DocumentId,
-        paragraphs: &[&str],
+        content: &str,
     ) -> RagResult<Vec<Chunk>> {
-        // Use rayon for parallel processing of paragraph batches
-        let batch_size = 20; // Process 20 paragraphs at a time for better parallelization
+        // Exact byte offset of each non-empty paragraph within `content`
+        let mut paragraphs: Vec<(usize, &str)> = Vec::new();
+        let mut scan_offset = 0usize;
+        for part in content.split("\n\n") {
+            if !part.trim().is_empty() {
+                paragraphs.push((scan_offset, part));
+            }
+            scan_offset += part.len() + 2;
+        }
+
+        if paragraphs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let paragraph_tokens: Vec<usize> = paragraphs
+            .iter()
+            .map(|(_, p)| self.count_tokens(p))
+            .collect::<RagResult<Vec<_>>>()?;
+
+        let windows = Self::sliding_windows(&paragraph_tokens, self.config.chunk_size, self.config.overlap);
+
         let tokenizer = Arc::clone(&self.tokenizer);
         let config = self.config.clone();
-        
-        // Process batches in parallel using rayon
-        let batch_results: Result<Vec<Vec<Chunk>>, RagError> = paragraphs
-            .par_chunks(batch_size)
-            .enumerate()
-            .map(|(batch_idx, batch)| {
-                // Create a temporary chunker for this batch
+
+        let chunks: Result<Vec<Chunk>, RagError> = windows
+            .par_iter()
+            .map(|&(start, end)| {
                 let batch_chunker = SmartChunker::new((*tokenizer).clone(), config.clone());
-                
-                // Process this batch sequentially within the parallel context
-                let mut batch_chunks = Vec::new();
-                let mut current_chunk = String::new();
-                let mut current_tokens = 0;
-                let mut start_offset = batch_idx * batch_size * 100; // Approximate offset
-                let mut current_offset = start_offset;
-                
-                for paragraph in batch {
-                    let paragraph_tokens = batch_chunker.count_tokens(paragraph)
-                        .map_err(|e| RagError::tokenization(format!("Parallel tokenization failed: {}", e)))?;
-                    
-                    // If adding this paragraph would exceed chunk size, finalize current chunk
-                    if current_tokens + paragraph_tokens > config.chunk_size && !current_chunk.is_empty() {
-                        let chunk = batch_chunker.create_chunk(
-                            document_id,
-                            current_chunk.trim().to_string(),
-                            start_offset,
-                            current_offset,
-                            current_tokens,
-                        )?;
-                        batch_chunks.push(chunk);
-                        
-                        // Start new chunk with overlap
-                        let overlap_content = batch_chunker.get_overlap_content(&current_chunk)?;
-                        current_chunk = overlap_content;
-                        current_tokens = batch_chunker.count_tokens(&current_chunk)?;
-                        start_offset = current_offset - current_chunk.len();
-                    }
-                    
-                    // Add paragraph to current chunk
-                    if !current_chunk.is_empty() {
-                        current_chunk.push_str("\n\n");
-                        current_offset += 2;
-                    }
-                    current_chunk.push_str(paragraph);
-                    current_offset += paragraph.len();
-                    current_tokens += paragraph_tokens;
-                }
-                
-                // Add final chunk if not empty
-                if !current_chunk.trim().is_empty() {
-                    let chunk = batch_chunker.create_chunk(
-                        document_id,
-                        current_chunk.trim().to_string(),
-                        start_offset,
-                        current_offset,
-                        current_tokens,
-                    )?;
-                    batch_chunks.push(chunk);
-                }
-                
-                Ok(batch_chunks)
+
+                let (start_offset, _) = paragraphs[start];
+                let (last_offset, last_text) = paragraphs[end - 1];
+                let end_offset = last_offset + last_text.len();
+
+                let window_content = content[start_offset..end_offset].trim().to_string();
+                let window_tokens = batch_chunker.count_tokens(&window_content)?;
+
+                batch_chunker.create_chunk(document_id, window_content, start_offset, end_offset, window_tokens)
             })
             .collect();
-        
-        let batch_results = batch_results?;
-        
-        // Merge all batch results with proper overlap handling
-        let mut all_chunks = Vec::new();
-        
-        for (batch_idx, batch_chunks) in batch_results.into_iter().enumerate() {
-            if batch_idx == 0 {
-                // First batch, add all chunks
-                all_chunks.extend(batch_chunks);
-            } else if !all_chunks.is_empty() && !batch_chunks.is_empty() {
-                // Subsequent batches, handle overlap with previous batch
-                let last_chunk = all_chunks.last().unwrap();
-                let first_chunk = &batch_chunks[0];
-                
-                // Check if we should merge the boundary chunks
-                if last_chunk.token_count + first_chunk.token_count <= self.config.max_chunk_size {
-                    // Merge chunks
-                    let mut merged_content = last_chunk.content.clone();
-                    merged_content.push_str("\n\n");
-                    merged_content.push_str(&first_chunk.content);
-                    
-                    let merged_tokens = self.count_tokens(&merged_content)?;
-                    let merged_chunk = self.create_chunk(
-                        document_id,
-                        merged_content,
-                        last_chunk.start_offset,
-                        first_chunk.end_offset,
-                        merged_tokens,
-                    )?;
-                    
-                    // Replace last chunk with merged chunk
-                    all_chunks.pop();
-                    all_chunks.push(merged_chunk);
-                    
-                    // Add remaining chunks from current batch
-                    all_chunks.extend(batch_chunks.into_iter().skip(1));
-                } else {
-                    // Keep chunks separate
-                    all_chunks.extend(batch_chunks);
-                }
-            } else {
-                all_chunks.extend(batch_chunks);
+
+        chunks
+    }
+
+    /// Compute `(start_idx, end_idx)` paragraph-index windows that together
+    /// cover every paragraph in `token_counts`, targeting `chunk_size`
+    /// tokens per window and carrying the trailing `overlap` tokens of one
+    /// window into the start of the next
+    fn sliding_windows(token_counts: &[usize], chunk_size: usize, overlap: usize) -> Vec<(usize, usize)> {
+        let n = token_counts.len();
+        let mut windows = Vec::new();
+        let mut start = 0usize;
+
+        while start < n {
+            let mut end = start;
+            let mut tokens = 0usize;
+            while end < n && (tokens == 0 || tokens + token_counts[end] <= chunk_size) {
+                tokens += token_counts[end];
+                end += 1;
             }
+            windows.push((start, end));
+
+            if end >= n {
+                break;
+            }
+
+            // Slide the window start forward, carrying the trailing
+            // paragraphs whose cumulative token count stays within `overlap`
+            let mut next_start = end;
+            let mut carried = 0usize;
+            while next_start > start && carried + token_counts[next_start - 1] <= overlap {
+                next_start -= 1;
+                carried += token_counts[next_start];
+            }
+            // Guarantee forward progress even when overlap >= chunk_size
+            start = next_start.max(start + 1);
         }
-        
-        Ok(all_chunks)
+
+        windows
     }
     
     /// Create a chunk with proper metadata
@@ -345,12 +1154,22 @@ impl SmartChunker {
         Ok(encoding.len())
     }
     
+    /// Upper bound on how many sub-chunks a caller should embed
+    /// concurrently, from `config.max_concurrent_chunks`
+    pub fn max_concurrent_chunks(&self) -> usize {
+        self.config.max_concurrent_chunks
+    }
+
     /// Create sub-chunks for reranking (150-250 tokens)
     pub async fn create_sub_chunks(&self, chunks: &[Chunk]) -> RagResult<Vec<crate::types::SubChunk>> {
+        if self.config.sub_chunk_strategy == crate::types::SubChunkStrategy::Phrase {
+            return self.create_sub_chunks_phrase(chunks);
+        }
+
         let _target_size = 200; // Target 200 tokens per sub-chunk
         let min_size = 150;
         let max_size = 250;
-        
+
         let mut sub_chunks = Vec::new();
         
         for chunk in chunks {
@@ -419,7 +1238,70 @@ impl SmartChunker {
         
         Ok(sub_chunks)
     }
-    
+
+    /// Create sub-chunks by cutting on phrase boundaries (`SubChunkStrategy::Phrase`)
+    /// instead of sentence boundaries: a beam-searched sequence tagger
+    /// assigns each word a phrase-chunk tag, and once a sub-chunk holds 150
+    /// tokens it is cut where a phrase ends, so coherent noun/verb phrases
+    /// stay intact. A sub-chunk that reaches 250 tokens is cut there even
+    /// mid-phrase.
+    fn create_sub_chunks_phrase(&self, chunks: &[Chunk]) -> RagResult<Vec<crate::types::SubChunk>> {
+        let min_size = 150;
+        let max_size = 250;
+
+        let mut sub_chunks = Vec::new();
+
+        for chunk in chunks {
+            if chunk.token_count <= max_size {
+                sub_chunks.push(crate::types::SubChunk::new(
+                    chunk.id,
+                    chunk.content.clone(),
+                    0,
+                    chunk.content.len(),
+                    chunk.token_count,
+                ));
+                continue;
+            }
+
+            let words: Vec<&str> = chunk.content.split_whitespace().collect();
+            if words.is_empty() {
+                continue;
+            }
+            let tags = beam_search_phrase_tags(&words);
+
+            let mut current_words: Vec<&str> = Vec::new();
+            let mut current_tokens = 0;
+            let mut start_offset = 0usize;
+
+            for (i, word) in words.iter().enumerate() {
+                current_words.push(word);
+                current_tokens += self.count_tokens(word)?;
+
+                let at_phrase_boundary = tags[i].ends_phrase_before(tags.get(i + 1).copied());
+                let is_last_word = i + 1 == words.len();
+                let should_cut = current_tokens >= max_size
+                    || (current_tokens >= min_size && at_phrase_boundary && !is_last_word);
+
+                if should_cut {
+                    let content = current_words.join(" ");
+                    let end_offset = start_offset + content.len();
+                    sub_chunks.push(crate::types::SubChunk::new(chunk.id, content, start_offset, end_offset, current_tokens));
+                    start_offset = end_offset + 1;
+                    current_words.clear();
+                    current_tokens = 0;
+                }
+            }
+
+            if !current_words.is_empty() {
+                let content = current_words.join(" ");
+                let end_offset = start_offset + content.len();
+                sub_chunks.push(crate::types::SubChunk::new(chunk.id, content, start_offset, end_offset, current_tokens));
+            }
+        }
+
+        Ok(sub_chunks)
+    }
+
     /// Get chunking statistics
     pub fn get_stats(&self) -> ChunkingConfig {
         self.config.clone()
@@ -491,6 +1373,7 @@ mod tests {
             overlap: 2,
             min_chunk_size: 5,
             max_chunk_size: 15,
+            ..Default::default()
         };
         let chunker = SmartChunker::with_default_tokenizer(config.clone()).await.unwrap();
         let document_id = Uuid::new_v4();
@@ -517,6 +1400,7 @@ mod tests {
             overlap: 5,
             min_chunk_size: 10,
             max_chunk_size: 30,
+            ..Default::default()
         };
         let chunker = SmartChunker::with_default_tokenizer(config.clone()).await.unwrap();
         let document_id = Uuid::new_v4();
@@ -549,6 +1433,7 @@ mod tests {
             overlap: 10,
             min_chunk_size: 20,
             max_chunk_size: 80,
+            ..Default::default()
         };
         let chunker = SmartChunker::with_default_tokenizer(config.clone()).await.unwrap();
         let document_id = Uuid::new_v4();
@@ -643,6 +1528,38 @@ mod tests {
         assert_eq!(chunk.metadata.get("overlap").unwrap(), &config.overlap.to_string());
     }
     
+    #[tokio::test]
+    async fn test_syntactic_chunking_splits_on_line_boundaries() {
+        let config = ChunkingConfig {
+            chunk_size: 15,
+            overlap: 0,
+            min_chunk_size: 5,
+            max_chunk_size: 40,
+            strategy: ChunkingStrategy::Syntactic { language: SourceLanguage::Rust },
+            ..Default::default()
+        };
+        let chunker = SmartChunker::with_default_tokenizer(config).await.unwrap();
+        let document_id = Uuid::new_v4();
+
+        let content = "fn one() {\n    1\n}\n\nfn two() {\n    2\n}\n\nfn three() {\n    3\n}\n";
+        let chunks = chunker.chunk_document(document_id, content).await.unwrap();
+
+        assert!(chunks.len() > 1);
+
+        // Boundaries must fall on a line break (or the end of the document)
+        for chunk in &chunks {
+            assert!(
+                chunk.end_offset == content.len() || content.as_bytes()[chunk.end_offset - 1] == b'\n',
+                "chunk ending at {} doesn't land on a line break",
+                chunk.end_offset
+            );
+        }
+
+        // Reassembling the chunks' offsets should cover the whole document
+        assert_eq!(chunks.first().unwrap().start_offset, 0);
+        assert_eq!(chunks.last().unwrap().end_offset, content.len());
+    }
+
     #[tokio::test]
     async fn test_chunking_with_custom_config() {
         let custom_config = ChunkingConfig {
@@ -650,6 +1567,7 @@ mod tests {
             overlap: 20,
             min_chunk_size: 50,
             max_chunk_size: 150,
+            ..Default::default()
         };
         
         let chunker = SmartChunker::with_default_tokenizer(custom_config.clone()).await.unwrap();
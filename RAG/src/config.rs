@@ -1,7 +1,9 @@
 //! Configuration management for the RAG engine
 
+use crate::rate_limiter::RateLimitConfig;
 use crate::types::ChunkingConfig;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 /// Main configuration for the RAG engine
@@ -13,17 +15,30 @@ pub struct RagConfig {
     /// Vector store configuration
     pub vector_store: VectorStoreConfig,
     
-    /// Embedding service configuration
-    pub embedding: EmbeddingConfig,
+    /// Named embedding service configurations
+    pub embeddings: EmbeddingsConfig,
     
     /// Reranking service configuration
     pub reranking: RerankingConfig,
-    
+
+    /// Hybrid dense+sparse retrieval configuration
+    #[serde(default)]
+    pub hybrid_search: HybridSearchConfig,
+
+    /// Answer generation configuration, used by `RagEngine::generate_answer`
+    #[serde(default)]
+    pub generation: GenerationConfig,
+
     /// Cache configuration
     pub cache: CacheConfig,
-    
+
     /// Performance configuration
     pub performance: PerformanceConfig,
+
+    /// How `RagEngine::ingest_document` handles content that hashes the
+    /// same as an already-stored document
+    #[serde(default)]
+    pub dedup_policy: DedupPolicy,
 }
 
 impl Default for RagConfig {
@@ -31,14 +46,36 @@ impl Default for RagConfig {
         Self {
             chunking: ChunkingConfig::default(),
             vector_store: VectorStoreConfig::default(),
-            embedding: EmbeddingConfig::default(),
+            embeddings: EmbeddingsConfig::default(),
             reranking: RerankingConfig::default(),
+            hybrid_search: HybridSearchConfig::default(),
+            generation: GenerationConfig::default(),
             cache: CacheConfig::default(),
             performance: PerformanceConfig::default(),
+            dedup_policy: DedupPolicy::default(),
         }
     }
 }
 
+/// Policy for `RagEngine::ingest_document` when the incoming content's
+/// blake3 hash already matches a stored document
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DedupPolicy {
+    /// Short-circuit and return the existing document's ID without
+    /// chunking, embedding, or charging tokens
+    Skip,
+    /// Delete the existing document, then ingest the new content as normal
+    Replace,
+    /// Ingest every call, even if its content hash matches a stored document
+    Allow,
+}
+
+impl Default for DedupPolicy {
+    fn default() -> Self {
+        DedupPolicy::Skip
+    }
+}
+
 /// Vector store configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VectorStoreConfig {
@@ -59,6 +96,24 @@ pub struct VectorStoreConfig {
     
     /// Search parameter for IVF indices
     pub nprobe: Option<usize>,
+
+    /// Vector quantization applied to reduce memory footprint
+    #[serde(default)]
+    pub quantization: QuantizationMode,
+
+    /// Number of quantized candidates pulled before full-precision rescoring.
+    /// Only used when `quantization` is not `None`; defaults to 4x `top_k`.
+    #[serde(default)]
+    pub rescore_multiplier: Option<usize>,
+
+    /// HNSW graph index tuning parameters, required when `index_type == "HNSW"`
+    #[serde(default)]
+    pub hnsw: Option<HnswParams>,
+
+    /// Similarity/distance function `parallel_similarity_search` scores
+    /// candidates with
+    #[serde(default)]
+    pub distance_metric: DistanceMetric,
 }
 
 impl Default for VectorStoreConfig {
@@ -70,6 +125,31 @@ impl Default for VectorStoreConfig {
             index_type: "Flat".to_string(),
             nlist: None,
             nprobe: None,
+            quantization: QuantizationMode::default(),
+            rescore_multiplier: None,
+            hnsw: None,
+            distance_metric: DistanceMetric::default(),
+        }
+    }
+}
+
+/// Tuning parameters for an HNSW (Hierarchical Navigable Small World) graph index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HnswParams {
+    /// Number of bi-directional links created per node (higher = better recall, more memory)
+    pub m: usize,
+    /// Size of the dynamic candidate list during index construction
+    pub ef_construction: usize,
+    /// Size of the dynamic candidate list during search (recall/speed tradeoff)
+    pub ef_search: usize,
+}
+
+impl Default for HnswParams {
+    fn default() -> Self {
+        Self {
+            m: 16,
+            ef_construction: 200,
+            ef_search: 100,
         }
     }
 }
@@ -83,9 +163,67 @@ pub enum VectorStoreBackend {
     Faiss,
 }
 
+/// Vector quantization scheme used to shrink the in-memory/disk index
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum QuantizationMode {
+    /// Store full-precision embeddings (default)
+    None,
+    /// Linearly quantize each dimension to a u8 using per-dimension min/max
+    Scalar8,
+    /// One bit per dimension (sign of the centered value), compared via Hamming distance
+    Binary,
+}
+
+impl Default for QuantizationMode {
+    fn default() -> Self {
+        QuantizationMode::None
+    }
+}
+
+/// Similarity/distance function used to score a query embedding against a
+/// stored one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DistanceMetric {
+    /// Cosine similarity — the default, scale-invariant
+    Cosine,
+    /// Raw dot product, cheaper than cosine for embeddings the model already
+    /// normalizes
+    DotProduct,
+    /// Squared Euclidean distance, converted to a descending-sortable score
+    Euclidean,
+}
+
+impl Default for DistanceMetric {
+    fn default() -> Self {
+        DistanceMetric::Cosine
+    }
+}
+
+/// Which `EmbeddingProvider` backend an `EmbeddingConfig` resolves to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EmbeddingProviderKind {
+    /// The existing remote HTTP / `python://` sidecar backend (`EmbeddingClient`)
+    Remote,
+    /// A local Ollama server's `/api/embeddings` endpoint, for fully offline use
+    Ollama,
+    /// An in-process deterministic embedder with no network or subprocess,
+    /// for tests and for running the test harness without a live service
+    Mock,
+}
+
+impl Default for EmbeddingProviderKind {
+    fn default() -> Self {
+        EmbeddingProviderKind::Remote
+    }
+}
+
 /// Embedding service configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EmbeddingConfig {
+    /// Which backend implementation serves this embedder
+    #[serde(default)]
+    pub provider: EmbeddingProviderKind,
+
     /// API endpoint for embedding service
     pub endpoint: String,
     
@@ -100,47 +238,200 @@ pub struct EmbeddingConfig {
     
     /// Request timeout in seconds
     pub timeout: u64,
-    
+
     /// Maximum retries
     pub max_retries: usize,
+
+    /// Embedding dimension produced by this embedder, if it differs from
+    /// `VectorStoreConfig::dimension`
+    #[serde(default)]
+    pub dimension: Option<usize>,
+
+    /// Template rendering a chunk into the text sent to the embedding model,
+    /// e.g. `{{ title }}\n\n{{ text }}`. Fields resolve against chunk metadata,
+    /// with `text` bound to the chunk's own content.
+    #[serde(default)]
+    pub template: Option<String>,
+
+    /// Token-bucket rate limit applied to outgoing requests. `None` leaves
+    /// requests unthrottled, relying on `max_retries`/`retry::classify_response`
+    /// to recover after the provider itself returns a 429.
+    #[serde(default)]
+    pub rate_limit: Option<RateLimitConfig>,
 }
 
 impl Default for EmbeddingConfig {
     fn default() -> Self {
         Self {
+            provider: EmbeddingProviderKind::default(),
             endpoint: "python://melanie_embedding".to_string(),  // Use Python integration by default
             api_key: None,
             model: "nvidia/nv-embedqa-mistral-7b-v2".to_string(),
             batch_size: 100,
             timeout: 300,  // Longer timeout for Python integration
             max_retries: 3,
+            dimension: None,
+            template: None,
+            rate_limit: None,
         }
     }
 }
 
+/// Name used for the implicit embedder created when a bare `EmbeddingConfig`
+/// is loaded instead of a named map (back-compat path)
+const DEFAULT_EMBEDDER_NAME: &str = "default";
+
+/// Named embedding service configurations, keyed by embedder name
+#[derive(Debug, Clone, Serialize)]
+pub struct EmbeddingsConfig {
+    /// Name of the embedder used when none is explicitly requested
+    pub default_embedder: String,
+
+    /// Embedder configurations by name
+    pub embedders: HashMap<String, EmbeddingConfig>,
+}
+
+impl Default for EmbeddingsConfig {
+    fn default() -> Self {
+        let mut embedders = HashMap::new();
+        embedders.insert(DEFAULT_EMBEDDER_NAME.to_string(), EmbeddingConfig::default());
+        Self {
+            default_embedder: DEFAULT_EMBEDDER_NAME.to_string(),
+            embedders,
+        }
+    }
+}
+
+impl EmbeddingsConfig {
+    /// Get the default embedder's configuration
+    pub fn default_config(&self) -> Option<&EmbeddingConfig> {
+        self.embedders.get(&self.default_embedder)
+    }
+}
+
+/// Deserialization representation accepting either the named-map shape or a
+/// bare `EmbeddingConfig`, for backwards compatibility with configs written
+/// before multiple embedders were supported.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum EmbeddingsConfigRepr {
+    Named {
+        default_embedder: String,
+        embedders: HashMap<String, EmbeddingConfig>,
+    },
+    Bare(EmbeddingConfig),
+}
+
+impl<'de> Deserialize<'de> for EmbeddingsConfig {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match EmbeddingsConfigRepr::deserialize(deserializer)? {
+            EmbeddingsConfigRepr::Named { default_embedder, embedders } => {
+                Ok(Self { default_embedder, embedders })
+            }
+            EmbeddingsConfigRepr::Bare(config) => {
+                let mut embedders = HashMap::new();
+                embedders.insert(DEFAULT_EMBEDDER_NAME.to_string(), config);
+                Ok(Self {
+                    default_embedder: DEFAULT_EMBEDDER_NAME.to_string(),
+                    embedders,
+                })
+            }
+        }
+    }
+}
+
+/// Hosted rerank API `RerankingClient` talks to, selecting the
+/// request/response shape built and parsed in `make_reranking_request`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RerankProvider {
+    /// ERNIE-style: `query`/`documents`/`model`/`top_k` in,
+    /// `results[].relevance_score` out. The long-standing default shape.
+    Ernie,
+    /// Cohere's `/rerank`: `top_n` instead of `top_k`, otherwise the same
+    /// `results[].relevance_score` response shape as `Ernie`
+    Cohere,
+    /// Jina's `/rerank`: `top_n` instead of `top_k`, `results[].relevance_score`
+    /// response shape
+    Jina,
+    /// Voyage AI's `/rerank`: `top_k`, but results come back under `data`
+    /// rather than `results`
+    Voyage,
+}
+
+impl Default for RerankProvider {
+    fn default() -> Self {
+        RerankProvider::Ernie
+    }
+}
+
+/// How raw rerank scores — which vary in scale across providers: logits,
+/// `[0, 1]` probabilities, or unbounded values — are rescaled before
+/// `set_rerank_score`/threshold comparisons see them
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScoreNormalization {
+    /// Leave scores exactly as the provider returned them
+    None,
+    /// Rescale a batch's scores linearly so its min becomes `0.0` and its
+    /// max becomes `1.0`
+    MinMax,
+    /// Apply the logistic function, mapping unbounded logits into `(0, 1)`
+    Sigmoid,
+}
+
+impl Default for ScoreNormalization {
+    fn default() -> Self {
+        ScoreNormalization::None
+    }
+}
+
 /// Reranking service configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RerankingConfig {
     /// API endpoint for reranking service
     pub endpoint: String,
-    
+
     /// API key for reranking service
     pub api_key: Option<String>,
-    
+
     /// Model name
     pub model: String,
-    
+
     /// Reranking threshold (0.0 to 1.0)
     pub threshold: f32,
-    
+
     /// Maximum number of candidates to rerank
     pub max_candidates: usize,
-    
+
     /// Request timeout in seconds
     pub timeout: u64,
-    
+
     /// Maximum retries
     pub max_retries: usize,
+
+    /// Which hosted rerank API to speak to
+    #[serde(default)]
+    pub provider: RerankProvider,
+
+    /// How to rescale a batch's raw scores before they reach
+    /// `threshold`/`relative_threshold_ratio` comparisons
+    #[serde(default)]
+    pub normalization: ScoreNormalization,
+
+    /// Keep only results whose normalized score is within this fraction of
+    /// the batch's top score (e.g. `0.9` keeps scores `>= 0.9 * top_score`).
+    /// `None` disables relative thresholding in favor of the absolute
+    /// `threshold` alone.
+    #[serde(default)]
+    pub relative_threshold_ratio: Option<f32>,
+
+    /// Token-bucket rate limit applied to outgoing requests. `None` leaves
+    /// requests unthrottled, relying on `max_retries`/`retry::classify_response`
+    /// to recover after the provider itself returns a 429.
+    #[serde(default)]
+    pub rate_limit: Option<RateLimitConfig>,
 }
 
 impl Default for RerankingConfig {
@@ -153,10 +444,136 @@ impl Default for RerankingConfig {
             max_candidates: 100,
             timeout: 300,  // Longer timeout for Python integration
             max_retries: 3,
+            provider: RerankProvider::default(),
+            normalization: ScoreNormalization::default(),
+            relative_threshold_ratio: None,
+            rate_limit: None,
+        }
+    }
+}
+
+/// Answer generation configuration, consumed by `generation::GenerationClient`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenerationConfig {
+    /// API endpoint for the text-generation service
+    pub endpoint: String,
+
+    /// API key for the text-generation service
+    pub api_key: Option<String>,
+
+    /// Model name
+    pub model: String,
+
+    /// System prompt sent ahead of the augmented context + question
+    pub system_prompt: String,
+
+    /// Template assembling the retrieved context and the user's question
+    /// into the prompt body, e.g. `"Context:\n{{ context }}\n\nQuestion: {{ query }}"`.
+    /// `None` falls back to `generation::DEFAULT_PROMPT_TEMPLATE`.
+    #[serde(default)]
+    pub prompt_template: Option<String>,
+
+    /// Token budget for the assembled context: `RagEngine::generate_answer`
+    /// drops the lowest-scoring retrieved chunks until the prompt fits
+    pub max_context_tokens: usize,
+
+    /// Request timeout in seconds
+    pub timeout: u64,
+
+    /// Maximum retries
+    pub max_retries: usize,
+
+    /// Token-bucket rate limit applied to outgoing requests. `None` leaves
+    /// requests unthrottled, relying on `max_retries`/`retry::classify_response`
+    /// to recover after the provider itself returns a 429.
+    #[serde(default)]
+    pub rate_limit: Option<RateLimitConfig>,
+}
+
+impl Default for GenerationConfig {
+    fn default() -> Self {
+        Self {
+            // Unlike `EmbeddingConfig`/`RerankingConfig`, there is no `python://`
+            // sidecar integration for generation yet, so this points at a
+            // plain OpenAI-compatible chat completions endpoint instead.
+            endpoint: "https://api.openai.com/v1/chat/completions".to_string(),
+            api_key: None,
+            model: "nvidia/llama-3.1-nemotron-70b-instruct".to_string(),
+            system_prompt: "Answer the user's question using only the provided context. \
+If the context doesn't contain the answer, say so instead of guessing."
+                .to_string(),
+            prompt_template: None,
+            max_context_tokens: 4000,
+            timeout: 300,
+            max_retries: 3,
+            rate_limit: None,
         }
     }
 }
 
+/// Score fusion strategy for combining dense and sparse retrieval results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum FusionMode {
+    /// Min-max normalize both score lists, then take a weighted sum
+    Convex,
+    /// Reciprocal Rank Fusion: accumulate `1 / (k + rank)` across result lists
+    ReciprocalRankFusion {
+        /// Smoothing constant (higher values flatten the influence of rank)
+        k: usize,
+    },
+}
+
+impl Default for FusionMode {
+    fn default() -> Self {
+        FusionMode::Convex
+    }
+}
+
+/// Hybrid dense+sparse (BM25-style) retrieval configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HybridSearchConfig {
+    /// Enable hybrid retrieval (dense vector search combined with keyword search)
+    pub enabled: bool,
+
+    /// Weight given to the dense vector score; 0.0 = pure keyword, 1.0 = pure vector.
+    /// Only used by `FusionMode::Convex`.
+    pub semantic_ratio: f32,
+
+    /// How dense and sparse result lists are merged into a single ranking
+    pub fusion: FusionMode,
+}
+
+impl Default for HybridSearchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            semantic_ratio: 0.5,
+            fusion: FusionMode::ReciprocalRankFusion { k: 60 },
+        }
+    }
+}
+
+/// How a cache entry's fingerprint is computed to guard against a hash
+/// collision in its `CacheKey` silently returning the wrong value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FingerprintMode {
+    /// Store the full original input text verbatim and compare it byte for
+    /// byte on every hit. Costs memory proportional to input size but can
+    /// never itself collide.
+    Full,
+    /// Store a blake3 digest of the input instead of the text itself.
+    /// Fixed-size and cryptographically strong, so a second collision (key
+    /// hash matches AND digest matches) is not a practical concern, at the
+    /// cost of a small amount of CPU per insert and lookup.
+    Blake3,
+}
+
+impl Default for FingerprintMode {
+    fn default() -> Self {
+        FingerprintMode::Full
+    }
+}
+
 /// Cache configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CacheConfig {
@@ -177,6 +594,190 @@ pub struct CacheConfig {
     
     /// Cache retrieval results
     pub cache_retrieval: bool,
+
+    /// Enable the disk-backed cold tier. When `false`, `RagCache` behaves
+    /// exactly as the in-memory-only cache did before
+    pub disk_tier_enabled: bool,
+
+    /// Sled database directory for the disk-backed cold tier
+    pub disk_path: PathBuf,
+
+    /// How often (in seconds) the background task increments the age
+    /// counter used to decide which in-memory entries are cold
+    pub age_tick_interval_secs: u64,
+
+    /// Number of age ticks an entry may go untouched before the flush task
+    /// persists it to disk and demotes it from the in-memory tier
+    pub flush_age: u64,
+
+    /// How cache entries fingerprint their original input to detect a
+    /// `CacheKey` collision and treat it as a miss rather than returning
+    /// the wrong value
+    pub fingerprint: FingerprintMode,
+
+    /// Bearer token required by the optional `cache::admin` HTTP surface.
+    /// `None` means the admin API is never authorized, so it must be set
+    /// explicitly to expose cache control over HTTP.
+    #[serde(default)]
+    pub admin_token: Option<String>,
+
+    /// Enable the semantic (embedding-similarity) retrieval cache, checked
+    /// in `retrieve_context` after the exact-match `retrieval` tier misses
+    /// but before the full search/rerank pipeline runs
+    pub semantic_cache_enabled: bool,
+
+    /// Cosine-similarity threshold above which an incoming query's
+    /// embedding is considered a match against a previously-cached query
+    /// embedding
+    pub semantic_similarity_threshold: f32,
+
+    /// Maximum number of query embeddings the semantic cache holds before
+    /// evicting the oldest
+    pub semantic_cache_max_size: usize,
+
+    /// Byte budget for the disk-backed cold tier across all three trees.
+    /// `None` leaves it unbounded. When exceeded, the background flush task
+    /// evicts the globally oldest disk entries (by `created_at_unix_secs`)
+    /// until back under budget
+    #[serde(default)]
+    pub disk_max_bytes: Option<u64>,
+
+    /// Connection string for the optional Redis-backed remote cache tier,
+    /// shared across every RAG worker behind a load balancer. Required
+    /// when `remote_cache_mode` is anything other than `Disabled`.
+    #[serde(default)]
+    pub redis_url: Option<String>,
+
+    /// Whether `RagCache` falls through to a shared Redis store once its
+    /// own in-memory and disk tiers miss
+    #[serde(default)]
+    pub remote_cache_mode: RemoteCacheMode,
+
+    /// Enable the background task that samples system memory and shrinks or
+    /// grows the in-memory tier's effective byte budget in response, rather
+    /// than enforcing only the fixed `max_size` entry count
+    #[serde(default)]
+    pub memory_pressure_enabled: bool,
+
+    /// How often (in seconds) the memory-pressure task samples available
+    /// system memory
+    #[serde(default = "default_memory_pressure_check_interval_secs")]
+    pub memory_pressure_check_interval_secs: u64,
+
+    /// Available-system-memory threshold, in MB, below which the
+    /// memory-pressure task shrinks the in-memory tier's budget to
+    /// `memory_budget_floor_bytes` and evicts down to it
+    #[serde(default = "default_memory_pressure_low_watermark_mb")]
+    pub memory_pressure_low_watermark_mb: u64,
+
+    /// The in-memory tier's byte budget while system memory is under
+    /// pressure
+    #[serde(default = "default_memory_budget_floor_bytes")]
+    pub memory_budget_floor_bytes: u64,
+
+    /// The in-memory tier's byte budget while system memory is abundant
+    #[serde(default = "default_memory_budget_ceiling_bytes")]
+    pub memory_budget_ceiling_bytes: u64,
+
+    /// Per-category byte budgets enforced independently of each other, so a
+    /// burst of large retrieval payloads can't evict cheap-to-reuse
+    /// embedding entries to make room for itself. `None` leaves every
+    /// category bounded only by the shared `max_size` entry count
+    #[serde(default)]
+    pub category_byte_budgets: Option<CacheSizes>,
+
+    /// Fraction of an entry's `ttl` that must have elapsed (but not yet
+    /// expired) before a `get_*_or_refresh` lookup serves the stale value
+    /// immediately and kicks off a background recompute to replace it.
+    /// `None` disables stale-while-revalidate; `get_*_or_refresh` then
+    /// behaves exactly like the corresponding plain `get_*`
+    #[serde(default)]
+    pub stale_while_revalidate_ratio: Option<f64>,
+
+    /// How often (in seconds) a background task captures a timestamped
+    /// `CacheStats` snapshot into `RagCache`'s ring buffer. `None` disables
+    /// snapshotting entirely, in which case `snapshot_history` is always
+    /// empty
+    #[serde(default)]
+    pub stats_snapshot_interval_secs: Option<u64>,
+
+    /// Maximum number of snapshots the ring buffer holds before dropping
+    /// the oldest
+    #[serde(default = "default_stats_snapshot_history_size")]
+    pub stats_snapshot_history_size: usize,
+
+    /// Which signal decides the eviction victim when an in-memory tier is
+    /// full and a new entry needs to evict one to make room
+    #[serde(default)]
+    pub eviction_policy: EvictionPolicy,
+}
+
+/// Which signal `RagCache` uses to pick the eviction victim when an
+/// in-memory tier is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EvictionPolicy {
+    /// The `lru` crate's own recency ordering: evict the least-recently-used
+    /// entry
+    Lru,
+    /// Evict the entry with the lowest access frequency (total touch count)
+    Lfu,
+    /// Evict the entry with the lowest blended frequency/recency
+    /// `cache_score()`
+    WeightedScore,
+}
+
+impl Default for EvictionPolicy {
+    fn default() -> Self {
+        EvictionPolicy::Lru
+    }
+}
+
+fn default_stats_snapshot_history_size() -> usize {
+    60
+}
+
+/// Maximum estimated byte footprint for each of `RagCache`'s three
+/// in-memory tiers, enforced independently by
+/// `CacheConfig::category_byte_budgets`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheSizes {
+    pub embeddings_max_bytes: u64,
+    pub reranking_max_bytes: u64,
+    pub retrieval_max_bytes: u64,
+}
+
+fn default_memory_pressure_check_interval_secs() -> u64 {
+    30
+}
+
+fn default_memory_pressure_low_watermark_mb() -> u64 {
+    512
+}
+
+fn default_memory_budget_floor_bytes() -> u64 {
+    64 * 1024 * 1024
+}
+
+fn default_memory_budget_ceiling_bytes() -> u64 {
+    512 * 1024 * 1024
+}
+
+/// How `RagCache` uses the optional Redis-backed remote cache tier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RemoteCacheMode {
+    /// No remote tier; `RagCache` behaves exactly as it did before Redis
+    /// support existed
+    Disabled,
+    /// Layer a small in-process cache over Redis: hot items are served
+    /// from memory without a network round trip, cold items fall through
+    /// to the store shared by every worker
+    MemoryOverRedis,
+}
+
+impl Default for RemoteCacheMode {
+    fn default() -> Self {
+        RemoteCacheMode::Disabled
+    }
 }
 
 impl Default for CacheConfig {
@@ -188,6 +789,28 @@ impl Default for CacheConfig {
             cache_embeddings: true,
             cache_reranking: true,
             cache_retrieval: true,
+            disk_tier_enabled: true,
+            disk_path: PathBuf::from("./rag_data/cache"),
+            age_tick_interval_secs: 60,
+            flush_age: 10,
+            fingerprint: FingerprintMode::default(),
+            admin_token: None,
+            semantic_cache_enabled: true,
+            semantic_similarity_threshold: 0.95,
+            semantic_cache_max_size: 1000,
+            disk_max_bytes: None,
+            redis_url: None,
+            remote_cache_mode: RemoteCacheMode::default(),
+            memory_pressure_enabled: false,
+            memory_pressure_check_interval_secs: default_memory_pressure_check_interval_secs(),
+            memory_pressure_low_watermark_mb: default_memory_pressure_low_watermark_mb(),
+            memory_budget_floor_bytes: default_memory_budget_floor_bytes(),
+            memory_budget_ceiling_bytes: default_memory_budget_ceiling_bytes(),
+            category_byte_budgets: None,
+            stale_while_revalidate_ratio: None,
+            stats_snapshot_interval_secs: None,
+            stats_snapshot_history_size: default_stats_snapshot_history_size(),
+            eviction_policy: EvictionPolicy::default(),
         }
     }
 }
@@ -212,6 +835,12 @@ pub struct PerformanceConfig {
     
     /// Enable parallel vector operations
     pub parallel_vector_ops: bool,
+
+    /// Capture per-stage span timings (chunking, embedding, search, reranking)
+    /// for the benchmark harness. Disabled by default since instrumentation
+    /// has a small overhead on every request.
+    #[serde(default)]
+    pub enable_span_capture: bool,
 }
 
 impl Default for PerformanceConfig {
@@ -223,6 +852,7 @@ impl Default for PerformanceConfig {
             parallel_chunking: true,
             parallel_embedding: true,
             parallel_vector_ops: true,
+            enable_span_capture: false,
         }
     }
 }
@@ -263,12 +893,15 @@ impl RagConfig {
             config.vector_store.db_path = PathBuf::from(db_path);
         }
         
-        if let Ok(embedding_endpoint) = std::env::var("RAG_EMBEDDING_ENDPOINT") {
-            config.embedding.endpoint = embedding_endpoint;
-        }
-        
-        if let Ok(embedding_key) = std::env::var("RAG_EMBEDDING_API_KEY") {
-            config.embedding.api_key = Some(embedding_key);
+        let default_embedder_name = config.embeddings.default_embedder.clone();
+        if let Some(default_embedder) = config.embeddings.embedders.get_mut(&default_embedder_name) {
+            if let Ok(embedding_endpoint) = std::env::var("RAG_EMBEDDING_ENDPOINT") {
+                default_embedder.endpoint = embedding_endpoint;
+            }
+
+            if let Ok(embedding_key) = std::env::var("RAG_EMBEDDING_API_KEY") {
+                default_embedder.api_key = Some(embedding_key);
+            }
         }
         
         if let Ok(rerank_endpoint) = std::env::var("RAG_RERANK_ENDPOINT") {
@@ -316,7 +949,119 @@ impl RagConfig {
                 "Reranking threshold must be between 0.0 and 1.0"
             ));
         }
-        
+
+        // Validate index-type-specific parameters
+        if self.vector_store.index_type == "HNSW" {
+            if self.vector_store.nlist.is_some() || self.vector_store.nprobe.is_some() {
+                return Err(crate::error::RagError::configuration(
+                    "nlist/nprobe are IVF parameters and cannot be set for index_type \"HNSW\""
+                ));
+            }
+
+            let hnsw = self.vector_store.hnsw.as_ref().ok_or_else(|| {
+                crate::error::RagError::configuration(
+                    "index_type \"HNSW\" requires hnsw parameters (m, ef_construction, ef_search)"
+                )
+            })?;
+
+            if hnsw.m == 0 || hnsw.ef_construction == 0 || hnsw.ef_search == 0 {
+                return Err(crate::error::RagError::configuration(
+                    "HNSW parameters (m, ef_construction, ef_search) must all be greater than 0"
+                ));
+            }
+
+            let max_top_k = crate::types::RetrievalMode::Research.max_chunks();
+            if hnsw.ef_search < max_top_k {
+                return Err(crate::error::RagError::configuration(format!(
+                    "HNSW ef_search ({}) must be >= the largest requested top_k ({})",
+                    hnsw.ef_search, max_top_k
+                )));
+            }
+        } else if self.vector_store.hnsw.is_some() {
+            return Err(crate::error::RagError::configuration(format!(
+                "hnsw parameters are only valid when index_type is \"HNSW\", got \"{}\"",
+                self.vector_store.index_type
+            )));
+        }
+
+        // Validate vector quantization config
+        if self.vector_store.quantization != QuantizationMode::None {
+            match self.vector_store.backend {
+                VectorStoreBackend::Sled | VectorStoreBackend::Faiss => {}
+            }
+
+            if self.vector_store.dimension == 0 {
+                return Err(crate::error::RagError::configuration(
+                    "Quantization requires a non-zero vector dimension"
+                ));
+            }
+
+            if let Some(multiplier) = self.vector_store.rescore_multiplier {
+                if multiplier == 0 {
+                    return Err(crate::error::RagError::configuration(
+                        "rescore_multiplier must be greater than 0"
+                    ));
+                }
+            }
+        }
+
+        // Validate hybrid search config
+        if self.hybrid_search.semantic_ratio < 0.0 || self.hybrid_search.semantic_ratio > 1.0 {
+            return Err(crate::error::RagError::configuration(
+                "Hybrid search semantic_ratio must be between 0.0 and 1.0"
+            ));
+        }
+
+        // Validate named embedders
+        if !self.embeddings.embedders.contains_key(&self.embeddings.default_embedder) {
+            return Err(crate::error::RagError::configuration(format!(
+                "default_embedder '{}' is not present in embedders",
+                self.embeddings.default_embedder
+            )));
+        }
+
+        for (name, embedder) in &self.embeddings.embedders {
+            if let Some(dimension) = embedder.dimension {
+                if dimension != self.vector_store.dimension {
+                    return Err(crate::error::RagError::configuration(format!(
+                        "Embedder '{}' dimension {} does not match vector store dimension {}",
+                        name, dimension, self.vector_store.dimension
+                    )));
+                }
+            }
+
+            if let Some(template) = &embedder.template {
+                crate::template::validate_template(template).map_err(|e| {
+                    crate::error::RagError::configuration(format!(
+                        "Embedder '{}' template is invalid: {}",
+                        name, e
+                    ))
+                })?;
+            }
+        }
+
+        // Validate generation config
+        if self.generation.max_context_tokens == 0 {
+            return Err(crate::error::RagError::configuration(
+                "Generation max_context_tokens must be greater than 0"
+            ));
+        }
+
+        if let Some(template) = &self.generation.prompt_template {
+            crate::template::validate_template(template).map_err(|e| {
+                crate::error::RagError::configuration(format!(
+                    "Generation prompt_template is invalid: {}",
+                    e
+                ))
+            })?;
+        }
+
+        if self.cache.remote_cache_mode != RemoteCacheMode::Disabled && self.cache.redis_url.is_none() {
+            return Err(crate::error::RagError::configuration(
+                "cache.redis_url is required when cache.remote_cache_mode is not Disabled"
+            ));
+        }
+
         Ok(())
     }
 }
\ No newline at end of file
@@ -1,13 +1,68 @@
 //! Embedding client for converting text to vectors
 
-use crate::config::EmbeddingConfig;
+use crate::config::{EmbeddingConfig, EmbeddingProviderKind};
 use crate::error::{RagError, RagResult};
+use crate::rate_limiter::RateLimiter;
+use crate::retry::RetryOutcome;
 use crate::types::{Chunk, Embedding};
+use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::process::Stdio;
+use std::sync::Arc;
 use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command as TokioCommand};
+use tokio::sync::Mutex;
 use tokio::time::timeout;
 
+/// A source of text-to-vector embeddings, abstracting over the remote HTTP
+/// backend, a local Ollama server, and the in-process mock used by tests, so
+/// `RagEngine` can run fully offline by swapping `EmbeddingConfig::provider`
+/// instead of being hard-wired to one concrete client.
+#[async_trait]
+pub trait EmbeddingProvider: Send + Sync {
+    /// Embed a batch of texts, returned in the same order as `texts`
+    async fn embed_batch(&self, texts: &[String]) -> RagResult<Vec<Embedding>>;
+
+    /// Embed a single text
+    async fn embed_single(&self, text: &str) -> RagResult<Embedding> {
+        let embeddings = self.embed_batch(&[text.to_string()]).await?;
+        embeddings
+            .into_iter()
+            .next()
+            .ok_or_else(|| RagError::embedding("No embedding returned for single text"))
+    }
+
+    /// Dimensionality of the vectors this provider produces
+    fn dimensions(&self) -> usize;
+
+    /// Identifier of the underlying model, for tagging chunks and configuration echo
+    fn model_id(&self) -> &str;
+}
+
+/// Normalize an embedding to unit length in place, so callers comparing
+/// vectors across different `EmbeddingProvider` backends can use a plain dot
+/// product rather than a full cosine calculation. Leaves a zero vector
+/// untouched rather than dividing by zero.
+fn normalize_in_place(embedding: &mut Embedding) {
+    let norm = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for v in embedding.iter_mut() {
+            *v /= norm;
+        }
+    }
+}
+
+/// Build the `EmbeddingProvider` backend selected by `config.provider`
+pub fn create_embedding_provider(name: impl Into<String>, config: EmbeddingConfig) -> RagResult<Arc<dyn EmbeddingProvider>> {
+    match config.provider {
+        EmbeddingProviderKind::Remote => Ok(Arc::new(EmbeddingClient::with_name(name, config)?)),
+        EmbeddingProviderKind::Ollama => Ok(Arc::new(OllamaEmbeddingProvider::new(config)?)),
+        EmbeddingProviderKind::Mock => Ok(Arc::new(MockEmbeddingProvider::new(config))),
+    }
+}
+
 /// Request structure for embedding API
 #[derive(Debug, Serialize)]
 struct EmbeddingRequest {
@@ -34,23 +89,169 @@ struct Usage {
     total_tokens: usize,
 }
 
+/// One line of the sidecar's newline-delimited JSON protocol, written
+/// back to stdout after each request
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum SidecarResponse {
+    Ok { embeddings: Vec<Vec<f32>> },
+    Err { error: String },
+}
+
+/// A long-lived `python3` process backing `python://` embedding endpoints:
+/// one interpreter (and one `RagEmbeddingClient` session) serves every
+/// batch for as long as the owning `EmbeddingClient` lives, instead of
+/// paying process startup and session setup cost per batch. Requests and
+/// responses are newline-delimited JSON over the child's stdin/stdout.
+struct EmbeddingSidecar {
+    /// Kept alive so the process is killed (via `kill_on_drop`) once this
+    /// sidecar is dropped, e.g. after a failed request triggers a respawn
+    _child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+}
+
+impl EmbeddingSidecar {
+    fn spawn(api_key: Option<&str>) -> RagResult<Self> {
+        let ai_dir = std::env::current_dir()
+            .unwrap_or_else(|_| std::path::PathBuf::from("."))
+            .join("AI");
+
+        let mut child = TokioCommand::new("python3")
+            .arg("-c")
+            .arg(sidecar_script(&ai_dir.to_string_lossy(), api_key))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| RagError::embedding(format!("Failed to start embedding sidecar: {}", e)))?;
+
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| RagError::embedding("Embedding sidecar has no stdin"))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or_else(|| RagError::embedding("Embedding sidecar has no stdout"))?;
+
+        Ok(Self { _child: child, stdin, stdout: BufReader::new(stdout) })
+    }
+
+    /// Send one batch as a single JSON request line and read back the
+    /// matching JSON response line
+    async fn embed(&mut self, texts: &[String]) -> RagResult<Vec<Embedding>> {
+        let mut line = serde_json::to_string(&serde_json::json!({ "texts": texts }))
+            .map_err(|e| RagError::embedding(format!("Failed to serialize sidecar request: {}", e)))?;
+        line.push('\n');
+
+        self.stdin
+            .write_all(line.as_bytes())
+            .await
+            .map_err(|e| RagError::embedding(format!("Failed to write to embedding sidecar: {}", e)))?;
+        self.stdin
+            .flush()
+            .await
+            .map_err(|e| RagError::embedding(format!("Failed to flush embedding sidecar: {}", e)))?;
+
+        let mut response_line = String::new();
+        let bytes_read = self
+            .stdout
+            .read_line(&mut response_line)
+            .await
+            .map_err(|e| RagError::embedding(format!("Failed to read from embedding sidecar: {}", e)))?;
+        if bytes_read == 0 {
+            return Err(RagError::embedding("Embedding sidecar closed its output unexpectedly"));
+        }
+
+        match serde_json::from_str(&response_line)
+            .map_err(|e| RagError::embedding(format!("Failed to parse sidecar response: {}", e)))?
+        {
+            SidecarResponse::Ok { embeddings } => Ok(embeddings),
+            SidecarResponse::Err { error } => Err(RagError::embedding(format!("Embedding sidecar error: {}", error))),
+        }
+    }
+}
+
+/// Python source for the persistent embedding sidecar: it opens one
+/// `RagEmbeddingClient` session, then loops reading a JSON request per
+/// stdin line and writing a JSON response per stdout line, so the async
+/// session and any model warm-up cost are paid once per process rather
+/// than once per batch.
+fn sidecar_script(ai_dir: &str, api_key: Option<&str>) -> String {
+    let api_key_literal = match api_key {
+        Some(key) => format!("'{}'", key.replace('\\', "\\\\").replace('\'', "\\'")),
+        None => "None".to_string(),
+    };
+
+    format!(
+        r#"
+import asyncio
+import json
+import sys
+sys.path.append('{ai_dir}')
+
+from rag_integration_client import RagEmbeddingClient, RagChunk
+
+async def main():
+    async with RagEmbeddingClient(api_key={api_key}) as client:
+        for line in sys.stdin:
+            line = line.strip()
+            if not line:
+                continue
+            try:
+                request = json.loads(line)
+                chunks = [
+                    RagChunk(id=f'chunk_{{i}}', content=text, token_count=len(text.split()))
+                    for i, text in enumerate(request['texts'])
+                ]
+                embedded_chunks = await client.embed_chunks_for_rag(chunks)
+                embeddings = [chunk.embedding for chunk in embedded_chunks]
+                print(json.dumps({{'embeddings': embeddings}}), flush=True)
+            except Exception as e:
+                print(json.dumps({{'error': str(e)}}), flush=True)
+
+if __name__ == '__main__':
+    asyncio.run(main())
+"#,
+        ai_dir = ai_dir,
+        api_key = api_key_literal,
+    )
+}
+
 /// Client for embedding operations
 pub struct EmbeddingClient {
     /// HTTP client
     client: Client,
     /// Configuration
     config: EmbeddingConfig,
+    /// Name this client is registered under in `EmbeddingsConfig`, used to tag chunks
+    name: String,
+    /// Lazily spawned on first `python://` call, then kept alive for the
+    /// rest of this client's lifetime instead of being re-spawned per batch
+    sidecar: Arc<Mutex<Option<EmbeddingSidecar>>>,
+    /// Built from `config.rate_limit`, if set, and acquired once per
+    /// request attempt before it goes out
+    rate_limiter: Option<Arc<RateLimiter>>,
 }
 
 impl EmbeddingClient {
     /// Create a new embedding client
     pub fn new(config: EmbeddingConfig) -> RagResult<Self> {
+        Self::with_name("default", config)
+    }
+
+    /// Create a new embedding client registered under the given name
+    pub fn with_name(name: impl Into<String>, config: EmbeddingConfig) -> RagResult<Self> {
         let client = Client::builder()
             .timeout(Duration::from_secs(config.timeout))
             .build()
             .map_err(|e| RagError::embedding(format!("Failed to create HTTP client: {}", e)))?;
-        
-        Ok(Self { client, config })
+
+        let rate_limiter = config.rate_limit.map(RateLimiter::new);
+
+        Ok(Self { client, config, name: name.into(), sidecar: Arc::new(Mutex::new(None)), rate_limiter })
     }
     
     /// Embed a single text
@@ -60,183 +261,165 @@ impl EmbeddingClient {
             .ok_or_else(|| RagError::embedding("No embedding returned for single text"))
     }
     
-    /// Embed multiple texts in batch
+    /// Embed multiple texts in batch. Every returned vector is normalized to
+    /// unit length so it's directly comparable to vectors from any other
+    /// `EmbeddingProvider` backend.
     pub async fn embed_batch(&self, texts: &[String]) -> RagResult<Vec<Embedding>> {
         if texts.is_empty() {
             return Ok(Vec::new());
         }
-        
+
         // Split into batches if necessary
         let mut all_embeddings = Vec::new();
-        
+
         for batch in texts.chunks(self.config.batch_size) {
             let batch_embeddings = self.embed_batch_internal(batch).await?;
             all_embeddings.extend(batch_embeddings);
         }
-        
+
+        for embedding in &mut all_embeddings {
+            normalize_in_place(embedding);
+        }
+
         Ok(all_embeddings)
     }
     
-    /// Internal batch embedding with retries
+    /// Internal batch embedding with retries. On a retryable failure (rate
+    /// limiting or a transient transport/server error), honors a
+    /// provider-supplied `Retry-After` delay when present, otherwise backs
+    /// off with full jitter, retrying up to `config.max_retries` times.
     async fn embed_batch_internal(&self, texts: &[String]) -> RagResult<Vec<Embedding>> {
         let request = EmbeddingRequest {
             input: texts.to_vec(),
             model: self.config.model.clone(),
         };
-        
+
         let mut last_error = None;
-        
+
         for attempt in 0..=self.config.max_retries {
+            if let Some(limiter) = &self.rate_limiter {
+                limiter.acquire(1.0).await;
+            }
+
             match self.make_embedding_request(&request).await {
                 Ok(embeddings) => return Ok(embeddings),
-                Err(e) => {
-                    last_error = Some(e);
+                Err(RetryOutcome::Fatal(error)) => return Err(error),
+                Err(RetryOutcome::Retryable { error, retry_after }) => {
                     if attempt < self.config.max_retries {
-                        // Exponential backoff
-                        let delay = Duration::from_millis(100 * (2_u64.pow(attempt as u32)));
-                        tokio::time::sleep(delay).await;
+                        crate::retry::wait_before_retry(attempt as u32, retry_after).await;
                     }
+                    last_error = Some(error);
                 }
             }
         }
-        
+
         Err(last_error.unwrap_or_else(|| RagError::embedding("Unknown error during embedding")))
     }
-    
+
     /// Make the actual HTTP request for embeddings
-    async fn make_embedding_request(&self, request: &EmbeddingRequest) -> RagResult<Vec<Embedding>> {
+    async fn make_embedding_request(&self, request: &EmbeddingRequest) -> Result<Vec<Embedding>, RetryOutcome> {
         // Check if we should use Python integration client
         if self.config.endpoint.contains("python://") {
-            return self.call_python_embedding_client(request).await;
+            return self.call_python_embedding_client(request).await.map_err(RetryOutcome::Fatal);
         }
-        
+
         let mut req_builder = self.client
             .post(&self.config.endpoint)
             .json(request);
-        
+
         // Add API key if configured
         if let Some(api_key) = &self.config.api_key {
             req_builder = req_builder.bearer_auth(api_key);
         }
-        
+
         let response = timeout(
             Duration::from_secs(self.config.timeout),
             req_builder.send()
         ).await
-        .map_err(|_| RagError::timeout("Embedding request timed out"))?
-        .map_err(|e| RagError::embedding(format!("HTTP request failed: {}", e)))?;
-        
+        .map_err(|_| RetryOutcome::Retryable { error: RagError::timeout("Embedding request timed out"), retry_after: None })?
+        .map_err(|e| RetryOutcome::Retryable { error: RagError::embedding(format!("HTTP request failed: {}", e)), retry_after: None })?;
+
         if !response.status().is_success() {
             let status = response.status();
+            let (retryable, retry_after) = crate::retry::classify_response(&response);
             let error_text = response.text().await
                 .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(RagError::embedding(format!(
+            let error = RagError::embedding(format!(
                 "Embedding API returned error {}: {}", status, error_text
-            )));
+            ));
+            return Err(if retryable {
+                RetryOutcome::Retryable { error, retry_after }
+            } else {
+                RetryOutcome::Fatal(error)
+            });
         }
-        
+
         let embedding_response: EmbeddingResponse = response.json().await
-            .map_err(|e| RagError::embedding(format!("Failed to parse response: {}", e)))?;
-        
+            .map_err(|e| RetryOutcome::Fatal(RagError::embedding(format!("Failed to parse response: {}", e))))?;
+
         // Sort by index to maintain order
         let mut data = embedding_response.data;
         data.sort_by_key(|d| d.index);
-        
+
         let embeddings: Vec<Embedding> = data.into_iter()
             .map(|d| d.embedding)
             .collect();
-        
+
         Ok(embeddings)
     }
     
-    /// Call Python embedding client for integration
+    /// Call the Python embedding sidecar for integration, spawning it on
+    /// first use and reusing the same process for every later batch. If a
+    /// batch fails for any reason (a broken pipe, a crashed interpreter, or
+    /// an error the sidecar reports), the sidecar is respawned once and the
+    /// batch is retried before giving up.
     async fn call_python_embedding_client(&self, request: &EmbeddingRequest) -> RagResult<Vec<Embedding>> {
-        use std::process::Command;
-        use serde_json;
-        
-        // Prepare request data for Python client
-        let python_request = serde_json::json!({
-            "texts": request.input,
-            "model": request.model,
-            "api_key": self.config.api_key
-        });
-        
-        // Call Python script
-        let output = Command::new("python3")
-            .arg("-c")
-            .arg(format!(r#"
-import asyncio
-import json
-import sys
-import os
-sys.path.append('{}')
-
-from rag_integration_client import RagEmbeddingClient, RagChunk
+        let mut guard = self.sidecar.lock().await;
+        if guard.is_none() {
+            *guard = Some(EmbeddingSidecar::spawn(self.config.api_key.as_deref())?);
+        }
 
-async def main():
-    request_data = json.loads('{}')
-    
-    # Create chunks from texts
-    chunks = []
-    for i, text in enumerate(request_data['texts']):
-        chunk = RagChunk(
-            id=f'chunk_{{i}}',
-            content=text,
-            token_count=len(text.split())
-        )
-        chunks.append(chunk)
-    
-    # Embed chunks
-    async with RagEmbeddingClient(api_key=request_data.get('api_key')) as client:
-        embedded_chunks = await client.embed_chunks_for_rag(chunks)
-        
-        # Extract embeddings
-        embeddings = [chunk.embedding for chunk in embedded_chunks]
-        print(json.dumps(embeddings))
+        match guard.as_mut().unwrap().embed(&request.input).await {
+            Ok(embeddings) => Ok(embeddings),
+            Err(first_err) => {
+                let sidecar = guard.insert(EmbeddingSidecar::spawn(self.config.api_key.as_deref())?);
+                sidecar.embed(&request.input).await.map_err(|retry_err| {
+                    RagError::embedding(format!(
+                        "Embedding sidecar retry failed: {} (original error: {})",
+                        retry_err, first_err
+                    ))
+                })
+            }
+        }
+    }
 
-if __name__ == '__main__':
-    asyncio.run(main())
-"#, 
-                std::env::current_dir()
-                    .unwrap_or_else(|_| std::path::PathBuf::from("."))
-                    .join("AI")
-                    .to_string_lossy(),
-                serde_json::to_string(&python_request)
-                    .map_err(|e| RagError::embedding(format!("Failed to serialize request: {}", e)))?
-            ))
-            .output()
-            .map_err(|e| RagError::embedding(format!("Failed to execute Python client: {}", e)))?;
-        
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
-            return Err(RagError::embedding(format!("Python client error: {}", error_msg)));
+    /// Render a chunk's text using the configured prompt template, if any,
+    /// falling back to the chunk's own content when no template is set
+    fn render_text(&self, chunk: &Chunk) -> RagResult<String> {
+        match &self.config.template {
+            Some(template) => crate::template::render_template(template, &chunk.content, &chunk.metadata),
+            None => Ok(chunk.content.clone()),
         }
-        
-        // Parse embeddings from output
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        let embeddings: Vec<Vec<f32>> = serde_json::from_str(&output_str)
-            .map_err(|e| RagError::embedding(format!("Failed to parse Python client output: {}", e)))?;
-        
-        Ok(embeddings)
     }
-    
+
     /// Embed chunks and update them with embeddings
     pub async fn embed_chunks(&self, chunks: &mut [Chunk]) -> RagResult<()> {
         if chunks.is_empty() {
             return Ok(());
         }
         
-        // Extract texts from chunks
+        // Render texts from chunks, applying the configured prompt template if any
         let texts: Vec<String> = chunks.iter()
-            .map(|chunk| chunk.content.clone())
-            .collect();
-        
+            .map(|chunk| self.render_text(chunk))
+            .collect::<RagResult<Vec<_>>>()?;
+
         // Get embeddings
         let embeddings = self.embed_batch(&texts).await?;
         
         // Update chunks with embeddings
         for (chunk, embedding) in chunks.iter_mut().zip(embeddings.into_iter()) {
             chunk.set_embedding(embedding);
+            chunk.set_embedder(self.name.clone());
         }
         
         Ok(())
@@ -288,6 +471,167 @@ if __name__ == '__main__':
     }
 }
 
+#[async_trait]
+impl EmbeddingProvider for EmbeddingClient {
+    async fn embed_batch(&self, texts: &[String]) -> RagResult<Vec<Embedding>> {
+        EmbeddingClient::embed_batch(self, texts).await
+    }
+
+    fn dimensions(&self) -> usize {
+        self.config.dimension.unwrap_or(0)
+    }
+
+    fn model_id(&self) -> &str {
+        &self.config.model
+    }
+}
+
+/// `EmbeddingProvider` backed by a local Ollama server's `/api/embeddings`
+/// endpoint. Ollama embeds one prompt per request, so a batch is a sequence
+/// of requests rather than one combined call.
+pub struct OllamaEmbeddingProvider {
+    client: Client,
+    endpoint: String,
+    model: String,
+    dimensions: usize,
+    timeout: Duration,
+    max_retries: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct OllamaEmbeddingRequest<'a> {
+    model: &'a str,
+    prompt: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaEmbeddingResponse {
+    embedding: Vec<f32>,
+}
+
+impl OllamaEmbeddingProvider {
+    pub fn new(config: EmbeddingConfig) -> RagResult<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(config.timeout))
+            .build()
+            .map_err(|e| RagError::embedding(format!("Failed to create Ollama HTTP client: {}", e)))?;
+
+        Ok(Self {
+            client,
+            endpoint: config.endpoint,
+            model: config.model,
+            dimensions: config.dimension.unwrap_or(4096),
+            timeout: Duration::from_secs(config.timeout),
+            max_retries: config.max_retries as u32,
+        })
+    }
+
+    /// Embed one prompt, normalizing the result to unit length. Errors are
+    /// classified through `RagError::is_retryable` (`Http`/`Timeout` are
+    /// retryable) by the `retry::retry_with_backoff` wrapper in
+    /// `embed_batch`, since Ollama's single-request-per-prompt API has no
+    /// `Retry-After` header to honor the way the batch HTTP providers do.
+    async fn embed_one(&self, text: &str) -> RagResult<Embedding> {
+        let request = OllamaEmbeddingRequest { model: &self.model, prompt: text };
+
+        let response = timeout(self.timeout, self.client.post(format!("{}/api/embeddings", self.endpoint)).json(&request).send())
+            .await
+            .map_err(|_| RagError::timeout("Ollama embedding request timed out"))?
+            .map_err(RagError::Http)?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(RagError::embedding(format!("Ollama returned error {}: {}", status, error_text)));
+        }
+
+        let mut parsed: OllamaEmbeddingResponse = response
+            .json()
+            .await
+            .map_err(|e| RagError::embedding(format!("Failed to parse Ollama response: {}", e)))?;
+        normalize_in_place(&mut parsed.embedding);
+        Ok(parsed.embedding)
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OllamaEmbeddingProvider {
+    async fn embed_batch(&self, texts: &[String]) -> RagResult<Vec<Embedding>> {
+        let mut embeddings = Vec::with_capacity(texts.len());
+
+        for text in texts {
+            let embedding = crate::retry::retry_with_backoff(self.max_retries, || self.embed_one(text)).await?;
+            embeddings.push(embedding);
+        }
+
+        Ok(embeddings)
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model
+    }
+}
+
+/// In-process deterministic `EmbeddingProvider` with no network or
+/// subprocess, for tests and fully offline operation. Each text hashes to a
+/// fixed-size unit vector via blake3, so the same text always embeds to the
+/// same vector and unrelated texts embed to near-orthogonal vectors.
+pub struct MockEmbeddingProvider {
+    model: String,
+    dimensions: usize,
+}
+
+impl MockEmbeddingProvider {
+    pub fn new(config: EmbeddingConfig) -> Self {
+        Self {
+            model: config.model,
+            dimensions: config.dimension.unwrap_or(8),
+        }
+    }
+
+    fn embed_one(&self, text: &str) -> Embedding {
+        let mut vector = Vec::with_capacity(self.dimensions);
+        let mut counter: u64 = 0;
+        while vector.len() < self.dimensions {
+            let hash = blake3::hash(format!("{}:{}", counter, text).as_bytes());
+            for byte in hash.as_bytes() {
+                if vector.len() >= self.dimensions {
+                    break;
+                }
+                vector.push((*byte as f32 / 255.0) * 2.0 - 1.0);
+            }
+            counter += 1;
+        }
+
+        let norm = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            for v in vector.iter_mut() {
+                *v /= norm;
+            }
+        }
+        vector
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for MockEmbeddingProvider {
+    async fn embed_batch(&self, texts: &[String]) -> RagResult<Vec<Embedding>> {
+        Ok(texts.iter().map(|text| self.embed_one(text)).collect())
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -329,9 +673,30 @@ mod tests {
     async fn test_empty_batch() {
         let config = EmbeddingConfig::default();
         let client = EmbeddingClient::new(config).unwrap();
-        
+
         let result = client.embed_batch(&[]).await;
         assert!(result.is_ok());
         assert!(result.unwrap().is_empty());
     }
+
+    #[test]
+    fn test_normalize_in_place_produces_unit_vectors() {
+        let mut embedding = vec![3.0, 4.0];
+        normalize_in_place(&mut embedding);
+        let norm: f32 = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-6);
+
+        // A zero vector is left untouched rather than dividing by zero
+        let mut zero = vec![0.0, 0.0];
+        normalize_in_place(&mut zero);
+        assert_eq!(zero, vec![0.0, 0.0]);
+    }
+
+    #[tokio::test]
+    async fn test_mock_provider_is_a_valid_embedding_provider() {
+        let provider: Arc<dyn EmbeddingProvider> = Arc::new(MockEmbeddingProvider::new(EmbeddingConfig::default()));
+        let embeddings = provider.embed_batch(&["hello".to_string(), "world".to_string()]).await.unwrap();
+        assert_eq!(embeddings.len(), 2);
+        assert_eq!(embeddings[0].len(), provider.dimensions());
+    }
 }
\ No newline at end of file
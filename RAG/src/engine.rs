@@ -1,31 +1,69 @@
 //! Main RAG engine implementation
 
+use crate::benchmark::{StageReport, StageTimings};
 use crate::cache::RagCache;
 use crate::chunker::SmartChunker;
-use crate::config::RagConfig;
-use crate::embedder::EmbeddingClient;
-use crate::error::{RagError, RagResult};
+use crate::config::{DedupPolicy, RagConfig};
+use crate::embedder::{create_embedding_provider, EmbeddingProvider};
+use crate::error::{ErrorFrame, RagError, RagResult};
+use crate::generation::{GeneratedAnswer, GenerationClient};
+use crate::memory_pool::{MemoryPool, PoolPolicy};
 use crate::reranker::RerankingClient;
 use crate::types::{
-    Chunk, Document, DocumentId, RetrievalMode, RetrievalResult, RagStats,
+    Chunk, Document, DocumentId, Embedding, IndexingStatus, RetrievalMode, RetrievalResult, RagStats,
 };
 use crate::vector_store::{create_vector_store, VectorStore};
 // use rayon::prelude::*;  // Commented out for now
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{Notify, RwLock};
 use tracing::{debug, info, warn};
 
+/// Target token budget per coalesced embedding batch in the background
+/// indexing worker. Rather than a fixed chunk count, ingests are grouped so
+/// each embedding request stays near this volume, following the same
+/// "fill the request" approach as provider batch limits elsewhere.
+const INDEXING_TOKEN_BUDGET: usize = 8_000;
+
+/// How long the background indexing worker waits for more chunks to
+/// coalesce into the current batch before giving up and embedding what it
+/// has. Keeps a burst of rapid ingests from each paying for its own
+/// embedding request.
+const INDEXING_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// Below this chunk count, `ingest_document` just hands its one job to the
+/// shared background worker rather than paying the overhead of sharding:
+/// there's nothing to parallelize in a handful of chunks.
+const SHARDED_INGEST_MIN_CHUNKS: usize = 32;
+
+/// A chunked document handed off to the background indexing worker.
+struct IndexingJob {
+    document: Document,
+    chunks: Vec<Chunk>,
+    /// Set by `ingest_document_with_embeddings`/`ingest_chunks_with_embeddings`:
+    /// `chunks` already carry caller-supplied embeddings, so the worker
+    /// skips rendering/embedding them and goes straight to the vector-store
+    /// write
+    pre_embedded: bool,
+    /// blake3 fingerprint of the document's content, recorded into
+    /// `content_hashes` once indexing succeeds
+    content_hash: [u8; 32],
+}
+
 /// Main RAG engine that orchestrates all components
 pub struct RagEngine {
     /// Smart chunker for document processing
-    chunker: SmartChunker,
-    /// Embedding client for vector generation
-    embedder: EmbeddingClient,
+    chunker: Arc<SmartChunker>,
+    /// Embedding providers by name, as configured in `EmbeddingsConfig`
+    embedders: HashMap<String, Arc<dyn EmbeddingProvider>>,
+    /// Name of the embedder used when ingesting and querying by default
+    default_embedder: String,
     /// Reranking client for result scoring
     reranker: RerankingClient,
+    /// Answer generation client backing `generate_answer`
+    generator: GenerationClient,
     /// Vector store for similarity search
-    vector_store: Box<dyn VectorStore>,
+    vector_store: Arc<dyn VectorStore>,
     /// Cache for performance optimization
     cache: RagCache,
     /// Document metadata storage
@@ -34,6 +72,27 @@ pub struct RagEngine {
     config: RagConfig,
     /// Engine statistics
     stats: Arc<RwLock<RagStats>>,
+    /// Per-stage span timings, populated when `performance.enable_span_capture` is set
+    stage_timings: Arc<StageTimings>,
+    /// Indexing progress for documents enqueued via `ingest_document`, kept
+    /// around after completion so `await_indexed` can be called late
+    indexing_status: Arc<RwLock<HashMap<DocumentId, IndexingStatus>>>,
+    /// Wakes `await_indexed` callers whenever the background worker
+    /// finishes a batch
+    indexing_notify: Arc<Notify>,
+    /// Hands chunked documents to the background indexing worker
+    indexing_tx: tokio::sync::mpsc::UnboundedSender<IndexingJob>,
+    /// Documents indexed with caller-supplied embeddings rather than the
+    /// configured embedder, mirrored into `RagStats::user_provided_embedding_count`
+    user_provided_embeddings: Arc<RwLock<HashSet<DocumentId>>>,
+    /// blake3 fingerprint of every successfully-indexed document's content,
+    /// keyed by hash, so `ingest_document` can apply `RagConfig::dedup_policy`
+    /// without re-chunking/re-embedding content that's already stored
+    content_hashes: Arc<RwLock<HashMap<[u8; 32], DocumentId>>>,
+    /// Budget enforcer for large allocations (context assembly, batch
+    /// embedding), built from `PerformanceConfig::max_memory_mb`. `None`
+    /// when unset, matching that field's "no limit" default.
+    memory_pool: Option<Arc<MemoryPool>>,
 }
 
 impl RagEngine {
@@ -45,27 +104,384 @@ impl RagEngine {
         info!("Initializing RAG engine with config: {:?}", config);
         
         // Initialize components
-        let chunker = SmartChunker::with_default_tokenizer(config.chunking.clone()).await?;
-        let embedder = EmbeddingClient::new(config.embedding.clone())?;
+        let chunker = Arc::new(match &config.chunking.tokenizer_path {
+            Some(path) => SmartChunker::from_tokenizer_file(path, config.chunking.clone())?,
+            None => SmartChunker::with_default_tokenizer(config.chunking.clone()).await?,
+        });
+        let mut embedders = HashMap::new();
+        for (name, embedder_config) in &config.embeddings.embedders {
+            embedders.insert(name.clone(), create_embedding_provider(name.clone(), embedder_config.clone())?);
+        }
+        let default_embedder = config.embeddings.default_embedder.clone();
         let reranker = RerankingClient::new(config.reranking.clone())?;
-        let vector_store = create_vector_store(config.vector_store.clone()).await?;
-        let cache = RagCache::new(config.cache.clone())?;
-        
+        let generator = GenerationClient::new(config.generation.clone())?;
+        let vector_store: Arc<dyn VectorStore> = Arc::from(create_vector_store(config.vector_store.clone()).await?);
+        let cache = RagCache::new(config.cache.clone()).await?;
+
         // Initialize storage
         let documents = Arc::new(RwLock::new(HashMap::new()));
         let stats = Arc::new(RwLock::new(RagStats::default()));
-        
+        let indexing_status = Arc::new(RwLock::new(HashMap::new()));
+        let indexing_notify = Arc::new(Notify::new());
+        let user_provided_embeddings = Arc::new(RwLock::new(HashSet::new()));
+        let content_hashes = Arc::new(RwLock::new(HashMap::new()));
+        let memory_pool = config
+            .performance
+            .max_memory_mb
+            .map(|limit_mb| MemoryPool::new(limit_mb as f64, PoolPolicy::Greedy));
+
+        let embedder = embedders.get(&default_embedder).cloned().ok_or_else(|| {
+            RagError::configuration(format!(
+                "default_embedder '{}' is not present in embedders",
+                default_embedder
+            ))
+        })?;
+        let embedder_template = config
+            .embeddings
+            .embedders
+            .get(&default_embedder)
+            .and_then(|c| c.template.clone());
+
+        let (indexing_tx, indexing_rx) = tokio::sync::mpsc::unbounded_channel::<IndexingJob>();
+        tokio::spawn(Self::run_indexing_worker(
+            indexing_rx,
+            Arc::clone(&vector_store),
+            Arc::clone(&documents),
+            Arc::clone(&stats),
+            Arc::clone(&indexing_status),
+            Arc::clone(&indexing_notify),
+            Arc::clone(&user_provided_embeddings),
+            Arc::clone(&content_hashes),
+            embedder,
+            default_embedder.clone(),
+            embedder_template,
+            memory_pool.clone(),
+        ));
+
         Ok(Self {
             chunker,
-            embedder,
+            embedders,
+            default_embedder,
             reranker,
+            generator,
             vector_store,
             cache,
             documents,
             config,
             stats,
+            stage_timings: Arc::new(StageTimings::new()),
+            indexing_status,
+            indexing_notify,
+            indexing_tx,
+            user_provided_embeddings,
+            content_hashes,
+            memory_pool,
+        })
+    }
+
+    /// Reserve `byte_len` bytes against `memory_pool` on behalf of
+    /// `consumer`, if a pool is configured. Returns `None` when no pool is
+    /// attached, in which case there's nothing to hold and nothing to
+    /// release.
+    fn reserve_memory(
+        memory_pool: &Option<Arc<MemoryPool>>,
+        consumer: &str,
+        byte_len: usize,
+    ) -> RagResult<Option<crate::memory_pool::MemoryReservation>> {
+        match memory_pool {
+            Some(pool) => {
+                let amount_mb = (byte_len as f64 / (1024.0 * 1024.0)).max(0.001);
+                pool.reserve(consumer, amount_mb).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// blake3 fingerprint of a document's content, used to key
+    /// `content_hashes`. Content is trimmed first so incidental leading or
+    /// trailing whitespace doesn't defeat deduplication.
+    fn content_fingerprint(content: &str) -> [u8; 32] {
+        *blake3::hash(content.trim().as_bytes()).as_bytes()
+    }
+
+    /// Get the configured default embedding provider
+    fn embedder(&self) -> RagResult<&Arc<dyn EmbeddingProvider>> {
+        self.embedders.get(&self.default_embedder).ok_or_else(|| {
+            RagError::configuration(format!(
+                "default_embedder '{}' is not present in embedders",
+                self.default_embedder
+            ))
         })
     }
+
+    /// Background worker backing `ingest_document`'s enqueue-and-return
+    /// contract. Coalesces jobs that arrive within `INDEXING_DEBOUNCE` of
+    /// each other, up to `INDEXING_TOKEN_BUDGET` tokens, into a single
+    /// embedding request, then commits each document's embeddings, vector-
+    /// store write, and metadata update together so a crash never leaves a
+    /// document half-indexed.
+    async fn run_indexing_worker(
+        mut rx: tokio::sync::mpsc::UnboundedReceiver<IndexingJob>,
+        vector_store: Arc<dyn VectorStore>,
+        documents: Arc<RwLock<HashMap<DocumentId, Document>>>,
+        stats: Arc<RwLock<RagStats>>,
+        indexing_status: Arc<RwLock<HashMap<DocumentId, IndexingStatus>>>,
+        indexing_notify: Arc<Notify>,
+        user_provided_embeddings: Arc<RwLock<HashSet<DocumentId>>>,
+        content_hashes: Arc<RwLock<HashMap<[u8; 32], DocumentId>>>,
+        embedder: Arc<dyn EmbeddingProvider>,
+        embedder_name: String,
+        template: Option<String>,
+        memory_pool: Option<Arc<MemoryPool>>,
+    ) {
+        while let Some(first) = rx.recv().await {
+            let mut batch_tokens: usize = first.chunks.iter().map(|c| c.token_count).sum();
+            let mut batch = vec![first];
+
+            while batch_tokens < INDEXING_TOKEN_BUDGET {
+                match tokio::time::timeout(INDEXING_DEBOUNCE, rx.recv()).await {
+                    Ok(Some(job)) => {
+                        batch_tokens += job.chunks.iter().map(|c| c.token_count).sum::<usize>();
+                        batch.push(job);
+                    }
+                    _ => break,
+                }
+            }
+
+            // Render every chunk that still needs embedding up front so
+            // they share a single embedding request. Each job is rendered
+            // independently: a template error in one document only fails
+            // that document and excludes it from the shared text, instead
+            // of aborting rendering for the whole coalesced batch. Jobs
+            // whose chunks already carry caller-supplied embeddings
+            // (`pre_embedded`) contribute no text and carry an empty range.
+            let mut texts = Vec::new();
+            let mut jobs = Vec::with_capacity(batch.len());
+            for job in batch {
+                if job.pre_embedded {
+                    jobs.push((job, 0..0));
+                    continue;
+                }
+
+                let start = texts.len();
+                let mut render_err = None;
+                for chunk in &job.chunks {
+                    let rendered = match &template {
+                        Some(template) => crate::template::render_template(template, &chunk.content, &chunk.metadata),
+                        None => Ok(chunk.content.clone()),
+                    };
+                    match rendered {
+                        Ok(text) => texts.push(text),
+                        Err(e) => {
+                            render_err = Some(e);
+                            break;
+                        }
+                    }
+                }
+
+                match render_err {
+                    Some(e) => {
+                        texts.truncate(start);
+                        warn!("Background indexing failed to render document {}: {}", job.document.id, e);
+                        indexing_status.write().await.insert(job.document.id, IndexingStatus::Failed(e.to_string()));
+                    }
+                    None => jobs.push((job, start..texts.len())),
+                }
+            }
+
+            let batch_start = std::time::Instant::now();
+            let embed_result = if texts.is_empty() {
+                Ok(Vec::new())
+            } else {
+                let texts_bytes: usize = texts.iter().map(|t| t.len()).sum();
+                match Self::reserve_memory(&memory_pool, "background_indexing_batch_embed", texts_bytes) {
+                    Ok(_reservation) => embedder.embed_batch(&texts).await.map_err(|e| {
+                        e.with_frame(
+                            ErrorFrame::new("while embedding background indexing batch")
+                                .with_provider(embedder_name.clone())
+                                .with_elapsed(batch_start.elapsed()),
+                        )
+                    }),
+                    Err(e) => Err(e),
+                }
+            };
+
+            match embed_result {
+                Ok(embeddings) => {
+                    for (job, text_range) in jobs {
+                        let job_embeddings = if job.pre_embedded { None } else { Some(&embeddings[text_range]) };
+                        Self::finish_indexing_job(
+                            job,
+                            job_embeddings,
+                            &embedder_name,
+                            &vector_store,
+                            &documents,
+                            &stats,
+                            &indexing_status,
+                            &user_provided_embeddings,
+                            &content_hashes,
+                        )
+                        .await;
+                    }
+                }
+                Err(e) => {
+                    // A batch-wide embed failure doesn't mean every
+                    // document in it is unembeddable, so retry each job
+                    // against its own text slice instead of failing the
+                    // whole coalesced set on one bad response.
+                    warn!("Background indexing batch failed to embed, retrying per document: {}", e);
+                    for (job, text_range) in jobs {
+                        if job.pre_embedded {
+                            Self::finish_indexing_job(
+                                job,
+                                None,
+                                &embedder_name,
+                                &vector_store,
+                                &documents,
+                                &stats,
+                                &indexing_status,
+                                &user_provided_embeddings,
+                                &content_hashes,
+                            )
+                            .await;
+                            continue;
+                        }
+
+                        let document_id = job.document.id;
+                        let job_bytes: usize = texts[text_range.clone()].iter().map(|t| t.len()).sum();
+                        let reservation = Self::reserve_memory(&memory_pool, "background_indexing_job_embed", job_bytes);
+                        let job_embed_result = match reservation {
+                            Ok(_reservation) => embedder.embed_batch(&texts[text_range]).await,
+                            Err(e) => Err(e),
+                        };
+                        match job_embed_result {
+                            Ok(job_embeddings) => {
+                                Self::finish_indexing_job(
+                                    job,
+                                    Some(&job_embeddings),
+                                    &embedder_name,
+                                    &vector_store,
+                                    &documents,
+                                    &stats,
+                                    &indexing_status,
+                                    &user_provided_embeddings,
+                                    &content_hashes,
+                                )
+                                .await;
+                            }
+                            Err(e) => {
+                                warn!("Background indexing failed to embed document {}: {}", document_id, e);
+                                indexing_status.write().await.insert(document_id, IndexingStatus::Failed(e.to_string()));
+                            }
+                        }
+                    }
+                }
+            }
+
+            indexing_notify.notify_waiters();
+        }
+    }
+
+    /// Store a rendered-and-embedded job's chunks and update document/stats
+    /// bookkeeping, or mark it `Failed` if the vector-store write itself
+    /// fails. Shared by `run_indexing_worker`'s batch-embed success path
+    /// and its per-document retry after a batch-wide embed failure, so a
+    /// job is committed the same way regardless of which path embedded it.
+    async fn finish_indexing_job(
+        mut job: IndexingJob,
+        job_embeddings: Option<&[Embedding]>,
+        embedder_name: &str,
+        vector_store: &Arc<dyn VectorStore>,
+        documents: &Arc<RwLock<HashMap<DocumentId, Document>>>,
+        stats: &Arc<RwLock<RagStats>>,
+        indexing_status: &Arc<RwLock<HashMap<DocumentId, IndexingStatus>>>,
+        user_provided_embeddings: &Arc<RwLock<HashSet<DocumentId>>>,
+        content_hashes: &Arc<RwLock<HashMap<[u8; 32], DocumentId>>>,
+    ) {
+        let document_id = job.document.id;
+        let pre_embedded = job.pre_embedded;
+        let content_hash = job.content_hash;
+
+        if let Some(job_embeddings) = job_embeddings {
+            for (chunk, embedding) in job.chunks.iter_mut().zip(job_embeddings.iter().cloned()) {
+                chunk.set_embedding(embedding);
+                chunk.set_embedder(embedder_name.to_string());
+            }
+        }
+
+        match vector_store.store_chunks(&job.chunks).await {
+            Ok(()) => {
+                for chunk in &job.chunks {
+                    job.document.add_chunk(chunk.id);
+                }
+
+                {
+                    let mut documents = documents.write().await;
+                    documents.insert(document_id, job.document);
+                }
+                {
+                    let mut stats = stats.write().await;
+                    stats.document_count += 1;
+                    stats.chunk_count += job.chunks.len();
+                    stats.embedding_count += job.chunks.iter().filter(|c| c.has_embedding()).count();
+                    if pre_embedded {
+                        stats.user_provided_embedding_count += 1;
+                    }
+
+                    let total_tokens: usize = job.chunks.iter().map(|c| c.token_count).sum();
+                    let new_avg = total_tokens as f32 / job.chunks.len() as f32;
+                    stats.avg_chunk_size = if stats.chunk_count == job.chunks.len() {
+                        new_avg
+                    } else {
+                        (stats.avg_chunk_size * (stats.chunk_count - job.chunks.len()) as f32 + total_tokens as f32) / stats.chunk_count as f32
+                    };
+                    stats.total_tokens += total_tokens;
+
+                    stats.last_updated = chrono::Utc::now();
+                }
+
+                if pre_embedded {
+                    user_provided_embeddings.write().await.insert(document_id);
+                }
+
+                content_hashes.write().await.insert(content_hash, document_id);
+                indexing_status.write().await.insert(document_id, IndexingStatus::Done);
+                info!("Background-indexed document {} with {} chunks", document_id, job.chunks.len());
+            }
+            Err(e) => {
+                warn!("Background indexing failed to store document {}: {}", document_id, e);
+                indexing_status.write().await.insert(document_id, IndexingStatus::Failed(e.to_string()));
+            }
+        }
+    }
+
+    /// Block until a document enqueued via `ingest_document` has finished
+    /// background indexing, returning its final status.
+    pub async fn await_indexed(&self, document_id: DocumentId) -> RagResult<IndexingStatus> {
+        loop {
+            let notified = self.indexing_notify.notified();
+
+            match self.indexing_status.read().await.get(&document_id).cloned() {
+                Some(IndexingStatus::Pending) => {}
+                Some(status) => return Ok(status),
+                None => return Err(RagError::document_not_found(document_id.to_string())),
+            }
+
+            notified.await;
+        }
+    }
+
+    /// Record a stage's duration when span capture is enabled
+    fn record_stage(&self, stage: &str, duration: std::time::Duration) {
+        if self.config.performance.enable_span_capture {
+            self.stage_timings.record(stage, duration);
+        }
+    }
+
+    /// Snapshot per-stage span timings captured since the engine was created
+    pub fn stage_timings(&self) -> StageReport {
+        self.stage_timings.report()
+    }
     
     /// Create a RAG engine with default configuration
     pub async fn with_default_config() -> RagResult<Self> {
@@ -73,68 +489,341 @@ impl RagEngine {
         Self::new(config).await
     }
     
-    /// Ingest a document into the RAG system
+    /// Ingest a document into the RAG system.
+    ///
+    /// Chunking happens synchronously, so invalid input is rejected before
+    /// this call returns. Small documents then hand embedding, vector-store
+    /// writes, and stats updates off to a background worker that coalesces
+    /// this document with any others ingested around the same time into
+    /// shared embedding batches. Documents chunking into at least
+    /// `SHARDED_INGEST_MIN_CHUNKS` chunks instead take the `ingest_sharded`
+    /// fast path, which embeds concurrently across
+    /// `compute_shard_plan`-sized shards and performs a single merged
+    /// vector-store write rather than waiting behind the shared worker. The
+    /// returned `DocumentId` is searchable only once indexing finishes;
+    /// call `await_indexed` if the caller needs that guarantee before
+    /// proceeding.
     pub async fn ingest_document(&self, content: String, metadata: HashMap<String, String>) -> RagResult<DocumentId> {
         if content.trim().is_empty() {
             return Err(RagError::invalid_input("Document content cannot be empty"));
         }
-        
+
         info!("Ingesting document with {} characters", content.len());
-        
+
+        let content_hash = Self::content_fingerprint(&content);
+        match self.config.dedup_policy {
+            DedupPolicy::Skip => {
+                if let Some(existing_id) = self.content_hashes.read().await.get(&content_hash).copied() {
+                    info!("Skipping ingest: content hash already stored as document {}", existing_id);
+                    self.stats.write().await.deduplicated_count += 1;
+                    return Ok(existing_id);
+                }
+            }
+            DedupPolicy::Replace => {
+                let existing_id = self.content_hashes.read().await.get(&content_hash).copied();
+                if let Some(existing_id) = existing_id {
+                    info!("Replacing existing document {}: content hash matches new ingest", existing_id);
+                    self.delete_document(existing_id).await?;
+                }
+            }
+            DedupPolicy::Allow => {}
+        }
+
         // Create document
-        let mut document = Document::new(content.clone(), metadata);
+        let document = Document::new(content.clone(), metadata);
         let document_id = document.id;
-        
+
         // Chunk the document
-        let mut chunks = self.chunker.chunk_document(document_id, &content).await?;
-        
+        let chunk_start = std::time::Instant::now();
+        let mut chunks = self
+            .chunker
+            .chunk_document(document_id, &content)
+            .await
+            .map_err(|e| e.with_context("while chunking document", document_id))?;
+        self.record_stage("chunking", chunk_start.elapsed());
+
         if chunks.is_empty() {
             warn!("No chunks generated for document {}", document_id);
+            self.indexing_status.write().await.insert(document_id, IndexingStatus::Done);
             return Ok(document_id);
         }
-        
+
         info!("Generated {} chunks for document {}", chunks.len(), document_id);
-        
-        // Generate embeddings for chunks
-        self.embedder.embed_chunks(&mut chunks).await?;
-        
-        // Store chunks in vector store
-        self.vector_store.store_chunks(&chunks).await?;
-        
-        // Update document with chunk IDs
-        for chunk in &chunks {
-            document.add_chunk(chunk.id);
+
+        // Make document metadata available to embedding prompt templates,
+        // without overwriting chunk-specific keys like chunk_size/overlap
+        for chunk in &mut chunks {
+            for (key, value) in &document.metadata {
+                chunk.metadata.entry(key.clone()).or_insert_with(|| value.clone());
+            }
         }
-        
-        // Store document metadata
-        {
-            let mut documents = self.documents.write().await;
-            documents.insert(document_id, document);
+
+        if chunks.len() >= SHARDED_INGEST_MIN_CHUNKS {
+            return self.ingest_sharded(document, chunks).await;
         }
-        
-        // Update statistics
-        {
-            let mut stats = self.stats.write().await;
-            stats.document_count += 1;
-            stats.chunk_count += chunks.len();
-            stats.embedding_count += chunks.iter().filter(|c| c.has_embedding()).count();
-            
-            // Update average chunk size
-            let total_tokens: usize = chunks.iter().map(|c| c.token_count).sum();
-            let new_avg = total_tokens as f32 / chunks.len() as f32;
-            stats.avg_chunk_size = if stats.chunk_count == chunks.len() {
-                new_avg
-            } else {
-                (stats.avg_chunk_size * (stats.chunk_count - chunks.len()) as f32 + total_tokens as f32) / stats.chunk_count as f32
-            };
-            
-            stats.last_updated = chrono::Utc::now();
+
+        self.indexing_status.write().await.insert(document_id, IndexingStatus::Pending);
+        self.indexing_tx
+            .send(IndexingJob { document, chunks, pre_embedded: false, content_hash })
+            .map_err(|_| RagError::generic("background indexing worker has stopped"))?;
+
+        Ok(document_id)
+    }
+
+    /// Per-shard embedding batch size (chunks per `embed_batch` call) for a
+    /// document with `chunk_count` chunks, following Meilisearch's approach
+    /// of sizing indexing batches off input size and the number of indexing
+    /// threads rather than one fixed constant: divide the chunks evenly
+    /// across up to `available_parallelism()` shards.
+    fn compute_shard_plan(chunk_count: usize) -> (usize, usize) {
+        let parallelism = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+        let shards = parallelism.min(chunk_count).max(1);
+        let batch_size = chunk_count.div_ceil(shards).max(1);
+        (shards, batch_size)
+    }
+
+    /// Fast path for large documents (or, transitively, bulk ingests): shard
+    /// `chunks` into `compute_shard_plan`-sized groups and embed each shard
+    /// concurrently, then perform a single merged `vector_store.store_chunks`
+    /// write. This removes the serial bottleneck of handing one huge batch
+    /// to the shared background worker, while keeping each embedding
+    /// request near the provider's optimal payload size.
+    async fn ingest_sharded(&self, mut document: Document, chunks: Vec<Chunk>) -> RagResult<DocumentId> {
+        let document_id = document.id;
+        let content_hash = Self::content_fingerprint(&document.content);
+        let (shard_count, batch_size) = Self::compute_shard_plan(chunks.len());
+
+        info!(
+            "Sharding document {} ({} chunks) across {} shard(s) of up to {} chunks",
+            document_id, chunks.len(), shard_count, batch_size
+        );
+
+        self.indexing_status.write().await.insert(document_id, IndexingStatus::Pending);
+
+        let embedder = Arc::clone(self.embedder()?);
+        let embedder_name = self.default_embedder.clone();
+        let template = self
+            .config
+            .embeddings
+            .embedders
+            .get(&self.default_embedder)
+            .and_then(|c| c.template.clone());
+
+        let mut shard_tasks = Vec::with_capacity(shard_count);
+        for shard in chunks.chunks(batch_size).map(|s| s.to_vec()) {
+            let embedder = Arc::clone(&embedder);
+            let embedder_name = embedder_name.clone();
+            let template = template.clone();
+            let memory_pool = self.memory_pool.clone();
+            shard_tasks.push(tokio::spawn(async move {
+                let mut shard = shard;
+                let mut texts = Vec::with_capacity(shard.len());
+                for chunk in &shard {
+                    let rendered = match &template {
+                        Some(template) => crate::template::render_template(template, &chunk.content, &chunk.metadata)?,
+                        None => chunk.content.clone(),
+                    };
+                    texts.push(rendered);
+                }
+
+                let texts_bytes: usize = texts.iter().map(|t| t.len()).sum();
+                let _reservation = Self::reserve_memory(&memory_pool, "sharded_ingest_embed", texts_bytes)?;
+                let embeddings = embedder.embed_batch(&texts).await?;
+                for (chunk, embedding) in shard.iter_mut().zip(embeddings.into_iter()) {
+                    chunk.set_embedding(embedding);
+                    chunk.set_embedder(embedder_name.clone());
+                }
+
+                Ok::<Vec<Chunk>, RagError>(shard)
+            }));
         }
-        
-        info!("Successfully ingested document {} with {} chunks", document_id, chunks.len());
+
+        let mut embedded_chunks = Vec::with_capacity(chunks.len());
+        for task in shard_tasks {
+            match task.await.map_err(|e| RagError::embedding(format!("ingest shard task panicked: {}", e))) {
+                Ok(Ok(shard)) => embedded_chunks.extend(shard),
+                Ok(Err(e)) | Err(e) => {
+                    warn!("Sharded indexing failed to embed document {}: {}", document_id, e);
+                    self.indexing_status.write().await.insert(document_id, IndexingStatus::Failed(e.to_string()));
+                    self.indexing_notify.notify_waiters();
+                    return Ok(document_id);
+                }
+            }
+        }
+
+        match self.vector_store.store_chunks(&embedded_chunks).await {
+            Ok(()) => {
+                for chunk in &embedded_chunks {
+                    document.add_chunk(chunk.id);
+                }
+
+                {
+                    let mut documents = self.documents.write().await;
+                    documents.insert(document_id, document);
+                }
+                {
+                    let mut stats = self.stats.write().await;
+                    stats.document_count += 1;
+                    stats.chunk_count += embedded_chunks.len();
+                    stats.embedding_count += embedded_chunks.iter().filter(|c| c.has_embedding()).count();
+                    stats.last_ingest_batch_size = batch_size;
+
+                    let total_tokens: usize = embedded_chunks.iter().map(|c| c.token_count).sum();
+                    let new_avg = total_tokens as f32 / embedded_chunks.len() as f32;
+                    stats.avg_chunk_size = if stats.chunk_count == embedded_chunks.len() {
+                        new_avg
+                    } else {
+                        (stats.avg_chunk_size * (stats.chunk_count - embedded_chunks.len()) as f32 + total_tokens as f32) / stats.chunk_count as f32
+                    };
+                    stats.total_tokens += total_tokens;
+
+                    stats.last_updated = chrono::Utc::now();
+                }
+
+                self.content_hashes.write().await.insert(content_hash, document_id);
+                self.indexing_status.write().await.insert(document_id, IndexingStatus::Done);
+                info!("Sharded-indexed document {} with {} chunks", document_id, embedded_chunks.len());
+            }
+            Err(e) => {
+                warn!("Sharded indexing failed to store document {}: {}", document_id, e);
+                self.indexing_status.write().await.insert(document_id, IndexingStatus::Failed(e.to_string()));
+            }
+        }
+
+        self.indexing_notify.notify_waiters();
         Ok(document_id)
     }
-    
+
+    /// Ingest a document using caller-supplied embeddings instead of the
+    /// configured embedder, mirroring Meilisearch's "userProvided" vectors.
+    ///
+    /// The document is chunked exactly as `ingest_document` would, and
+    /// `embeddings` must supply one vector per resulting chunk, in order,
+    /// each matching the default embedder's `dimensions()`. This is useful
+    /// for migrations and for reusing vectors computed by another pipeline,
+    /// since it skips the `embedder.embed_batch` call entirely.
+    pub async fn ingest_document_with_embeddings(
+        &self,
+        content: String,
+        metadata: HashMap<String, String>,
+        embeddings: Vec<Embedding>,
+    ) -> RagResult<DocumentId> {
+        if content.trim().is_empty() {
+            return Err(RagError::invalid_input("Document content cannot be empty"));
+        }
+
+        info!("Ingesting document with {} characters and {} user-provided embeddings", content.len(), embeddings.len());
+
+        let document = Document::new(content.clone(), metadata);
+        let document_id = document.id;
+
+        let chunk_start = std::time::Instant::now();
+        let mut chunks = self.chunker.chunk_document(document_id, &content).await?;
+        self.record_stage("chunking", chunk_start.elapsed());
+
+        for chunk in &mut chunks {
+            for (key, value) in &document.metadata {
+                chunk.metadata.entry(key.clone()).or_insert_with(|| value.clone());
+            }
+        }
+
+        self.ingest_pre_embedded(document, chunks, embeddings).await
+    }
+
+    /// Ingest caller-supplied chunks and their embeddings directly, bypassing
+    /// the configured chunker as well as the embedder.
+    ///
+    /// This is the per-chunk counterpart to `ingest_document_with_embeddings`
+    /// for callers who already split their own text into chunks (e.g. a
+    /// different chunking strategy run in another pipeline) and just need
+    /// Melanie to store and index them.
+    pub async fn ingest_chunks_with_embeddings(
+        &self,
+        content: String,
+        metadata: HashMap<String, String>,
+        chunks: Vec<(String, HashMap<String, String>)>,
+        embeddings: Vec<Embedding>,
+    ) -> RagResult<DocumentId> {
+        if chunks.is_empty() {
+            return Err(RagError::invalid_input("At least one chunk is required"));
+        }
+
+        info!("Ingesting {} caller-supplied chunks with user-provided embeddings", chunks.len());
+
+        let document = Document::new(content, metadata);
+        let document_id = document.id;
+
+        let mut built_chunks = Vec::with_capacity(chunks.len());
+        let mut offset = 0usize;
+        for (text, chunk_metadata) in chunks {
+            let token_count = self.chunker.count_tokens(&text)?;
+            let end = offset + text.len();
+            let mut chunk = Chunk::new(document_id, text, offset, end, token_count);
+            chunk.metadata = chunk_metadata;
+            for (key, value) in &document.metadata {
+                chunk.metadata.entry(key.clone()).or_insert_with(|| value.clone());
+            }
+            offset = end;
+            built_chunks.push(chunk);
+        }
+
+        self.ingest_pre_embedded(document, built_chunks, embeddings).await
+    }
+
+    /// Validate caller-supplied embeddings against the configured embedder's
+    /// dimensionality, attach them to `chunks`, and hand the result to the
+    /// background indexing worker marked `pre_embedded` so it skips
+    /// `embedder.embed_batch` for them.
+    async fn ingest_pre_embedded(
+        &self,
+        document: Document,
+        mut chunks: Vec<Chunk>,
+        embeddings: Vec<Embedding>,
+    ) -> RagResult<DocumentId> {
+        let document_id = document.id;
+
+        if chunks.is_empty() {
+            warn!("No chunks generated for document {}", document_id);
+            self.indexing_status.write().await.insert(document_id, IndexingStatus::Done);
+            return Ok(document_id);
+        }
+
+        if embeddings.len() != chunks.len() {
+            return Err(RagError::invalid_input(format!(
+                "expected {} user-provided embeddings (one per chunk), got {}",
+                chunks.len(),
+                embeddings.len()
+            )));
+        }
+
+        let expected_dimensions = self.embedder()?.dimensions();
+        for (i, embedding) in embeddings.iter().enumerate() {
+            if embedding.len() != expected_dimensions {
+                return Err(RagError::invalid_input(format!(
+                    "user-provided embedding {} has {} dimensions, but the configured embedder expects {}",
+                    i,
+                    embedding.len(),
+                    expected_dimensions
+                )));
+            }
+        }
+
+        let embedder_name = self.default_embedder.clone();
+        for (chunk, embedding) in chunks.iter_mut().zip(embeddings.into_iter()) {
+            chunk.set_embedding(embedding);
+            chunk.set_embedder(embedder_name.clone());
+        }
+
+        let content_hash = Self::content_fingerprint(&document.content);
+
+        self.indexing_status.write().await.insert(document_id, IndexingStatus::Pending);
+        self.indexing_tx
+            .send(IndexingJob { document, chunks, pre_embedded: true, content_hash })
+            .map_err(|_| RagError::generic("background indexing worker has stopped"))?;
+
+        Ok(document_id)
+    }
+
     /// Retrieve relevant context for a query
     pub async fn retrieve_context(&self, query: &str, mode: RetrievalMode) -> RagResult<Vec<RetrievalResult>> {
         if query.trim().is_empty() {
@@ -143,23 +832,56 @@ impl RagEngine {
         
         debug!("Retrieving context for query: '{}' in mode: {:?}", query, mode);
         
-        // Check cache first
+        // Check the exact-match cache first
         if let Some(cached_results) = self.cache.get_retrieval(query).await {
             debug!("Found cached results for query");
             return Ok(cached_results);
         }
-        
+
         // Generate query embedding
-        let query_embedding = self.embedder.embed_single(query).await?;
-        
+        let embed_start = std::time::Instant::now();
+        let query_embedding = self.embedder()?.embed_single(query).await.map_err(|e| {
+            e.with_frame(
+                ErrorFrame::new("while embedding query")
+                    .with_provider(self.default_embedder.clone())
+                    .with_elapsed(embed_start.elapsed()),
+            )
+        })?;
+
+        // Before running the full search/rerank pipeline, check the
+        // semantic cache for a previously-seen query embedding close
+        // enough in cosine similarity to count as a paraphrase
+        if let Some(cached_results) = self.cache.get_semantic_retrieval(&query_embedding).await {
+            debug!("Found semantically cached results for query");
+            return Ok(cached_results);
+        }
+
         // Search for similar chunks
         let max_candidates = match mode {
             RetrievalMode::General => 100,  // Get more candidates for better reranking
             RetrievalMode::Research => 200, // Even more for research mode
+            RetrievalMode::Hybrid { .. } => 100,
         };
-        
-        let similar_chunks = self.vector_store.search_similar(&query_embedding, max_candidates).await?;
-        
+
+        let search_start = std::time::Instant::now();
+        let dense_chunks = self.vector_store.search_similar(&query_embedding, max_candidates).await?;
+
+        let similar_chunks = if let RetrievalMode::Hybrid { semantic_ratio } = mode {
+            let sparse_chunks = self.vector_store.keyword_search(query, max_candidates).await?;
+            let hybrid_config = crate::config::HybridSearchConfig {
+                enabled: true,
+                semantic_ratio,
+                ..self.config.hybrid_search.clone()
+            };
+            Self::fuse_hybrid_results(dense_chunks, sparse_chunks, &hybrid_config, max_candidates)
+        } else if self.config.hybrid_search.enabled {
+            let sparse_chunks = self.vector_store.keyword_search(query, max_candidates).await?;
+            Self::fuse_hybrid_results(dense_chunks, sparse_chunks, &self.config.hybrid_search, max_candidates)
+        } else {
+            dense_chunks
+        };
+        self.record_stage("vector_search", search_start.elapsed());
+
         if similar_chunks.is_empty() {
             debug!("No similar chunks found for query");
             return Ok(Vec::new());
@@ -175,13 +897,22 @@ impl RagEngine {
                 chunks.push(result);
             }
         }
-        
+
+        // Sub-chunking and reranking each clone the retrieved content at
+        // least once more below, so budget for several times the raw chunk
+        // bytes rather than just the one copy already in `chunks`. Held
+        // until `retrieve_context` returns.
+        let context_bytes: usize = chunks.iter().map(|r| r.chunk.content.len()).sum();
+        let _memory_reservation = Self::reserve_memory(&self.memory_pool, "retrieve_context", context_bytes * 4)?;
+
         // Create sub-chunks for reranking
+        let rerank_start = std::time::Instant::now();
         let chunk_refs: Vec<Chunk> = chunks.iter().map(|r| r.chunk.clone()).collect();
         let sub_chunks = self.chunker.create_sub_chunks(&chunk_refs).await?;
-        
+
         // Rerank sub-chunks
         let reranked_sub_chunks = self.reranker.rerank_sub_chunks(query, &sub_chunks).await?;
+        self.record_stage("reranking", rerank_start.elapsed());
         
         // Convert back to retrieval results and apply threshold
         let mut final_results = Vec::new();
@@ -210,12 +941,72 @@ impl RagEngine {
         
         debug!("Returning {} diverse results", diverse_results.len());
         
-        // Cache results
+        // Cache results, both by exact query text and by query embedding
         self.cache.cache_retrieval(query, &diverse_results).await?;
-        
+        self.cache.cache_semantic_retrieval(query_embedding, &diverse_results).await?;
+
         Ok(diverse_results)
     }
-    
+
+    /// Close the retrieval loop: run `retrieve_context` for `query`, then
+    /// hand the ranked results to `GenerationClient` so it can assemble an
+    /// augmented prompt (retrieved passages ahead of the question, trimmed
+    /// to the configured context budget) and produce a grounded answer.
+    pub async fn generate_answer(&self, query: &str, mode: RetrievalMode) -> RagResult<GeneratedAnswer> {
+        let context = self.retrieve_context(query, mode).await?;
+        self.generator.generate(query, &context).await
+    }
+
+    /// Fuse dense vector and sparse keyword result lists into a single
+    /// ranking, merging by chunk id according to the configured fusion mode.
+    fn fuse_hybrid_results(
+        dense: Vec<(crate::types::ChunkId, f32)>,
+        sparse: Vec<(crate::types::ChunkId, f32)>,
+        config: &crate::config::HybridSearchConfig,
+        top_k: usize,
+    ) -> Vec<(crate::types::ChunkId, f32)> {
+        use crate::config::FusionMode;
+
+        let mut fused: Vec<(crate::types::ChunkId, f32)> = match &config.fusion {
+            FusionMode::Convex => {
+                let normalize = |scores: &[(crate::types::ChunkId, f32)]| -> HashMap<crate::types::ChunkId, f32> {
+                    let min = scores.iter().map(|(_, s)| *s).fold(f32::INFINITY, f32::min);
+                    let max = scores.iter().map(|(_, s)| *s).fold(f32::NEG_INFINITY, f32::max);
+                    let range = (max - min).max(f32::EPSILON);
+                    scores.iter().map(|(id, s)| (*id, (s - min) / range)).collect()
+                };
+
+                let dense_norm = if dense.is_empty() { HashMap::new() } else { normalize(&dense) };
+                let sparse_norm = if sparse.is_empty() { HashMap::new() } else { normalize(&sparse) };
+
+                let mut ids: std::collections::HashSet<crate::types::ChunkId> = dense_norm.keys().copied().collect();
+                ids.extend(sparse_norm.keys().copied());
+
+                ids.into_iter()
+                    .map(|id| {
+                        let d = dense_norm.get(&id).copied().unwrap_or(0.0);
+                        let s = sparse_norm.get(&id).copied().unwrap_or(0.0);
+                        (id, config.semantic_ratio * d + (1.0 - config.semantic_ratio) * s)
+                    })
+                    .collect()
+            }
+            FusionMode::ReciprocalRankFusion { k } => {
+                let mut rrf: HashMap<crate::types::ChunkId, f32> = HashMap::new();
+                for (rank, (id, _)) in dense.iter().enumerate() {
+                    *rrf.entry(*id).or_insert(0.0) += 1.0 / (*k as f32 + rank as f32 + 1.0);
+                }
+                for (rank, (id, _)) in sparse.iter().enumerate() {
+                    *rrf.entry(*id).or_insert(0.0) += 1.0 / (*k as f32 + rank as f32 + 1.0);
+                }
+                rrf.into_iter().collect()
+            }
+        };
+
+        fused.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        fused.truncate(top_k);
+        fused
+    }
+
     /// Get document by ID
     pub async fn get_document(&self, document_id: DocumentId) -> RagResult<Option<Document>> {
         let documents = self.documents.read().await;
@@ -233,23 +1024,41 @@ impl RagEngine {
         };
         
         if let Some(doc) = document {
-            // Delete all chunks from vector store
+            // Delete all chunks from vector store, tallying their token
+            // counts first so `stats.total_tokens` stays accurate
+            let mut deleted_tokens = 0usize;
             for chunk_id in &doc.chunk_ids {
+                if let Some(chunk) = self.vector_store.get_chunk(*chunk_id).await? {
+                    deleted_tokens += chunk.token_count;
+                }
                 self.vector_store.delete_chunk(*chunk_id).await?;
             }
-            
+
             // Remove document from metadata
             {
                 let mut documents = self.documents.write().await;
                 documents.remove(&document_id);
             }
-            
+
+            // Drop the content-hash entry, but only if it still points at
+            // this document - a `DedupPolicy::Allow` ingest may have stored
+            // the same content under a newer document, and that mapping must
+            // survive this delete
+            {
+                let hash = Self::content_fingerprint(&doc.content);
+                let mut content_hashes = self.content_hashes.write().await;
+                if content_hashes.get(&hash) == Some(&document_id) {
+                    content_hashes.remove(&hash);
+                }
+            }
+
             // Update statistics
             {
                 let mut stats = self.stats.write().await;
                 stats.document_count = stats.document_count.saturating_sub(1);
                 stats.chunk_count = stats.chunk_count.saturating_sub(doc.chunk_ids.len());
                 stats.embedding_count = stats.embedding_count.saturating_sub(doc.chunk_ids.len());
+                stats.total_tokens = stats.total_tokens.saturating_sub(deleted_tokens);
                 stats.last_updated = chrono::Utc::now();
             }
             
@@ -290,7 +1099,13 @@ impl RagEngine {
             let mut documents = self.documents.write().await;
             documents.clear();
         }
-        
+
+        // Clear content-hash dedup index
+        {
+            let mut content_hashes = self.content_hashes.write().await;
+            content_hashes.clear();
+        }
+
         // Clear cache
         self.cache.clear().await?;
         
@@ -327,6 +1142,13 @@ impl RagEngine {
     pub fn get_config(&self) -> &RagConfig {
         &self.config
     }
+
+    /// Count tokens in `text` using the configured tokenizer, for callers
+    /// that need an accurate budget check before ingesting or querying
+    /// rather than a `len() / 4` approximation
+    pub fn count_tokens(&self, text: &str) -> RagResult<usize> {
+        self.chunker.count_tokens(text)
+    }
     
     /// Check if the engine is healthy
     pub async fn health_check(&self) -> RagResult<bool> {
@@ -334,7 +1156,7 @@ impl RagEngine {
         let _count = self.vector_store.count().await?;
         
         // Check if we can generate embeddings
-        let _test_embedding = self.embedder.embed_single("health check").await?;
+        let _test_embedding = self.embedder()?.embed_single("health check").await?;
         
         Ok(true)
     }
@@ -368,11 +1190,12 @@ mod tests {
         let metadata = HashMap::new();
         
         let doc_id = engine.ingest_document(content, metadata).await.unwrap();
-        
+        assert_eq!(engine.await_indexed(doc_id).await.unwrap(), IndexingStatus::Done);
+
         // Verify document was stored
         let document = engine.get_document(doc_id).await.unwrap();
         assert!(document.is_some());
-        
+
         // Verify statistics were updated
         let stats = engine.get_stats().await;
         assert_eq!(stats.document_count, 1);
@@ -387,8 +1210,9 @@ mod tests {
         let content = "Artificial intelligence is a branch of computer science. Machine learning is a subset of AI.".to_string();
         let metadata = HashMap::new();
         
-        engine.ingest_document(content, metadata).await.unwrap();
-        
+        let doc_id = engine.ingest_document(content, metadata).await.unwrap();
+        engine.await_indexed(doc_id).await.unwrap();
+
         // Retrieve context
         let results = engine.retrieve_context("artificial intelligence", RetrievalMode::General).await.unwrap();
         
@@ -406,7 +1230,8 @@ mod tests {
         let metadata = HashMap::new();
         
         let doc_id = engine.ingest_document(content, metadata).await.unwrap();
-        
+        engine.await_indexed(doc_id).await.unwrap();
+
         // Verify document exists
         assert!(engine.get_document(doc_id).await.unwrap().is_some());
         
@@ -432,8 +1257,67 @@ mod tests {
     #[tokio::test]
     async fn test_health_check() {
         let (engine, _temp_dir) = create_test_engine().await;
-        
+
         let health = engine.health_check().await.unwrap();
         assert!(health);
     }
+
+    #[tokio::test]
+    async fn test_ingest_fails_with_resource_exhausted_under_a_tiny_memory_budget() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = RagConfig::default();
+        config.vector_store.db_path = temp_dir.path().to_path_buf();
+        // Small enough that even one document's embedding batch can't fit.
+        config.performance.max_memory_mb = Some(0);
+        let engine = RagEngine::new(config).await.unwrap();
+
+        let content = "This document's embedding batch should be rejected before it runs.".to_string();
+        let doc_id = engine.ingest_document(content, HashMap::new()).await.unwrap();
+
+        match engine.await_indexed(doc_id).await.unwrap() {
+            IndexingStatus::Failed(reason) => assert!(reason.contains("exhausted") || reason.contains("Exhausted")),
+            other => panic!("expected indexing to fail under an exhausted memory budget, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_compute_shard_plan_shards_evenly() {
+        let (shards, batch_size) = RagEngine::compute_shard_plan(SHARDED_INGEST_MIN_CHUNKS * 3);
+        assert!(shards >= 1);
+        assert!(batch_size * shards >= SHARDED_INGEST_MIN_CHUNKS * 3);
+
+        // Never shards more finely than there are chunks to shard.
+        let (shards, batch_size) = RagEngine::compute_shard_plan(1);
+        assert_eq!(shards, 1);
+        assert_eq!(batch_size, 1);
+    }
+
+    #[tokio::test]
+    async fn test_large_document_takes_sharded_path() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = RagConfig::default();
+        config.vector_store.db_path = temp_dir.path().to_path_buf();
+        // Small enough that every paragraph below becomes its own chunk,
+        // guaranteeing the document clears `SHARDED_INGEST_MIN_CHUNKS`.
+        config.chunking.chunk_size = 5;
+        config.chunking.min_chunk_size = 1;
+        config.chunking.overlap = 0;
+        let engine = RagEngine::new(config).await.unwrap();
+
+        let content = (0..SHARDED_INGEST_MIN_CHUNKS * 2)
+            .map(|i| format!("Paragraph number {} about artificial intelligence.", i))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        let doc_id = engine.ingest_document(content, HashMap::new()).await.unwrap();
+        assert_eq!(engine.await_indexed(doc_id).await.unwrap(), IndexingStatus::Done);
+
+        let document = engine.get_document(doc_id).await.unwrap();
+        assert!(document.is_some());
+
+        let stats = engine.get_stats().await;
+        assert_eq!(stats.document_count, 1);
+        assert!(stats.chunk_count >= SHARDED_INGEST_MIN_CHUNKS);
+        assert!(stats.last_ingest_batch_size > 0);
+    }
 }
\ No newline at end of file
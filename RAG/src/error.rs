@@ -1,10 +1,105 @@
 //! Error types for the RAG engine
 
+use std::fmt;
+use std::time::Duration;
 use thiserror::Error;
 
 /// Result type alias for RAG operations
 pub type RagResult<T> = Result<T, RagError>;
 
+/// One layer of diagnostic context attached to a `RagError` via
+/// `RagError::with_context` as it propagates up through the multi-stage
+/// ingestion/retrieval pipeline: what operation was running, which
+/// document/chunk/provider it concerned, and how long it had been running
+/// when it failed. Frames are additive, so a caller several layers up can
+/// attach its own without needing to know (or re-wrap) what's already there.
+#[derive(Debug, Clone)]
+pub struct ErrorFrame {
+    /// Short description of the operation that was in progress, e.g.
+    /// "embedding chunk" or "background indexing batch"
+    pub operation: String,
+    /// Identifier the operation concerned - a document id, chunk id, cache
+    /// key, etc. Kept as a plain string since frames log alongside errors
+    /// from several id spaces.
+    pub id: Option<String>,
+    /// Name of the provider/backend involved, e.g. "ollama" or "cohere"
+    pub provider: Option<String>,
+    /// How long the operation had been running when it failed
+    pub elapsed: Option<Duration>,
+}
+
+impl ErrorFrame {
+    /// Start a frame for `operation`, with `id`/`provider`/`elapsed` unset
+    pub fn new(operation: impl Into<String>) -> Self {
+        Self { operation: operation.into(), id: None, provider: None, elapsed: None }
+    }
+
+    /// Attach the identifier the operation concerned
+    pub fn with_id(mut self, id: impl fmt::Display) -> Self {
+        self.id = Some(id.to_string());
+        self
+    }
+
+    /// Attach the provider/backend involved
+    pub fn with_provider(mut self, provider: impl Into<String>) -> Self {
+        self.provider = Some(provider.into());
+        self
+    }
+
+    /// Attach how long the operation had been running when it failed
+    pub fn with_elapsed(mut self, elapsed: Duration) -> Self {
+        self.elapsed = Some(elapsed);
+        self
+    }
+}
+
+impl fmt::Display for ErrorFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.operation)?;
+
+        let mut extras = Vec::new();
+        if let Some(id) = &self.id {
+            extras.push(format!("id={}", id));
+        }
+        if let Some(provider) = &self.provider {
+            extras.push(format!("provider={}", provider));
+        }
+        if let Some(elapsed) = &self.elapsed {
+            extras.push(format!("elapsed={:?}", elapsed));
+        }
+
+        if !extras.is_empty() {
+            write!(f, " ({})", extras.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// A `RagError` annotated with one or more `ErrorFrame`s describing how it
+/// propagated through the pipeline, most-recently-attached first. Built by
+/// `RagError::with_context`/`RagError::with_frame` rather than constructed
+/// directly; see `RagError::frames`.
+#[derive(Debug)]
+pub struct ContextualError {
+    source: Box<RagError>,
+    frames: Vec<ErrorFrame>,
+}
+
+impl fmt::Display for ContextualError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for frame in &self.frames {
+            write!(f, "{}: ", frame)?;
+        }
+        write!(f, "{}", self.source)
+    }
+}
+
+impl std::error::Error for ContextualError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
 /// Main error type for the RAG engine
 #[derive(Error, Debug)]
 pub enum RagError {
@@ -35,6 +130,10 @@ pub enum RagError {
     /// Reranking errors
     #[error("Reranking error: {0}")]
     Reranking(String),
+
+    /// Answer generation errors
+    #[error("Generation error: {0}")]
+    Generation(String),
     
     /// Vector store errors
     #[error("Vector store error: {0}")]
@@ -55,7 +154,20 @@ pub enum RagError {
     /// Invalid input
     #[error("Invalid input: {0}")]
     InvalidInput(String),
-    
+
+    /// Document passed to `SmartChunker` validation was empty
+    #[error("document is empty")]
+    EmptyInput,
+
+    /// Document exceeds `ValidationConfig::max_total_tokens`
+    #[error("document has {got} tokens, exceeding the limit of {limit}")]
+    InputTooLong {
+        /// Total tokens actually counted in the document
+        got: usize,
+        /// The configured `max_total_tokens` ceiling
+        limit: usize,
+    },
+
     /// Timeout errors
     #[error("Operation timed out: {0}")]
     Timeout(String),
@@ -67,6 +179,23 @@ pub enum RagError {
     /// Generic errors
     #[error("RAG engine error: {0}")]
     Generic(String),
+
+    /// Raised by `MemoryPool` when a reservation would exceed the
+    /// configured budget
+    #[error("resource exhausted: requested {requested_mb:.1}MB but only {available_mb:.1}MB of the memory pool is available for '{consumer}'")]
+    ResourceExhausted {
+        /// Name of the consumer whose reservation was denied
+        consumer: String,
+        /// Amount the consumer tried to reserve, in MB
+        requested_mb: f64,
+        /// Amount actually available to that consumer at the time, in MB
+        available_mb: f64,
+    },
+
+    /// Any other variant annotated with one or more `ErrorFrame`s via
+    /// `with_context`/`with_frame`. See `ContextualError`.
+    #[error(transparent)]
+    Contextual(ContextualError),
 }
 
 impl RagError {
@@ -84,6 +213,11 @@ impl RagError {
     pub fn reranking<S: Into<String>>(msg: S) -> Self {
         RagError::Reranking(msg.into())
     }
+
+    /// Create a new generation error
+    pub fn generation<S: Into<String>>(msg: S) -> Self {
+        RagError::Generation(msg.into())
+    }
     
     /// Create a new vector store error
     pub fn vector_store<S: Into<String>>(msg: S) -> Self {
@@ -124,6 +258,101 @@ impl RagError {
     pub fn generic<S: Into<String>>(msg: S) -> Self {
         RagError::Generic(msg.into())
     }
+
+    /// Create a new resource-exhausted error for a denied `MemoryPool` reservation
+    pub fn resource_exhausted<S: Into<String>>(consumer: S, requested_mb: f64, available_mb: f64) -> Self {
+        RagError::ResourceExhausted { consumer: consumer.into(), requested_mb, available_mb }
+    }
+
+    /// Attach a context frame built from `operation` and `id`, e.g.
+    /// `RagError::embedding("...").with_context("while embedding chunk", chunk_id)`.
+    /// Shorthand for `with_frame(ErrorFrame::new(operation).with_id(id))`.
+    pub fn with_context(self, operation: impl Into<String>, id: impl fmt::Display) -> Self {
+        self.with_frame(ErrorFrame::new(operation).with_id(id))
+    }
+
+    /// Attach a fully-built `ErrorFrame`, for callers that also want to
+    /// record `provider`/`elapsed`. Frames accumulate across repeated calls
+    /// rather than replacing each other, so every layer of the pipeline can
+    /// add its own without inspecting what's already attached.
+    pub fn with_frame(self, frame: ErrorFrame) -> Self {
+        match self {
+            RagError::Contextual(mut ctx) => {
+                ctx.frames.insert(0, frame);
+                RagError::Contextual(ctx)
+            }
+            other => RagError::Contextual(ContextualError { source: Box::new(other), frames: vec![frame] }),
+        }
+    }
+
+    /// The context frames attached via `with_context`/`with_frame`,
+    /// most-recently-attached first. Empty for an error nothing has
+    /// annotated yet.
+    pub fn frames(&self) -> &[ErrorFrame] {
+        match self {
+            RagError::Contextual(ctx) => &ctx.frames,
+            _ => &[],
+        }
+    }
+
+    /// The innermost, non-`Contextual` error under any attached frames -
+    /// what actually went wrong, as opposed to the context it propagated
+    /// through.
+    pub fn root_cause(&self) -> &RagError {
+        match self {
+            RagError::Contextual(ctx) => ctx.source.root_cause(),
+            other => other,
+        }
+    }
+
+    /// Whether a retry loop is likely to succeed on a second attempt.
+    /// `Http` and `Timeout` are always worth retrying (transport hiccups,
+    /// provider rate limits); `Database` is retryable only for the
+    /// underlying `sled::Error::Io` variant, since corruption or an
+    /// unsupported operation won't resolve itself. Everything else -
+    /// `InvalidInput`, `Configuration`, `DocumentNotFound`, `ChunkNotFound`,
+    /// and the rest - is fatal.
+    ///
+    /// This is a coarser, variant-level classification than
+    /// `retry::classify_response`'s per-HTTP-status one; it's meant for
+    /// callers that only have a `RagError` in hand, not the original
+    /// response.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            RagError::Http(_) | RagError::Timeout(_) => true,
+            RagError::Database(sled::Error::Io(_)) => true,
+            RagError::Contextual(ctx) => ctx.source.is_retryable(),
+            _ => false,
+        }
+    }
+
+    /// A short, stable, machine-readable identifier for this error's
+    /// variant, suitable for metrics labels and log fields where the full
+    /// `Display` message is too high-cardinality.
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            RagError::Io(_) => "io",
+            RagError::Serialization(_) => "serialization",
+            RagError::Database(_) => "database",
+            RagError::Http(_) => "http",
+            RagError::Tokenization(_) => "tokenization",
+            RagError::Embedding(_) => "embedding",
+            RagError::Reranking(_) => "reranking",
+            RagError::Generation(_) => "generation",
+            RagError::VectorStore(_) => "vector_store",
+            RagError::Configuration(_) => "configuration",
+            RagError::DocumentNotFound(_) => "document_not_found",
+            RagError::ChunkNotFound(_) => "chunk_not_found",
+            RagError::InvalidInput(_) => "invalid_input",
+            RagError::EmptyInput => "empty_input",
+            RagError::InputTooLong { .. } => "input_too_long",
+            RagError::Timeout(_) => "timeout",
+            RagError::Cache(_) => "cache",
+            RagError::Generic(_) => "generic",
+            RagError::ResourceExhausted { .. } => "resource_exhausted",
+            RagError::Contextual(ctx) => ctx.source.error_code(),
+        }
+    }
 }
 
 /// Convert anyhow errors to RagError
@@ -138,4 +367,67 @@ impl From<uuid::Error> for RagError {
     fn from(err: uuid::Error) -> Self {
         RagError::InvalidInput(format!("Invalid UUID: {}", err))
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_retryable_classifies_transient_vs_fatal_variants() {
+        assert!(RagError::timeout("slow").is_retryable());
+        assert!(!RagError::invalid_input("bad").is_retryable());
+        assert!(!RagError::configuration("bad config").is_retryable());
+        assert!(!RagError::document_not_found("doc-1").is_retryable());
+        assert!(!RagError::chunk_not_found("chunk-1").is_retryable());
+    }
+
+    #[test]
+    fn error_code_is_stable_per_variant() {
+        assert_eq!(RagError::embedding("x").error_code(), "embedding");
+        assert_eq!(RagError::reranking("x").error_code(), "reranking");
+        assert_eq!(RagError::generation("x").error_code(), "generation");
+        assert_eq!(RagError::cache("x").error_code(), "cache");
+        assert_eq!(RagError::invalid_input("x").error_code(), "invalid_input");
+    }
+
+    #[test]
+    fn with_context_accumulates_frames_most_recent_first() {
+        let error = RagError::embedding("connection reset")
+            .with_context("while embedding chunk", "chunk-1")
+            .with_context("while ingesting document", "doc-1");
+
+        let frames = error.frames();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].operation, "while ingesting document");
+        assert_eq!(frames[0].id.as_deref(), Some("doc-1"));
+        assert_eq!(frames[1].operation, "while embedding chunk");
+        assert_eq!(frames[1].id.as_deref(), Some("chunk-1"));
+    }
+
+    #[test]
+    fn with_context_preserves_classification_of_the_wrapped_error() {
+        let error = RagError::timeout("slow").with_context("while embedding chunk", "chunk-1");
+        assert!(error.is_retryable());
+        assert_eq!(error.error_code(), "timeout");
+    }
+
+    #[test]
+    fn contextual_display_shows_frames_then_source() {
+        let error = RagError::embedding("connection reset")
+            .with_context("while embedding chunk", "chunk-1")
+            .with_frame(ErrorFrame::new("while reranking").with_provider("cohere"));
+
+        let rendered = error.to_string();
+        assert_eq!(
+            rendered,
+            "while reranking (provider=cohere): while embedding chunk (id=chunk-1): Embedding error: connection reset"
+        );
+    }
+
+    #[test]
+    fn root_cause_unwraps_every_attached_frame() {
+        let error = RagError::cache("miss").with_context("a", "1").with_context("b", "2");
+        assert!(matches!(error.root_cause(), RagError::Cache(_)));
+    }
 }
\ No newline at end of file
@@ -0,0 +1,262 @@
+//! Answer generation: turns a query plus retrieved context into a grounded
+//! completion. `GenerationClient` is a thin HTTP client following the same
+//! retry/backoff and rate-limiting conventions as `EmbeddingClient` and
+//! `RerankingClient`, and `GenerationClient::generate` assembles the
+//! augmented prompt. `RagEngine::generate_answer` drives it with the
+//! results of `retrieve_context`.
+
+use crate::config::GenerationConfig;
+use crate::error::{RagError, RagResult};
+use crate::rate_limiter::RateLimiter;
+use crate::retry::RetryOutcome;
+use crate::types::RetrievalResult;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::timeout;
+
+/// Fallback prompt template used when `GenerationConfig::prompt_template` is unset
+pub const DEFAULT_PROMPT_TEMPLATE: &str = "Context:\n{{ context }}\n\nQuestion: {{ query }}";
+
+/// A completion grounded in retrieved context, returned by
+/// `RagEngine::generate_answer`
+#[derive(Debug, Clone, Serialize)]
+pub struct GeneratedAnswer {
+    /// The model's generated text
+    pub text: String,
+    /// Retrieval results whose chunks were folded into the prompt, in the
+    /// order they were numbered, i.e. the citations for `text`
+    pub sources: Vec<RetrievalResult>,
+    /// Total tokens assembled into the prompt's context section (sum of
+    /// `sources`' `Chunk::token_count`)
+    pub tokens_used: usize,
+}
+
+/// Context sections assembled so far, alongside their source
+/// `RetrievalResult`s and running token total
+struct ContextBudget {
+    sections: Vec<String>,
+    sources: Vec<RetrievalResult>,
+    tokens_used: usize,
+}
+
+/// Greedily assemble `results` (already ranked, highest score first) into
+/// numbered context sections until `max_context_tokens` would be exceeded,
+/// so a large retrieval set degrades by dropping its lowest-scoring tail
+/// rather than overflowing the model's context window. Only the chunks'
+/// `token_count`s are counted, and the first result is always kept, even
+/// when it alone exceeds the budget.
+fn build_context(results: &[RetrievalResult], max_context_tokens: usize) -> ContextBudget {
+    let mut budget = ContextBudget { sections: Vec::new(), sources: Vec::new(), tokens_used: 0 };
+
+    for result in results {
+        let remaining = max_context_tokens.saturating_sub(budget.tokens_used);
+        if result.chunk.token_count > remaining && !budget.sources.is_empty() {
+            break;
+        }
+
+        budget.sections.push(format!("[{}] {}", budget.sources.len() + 1, result.chunk.content));
+        budget.tokens_used += result.chunk.token_count;
+        budget.sources.push(result.clone());
+
+        if budget.tokens_used >= max_context_tokens {
+            break;
+        }
+    }
+
+    budget
+}
+
+#[derive(Debug, Serialize)]
+struct ChatMessage {
+    role: &'static str,
+    content: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatRequest {
+    model: String,
+    messages: Vec<ChatMessage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    choices: Vec<ChatChoice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatResponseMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponseMessage {
+    content: String,
+}
+
+/// Client for answer-generation requests
+pub struct GenerationClient {
+    /// HTTP client
+    client: Client,
+    /// Configuration
+    config: GenerationConfig,
+    /// Built from `config.rate_limit`, if set, and acquired once per
+    /// request attempt before it goes out
+    rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+impl GenerationClient {
+    /// Create a new generation client
+    pub fn new(config: GenerationConfig) -> RagResult<Self> {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(config.timeout))
+            .build()
+            .map_err(|e| RagError::generation(format!("Failed to create HTTP client: {}", e)))?;
+
+        let rate_limiter = config.rate_limit.map(RateLimiter::new);
+
+        Ok(Self { client, config, rate_limiter })
+    }
+
+    /// Generate an answer to `query`, grounded in `context`. `context` is
+    /// assumed to already be ranked highest score first (as
+    /// `RagEngine::retrieve_context` returns it); the lowest-scoring tail is
+    /// dropped once the passages' `Chunk::token_count` total would exceed
+    /// `config.max_context_tokens`. The system prompt, template text and
+    /// question are not counted against that budget, and the top result is
+    /// kept even when it alone exceeds it.
+    pub async fn generate(&self, query: &str, context: &[RetrievalResult]) -> RagResult<GeneratedAnswer> {
+        let budget = build_context(context, self.config.max_context_tokens);
+        let prompt = self.render_prompt(query, &budget.sections.join("\n\n"))?;
+
+        let messages = vec![
+            ChatMessage { role: "system", content: self.config.system_prompt.clone() },
+            ChatMessage { role: "user", content: prompt },
+        ];
+        let request = ChatRequest { model: self.config.model.clone(), messages };
+
+        let text = self.generate_with_retries(&request).await?;
+
+        Ok(GeneratedAnswer { text, sources: budget.sources, tokens_used: budget.tokens_used })
+    }
+
+    /// Render the configured (or default) prompt template against the
+    /// assembled context and the user's question
+    fn render_prompt(&self, query: &str, context: &str) -> RagResult<String> {
+        let template = self.config.prompt_template.as_deref().unwrap_or(DEFAULT_PROMPT_TEMPLATE);
+        let mut fields = HashMap::new();
+        fields.insert("context".to_string(), context.to_string());
+        fields.insert("query".to_string(), query.to_string());
+        crate::template::render_template(template, "", &fields)
+    }
+
+    /// Call the generation endpoint with retries. On a retryable failure
+    /// (rate limiting or a transient transport/server error), honors a
+    /// provider-supplied `Retry-After` delay when present, otherwise backs
+    /// off with full jitter, retrying up to `config.max_retries` times.
+    async fn generate_with_retries(&self, request: &ChatRequest) -> RagResult<String> {
+        let mut last_error = None;
+
+        for attempt in 0..=self.config.max_retries {
+            if let Some(limiter) = &self.rate_limiter {
+                limiter.acquire(1.0).await;
+            }
+
+            match self.make_generation_request(request).await {
+                Ok(text) => return Ok(text),
+                Err(RetryOutcome::Fatal(error)) => return Err(error),
+                Err(RetryOutcome::Retryable { error, retry_after }) => {
+                    if attempt < self.config.max_retries {
+                        crate::retry::wait_before_retry(attempt as u32, retry_after).await;
+                    }
+                    last_error = Some(error);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| RagError::generation("Unknown error during generation")))
+    }
+
+    /// Make the actual HTTP request for a chat completion
+    async fn make_generation_request(&self, request: &ChatRequest) -> Result<String, RetryOutcome> {
+        let mut req_builder = self.client.post(&self.config.endpoint).json(request);
+
+        if let Some(api_key) = &self.config.api_key {
+            req_builder = req_builder.bearer_auth(api_key);
+        }
+
+        let response = timeout(Duration::from_secs(self.config.timeout), req_builder.send())
+            .await
+            .map_err(|_| RetryOutcome::Retryable { error: RagError::timeout("Generation request timed out"), retry_after: None })?
+            .map_err(|e| RetryOutcome::Retryable { error: RagError::generation(format!("HTTP request failed: {}", e)), retry_after: None })?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let (retryable, retry_after) = crate::retry::classify_response(&response);
+            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+            let error = RagError::generation(format!("Generation API returned error {}: {}", status, error_text));
+            return Err(if retryable {
+                RetryOutcome::Retryable { error, retry_after }
+            } else {
+                RetryOutcome::Fatal(error)
+            });
+        }
+
+        let chat_response: ChatResponse = response
+            .json()
+            .await
+            .map_err(|e| RetryOutcome::Fatal(RagError::generation(format!("Failed to parse response: {}", e))))?;
+
+        chat_response
+            .choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message.content)
+            .ok_or_else(|| RetryOutcome::Fatal(RagError::generation("Generation API returned no choices")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Chunk;
+    use uuid::Uuid;
+
+    fn result_with(content: &str, token_count: usize, score: f32) -> RetrievalResult {
+        let chunk = Chunk::new(Uuid::new_v4(), content.to_string(), 0, content.len(), token_count);
+        RetrievalResult::new(chunk, score)
+    }
+
+    #[test]
+    fn build_context_includes_everything_within_budget() {
+        let results = vec![result_with("a", 10, 0.9), result_with("b", 10, 0.8)];
+        let budget = build_context(&results, 100);
+        assert_eq!(budget.sources.len(), 2);
+        assert_eq!(budget.tokens_used, 20);
+    }
+
+    #[test]
+    fn build_context_drops_the_lowest_scoring_tail_once_over_budget() {
+        let results = vec![result_with("a", 60, 0.9), result_with("b", 60, 0.5)];
+        let budget = build_context(&results, 100);
+        assert_eq!(budget.sources.len(), 1);
+        assert_eq!(budget.sources[0].chunk.content, "a");
+    }
+
+    #[test]
+    fn build_context_always_includes_at_least_the_top_result() {
+        let results = vec![result_with("a", 500, 0.9)];
+        let budget = build_context(&results, 100);
+        assert_eq!(budget.sources.len(), 1);
+    }
+
+    #[test]
+    fn render_prompt_substitutes_context_and_query() {
+        let client = GenerationClient::new(GenerationConfig::default()).unwrap();
+        let prompt = client.render_prompt("what is it?", "[1] some fact").unwrap();
+        assert!(prompt.contains("[1] some fact"));
+        assert!(prompt.contains("what is it?"));
+    }
+}
@@ -0,0 +1,144 @@
+//! Exponential-bucket histograms rendered as Prometheus text exposition.
+//!
+//! `PerformanceMetrics` only tracks scalar averages and a couple of
+//! streaming quantiles, which hides the shape of the distribution. A
+//! `Histogram` buckets every observation by magnitude so operators can
+//! derive arbitrary percentiles and alert on tail behavior in Grafana
+//! instead of trusting an average. Buckets are plain `AtomicU64` counters
+//! - the same lock-free shape as `RetrievalAccumulator` in `performance.rs`
+//! - so recording an observation never blocks the hot path.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A set of exponentially-spaced bucket upper bounds, starting at `base`
+/// and multiplying by `factor` for `count` buckets, plus an implicit
+/// `+Inf` bucket. Units (milliseconds, megabytes, ...) are up to the
+/// caller - `Histogram` just buckets by magnitude.
+#[derive(Debug, Clone, Copy)]
+pub struct BucketLayout {
+    base: f64,
+    factor: f64,
+    count: usize,
+}
+
+impl BucketLayout {
+    /// `base = 1.0, factor = 2.0, count = 16` covers 1..32768 in whatever
+    /// unit the histogram observes.
+    pub const fn new(base: f64, factor: f64, count: usize) -> Self {
+        Self { base, factor, count }
+    }
+
+    fn upper_bound(&self, index: usize) -> f64 {
+        self.base * self.factor.powi(index as i32)
+    }
+}
+
+/// Default layout for millisecond latency histograms: 1ms, 2ms, 4ms, ...
+/// up to ~32s, covering everything from a cache hit to a stalled request.
+pub const DEFAULT_LATENCY_BUCKETS: BucketLayout = BucketLayout::new(1.0, 2.0, 16);
+
+/// Default layout for megabyte-scale memory histograms: 8MB, 16MB, ...
+/// up to ~64GB.
+pub const DEFAULT_MEMORY_MB_BUCKETS: BucketLayout = BucketLayout::new(8.0, 2.0, 14);
+
+/// Lock-free cumulative histogram. Each bucket counts observations
+/// less-than-or-equal-to its upper bound, matching Prometheus's
+/// `_bucket{le="..."}` convention. The sum is accumulated as an integer
+/// atomic in thousandths of the observed unit, the same fixed-point trick
+/// `RetrievalAccumulator::sum_micros` uses for millisecond sums.
+pub struct Histogram {
+    name: String,
+    help: String,
+    layout: BucketLayout,
+    buckets: Vec<AtomicU64>,
+    sum_milli_units: AtomicU64,
+    count: AtomicU64,
+}
+
+impl Histogram {
+    /// Create a histogram named `name` (used as the Prometheus metric name
+    /// and exposition `# HELP`/`# TYPE` lines) with the given bucket layout.
+    pub fn new(name: impl Into<String>, help: impl Into<String>, layout: BucketLayout) -> Self {
+        Self {
+            name: name.into(),
+            help: help.into(),
+            layout,
+            buckets: (0..layout.count).map(|_| AtomicU64::new(0)).collect(),
+            sum_milli_units: AtomicU64::new(0),
+            count: AtomicU64::new(0),
+        }
+    }
+
+    /// Record one observation. Every bucket whose upper bound is `>=
+    /// value` is incremented, so reading bucket `i` gives the count of
+    /// observations `<= upper_bound(i)` directly. Values past the last
+    /// finite bucket only land in `+Inf`.
+    pub fn observe(&self, value: f64) {
+        for (i, bucket) in self.buckets.iter().enumerate() {
+            if value <= self.layout.upper_bound(i) {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.sum_milli_units.fetch_add((value * 1000.0).round() as u64, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Total number of observations recorded so far
+    pub fn count(&self) -> u64 {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Sum of every observed value recorded so far, in the histogram's unit
+    pub fn sum(&self) -> f64 {
+        self.sum_milli_units.load(Ordering::Relaxed) as f64 / 1000.0
+    }
+
+    /// Render this histogram as Prometheus text exposition format:
+    /// `# HELP`/`# TYPE` lines, one `_bucket{le="..."}` line per bucket plus
+    /// `+Inf`, then `_sum` and `_count`.
+    pub fn render_prometheus(&self) -> String {
+        let mut out = String::new();
+        out.push_str(&format!("# HELP {} {}\n", self.name, self.help));
+        out.push_str(&format!("# TYPE {} histogram\n", self.name));
+
+        let total = self.count.load(Ordering::Relaxed);
+        for (i, bucket) in self.buckets.iter().enumerate() {
+            let le = self.layout.upper_bound(i);
+            let observed = bucket.load(Ordering::Relaxed);
+            out.push_str(&format!("{}_bucket{{le=\"{}\"}} {}\n", self.name, le, observed));
+        }
+        out.push_str(&format!("{}_bucket{{le=\"+Inf\"}} {}\n", self.name, total));
+        out.push_str(&format!("{}_sum {}\n", self.name, self.sum_milli_units.load(Ordering::Relaxed) as f64 / 1000.0));
+        out.push_str(&format!("{}_count {}\n", self.name, total));
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn observations_accumulate_into_every_covering_bucket() {
+        let histogram = Histogram::new("test_latency_ms", "test histogram", BucketLayout::new(1.0, 2.0, 4));
+        histogram.observe(1.5); // falls in buckets with le >= 2 (2, 4, 8)
+        histogram.observe(0.5); // falls in every bucket (1, 2, 4, 8)
+
+        let rendered = histogram.render_prometheus();
+        assert!(rendered.contains("test_latency_ms_bucket{le=\"1\"} 1"));
+        assert!(rendered.contains("test_latency_ms_bucket{le=\"2\"} 2"));
+        assert!(rendered.contains("test_latency_ms_bucket{le=\"+Inf\"} 2"));
+        assert!(rendered.contains("test_latency_ms_count 2"));
+    }
+
+    #[test]
+    fn sum_and_count_track_raw_observations() {
+        let histogram = Histogram::new("test_latency_ms", "test histogram", DEFAULT_LATENCY_BUCKETS);
+        histogram.observe(10.0);
+        histogram.observe(20.0);
+
+        let rendered = histogram.render_prometheus();
+        assert!(rendered.contains("test_latency_ms_sum 30"));
+        assert!(rendered.contains("test_latency_ms_count 2"));
+    }
+}
@@ -0,0 +1,198 @@
+//! Deterministic, instruction-count benchmarking for hot paths
+//! (embedding lookup, top-k vector scan, cache probe), to catch small
+//! performance regressions that wall-clock tests like
+//! `test_retrieval_recording` can't see reliably on noisy CI.
+//!
+//! Mirrors the single-shot cachegrind harness popularized by the `iai`
+//! crate: a `benches/` binary built with `harness = false` calls `bench`
+//! for each named closure. The first invocation (not yet running under
+//! Valgrind) re-executes the current binary under `valgrind --tool=cachegrind`
+//! with an environment variable naming the one benchmark to run; that
+//! child process does one warm-up call to pre-fault caches, then one
+//! measured call, and exits. The parent then parses the child's
+//! cachegrind output file for instruction counts and an estimated cycle
+//! count, and compares both against a committed baseline file, failing
+//! the benchmark if either drifts beyond `BASELINE_DRIFT_FRACTION`.
+//! Cachegrind counts the whole child process, so the figures also include
+//! process start-up and the warm-up call, not the measured call alone.
+//! Counting instructions rather than wall-clock time makes results
+//! reproducible across machines, which is what makes this suitable for
+//! gating merges in CI.
+
+use crate::error::{RagError, RagResult};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Environment variable `bench` sets on the re-exec'd child to name the
+/// single benchmark that child should run.
+const CHILD_ENV_VAR: &str = "RAG_CACHEGRIND_BENCH";
+
+/// Maximum fractional drift from a committed baseline before `bench`
+/// reports a regression (5%).
+pub const BASELINE_DRIFT_FRACTION: f64 = 0.05;
+
+/// Instruction counts and an estimated cycle count for one measured call.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct BenchResult {
+    /// Total instructions read (Cachegrind's `Ir`)
+    pub instructions: u64,
+    /// Estimated CPU cycles, weighting cache misses heavier than hits
+    pub estimated_cycles: u64,
+}
+
+/// A committed baseline for one named benchmark, checked into
+/// `benches/baselines/<name>.json`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Baseline {
+    pub instructions: u64,
+    pub estimated_cycles: u64,
+}
+
+/// Outcome of comparing a fresh `BenchResult` against its `Baseline`.
+#[derive(Debug, Clone, Copy)]
+pub struct BenchComparison {
+    pub result: BenchResult,
+    pub baseline: Baseline,
+    pub instructions_drift: f64,
+    pub cycles_drift: f64,
+}
+
+impl BenchComparison {
+    /// Whether either metric drifted beyond `BASELINE_DRIFT_FRACTION`.
+    pub fn regressed(&self) -> bool {
+        self.instructions_drift.abs() > BASELINE_DRIFT_FRACTION || self.cycles_drift.abs() > BASELINE_DRIFT_FRACTION
+    }
+}
+
+fn fractional_drift(baseline: u64, measured: u64) -> f64 {
+    if baseline == 0 {
+        return if measured == 0 { 0.0 } else { f64::INFINITY };
+    }
+    (measured as f64 - baseline as f64) / baseline as f64
+}
+
+/// Run `f` once as a single-shot Cachegrind-measured benchmark named
+/// `name`, compare it against `baselines_dir/<name>.json` if present, and
+/// return both. Call this from a `benches/*.rs` binary built with
+/// `harness = false`; `name` should be unique within that binary.
+///
+/// On the first (parent) invocation this re-executes the current binary
+/// under Valgrind to get a clean, single-shot instruction count; inside
+/// that child invocation (detected via `CHILD_ENV_VAR`), it instead runs
+/// `f` directly - once to warm caches, once measured - and exits the
+/// process, so the child never reaches the binary's other benchmarks.
+pub fn bench(name: &str, mut f: impl FnMut(), baselines_dir: &Path) -> RagResult<Option<BenchComparison>> {
+    if std::env::var(CHILD_ENV_VAR).as_deref() == Ok(name) {
+        // Inside the Valgrind child: warm up, then run once, measured.
+        f();
+        f();
+        std::process::exit(0);
+    }
+
+    let out_path = cachegrind_out_path(name);
+    run_under_cachegrind(name, &out_path)?;
+    let result = parse_cachegrind_result(&out_path)?;
+
+    let baseline_path = baselines_dir.join(format!("{}.json", name));
+    if !baseline_path.exists() {
+        return Ok(None);
+    }
+    let baseline_data = std::fs::read_to_string(&baseline_path)?;
+    let baseline: Baseline = serde_json::from_str(&baseline_data)?;
+
+    Ok(Some(BenchComparison {
+        result,
+        baseline,
+        instructions_drift: fractional_drift(baseline.instructions, result.instructions),
+        cycles_drift: fractional_drift(baseline.estimated_cycles, result.estimated_cycles),
+    }))
+}
+
+fn cachegrind_out_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("rag-cachegrind-{}.out", name))
+}
+
+fn run_under_cachegrind(name: &str, out_path: &Path) -> RagResult<()> {
+    let current_exe = std::env::current_exe()?;
+    let status = std::process::Command::new("valgrind")
+        .arg("--tool=cachegrind")
+        .arg(format!("--cachegrind-out-file={}", out_path.display()))
+        .arg(&current_exe)
+        .env(CHILD_ENV_VAR, name)
+        .status()?;
+
+    if !status.success() {
+        return Err(RagError::generic(format!("cachegrind run for benchmark '{}' failed: {}", name, status)));
+    }
+    Ok(())
+}
+
+/// Parse a Cachegrind output file's `summary:` line - the same
+/// `Ir I1mr ILmr Dr D1mr DLmr Dw D1mw DLmw` columns `cg_annotate` reports
+/// - into a `BenchResult`. The estimated cycle count uses Cachegrind's own
+/// cost model: an L1 miss costs 10 cycles, an LL (last-level) miss costs
+/// 100, everything else costs 1.
+fn parse_cachegrind_result(path: &Path) -> RagResult<BenchResult> {
+    let contents = std::fs::read_to_string(path)?;
+    let summary_line = contents
+        .lines()
+        .find(|line| line.starts_with("summary:"))
+        .ok_or_else(|| RagError::generic("cachegrind output had no summary line"))?;
+
+    let values: Vec<u64> = summary_line
+        .trim_start_matches("summary:")
+        .split_whitespace()
+        .map(|field| field.parse().unwrap_or(0))
+        .collect();
+
+    if values.len() < 9 {
+        return Err(RagError::generic("cachegrind summary line had fewer than 9 fields"));
+    }
+
+    let [ir, i1mr, ilmr, _dr, d1mr, dlmr, _dw, d1mw, dlmw]: [u64; 9] = values[..9].try_into().unwrap();
+
+    let l1_misses = i1mr + d1mr + d1mw;
+    let ll_misses = ilmr + dlmr + dlmw;
+    let estimated_cycles = ir + 10 * l1_misses + 100 * ll_misses;
+
+    Ok(BenchResult { instructions: ir, estimated_cycles })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_cachegrind_result_reads_summary_line() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("cachegrind.out.fake");
+        std::fs::write(
+            &path,
+            "events: Ir I1mr ILmr Dr D1mr DLmr Dw D1mw DLmw\nsummary: 1000 5 2 500 10 1 300 8 0\n",
+        )
+        .unwrap();
+
+        let result = parse_cachegrind_result(&path).unwrap();
+        assert_eq!(result.instructions, 1000);
+        // 1000 + 10*(5+10+8) + 100*(2+1+0) = 1000 + 230 + 300 = 1530
+        assert_eq!(result.estimated_cycles, 1530);
+    }
+
+    #[test]
+    fn fractional_drift_reports_signed_relative_change() {
+        assert_eq!(fractional_drift(100, 110), 0.1);
+        assert_eq!(fractional_drift(100, 90), -0.1);
+        assert_eq!(fractional_drift(0, 0), 0.0);
+    }
+
+    #[test]
+    fn bench_comparison_flags_regression_past_threshold() {
+        let comparison = BenchComparison {
+            result: BenchResult { instructions: 1_100_000, estimated_cycles: 2_000_000 },
+            baseline: Baseline { instructions: 1_000_000, estimated_cycles: 2_000_000 },
+            instructions_drift: 0.1,
+            cycles_drift: 0.0,
+        };
+        assert!(comparison.regressed());
+    }
+}
@@ -22,13 +22,41 @@ pub mod types;
 pub mod error;
 pub mod config;
 pub mod cache;
+pub mod remote_cache;
+pub mod template;
+pub mod benchmark;
+pub mod retry;
+pub mod source;
+pub mod sampler;
+pub mod memory_pool;
+pub mod profiling;
+pub mod histogram;
+pub mod memory_tracker;
+pub mod runtime_metrics;
+pub mod instruction_bench;
+pub mod partitioning;
+pub mod rate_limiter;
+pub mod generation;
 
 // Python bindings (optional)
 #[cfg(feature = "python-bindings")]
 pub mod python_bindings;
 
+// OpenTelemetry OTLP export (optional)
+#[cfg(feature = "otel")]
+pub mod otel;
+
+// jemalloc-backed memory introspection (optional)
+#[cfg(feature = "jemalloc")]
+pub mod jemalloc;
+
+#[cfg(feature = "jemalloc")]
+#[global_allocator]
+static GLOBAL_ALLOCATOR: tikv_jemallocator::Jemalloc = tikv_jemallocator::Jemalloc;
+
 // Re-exports for convenience
 pub use engine::RagEngine;
+pub use generation::GeneratedAnswer;
 pub use types::{Chunk, Document, RetrievalMode, RetrievalResult};
 pub use error::{RagError, RagResult};
 pub use config::RagConfig;
@@ -43,7 +71,7 @@ pub fn init_tracing() {
 /// Python module definition (only available with python-bindings feature)
 #[cfg(feature = "python-bindings")]
 #[pyo3::pymodule]
-fn melanie_rag(_py: pyo3::Python, m: &PyModule) -> pyo3::PyResult<()> {
+fn melanie_rag(py: pyo3::Python, m: &PyModule) -> pyo3::PyResult<()> {
     use pyo3::wrap_pyfunction;
     
     // Add Python classes and functions
@@ -51,11 +79,17 @@ fn melanie_rag(_py: pyo3::Python, m: &PyModule) -> pyo3::PyResult<()> {
     m.add_class::<python_bindings::PyChunk>()?;
     m.add_class::<python_bindings::PyDocument>()?;
     m.add_class::<python_bindings::PyRetrievalResult>()?;
+    m.add_class::<python_bindings::PyContextStream>()?;
+    m.add_class::<python_bindings::PyIngestOutcome>()?;
     m.add_class::<python_bindings::PyRagStats>()?;
-    
+
+    // Add exception types
+    m.add("InjectedFault", py.get_type::<python_bindings::InjectedFault>())?;
+
     // Add utility functions
     m.add_function(wrap_pyfunction!(python_bindings::init_logging, m)?)?;
     m.add_function(wrap_pyfunction!(python_bindings::get_version, m)?)?;
+    m.add_function(wrap_pyfunction!(python_bindings::count_tokens, m)?)?;
     
     // Add constants
     m.add("__version__", env!("CARGO_PKG_VERSION"))?;
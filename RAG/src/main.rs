@@ -60,8 +60,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     info!("Ingesting sample document...");
     let document_id = engine.ingest_document(sample_content.to_string(), metadata).await?;
+    engine.await_indexed(document_id).await?;
     info!("Document ingested with ID: {}", document_id);
-    
+
     // Demo: Retrieve context for different queries
     let queries = vec![
         "What is artificial intelligence?",
@@ -0,0 +1,189 @@
+//! Memory budget enforcement for large RAG operations.
+//!
+//! `MemoryMetrics` only observes usage after the fact, so there's nothing
+//! stopping an operation like building a 500k-token context, a large batch
+//! embed, or loading a vector shard from blowing past
+//! `PerformanceThresholds::max_memory_usage_mb` before anyone notices.
+//! `MemoryPool` lets callers reserve memory up front via a RAII
+//! `MemoryReservation` guard that releases its share on drop, so an
+//! over-budget operation is rejected with `RagError::ResourceExhausted`
+//! before it runs rather than merely showing up in a metric.
+//! `RagEngine` attaches a pool sized from `PerformanceConfig::max_memory_mb`
+//! and reserves against it in `retrieve_context` (context assembly) and
+//! `run_indexing_worker` (batch embedding) when one is configured.
+
+use crate::error::{RagError, RagResult};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+/// How a `MemoryPool` divides its budget across consumers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolPolicy {
+    /// Grant reservations first-come, first-served until the budget is
+    /// exhausted.
+    Greedy,
+    /// Divide the budget evenly across every consumer registered with
+    /// `MemoryPool::register_consumer`, so one consumer can't starve the
+    /// rest. A consumer that hasn't registered is rejected outright.
+    Fair,
+}
+
+#[derive(Debug, Default)]
+struct PoolState {
+    reserved_total_mb: f64,
+    reserved_by_consumer: HashMap<String, f64>,
+    registered_consumers: HashSet<String>,
+    failed_reservations: HashMap<String, u64>,
+}
+
+/// A fixed memory budget shared by registered consumers.
+pub struct MemoryPool {
+    limit_mb: f64,
+    policy: PoolPolicy,
+    state: Mutex<PoolState>,
+}
+
+impl MemoryPool {
+    /// Create a pool with a total budget of `limit_mb`, enforced according
+    /// to `policy`.
+    pub fn new(limit_mb: f64, policy: PoolPolicy) -> Arc<Self> {
+        Arc::new(Self { limit_mb, policy, state: Mutex::new(PoolState::default()) })
+    }
+
+    /// Register `consumer` so `PoolPolicy::Fair` gives it an equal share of
+    /// the budget. A no-op under `PoolPolicy::Greedy`.
+    pub fn register_consumer(&self, consumer: impl Into<String>) {
+        self.state.lock().unwrap().registered_consumers.insert(consumer.into());
+    }
+
+    /// Reserve `amount_mb` on behalf of `consumer`. On success, the returned
+    /// `MemoryReservation` releases that amount when dropped. On failure,
+    /// returns `RagError::ResourceExhausted` and records the denial against
+    /// `consumer` for `consumers_with_repeated_failures`.
+    pub fn reserve(self: &Arc<Self>, consumer: impl Into<String>, amount_mb: f64) -> RagResult<MemoryReservation> {
+        let consumer = consumer.into();
+        let mut state = self.state.lock().unwrap();
+
+        let available = match self.policy {
+            PoolPolicy::Greedy => self.limit_mb - state.reserved_total_mb,
+            PoolPolicy::Fair => {
+                if !state.registered_consumers.contains(&consumer) {
+                    0.0
+                } else {
+                    let share = self.limit_mb / state.registered_consumers.len() as f64;
+                    let already_reserved = state.reserved_by_consumer.get(&consumer).copied().unwrap_or(0.0);
+                    share - already_reserved
+                }
+            }
+        };
+
+        if amount_mb > available {
+            *state.failed_reservations.entry(consumer.clone()).or_insert(0) += 1;
+            return Err(RagError::resource_exhausted(consumer, amount_mb, available.max(0.0)));
+        }
+
+        state.reserved_total_mb += amount_mb;
+        *state.reserved_by_consumer.entry(consumer.clone()).or_insert(0.0) += amount_mb;
+
+        Ok(MemoryReservation { pool: self.clone(), consumer, amount_mb })
+    }
+
+    /// Total memory currently reserved across all consumers, in MB.
+    pub fn reserved_mb(&self) -> f64 {
+        self.state.lock().unwrap().reserved_total_mb
+    }
+
+    /// Consumers whose reservation requests have been denied at least
+    /// `threshold` times, most-denied first. Intended for
+    /// `PerformanceMonitor::check_performance_health` to flag so the
+    /// optimizer can recommend shrinking that consumer's batch size.
+    pub fn consumers_with_repeated_failures(&self, threshold: u64) -> Vec<(String, u64)> {
+        let state = self.state.lock().unwrap();
+        let mut offenders: Vec<(String, u64)> = state
+            .failed_reservations
+            .iter()
+            .filter(|(_, &count)| count >= threshold)
+            .map(|(consumer, &count)| (consumer.clone(), count))
+            .collect();
+        offenders.sort_by(|a, b| b.1.cmp(&a.1));
+        offenders
+    }
+}
+
+/// RAII guard for a `MemoryPool` reservation. Releases its share of the
+/// budget back to the pool when dropped.
+pub struct MemoryReservation {
+    pool: Arc<MemoryPool>,
+    consumer: String,
+    amount_mb: f64,
+}
+
+impl MemoryReservation {
+    /// The amount of memory this reservation holds, in MB.
+    pub fn amount_mb(&self) -> f64 {
+        self.amount_mb
+    }
+}
+
+impl Drop for MemoryReservation {
+    fn drop(&mut self) {
+        let mut state = self.pool.state.lock().unwrap();
+        state.reserved_total_mb = (state.reserved_total_mb - self.amount_mb).max(0.0);
+        if let Some(reserved) = state.reserved_by_consumer.get_mut(&self.consumer) {
+            *reserved = (*reserved - self.amount_mb).max(0.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn greedy_pool_grants_until_limit_then_exhausts() {
+        let pool = MemoryPool::new(100.0, PoolPolicy::Greedy);
+
+        let first = pool.reserve("embedder", 60.0).unwrap();
+        assert_eq!(pool.reserved_mb(), 60.0);
+
+        let err = pool.reserve("reranker", 60.0).unwrap_err();
+        assert!(matches!(err, RagError::ResourceExhausted { .. }));
+
+        drop(first);
+        assert_eq!(pool.reserved_mb(), 0.0);
+
+        let second = pool.reserve("reranker", 60.0).unwrap();
+        assert_eq!(second.amount_mb(), 60.0);
+    }
+
+    #[test]
+    fn fair_pool_divides_budget_across_registered_consumers() {
+        let pool = MemoryPool::new(100.0, PoolPolicy::Fair);
+        pool.register_consumer("embedder");
+        pool.register_consumer("reranker");
+
+        // Each registered consumer gets a 50MB share.
+        let _embedder_reservation = pool.reserve("embedder", 50.0).unwrap();
+        assert!(pool.reserve("embedder", 1.0).is_err());
+
+        // The other consumer's share is untouched.
+        let _reranker_reservation = pool.reserve("reranker", 50.0).unwrap();
+
+        // An unregistered consumer is rejected outright.
+        assert!(pool.reserve("stranger", 1.0).is_err());
+    }
+
+    #[test]
+    fn repeated_failures_are_tracked_per_consumer() {
+        let pool = MemoryPool::new(10.0, PoolPolicy::Greedy);
+        let _hold = pool.reserve("embedder", 10.0).unwrap();
+
+        for _ in 0..3 {
+            assert!(pool.reserve("reranker", 1.0).is_err());
+        }
+
+        let offenders = pool.consumers_with_repeated_failures(3);
+        assert_eq!(offenders, vec![("reranker".to_string(), 3)]);
+        assert!(pool.consumers_with_repeated_failures(4).is_empty());
+    }
+}
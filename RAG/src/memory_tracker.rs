@@ -0,0 +1,95 @@
+//! Real process memory sampling via `getrusage`, instead of trusting
+//! caller-supplied MB figures.
+//!
+//! `PerformanceMonitor::record_memory_usage` only knows what it's told, so
+//! `check_performance_health`'s comparison against
+//! `PerformanceThresholds::max_memory_usage_mb` is only as good as whatever
+//! the caller guesses. `MemoryTracker` instead samples the process's own
+//! peak resident set size on a background task and feeds it straight into
+//! `record_memory_usage`, the same way `SystemSampler` feeds real host
+//! stats into `update_system_metrics`.
+
+use crate::performance::PerformanceMonitor;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+/// Handle to the background task started by `MemoryTracker::start`. Stops
+/// sampling when dropped.
+pub struct MemoryTrackerHandle {
+    task: JoinHandle<()>,
+}
+
+impl Drop for MemoryTrackerHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Samples this process's resident set size on an interval and feeds it
+/// into a `PerformanceMonitor`.
+pub struct MemoryTracker;
+
+impl MemoryTracker {
+    /// Spawn a background task that calls `current_rss_mb` every `interval`
+    /// (a few hundred milliseconds is a reasonable default) and reports it
+    /// via `monitor.record_memory_usage`. On platforms `current_rss_mb`
+    /// can't sample, the task exits immediately rather than reporting
+    /// fabricated numbers.
+    pub fn start(monitor: Arc<PerformanceMonitor>, interval: Duration) -> MemoryTrackerHandle {
+        let task = tokio::spawn(async move {
+            loop {
+                match current_rss_mb() {
+                    Some(rss_mb) => {
+                        monitor.record_memory_usage(rss_mb, 0).await;
+                        tokio::time::sleep(interval).await;
+                    }
+                    None => {
+                        debug!("MemoryTracker: no getrusage support on this platform, stopping");
+                        break;
+                    }
+                }
+            }
+        });
+        MemoryTrackerHandle { task }
+    }
+}
+
+/// Peak resident-set size of this process so far, in megabytes, via
+/// `getrusage(2)`'s `ru_maxrss`; the value never decreases, even after
+/// memory is freed. `None` on platforms without a `getrusage`-based RSS
+/// reading.
+#[cfg(unix)]
+pub fn current_rss_mb() -> Option<f64> {
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    let result = unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) };
+    if result != 0 {
+        return None;
+    }
+
+    // `ru_maxrss` is kilobytes on Linux but bytes on macOS/BSD.
+    let raw = usage.ru_maxrss as f64;
+    let bytes = if cfg!(target_os = "macos") { raw } else { raw * 1024.0 };
+    Some(bytes / (1024.0 * 1024.0))
+}
+
+/// Portable fallback for platforms with no `getrusage`-based RSS reading.
+#[cfg(not(unix))]
+pub fn current_rss_mb() -> Option<f64> {
+    None
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn current_rss_mb_reports_a_plausible_value() {
+        // Every process has some resident memory, and nothing sane is
+        // going to be resident in terabytes.
+        let rss = current_rss_mb().expect("getrusage should succeed on unix");
+        assert!(rss > 0.0);
+        assert!(rss < 1_000_000.0);
+    }
+}
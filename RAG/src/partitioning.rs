@@ -0,0 +1,309 @@
+//! Adaptive key-range load tracking for the vector index, in the style of
+//! distributed-KV "region" bucketing (e.g. CockroachDB/TiKV): a
+//! `PartitionTable` tracks per-partition op count and approximate resident
+//! bytes, and `reshard` splits a partition once its load crosses a high
+//! watermark and merges adjacent partitions once both fall below a low
+//! watermark. This is bookkeeping over a key space, not storage: no
+//! `SledVectorStore`/`FaissVectorStore`/`HnswIndex` chunk is actually moved
+//! or routed by a split or merge, so `PerformanceOptimizer::optimize`
+//! reports where load is concentrated rather than rebalancing it. Treat a
+//! `reshard` action string as a signal for where manual resharding or a
+//! future storage-routing layer would pay off, not as something that has
+//! already happened to the index.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tokio::sync::RwLock;
+
+use crate::performance::P2Quantile;
+
+/// Half-open key range `[start, end)` owned by one partition. `end ==
+/// u64::MAX` means "unbounded on the right" (only ever true for the last
+/// partition in the table).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl KeyRange {
+    /// The full keyspace, as held by a freshly-created `PartitionTable`.
+    pub const FULL: KeyRange = KeyRange { start: 0, end: u64::MAX };
+
+    fn contains(&self, key: u64) -> bool {
+        key >= self.start && (self.end == u64::MAX || key < self.end)
+    }
+}
+
+/// Hysteresis thresholds controlling `PartitionTable::reshard`. `high_ops`
+/// must exceed `low_ops` (and `high_bytes` must exceed `low_bytes`) by a
+/// comfortable gap - otherwise a partition sitting near the boundary would
+/// split on one `reshard` call and immediately merge back on the next.
+#[derive(Debug, Clone, Copy)]
+pub struct PartitionWatermarks {
+    /// Op count above which a partition is split
+    pub high_ops: u64,
+    /// Op count below which a partition is a merge candidate
+    pub low_ops: u64,
+    /// Approximate resident bytes above which a partition is split
+    pub high_bytes: u64,
+    /// Approximate resident bytes below which a partition is a merge candidate
+    pub low_bytes: u64,
+}
+
+impl Default for PartitionWatermarks {
+    fn default() -> Self {
+        Self {
+            high_ops: 100_000,
+            low_ops: 10_000,
+            high_bytes: 512 * 1024 * 1024,
+            low_bytes: 64 * 1024 * 1024,
+        }
+    }
+}
+
+/// Optional per-call attribution for `PerformanceMonitor::record_retrieval`
+/// / `record_vector_operation`: which partition's keyspace the operation
+/// touched, and an estimate of how many bytes of index it read. Pass
+/// `None` if the caller has no partition key to attribute the operation to
+/// (e.g. it spans the whole index); resharding simply sees less signal.
+#[derive(Debug, Clone, Copy)]
+pub struct PartitionHint {
+    pub key: u64,
+    pub approx_bytes: u64,
+}
+
+/// One slice of the vector index keyspace, plus the load counters
+/// `PartitionTable::reshard` splits and merges on.
+#[derive(Debug)]
+pub struct Partition {
+    pub id: u64,
+    pub range: KeyRange,
+    ops: AtomicU64,
+    bytes: AtomicU64,
+    /// Streaming estimate of the median observed key, used to pick a split
+    /// point that gives each half roughly equal load.
+    median_key: Mutex<P2Quantile>,
+}
+
+impl Partition {
+    fn new(id: u64, range: KeyRange) -> Self {
+        Self {
+            id,
+            range,
+            ops: AtomicU64::new(0),
+            bytes: AtomicU64::new(0),
+            median_key: Mutex::new(P2Quantile::new(0.5)),
+        }
+    }
+
+    fn record(&self, key: u64, approx_bytes: u64) {
+        self.ops.fetch_add(1, Ordering::Relaxed);
+        self.bytes.fetch_add(approx_bytes, Ordering::Relaxed);
+        self.median_key.lock().unwrap().observe(key as f64);
+    }
+
+    pub fn ops(&self) -> u64 {
+        self.ops.load(Ordering::Relaxed)
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    /// Split at the partition's observed median key, dividing the counters
+    /// in half between the two children as the estimate of the load each
+    /// now carries. Returns `None` if the median estimate doesn't fall
+    /// strictly inside the range (too few observations, or every key seen
+    /// so far is identical), since there's nothing sensible to split on.
+    fn split(&self, left_id: u64, right_id: u64) -> Option<(Partition, Partition)> {
+        let median = self.median_key.lock().unwrap().value().round() as u64;
+        if median <= self.range.start || (self.range.end != u64::MAX && median >= self.range.end) {
+            return None;
+        }
+
+        let total_ops = self.ops();
+        let total_bytes = self.bytes();
+        let half_ops = total_ops / 2;
+        let half_bytes = total_bytes / 2;
+
+        let left = Partition::new(left_id, KeyRange { start: self.range.start, end: median });
+        left.ops.store(half_ops, Ordering::Relaxed);
+        left.bytes.store(half_bytes, Ordering::Relaxed);
+
+        let right = Partition::new(right_id, KeyRange { start: median, end: self.range.end });
+        right.ops.store(total_ops - half_ops, Ordering::Relaxed);
+        right.bytes.store(total_bytes - half_bytes, Ordering::Relaxed);
+
+        Some((left, right))
+    }
+
+    /// Merge with an adjacent partition (`self.range.end == other.range.start`),
+    /// summing their counters and starting a fresh median estimate over the
+    /// combined range.
+    fn merge(&self, other: &Partition, new_id: u64) -> Partition {
+        let merged = Partition::new(new_id, KeyRange { start: self.range.start, end: other.range.end });
+        merged.ops.store(self.ops() + other.ops(), Ordering::Relaxed);
+        merged.bytes.store(self.bytes() + other.bytes(), Ordering::Relaxed);
+        merged
+    }
+}
+
+/// A key-range-partitioned view over the vector index. Starts as a single
+/// partition spanning the whole keyspace and splits/merges over time as
+/// `reshard` observes load crossing `PartitionWatermarks`.
+pub struct PartitionTable {
+    watermarks: PartitionWatermarks,
+    partitions: RwLock<Vec<Partition>>,
+    next_id: AtomicU64,
+}
+
+impl PartitionTable {
+    pub fn new(watermarks: PartitionWatermarks) -> Self {
+        Self {
+            watermarks,
+            partitions: RwLock::new(vec![Partition::new(0, KeyRange::FULL)]),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Record one operation against whichever partition owns `key`,
+    /// attributing `approx_bytes` of load to it. A held read lock, so it
+    /// runs concurrently with other `record_op` calls and only blocks
+    /// behind an in-progress `reshard`.
+    pub async fn record_op(&self, key: u64, approx_bytes: u64) {
+        let partitions = self.partitions.read().await;
+        if let Some(partition) = partitions.iter().find(|p| p.range.contains(key)) {
+            partition.record(key, approx_bytes);
+        }
+    }
+
+    /// Snapshot of each partition's id, range, and load counters, for
+    /// diagnostics and tests.
+    pub async fn snapshot(&self) -> Vec<(u64, KeyRange, u64, u64)> {
+        self.partitions.read().await.iter().map(|p| (p.id, p.range, p.ops(), p.bytes())).collect()
+    }
+
+    /// Split every partition over a high watermark, then merge every
+    /// adjacent pair both under the low watermark. Runs under the table's
+    /// write lock, so `record_op` callers see either the pre- or
+    /// post-reshard layout, never a half-updated one. Returns a
+    /// human-readable description of each action taken, e.g. `"split
+    /// partition 3 -> 3a/3b"`.
+    pub async fn reshard(&self) -> Vec<String> {
+        let mut partitions = self.partitions.write().await;
+        let mut actions = Vec::new();
+
+        let mut i = 0;
+        while i < partitions.len() {
+            let (ops, bytes) = (partitions[i].ops(), partitions[i].bytes());
+            if ops > self.watermarks.high_ops || bytes > self.watermarks.high_bytes {
+                let old_id = partitions[i].id;
+                let left_id = self.next_id.fetch_add(1, Ordering::Relaxed);
+                let right_id = self.next_id.fetch_add(1, Ordering::Relaxed);
+                if let Some((left, right)) = partitions[i].split(left_id, right_id) {
+                    actions.push(format!("split partition {} -> {}a/{}b", old_id, old_id, old_id));
+                    partitions.splice(i..=i, [left, right]);
+                    i += 2;
+                    continue;
+                }
+            }
+            i += 1;
+        }
+
+        let mut i = 0;
+        while i + 1 < partitions.len() {
+            let below_low = |p: &Partition| p.ops() < self.watermarks.low_ops && p.bytes() < self.watermarks.low_bytes;
+            if below_low(&partitions[i]) && below_low(&partitions[i + 1]) {
+                let (a_id, b_id) = (partitions[i].id, partitions[i + 1].id);
+                let new_id = self.next_id.fetch_add(1, Ordering::Relaxed);
+                let merged = partitions[i].merge(&partitions[i + 1], new_id);
+                actions.push(format!("merged partitions {} and {} -> {}", a_id, b_id, new_id));
+                partitions.splice(i..=i + 1, [merged]);
+            } else {
+                i += 1;
+            }
+        }
+
+        actions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn starts_as_a_single_full_range_partition() {
+        let table = PartitionTable::new(PartitionWatermarks::default());
+        let snapshot = table.snapshot().await;
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[0].1, KeyRange::FULL);
+    }
+
+    #[tokio::test]
+    async fn reshard_splits_a_partition_over_the_high_watermark() {
+        let watermarks = PartitionWatermarks { high_ops: 10, low_ops: 2, high_bytes: u64::MAX, low_bytes: 0 };
+        let table = PartitionTable::new(watermarks);
+
+        for key in 0..20u64 {
+            table.record_op(key * 100, 1).await;
+        }
+
+        let actions = table.reshard().await;
+        assert_eq!(actions.len(), 1);
+        assert!(actions[0].contains("split partition 0"));
+
+        let snapshot = table.snapshot().await;
+        assert_eq!(snapshot.len(), 2);
+        // Every key landed in exactly one of the two children.
+        let total_ops: u64 = snapshot.iter().map(|(_, _, ops, _)| ops).sum();
+        assert_eq!(total_ops, 20);
+    }
+
+    #[tokio::test]
+    async fn reshard_splits_with_a_comfortable_gap_do_not_immediately_remerge() {
+        // `high_ops` is (more than) double `low_ops`, so even a total just
+        // over `high_ops` leaves both post-split halves above `low_ops` -
+        // the gap `PartitionWatermarks` documents as required to avoid
+        // split/merge thrashing within a single `reshard` call.
+        let watermarks = PartitionWatermarks { high_ops: 10, low_ops: 4, high_bytes: u64::MAX, low_bytes: u64::MAX };
+        let table = PartitionTable::new(watermarks);
+
+        for key in 0..11u64 {
+            table.record_op(key * 100, 1).await;
+        }
+
+        let actions = table.reshard().await;
+        assert_eq!(actions.len(), 1);
+        assert!(actions[0].contains("split partition 0"));
+        assert_eq!(table.snapshot().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn reshard_merges_a_split_back_when_the_gap_is_too_tight() {
+        // With `low_ops` set right up against `high_ops / 2`, the post-split
+        // halves (~5/6 ops) land right back under `low_ops`, so the same
+        // `reshard` call that splits the partition also merges it straight
+        // back - exactly the thrashing a wider gap is meant to prevent.
+        let watermarks = PartitionWatermarks { high_ops: 10, low_ops: 7, high_bytes: u64::MAX, low_bytes: u64::MAX };
+        let table = PartitionTable::new(watermarks);
+
+        for key in 0..11u64 {
+            table.record_op(key * 100, 1).await;
+        }
+
+        let actions = table.reshard().await;
+        assert!(actions.iter().any(|a| a.contains("split partition 0")));
+        assert!(actions.iter().any(|a| a.contains("merged partitions")));
+        assert_eq!(table.snapshot().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn reshard_is_a_no_op_between_the_watermarks() {
+        let table = PartitionTable::new(PartitionWatermarks::default());
+        table.record_op(42, 10).await;
+        assert!(table.reshard().await.is_empty());
+        assert_eq!(table.snapshot().await.len(), 1);
+    }
+}
@@ -1,13 +1,25 @@
 //! Performance monitoring and optimization utilities for the RAG engine
 
 use crate::error::{RagError, RagResult};
+use crate::histogram::{Histogram, DEFAULT_LATENCY_BUCKETS, DEFAULT_MEMORY_MB_BUCKETS};
+use crate::memory_pool::MemoryPool;
+use crate::partitioning::{PartitionHint, PartitionTable, PartitionWatermarks};
+use crate::profiling::{OperationKind, ProfileEvent, Profiler};
+use crate::runtime_metrics::RuntimeHealth;
+use crate::sampler::{SystemSampler, SystemSamplerHandle};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
 use tracing::{debug, info, warn};
 
+/// Number of denied `MemoryPool` reservations for a single consumer before
+/// `check_performance_health` raises a warning about it.
+const MEMORY_POOL_FAILURE_WARNING_THRESHOLD: u64 = 3;
+
 /// Performance metrics for RAG operations
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PerformanceMetrics {
@@ -45,6 +57,10 @@ pub struct RetrievalMetrics {
 pub struct VectorOperationMetrics {
     /// Average similarity search time in milliseconds
     pub avg_search_time_ms: f64,
+    /// 95th percentile similarity search time in milliseconds
+    pub p95_search_time_ms: f64,
+    /// 99th percentile similarity search time in milliseconds
+    pub p99_search_time_ms: f64,
     /// Parallel processing efficiency (0.0 to 1.0)
     pub parallel_efficiency: f64,
     /// Vector operations per second
@@ -67,6 +83,8 @@ pub struct MemoryMetrics {
     pub efficiency_score: f64,
     /// Garbage collection frequency
     pub gc_frequency: f64,
+    /// Total memory currently reserved through an attached `MemoryPool`, in MB
+    pub pool_reserved_mb: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -103,6 +121,8 @@ pub struct SystemMetrics {
     pub disk_io_rate: f64,
     /// Network I/O rate in MB/s
     pub network_io_rate: f64,
+    /// Available disk space on the index partition, in MB
+    pub available_disk_mb: f64,
     /// System uptime in seconds
     pub uptime_seconds: u64,
 }
@@ -120,6 +140,8 @@ impl Default for PerformanceMetrics {
             },
             vector_ops: VectorOperationMetrics {
                 avg_search_time_ms: 0.0,
+                p95_search_time_ms: 0.0,
+                p99_search_time_ms: 0.0,
                 parallel_efficiency: 1.0,
                 ops_per_second: 0.0,
                 total_operations: 0,
@@ -131,6 +153,7 @@ impl Default for PerformanceMetrics {
                 context_500k_usage_mb: 0.0,
                 efficiency_score: 1.0,
                 gc_frequency: 0.0,
+                pool_reserved_mb: 0.0,
             },
             cache: CacheMetrics {
                 hit_rate: 0.0,
@@ -149,12 +172,152 @@ impl Default for PerformanceMetrics {
                 memory_utilization: 0.0,
                 disk_io_rate: 0.0,
                 network_io_rate: 0.0,
+                available_disk_mb: 0.0,
                 uptime_seconds: 0,
             },
         }
     }
 }
 
+/// Constant-memory streaming p-quantile estimator (Jain & Chlamtac's "P²"
+/// algorithm). Tracks five markers - height, integer position, and desired
+/// position - instead of the full sample population, so tail-latency
+/// metrics stay accurate under skewed distributions without storing every
+/// observation.
+#[derive(Debug, Clone)]
+pub struct P2Quantile {
+    /// Target quantile, e.g. 0.95 for p95
+    p: f64,
+    /// Marker heights: the estimated value at each marker
+    q: [f64; 5],
+    /// Marker positions (conceptually integers, kept as `f64` since every
+    /// formula below mixes them with `np`/`dn`)
+    n: [f64; 5],
+    /// Desired (generally fractional) marker positions
+    np: [f64; 5],
+    /// Desired position increments applied to `np` on every observation
+    dn: [f64; 5],
+    /// Holds the first five observations until there are enough to
+    /// initialize `q`/`n`/`np` from a sorted sample
+    init_buffer: Vec<f64>,
+}
+
+impl P2Quantile {
+    /// Create an estimator for quantile `p` (e.g. `0.95` for p95)
+    pub fn new(p: f64) -> Self {
+        Self {
+            p,
+            q: [0.0; 5],
+            n: [0.0; 5],
+            np: [0.0; 5],
+            dn: [0.0, p / 2.0, p, (1.0 + p) / 2.0, 1.0],
+            init_buffer: Vec::with_capacity(5),
+        }
+    }
+
+    /// Feed one more sample into the estimator
+    pub fn observe(&mut self, x: f64) {
+        if self.init_buffer.len() < 5 {
+            self.init_buffer.push(x);
+            if self.init_buffer.len() == 5 {
+                self.init_buffer.sort_by(|a, b| a.partial_cmp(b).unwrap());
+                for i in 0..5 {
+                    self.q[i] = self.init_buffer[i];
+                    self.n[i] = i as f64;
+                }
+                self.np = [0.0, 4.0 * self.p / 2.0, 4.0 * self.p, 4.0 * (1.0 + self.p) / 2.0, 4.0];
+            }
+            return;
+        }
+
+        let k = if x < self.q[0] {
+            self.q[0] = x;
+            0
+        } else if x >= self.q[4] {
+            self.q[4] = x;
+            3
+        } else {
+            (0..4).find(|&i| self.q[i] <= x && x < self.q[i + 1]).unwrap_or(3)
+        };
+
+        for n in self.n.iter_mut().skip(k + 1) {
+            *n += 1.0;
+        }
+        for i in 0..5 {
+            self.np[i] += self.dn[i];
+        }
+
+        for i in 1..4 {
+            let d = self.np[i] - self.n[i];
+            let moves_right = d >= 1.0 && self.n[i + 1] - self.n[i] > 1.0;
+            let moves_left = d <= -1.0 && self.n[i - 1] - self.n[i] < -1.0;
+            if !moves_right && !moves_left {
+                continue;
+            }
+
+            let d = d.signum();
+            let parabolic = self.q[i]
+                + d / (self.n[i + 1] - self.n[i - 1])
+                    * ((self.n[i] - self.n[i - 1] + d) * (self.q[i + 1] - self.q[i]) / (self.n[i + 1] - self.n[i])
+                        + (self.n[i + 1] - self.n[i] - d) * (self.q[i] - self.q[i - 1]) / (self.n[i] - self.n[i - 1]));
+
+            self.q[i] = if self.q[i - 1] < parabolic && parabolic < self.q[i + 1] {
+                parabolic
+            } else {
+                let j = (i as f64 + d) as usize;
+                self.q[i] + d * (self.q[j] - self.q[i]) / (self.n[j] - self.n[i])
+            };
+            self.n[i] += d;
+        }
+    }
+
+    /// Current estimate of the tracked quantile. Before five samples have
+    /// been observed there aren't enough markers yet, so this falls back to
+    /// the largest value seen so far.
+    pub fn value(&self) -> f64 {
+        if self.init_buffer.len() < 5 {
+            self.init_buffer.iter().cloned().fold(0.0, f64::max)
+        } else {
+            self.q[2]
+        }
+    }
+}
+
+/// Lock-free accumulators for the retrieval hot path, grouped into one
+/// atomics bundle the same way a validator/RPC stats holder (e.g. Solana's)
+/// groups per-subsystem counters: `record_retrieval` updates these with a
+/// plain `fetch_add`, no lock. `PerformanceMonitor::flush` periodically
+/// reads their running totals (it does not reset them) into the
+/// `RwLock`-guarded snapshot that `get_metrics` reads.
+#[derive(Debug, Default)]
+struct RetrievalAccumulator {
+    total: AtomicU64,
+    under_1s: AtomicU64,
+    success: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+/// Lock-free accumulators for the vector-operation hot path; see
+/// `RetrievalAccumulator` for the rationale.
+#[derive(Debug, Default)]
+struct VectorOpAccumulator {
+    total_operations: AtomicU64,
+    calls: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+/// Handle to the background flush task started by
+/// `PerformanceMonitor::start_flusher`. Stops the task when dropped.
+pub struct FlusherHandle {
+    task: JoinHandle<()>,
+}
+
+impl Drop for FlusherHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
 /// Performance monitor for tracking and optimizing RAG operations
 pub struct PerformanceMonitor {
     /// Current metrics
@@ -165,6 +328,29 @@ pub struct PerformanceMonitor {
     thresholds: PerformanceThresholds,
     /// Start time for uptime calculation
     start_time: Instant,
+    /// Lock-free retrieval counters, read by `flush`
+    retrieval_acc: RetrievalAccumulator,
+    /// Lock-free vector-operation counters, read by `flush`
+    vector_op_acc: VectorOpAccumulator,
+    /// Streaming p95/p99 estimators for retrieval latency
+    retrieval_p95: RwLock<P2Quantile>,
+    retrieval_p99: RwLock<P2Quantile>,
+    /// Streaming p95/p99 estimators for vector-op search latency
+    vector_op_p95: RwLock<P2Quantile>,
+    vector_op_p99: RwLock<P2Quantile>,
+    /// Memory budget enforcer, if one has been attached with `attach_memory_pool`
+    memory_pool: RwLock<Option<Arc<MemoryPool>>>,
+    /// Opt-in raw per-operation event trace for offline flamegraph analysis
+    profiler: Profiler,
+    /// Exponential-bucket histograms backing `export_prometheus`
+    retrieval_latency_histogram: Histogram,
+    memory_rss_histogram: Histogram,
+    vector_op_latency_histogram: Histogram,
+    /// Latest Tokio scheduler health EWMA, fed by `update_runtime_health`
+    runtime_health: RwLock<Option<RuntimeHealth>>,
+    /// Adaptive key-range partitioning of the vector index, split/merged by
+    /// `PerformanceOptimizer::optimize` via `reshard_partitions`
+    partitions: PartitionTable,
 }
 
 #[derive(Debug, Clone)]
@@ -177,6 +363,13 @@ pub struct PerformanceThresholds {
     pub max_memory_usage_mb: f64,
     /// Minimum parallel efficiency
     pub min_parallel_efficiency: f64,
+    /// Minimum available disk space on the index partition, in MB, before
+    /// `check_performance_health` raises a capacity-planning warning
+    pub min_available_disk_mb: f64,
+    /// Mean task poll time EWMA, in milliseconds, above which
+    /// `check_performance_health` suspects scheduler contention rather
+    /// than CPU-bound vector work
+    pub max_runtime_poll_time_ewma_ms: f64,
 }
 
 impl Default for PerformanceThresholds {
@@ -186,6 +379,8 @@ impl Default for PerformanceThresholds {
             min_cache_hit_rate: 0.7,
             max_memory_usage_mb: 8192.0, // 8GB
             min_parallel_efficiency: 0.8,
+            min_available_disk_mb: 2048.0, // 2GB
+            max_runtime_poll_time_ewma_ms: 5.0,
         }
     }
 }
@@ -193,102 +388,283 @@ impl Default for PerformanceThresholds {
 impl PerformanceMonitor {
     /// Create a new performance monitor
     pub fn new(thresholds: Option<PerformanceThresholds>) -> Self {
+        Self::with_partition_watermarks(thresholds, None)
+    }
+
+    /// Create a new performance monitor with non-default vector-index
+    /// partition split/merge watermarks; see `partitioning::PartitionWatermarks`.
+    pub fn with_partition_watermarks(
+        thresholds: Option<PerformanceThresholds>,
+        partition_watermarks: Option<PartitionWatermarks>,
+    ) -> Self {
         Self {
             metrics: Arc::new(RwLock::new(PerformanceMetrics::default())),
             history: Arc::new(RwLock::new(Vec::new())),
             thresholds: thresholds.unwrap_or_default(),
             start_time: Instant::now(),
+            retrieval_acc: RetrievalAccumulator::default(),
+            vector_op_acc: VectorOpAccumulator::default(),
+            retrieval_p95: RwLock::new(P2Quantile::new(0.95)),
+            retrieval_p99: RwLock::new(P2Quantile::new(0.99)),
+            vector_op_p95: RwLock::new(P2Quantile::new(0.95)),
+            vector_op_p99: RwLock::new(P2Quantile::new(0.99)),
+            memory_pool: RwLock::new(None),
+            profiler: Profiler::new(),
+            retrieval_latency_histogram: Histogram::new(
+                "rag_retrieval_latency_ms",
+                "Retrieval latency in milliseconds",
+                DEFAULT_LATENCY_BUCKETS,
+            ),
+            memory_rss_histogram: Histogram::new(
+                "rag_memory_rss_mb",
+                "Process memory usage in megabytes",
+                DEFAULT_MEMORY_MB_BUCKETS,
+            ),
+            vector_op_latency_histogram: Histogram::new(
+                "rag_vector_op_latency_ms",
+                "Vector operation latency in milliseconds",
+                DEFAULT_LATENCY_BUCKETS,
+            ),
+            runtime_health: RwLock::new(None),
+            partitions: PartitionTable::new(partition_watermarks.unwrap_or_default()),
         }
     }
 
-    /// Record a retrieval operation
-    pub async fn record_retrieval(&self, duration: Duration, success: bool) {
+    /// Turn on raw per-operation event tracing. `capacity` bounds the
+    /// in-memory ring buffer; `sampling_ratio` is the fraction of recorded
+    /// operations actually kept (`1.0` keeps everything). Disabled by
+    /// default, so there's no overhead until this is called.
+    pub fn enable_profiling(&self, capacity: usize, sampling_ratio: f64) {
+        self.profiler.enable(capacity, sampling_ratio);
+    }
+
+    /// Turn off raw per-operation event tracing. Already-buffered events
+    /// are left in place and can still be drained with `profiled_events`
+    /// or `dump_profiled_events`.
+    pub fn disable_profiling(&self) {
+        self.profiler.disable();
+    }
+
+    /// Snapshot of the currently-buffered profiling events, oldest first.
+    pub fn profiled_events(&self) -> Vec<ProfileEvent> {
+        self.profiler.events()
+    }
+
+    /// Write the profiling ring buffer's current events to `path` in the length-prefixed
+    /// format `Profiler::dump_events` writes; convert with
+    /// `profiling::events_to_chrome_trace` or
+    /// `profiling::events_to_folded_stacks` to load into a trace viewer.
+    pub fn dump_profiled_events(&self, path: impl AsRef<std::path::Path>) -> RagResult<()> {
+        self.profiler.dump_events(path)
+    }
+
+    /// Attach a `MemoryPool` so `record_memory_usage` reports its reserved
+    /// total and `check_performance_health` can flag consumers that keep
+    /// failing reservations.
+    pub async fn attach_memory_pool(&self, pool: Arc<MemoryPool>) {
+        *self.memory_pool.write().await = Some(pool);
+    }
+
+    /// Record a retrieval operation. Never takes the
+    /// `RwLock<PerformanceMetrics>` that `get_metrics` reads: the counters
+    /// are atomic `fetch_add`s, but the p95/p99 estimators are each updated
+    /// under a brief async write lock, and a `partition` hint goes through
+    /// the partition table's read lock. Call `flush`
+    /// (or run the task from `start_flusher`) to make a recorded operation
+    /// visible through `get_metrics`. `correlation_id` (e.g. a query id)
+    /// is only used when profiling is enabled; pass `None` if unavailable
+    /// or uninteresting. `partition` attributes the retrieval to a vector-index
+    /// partition for adaptive resharding; pass `None` if there's no natural key.
+    pub async fn record_retrieval(
+        &self,
+        duration: Duration,
+        success: bool,
+        correlation_id: Option<&str>,
+        partition: Option<PartitionHint>,
+    ) {
         let duration_ms = duration.as_millis() as f64;
-        let mut metrics = self.metrics.write().await;
-        
-        // Update retrieval metrics
-        let retrieval = &mut metrics.retrieval;
-        
-        // Update average using exponential moving average
-        if retrieval.total_retrievals == 0 {
-            retrieval.avg_retrieval_time_ms = duration_ms;
-        } else {
-            retrieval.avg_retrieval_time_ms = 
-                retrieval.avg_retrieval_time_ms * 0.9 + duration_ms * 0.1;
-        }
-        
-        retrieval.total_retrievals += 1;
-        
+
+        self.retrieval_acc.total.fetch_add(1, Ordering::Relaxed);
+        self.retrieval_acc.sum_micros.fetch_add(duration.as_micros() as u64, Ordering::Relaxed);
         if duration_ms < 1000.0 {
-            retrieval.under_1s_retrievals += 1;
+            self.retrieval_acc.under_1s.fetch_add(1, Ordering::Relaxed);
         }
-        
         if success {
-            retrieval.success_rate = 
-                (retrieval.success_rate * (retrieval.total_retrievals - 1) as f64 + 1.0) 
-                / retrieval.total_retrievals as f64;
-        } else {
-            retrieval.success_rate = 
-                (retrieval.success_rate * (retrieval.total_retrievals - 1) as f64) 
-                / retrieval.total_retrievals as f64;
+            self.retrieval_acc.success.fetch_add(1, Ordering::Relaxed);
         }
-        
-        // Update percentiles (simplified approximation)
-        retrieval.p95_retrieval_time_ms = retrieval.avg_retrieval_time_ms * 1.5;
-        retrieval.p99_retrieval_time_ms = retrieval.avg_retrieval_time_ms * 2.0;
-        
+
+        // Update streaming p95/p99 estimates
+        let mut p95 = self.retrieval_p95.write().await;
+        p95.observe(duration_ms);
+        drop(p95);
+
+        let mut p99 = self.retrieval_p99.write().await;
+        p99.observe(duration_ms);
+        drop(p99);
+
+        self.retrieval_latency_histogram.observe(duration_ms);
+        self.profiler.record(OperationKind::Retrieval, duration, success, correlation_id.map(String::from));
+
+        if let Some(hint) = partition {
+            self.partitions.record_op(hint.key, hint.approx_bytes).await;
+        }
+
         debug!("Recorded retrieval: {}ms, success: {}", duration_ms, success);
     }
 
-    /// Record vector operation performance
-    pub async fn record_vector_operation(&self, duration: Duration, operation_count: u64) {
+    /// Record vector operation performance. Takes the same locks as
+    /// `record_retrieval` - see its doc comment. `partition` attributes the
+    /// operation to a vector-index partition for adaptive resharding.
+    pub async fn record_vector_operation(
+        &self,
+        duration: Duration,
+        operation_count: u64,
+        correlation_id: Option<&str>,
+        partition: Option<PartitionHint>,
+    ) {
+        let duration_ms = duration.as_millis() as f64;
+
+        self.vector_op_acc.total_operations.fetch_add(operation_count, Ordering::Relaxed);
+        self.vector_op_acc.calls.fetch_add(1, Ordering::Relaxed);
+        self.vector_op_acc.sum_micros.fetch_add(duration.as_micros() as u64, Ordering::Relaxed);
+
+        // Update streaming p95/p99 estimates
+        let mut p95 = self.vector_op_p95.write().await;
+        p95.observe(duration_ms);
+        drop(p95);
+
+        let mut p99 = self.vector_op_p99.write().await;
+        p99.observe(duration_ms);
+        drop(p99);
+
+        self.vector_op_latency_histogram.observe(duration_ms);
+        self.profiler.record(OperationKind::VectorSearch, duration, true, correlation_id.map(String::from));
+
+        if let Some(hint) = partition {
+            self.partitions.record_op(hint.key, hint.approx_bytes).await;
+        }
+
+        debug!("Recorded vector operation: {}ms, {} ops", duration_ms, operation_count);
+    }
+
+    /// Record an embedding operation, updating `vector_ops.avg_embedding_time_ms`
+    /// and (when profiling is enabled) tracing it as an `OperationKind::Embedding` event.
+    pub async fn record_embedding(&self, duration: Duration, success: bool, correlation_id: Option<&str>) {
         let duration_ms = duration.as_millis() as f64;
         let mut metrics = self.metrics.write().await;
-        
         let vector_ops = &mut metrics.vector_ops;
-        
-        // Update average search time
-        if vector_ops.total_operations == 0 {
-            vector_ops.avg_search_time_ms = duration_ms;
+
+        if vector_ops.avg_embedding_time_ms == 0.0 {
+            vector_ops.avg_embedding_time_ms = duration_ms;
         } else {
-            vector_ops.avg_search_time_ms = 
-                vector_ops.avg_search_time_ms * 0.9 + duration_ms * 0.1;
+            vector_ops.avg_embedding_time_ms = vector_ops.avg_embedding_time_ms * 0.9 + duration_ms * 0.1;
         }
-        
-        vector_ops.total_operations += operation_count;
-        
-        // Calculate operations per second
-        if duration_ms > 0.0 {
-            let ops_per_second = (operation_count as f64) / (duration_ms / 1000.0);
-            vector_ops.ops_per_second = 
-                vector_ops.ops_per_second * 0.9 + ops_per_second * 0.1;
+        drop(metrics);
+
+        self.profiler.record(OperationKind::Embedding, duration, success, correlation_id.map(String::from));
+
+        debug!("Recorded embedding: {}ms, success: {}", duration_ms, success);
+    }
+
+    /// Drain the lock-free retrieval/vector-op accumulators and streaming
+    /// quantile estimators into the `RwLock`-guarded metrics snapshot that
+    /// `get_metrics` reads, append the result to `history`, and log a
+    /// one-line summary. Safe to call directly for a deterministic,
+    /// immediate flush (e.g. in tests); `start_flusher` just calls this on
+    /// a timer.
+    pub async fn flush(&self) {
+        let retrieval_total = self.retrieval_acc.total.load(Ordering::Relaxed);
+        let retrieval_under_1s = self.retrieval_acc.under_1s.load(Ordering::Relaxed);
+        let retrieval_success = self.retrieval_acc.success.load(Ordering::Relaxed);
+        let retrieval_sum_micros = self.retrieval_acc.sum_micros.load(Ordering::Relaxed);
+
+        let vector_total_ops = self.vector_op_acc.total_operations.load(Ordering::Relaxed);
+        let vector_calls = self.vector_op_acc.calls.load(Ordering::Relaxed);
+        let vector_sum_micros = self.vector_op_acc.sum_micros.load(Ordering::Relaxed);
+
+        let retrieval_p95 = self.retrieval_p95.read().await.value();
+        let retrieval_p99 = self.retrieval_p99.read().await.value();
+        let vector_p95 = self.vector_op_p95.read().await.value();
+        let vector_p99 = self.vector_op_p99.read().await.value();
+
+        let elapsed_secs = self.start_time.elapsed().as_secs_f64().max(1.0);
+
+        {
+            let mut metrics = self.metrics.write().await;
+
+            let retrieval = &mut metrics.retrieval;
+            retrieval.total_retrievals = retrieval_total;
+            retrieval.under_1s_retrievals = retrieval_under_1s;
+            if retrieval_total > 0 {
+                retrieval.avg_retrieval_time_ms = retrieval_sum_micros as f64 / 1000.0 / retrieval_total as f64;
+                retrieval.success_rate = retrieval_success as f64 / retrieval_total as f64;
+            }
+            retrieval.p95_retrieval_time_ms = retrieval_p95;
+            retrieval.p99_retrieval_time_ms = retrieval_p99;
+
+            let vector_ops = &mut metrics.vector_ops;
+            vector_ops.total_operations = vector_total_ops;
+            if vector_calls > 0 {
+                vector_ops.avg_search_time_ms = vector_sum_micros as f64 / 1000.0 / vector_calls as f64;
+            }
+            vector_ops.ops_per_second = vector_total_ops as f64 / elapsed_secs;
+            vector_ops.p95_search_time_ms = vector_p95;
+            vector_ops.p99_search_time_ms = vector_p99;
         }
-        
-        debug!("Recorded vector operation: {}ms, {} ops", duration_ms, operation_count);
+
+        info!(
+            "Performance flush: retrievals={} (avg {:.1}ms, p95 {:.1}ms), vector_ops={} ({:.1} ops/s)",
+            retrieval_total, retrieval_sum_micros as f64 / 1000.0 / retrieval_total.max(1) as f64, retrieval_p95,
+            vector_total_ops, vector_total_ops as f64 / elapsed_secs
+        );
+
+        self.save_to_history().await;
+    }
+
+    /// Spawn a background task that calls `flush` on `interval` (10 seconds
+    /// is a reasonable default). The returned handle stops the task when
+    /// dropped.
+    pub fn start_flusher(self: &Arc<Self>, interval: Duration) -> FlusherHandle {
+        let monitor = self.clone();
+        let task = tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                monitor.flush().await;
+            }
+        });
+        FlusherHandle { task }
     }
 
     /// Record memory usage
     pub async fn record_memory_usage(&self, current_mb: f64, context_tokens: u64) {
+        let pool_reserved_mb = match self.memory_pool.read().await.as_ref() {
+            Some(pool) => pool.reserved_mb(),
+            None => 0.0,
+        };
+
+        self.memory_rss_histogram.observe(current_mb);
+
         let mut metrics = self.metrics.write().await;
         let memory = &mut metrics.memory;
-        
+
         memory.current_usage_mb = current_mb;
-        
+        memory.pool_reserved_mb = pool_reserved_mb;
+
         if current_mb > memory.peak_usage_mb {
             memory.peak_usage_mb = current_mb;
         }
-        
+
         // Estimate memory usage for 500k context
         if context_tokens > 0 {
             let tokens_per_mb = context_tokens as f64 / current_mb;
             memory.context_500k_usage_mb = 500_000.0 / tokens_per_mb;
         }
-        
+
         // Calculate efficiency score (lower memory usage = higher efficiency)
         memory.efficiency_score = 1.0 - (current_mb / self.thresholds.max_memory_usage_mb).min(1.0);
-        
-        debug!("Recorded memory usage: {:.2}MB, efficiency: {:.2}", 
-               current_mb, memory.efficiency_score);
+
+        debug!("Recorded memory usage: {:.2}MB (pool reserved {:.2}MB), efficiency: {:.2}",
+               current_mb, pool_reserved_mb, memory.efficiency_score);
     }
 
     /// Record cache performance
@@ -305,7 +681,13 @@ impl PerformanceMonitor {
     }
 
     /// Record agent performance
-    pub async fn record_agent_performance(&self, active_agents: u32, response_time: Duration, success: bool) {
+    pub async fn record_agent_performance(
+        &self,
+        active_agents: u32,
+        response_time: Duration,
+        success: bool,
+        correlation_id: Option<&str>,
+    ) {
         let mut metrics = self.metrics.write().await;
         let agents = &mut metrics.agents;
         
@@ -333,24 +715,91 @@ impl PerformanceMonitor {
             1.0
         };
         
-        debug!("Recorded agent performance: {} active, {}ms response", 
+        drop(metrics);
+
+        self.profiler.record(OperationKind::AgentCall, response_time, success, correlation_id.map(String::from));
+
+        debug!("Recorded agent performance: {} active, {}ms response",
                active_agents, response_time_ms);
     }
 
     /// Update system metrics
-    pub async fn update_system_metrics(&self, cpu_usage: f64, memory_usage: f64, 
-                                      disk_io: f64, network_io: f64) {
+    pub async fn update_system_metrics(&self, cpu_usage: f64, memory_usage: f64,
+                                      disk_io: f64, network_io: f64, available_disk_mb: f64) {
         let mut metrics = self.metrics.write().await;
         let system = &mut metrics.system;
-        
+
         system.cpu_utilization = cpu_usage;
         system.memory_utilization = memory_usage;
         system.disk_io_rate = disk_io;
         system.network_io_rate = network_io;
+        system.available_disk_mb = available_disk_mb;
         system.uptime_seconds = self.start_time.elapsed().as_secs();
-        
-        debug!("Updated system metrics: CPU={:.1}%, Memory={:.1}%", 
-               cpu_usage, memory_usage);
+
+        debug!("Updated system metrics: CPU={:.1}%, Memory={:.1}%, Disk free={:.0}MB",
+               cpu_usage, memory_usage, available_disk_mb);
+    }
+
+    /// Spawn a background task that samples real host stats on `interval`
+    /// and feeds them into `update_system_metrics`, so `SystemMetrics` stays
+    /// populated without the embedding application wiring in its own
+    /// probes. The returned handle stops the task when dropped.
+    pub fn start_system_sampling(self: &Arc<Self>, interval: Duration) -> SystemSamplerHandle {
+        SystemSampler::spawn(self.clone(), interval)
+    }
+
+    /// Spawn a background task that samples Tokio scheduler health on
+    /// `interval` and folds it into `RuntimeHealth` with EWMA smoothing
+    /// factor `alpha`. Requires `--cfg tokio_unstable`; see
+    /// `runtime_metrics::RuntimeMetricsTracker::start`. The returned handle
+    /// stops the task when dropped.
+    pub fn start_runtime_sampling(self: &Arc<Self>, interval: Duration, alpha: f64) -> crate::runtime_metrics::RuntimeMetricsHandle {
+        crate::runtime_metrics::RuntimeMetricsTracker::start(self.clone(), interval, alpha)
+    }
+
+    /// Fold one Tokio scheduler health sample into the `RuntimeHealth` EWMA:
+    /// `new = alpha*sample + (1-alpha)*old`. Park/unpark and queue-depth
+    /// counters are reported as the latest raw sample rather than smoothed,
+    /// since they're already cumulative (parks/unparks) or instantaneous
+    /// (queue depths).
+    pub async fn update_runtime_health(
+        &self,
+        poll_time_ms: f64,
+        parks: u64,
+        unparks: u64,
+        injection_queue_depth: usize,
+        local_queue_depth: usize,
+        alpha: f64,
+    ) {
+        let mut runtime_health = self.runtime_health.write().await;
+        let poll_time_ewma_ms = match runtime_health.as_ref() {
+            Some(previous) => alpha * poll_time_ms + (1.0 - alpha) * previous.poll_time_ewma_ms,
+            None => poll_time_ms,
+        };
+
+        *runtime_health = Some(RuntimeHealth {
+            poll_time_ewma_ms,
+            parks,
+            unparks,
+            injection_queue_depth,
+            local_queue_depth,
+        });
+
+        debug!("Updated runtime health: poll_time_ewma={:.3}ms, parks={}, unparks={}", poll_time_ewma_ms, parks, unparks);
+    }
+
+    /// Split any vector-index partition over its high watermark and merge any
+    /// adjacent pair under its low watermark; see `partitioning::PartitionTable::reshard`.
+    /// Called from `PerformanceOptimizer::optimize`, which is also where the
+    /// returned action descriptions end up surfaced to callers.
+    pub async fn reshard_partitions(&self) -> Vec<String> {
+        self.partitions.reshard().await
+    }
+
+    /// Snapshot of the current vector-index partition layout and load, for
+    /// diagnostics and tests.
+    pub async fn partition_snapshot(&self) -> Vec<(u64, crate::partitioning::KeyRange, u64, u64)> {
+        self.partitions.snapshot().await
     }
 
     /// Get current performance metrics
@@ -358,6 +807,27 @@ impl PerformanceMonitor {
         self.metrics.read().await.clone()
     }
 
+    /// Non-blocking snapshot of the current metrics, for callers that can't
+    /// await (e.g. an OpenTelemetry observable-instrument callback). Returns
+    /// `Err` only if a writer is mid-update; callers should just skip that
+    /// export tick rather than retry.
+    pub fn try_get_metrics(&self) -> Result<PerformanceMetrics, tokio::sync::TryLockError> {
+        self.metrics.try_read().map(|guard| guard.clone())
+    }
+
+    /// Render retrieval latency, memory RSS, and vector-op latency as
+    /// Prometheus text exposition format, one histogram block per metric.
+    /// Unlike `get_metrics`, this reads the lock-free histograms directly,
+    /// so it reflects every `record_retrieval`/`record_memory_usage`/
+    /// `record_vector_operation` call immediately - no `flush` required.
+    pub fn export_prometheus(&self) -> String {
+        let mut out = String::new();
+        out.push_str(&self.retrieval_latency_histogram.render_prometheus());
+        out.push_str(&self.memory_rss_histogram.render_prometheus());
+        out.push_str(&self.vector_op_latency_histogram.render_prometheus());
+        out
+    }
+
     /// Check if performance meets thresholds
     pub async fn check_performance_health(&self) -> RagResult<PerformanceHealth> {
         let metrics = self.metrics.read().await;
@@ -407,7 +877,45 @@ impl PerformanceMonitor {
                 metrics.memory.context_500k_usage_mb
             ));
         }
-        
+
+        // Check available disk space on the index partition
+        if metrics.system.available_disk_mb > 0.0
+            && metrics.system.available_disk_mb < self.thresholds.min_available_disk_mb
+        {
+            warnings.push(format!(
+                "Available disk space {:.2}MB below threshold {:.2}MB",
+                metrics.system.available_disk_mb,
+                self.thresholds.min_available_disk_mb
+            ));
+        }
+
+        // Flag memory pool consumers that keep getting their reservations denied,
+        // so the optimizer can recommend shrinking that consumer's batch size.
+        if let Some(pool) = self.memory_pool.read().await.as_ref() {
+            for (consumer, failures) in pool.consumers_with_repeated_failures(MEMORY_POOL_FAILURE_WARNING_THRESHOLD) {
+                warnings.push(format!(
+                    "Consumer '{}' has had {} memory reservations denied; consider shrinking its batch size",
+                    consumer, failures
+                ));
+            }
+        }
+
+        // Distinguish "CPU-bound in vector ops" from "the scheduler is
+        // starved": elevated poll-time EWMA while parallel efficiency
+        // still looks healthy points at worker contention, not slow math.
+        let runtime = self.runtime_health.read().await.clone();
+        if let Some(runtime_health) = &runtime {
+            if runtime_health.poll_time_ewma_ms > self.thresholds.max_runtime_poll_time_ewma_ms
+                && metrics.vector_ops.parallel_efficiency >= self.thresholds.min_parallel_efficiency
+            {
+                warnings.push(format!(
+                    "runtime poll time elevated ({:.2}ms EWMA) / workers parked under load \
+                     ({} parks) despite healthy parallel efficiency - scheduler contention, not slow vector ops",
+                    runtime_health.poll_time_ewma_ms, runtime_health.parks
+                ));
+            }
+        }
+
         let health_status = if !issues.is_empty() {
             HealthStatus::Critical
         } else if !warnings.is_empty() {
@@ -415,12 +923,13 @@ impl PerformanceMonitor {
         } else {
             HealthStatus::Healthy
         };
-        
+
         Ok(PerformanceHealth {
             status: health_status,
             issues,
             warnings,
             metrics: metrics.clone(),
+            runtime,
         })
     }
 
@@ -457,6 +966,7 @@ impl PerformanceMonitor {
                 issues: vec!["Failed to check health".to_string()],
                 warnings: vec![],
                 metrics: metrics.clone(),
+                runtime: None,
             }
         });
         
@@ -506,6 +1016,45 @@ impl PerformanceMonitor {
     }
 }
 
+/// Jemalloc-backed memory introspection, gated behind the `jemalloc` feature;
+/// see `crate::jemalloc` for the `mallctl` plumbing underneath these.
+#[cfg(feature = "jemalloc")]
+impl PerformanceMonitor {
+    /// Refresh `MemoryMetrics` from jemalloc's own `stats.resident` rather
+    /// than a caller-supplied RSS figure - far more accurate than `ru_maxrss`
+    /// since it accounts for fragmentation jemalloc itself is aware of.
+    pub async fn record_jemalloc_memory_usage(&self, context_tokens: u64) -> RagResult<()> {
+        let stats = crate::jemalloc::read_stats()?;
+        self.record_memory_usage(stats.resident_mb, context_tokens).await;
+        Ok(())
+    }
+
+    /// Trigger a jemalloc heap profile dump to `path`, so operators can see
+    /// which allocation sites are responsible when `check_performance_health`
+    /// reports memory as `HealthStatus::Critical`. See `crate::jemalloc::dump_profile`.
+    pub fn dump_allocation_profile(&self, path: impl AsRef<std::path::Path>) -> RagResult<()> {
+        crate::jemalloc::dump_profile(path)
+    }
+}
+
+/// An axum handler exposing `PerformanceMonitor::export_prometheus` over
+/// HTTP, e.g. `Router::new().route("/metrics", get(performance_metrics_handler)).with_state(monitor)`.
+/// Kept behind a feature flag so pulling in axum is opt-in for callers
+/// that already expose their own metrics endpoint, following the same
+/// shape as `cache::http`.
+#[cfg(feature = "performance-metrics-http")]
+pub mod http {
+    use super::PerformanceMonitor;
+    use axum::extract::State;
+    use axum::http::{header, StatusCode};
+    use axum::response::IntoResponse;
+    use std::sync::Arc;
+
+    pub async fn performance_metrics_handler(State(monitor): State<Arc<PerformanceMonitor>>) -> impl IntoResponse {
+        (StatusCode::OK, [(header::CONTENT_TYPE, "text/plain; version=0.0.4")], monitor.export_prometheus())
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum HealthStatus {
     Healthy,
@@ -520,6 +1069,8 @@ pub struct PerformanceHealth {
     pub issues: Vec<String>,
     pub warnings: Vec<String>,
     pub metrics: PerformanceMetrics,
+    /// Latest Tokio scheduler health sample, if runtime sampling is active
+    pub runtime: Option<RuntimeHealth>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -536,11 +1087,12 @@ impl Serialize for PerformanceHealth {
         S: serde::Serializer,
     {
         use serde::ser::SerializeStruct;
-        let mut state = serializer.serialize_struct("PerformanceHealth", 4)?;
+        let mut state = serializer.serialize_struct("PerformanceHealth", 5)?;
         state.serialize_field("status", &format!("{:?}", self.status))?;
         state.serialize_field("issues", &self.issues)?;
         state.serialize_field("warnings", &self.warnings)?;
         state.serialize_field("metrics", &self.metrics)?;
+        state.serialize_field("runtime", &self.runtime)?;
         state.end()
     }
 }
@@ -570,6 +1122,7 @@ impl<'de> Deserialize<'de> for PerformanceHealth {
                 let mut issues = None;
                 let mut warnings = None;
                 let mut metrics = None;
+                let mut runtime = None;
 
                 while let Some(key) = map.next_key()? {
                     match key {
@@ -591,6 +1144,9 @@ impl<'de> Deserialize<'de> for PerformanceHealth {
                         "metrics" => {
                             metrics = Some(map.next_value()?);
                         }
+                        "runtime" => {
+                            runtime = map.next_value()?;
+                        }
                         _ => {
                             let _: serde_json::Value = map.next_value()?;
                         }
@@ -607,13 +1163,14 @@ impl<'de> Deserialize<'de> for PerformanceHealth {
                     issues,
                     warnings,
                     metrics,
+                    runtime,
                 })
             }
         }
 
         deserializer.deserialize_struct(
             "PerformanceHealth",
-            &["status", "issues", "warnings", "metrics"],
+            &["status", "issues", "warnings", "metrics", "runtime"],
             PerformanceHealthVisitor,
         )
     }
@@ -629,7 +1186,12 @@ impl PerformanceOptimizer {
         Self { monitor }
     }
 
-    /// Optimize based on current performance metrics
+    /// Optimize based on current performance metrics. Every branch here,
+    /// including resharding, only ever produces human-readable
+    /// recommendations: `reshard_partitions` splits/merges entries in the
+    /// in-memory `PartitionTable` bookkeeping, but no chunk's actual
+    /// storage location changes as a result - see
+    /// `partitioning::PartitionTable` and `PerformanceMonitor::reshard_partitions`.
     pub async fn optimize(&self) -> RagResult<Vec<String>> {
         let health = self.monitor.check_performance_health().await?;
         let mut optimizations = Vec::new();
@@ -649,6 +1211,8 @@ impl PerformanceOptimizer {
             }
         }
 
+        optimizations.extend(self.monitor.reshard_partitions().await);
+
         Ok(optimizations)
     }
 
@@ -659,6 +1223,15 @@ impl PerformanceOptimizer {
         if health.metrics.memory.current_usage_mb > 6000.0 {
             optimizations.push("Triggered emergency garbage collection".to_string());
             optimizations.push("Reduced cache size by 50%".to_string());
+
+            #[cfg(feature = "jemalloc")]
+            {
+                let path = std::env::temp_dir().join(format!("rag-heap-{}.prof", chrono::Utc::now().timestamp()));
+                match self.monitor.dump_allocation_profile(&path) {
+                    Ok(()) => optimizations.push(format!("Dumped jemalloc heap profile to {}", path.display())),
+                    Err(e) => warn!("failed to dump jemalloc heap profile: {}", e),
+                }
+            }
         }
 
         // Critical retrieval time optimization
@@ -721,8 +1294,9 @@ mod tests {
         let monitor = PerformanceMonitor::new(None);
         
         // Record a fast retrieval
-        monitor.record_retrieval(Duration::from_millis(500), true).await;
-        
+        monitor.record_retrieval(Duration::from_millis(500), true, None, None).await;
+        monitor.flush().await;
+
         let metrics = monitor.get_metrics().await;
         assert_eq!(metrics.retrieval.total_retrievals, 1);
         assert_eq!(metrics.retrieval.under_1s_retrievals, 1);
@@ -736,11 +1310,14 @@ mod tests {
             min_cache_hit_rate: 0.8,
             max_memory_usage_mb: 1000.0,
             min_parallel_efficiency: 0.8,
+            min_available_disk_mb: 2048.0,
+            max_runtime_poll_time_ewma_ms: 5.0,
         }));
         
         // Record slow retrieval
-        monitor.record_retrieval(Duration::from_millis(1500), true).await;
-        
+        monitor.record_retrieval(Duration::from_millis(1500), true, None, None).await;
+        monitor.flush().await;
+
         let health = monitor.check_performance_health().await.unwrap();
         
         match health.status {
@@ -751,18 +1328,153 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_p2_quantile_converges_on_uniform_samples() {
+        let mut p95 = P2Quantile::new(0.95);
+        for i in 1..=1000 {
+            p95.observe(i as f64);
+        }
+
+        // True p95 of 1..=1000 is 950; the P² estimate should land close.
+        assert!((p95.value() - 950.0).abs() < 25.0, "p95 estimate was {}", p95.value());
+    }
+
+    #[tokio::test]
+    async fn test_retrieval_percentiles_track_observed_latencies() {
+        let monitor = PerformanceMonitor::new(None);
+
+        for ms in [100, 200, 300, 400, 500, 600, 700, 800, 900, 1000] {
+            monitor.record_retrieval(Duration::from_millis(ms), true, None, None).await;
+        }
+        monitor.flush().await;
+
+        let metrics = monitor.get_metrics().await;
+        assert!(metrics.retrieval.p95_retrieval_time_ms >= 100.0);
+        assert!(metrics.retrieval.p95_retrieval_time_ms <= 1000.0);
+        assert!(metrics.retrieval.p99_retrieval_time_ms >= 100.0);
+        assert!(metrics.retrieval.p99_retrieval_time_ms <= 1000.0);
+    }
+
+    #[tokio::test]
+    async fn test_recorded_retrieval_is_invisible_until_flush() {
+        let monitor = PerformanceMonitor::new(None);
+
+        monitor.record_retrieval(Duration::from_millis(500), true, None, None).await;
+        assert_eq!(monitor.get_metrics().await.retrieval.total_retrievals, 0);
+
+        monitor.flush().await;
+        assert_eq!(monitor.get_metrics().await.retrieval.total_retrievals, 1);
+    }
+
+    #[tokio::test]
+    async fn test_start_flusher_flushes_on_interval() {
+        let monitor = Arc::new(PerformanceMonitor::new(None));
+        monitor.record_retrieval(Duration::from_millis(500), true, None, None).await;
+
+        let _handle = monitor.start_flusher(Duration::from_millis(20));
+        sleep(Duration::from_millis(100)).await;
+
+        assert_eq!(monitor.get_metrics().await.retrieval.total_retrievals, 1);
+    }
+
+    #[tokio::test]
+    async fn test_attached_memory_pool_reports_reserved_mb_and_repeated_failures() {
+        use crate::memory_pool::{MemoryPool, PoolPolicy};
+
+        let monitor = PerformanceMonitor::new(None);
+        let pool = MemoryPool::new(10.0, PoolPolicy::Greedy);
+        monitor.attach_memory_pool(pool.clone()).await;
+
+        let _hold = pool.reserve("embedder", 10.0).unwrap();
+        for _ in 0..MEMORY_POOL_FAILURE_WARNING_THRESHOLD {
+            assert!(pool.reserve("reranker", 1.0).is_err());
+        }
+
+        monitor.record_memory_usage(100.0, 0).await;
+        let metrics = monitor.get_metrics().await;
+        assert_eq!(metrics.memory.pool_reserved_mb, 10.0);
+
+        let health = monitor.check_performance_health().await.unwrap();
+        assert!(health.warnings.iter().any(|w| w.contains("reranker")));
+    }
+
+    #[tokio::test]
+    async fn test_export_prometheus_reflects_observations_without_flush() {
+        let monitor = PerformanceMonitor::new(None);
+
+        monitor.record_retrieval(Duration::from_millis(50), true, None, None).await;
+        monitor.record_vector_operation(Duration::from_millis(5), 3, None, None).await;
+        monitor.record_memory_usage(256.0, 0).await;
+
+        let rendered = monitor.export_prometheus();
+        assert!(rendered.contains("rag_retrieval_latency_ms_count 1"));
+        assert!(rendered.contains("rag_vector_op_latency_ms_count 1"));
+        assert!(rendered.contains("rag_memory_rss_mb_count 1"));
+    }
+
     #[tokio::test]
     async fn test_performance_optimizer() {
         let monitor = Arc::new(PerformanceMonitor::new(None));
         let optimizer = PerformanceOptimizer::new(monitor.clone());
         
         // Record some performance data
-        monitor.record_retrieval(Duration::from_millis(800), true).await;
+        monitor.record_retrieval(Duration::from_millis(800), true, None, None).await;
         monitor.record_memory_usage(500.0, 100000).await;
-        
+        monitor.flush().await;
+
         let optimizations = optimizer.optimize().await.unwrap();
-        
+
         // Should return some optimizations (even if proactive)
         assert!(!optimizations.is_empty() || optimizations.is_empty()); // Either is valid
     }
+
+    #[tokio::test]
+    async fn test_optimize_reshards_a_partition_over_its_high_watermark() {
+        use crate::partitioning::{PartitionHint, PartitionWatermarks};
+
+        let monitor = Arc::new(PerformanceMonitor::with_partition_watermarks(
+            None,
+            Some(PartitionWatermarks { high_ops: 10, low_ops: 2, high_bytes: u64::MAX, low_bytes: 0 }),
+        ));
+        let optimizer = PerformanceOptimizer::new(monitor.clone());
+
+        for key in 0..20u64 {
+            monitor
+                .record_retrieval(Duration::from_millis(10), true, None, Some(PartitionHint { key: key * 100, approx_bytes: 1 }))
+                .await;
+        }
+        monitor.flush().await;
+
+        let optimizations = optimizer.optimize().await.unwrap();
+        assert!(optimizations.iter().any(|a| a.contains("split partition 0")));
+        assert_eq!(monitor.partition_snapshot().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_runtime_health_warns_on_elevated_poll_time_with_high_parallel_efficiency() {
+        let monitor = PerformanceMonitor::new(Some(PerformanceThresholds {
+            max_runtime_poll_time_ewma_ms: 5.0,
+            ..PerformanceThresholds::default()
+        }));
+
+        // `parallel_efficiency` defaults to 1.0 (healthy), so an elevated
+        // poll-time EWMA here should read as scheduler contention.
+        monitor.update_runtime_health(20.0, 10, 9, 0, 0, 1.0).await;
+
+        let health = monitor.check_performance_health().await.unwrap();
+        assert!(health.runtime.is_some());
+        assert!(health.warnings.iter().any(|w| w.contains("runtime poll time elevated")));
+    }
+
+    #[tokio::test]
+    async fn test_runtime_health_ewma_smooths_across_samples() {
+        let monitor = PerformanceMonitor::new(None);
+
+        monitor.update_runtime_health(10.0, 1, 1, 0, 0, 0.5).await;
+        monitor.update_runtime_health(20.0, 1, 1, 0, 0, 0.5).await;
+
+        let health = monitor.check_performance_health().await.unwrap();
+        let runtime = health.runtime.unwrap();
+        assert_eq!(runtime.poll_time_ewma_ms, 15.0);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,269 @@
+//! Opt-in raw event tracing for offline flamegraph/trace-viewer analysis.
+//!
+//! Aggregated metrics like `avg_retrieval_time_ms` hide which individual
+//! operations were slow. When enabled, `Profiler` appends one
+//! `ProfileEvent` per recorded operation to a bounded, append-only ring
+//! buffer. `dump_events` writes a copy of it to a compact length-prefixed
+//! file, leaving the buffer as it is, and
+//! `events_to_chrome_trace`/`events_to_folded_stacks` convert that into
+//! formats standard flamegraph/trace viewers already understand.
+//! Recording is cheap - a single atomic check when disabled, a struct push
+//! behind a mutex otherwise - so the buffer capacity and sampling ratio
+//! are configurable to keep production overhead negligible.
+
+use crate::error::{RagError, RagResult};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// The kind of operation a `ProfileEvent` was recorded for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OperationKind {
+    Retrieval,
+    VectorSearch,
+    Embedding,
+    AgentCall,
+}
+
+impl OperationKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            OperationKind::Retrieval => "retrieval",
+            OperationKind::VectorSearch => "vector_search",
+            OperationKind::Embedding => "embedding",
+            OperationKind::AgentCall => "agent_call",
+        }
+    }
+}
+
+/// One recorded operation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileEvent {
+    /// What kind of operation this was
+    pub kind: OperationKind,
+    /// Microseconds since the owning `Profiler` was created
+    pub start_micros: u64,
+    /// How long the operation took
+    pub duration: Duration,
+    /// Whether the operation succeeded
+    pub success: bool,
+    /// Caller-supplied correlation/query id, if any
+    pub correlation_id: Option<String>,
+}
+
+/// Bounded, append-only event recorder. Disabled by default; `record` is
+/// then a single relaxed atomic load that returns immediately, so there's
+/// no hot-path cost until profiling is turned on.
+pub struct Profiler {
+    enabled: AtomicBool,
+    capacity: Mutex<usize>,
+    sampling_every: AtomicU64,
+    counter: AtomicU64,
+    buffer: Mutex<VecDeque<ProfileEvent>>,
+    start: Instant,
+}
+
+impl Profiler {
+    /// Create a disabled profiler. Call `enable` to start recording.
+    pub fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            capacity: Mutex::new(10_000),
+            sampling_every: AtomicU64::new(1),
+            counter: AtomicU64::new(0),
+            buffer: Mutex::new(VecDeque::new()),
+            start: Instant::now(),
+        }
+    }
+
+    /// Turn profiling on. `capacity` bounds the ring buffer (oldest events
+    /// are dropped once full); `sampling_ratio` is the fraction of recorded
+    /// operations actually kept (`1.0` keeps everything, `0.1` keeps
+    /// roughly one in ten).
+    pub fn enable(&self, capacity: usize, sampling_ratio: f64) {
+        *self.capacity.lock().unwrap() = capacity.max(1);
+        let sampling_every = (1.0 / sampling_ratio.clamp(f64::MIN_POSITIVE, 1.0)).round().max(1.0) as u64;
+        self.sampling_every.store(sampling_every, Ordering::Relaxed);
+        self.enabled.store(true, Ordering::Relaxed);
+    }
+
+    /// Turn profiling off. Already-buffered events are left in place.
+    pub fn disable(&self) {
+        self.enabled.store(false, Ordering::Relaxed);
+    }
+
+    /// Record one operation. No-op, with no formatting or allocation, when
+    /// profiling is disabled or this particular event is sampled out.
+    pub fn record(&self, kind: OperationKind, duration: Duration, success: bool, correlation_id: Option<String>) {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return;
+        }
+        let n = self.counter.fetch_add(1, Ordering::Relaxed);
+        if n % self.sampling_every.load(Ordering::Relaxed) != 0 {
+            return;
+        }
+
+        let event = ProfileEvent {
+            kind,
+            start_micros: self.start.elapsed().as_micros() as u64,
+            duration,
+            success,
+            correlation_id,
+        };
+
+        let capacity = *self.capacity.lock().unwrap();
+        let mut buffer = self.buffer.lock().unwrap();
+        if buffer.len() >= capacity {
+            buffer.pop_front();
+        }
+        buffer.push_back(event);
+    }
+
+    /// Snapshot of currently-buffered events, oldest first.
+    pub fn events(&self) -> Vec<ProfileEvent> {
+        self.buffer.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// Write the current buffer to `path` as a sequence of length-prefixed,
+    /// JSON-encoded records: a little-endian `u32` byte length followed by
+    /// that many bytes of `serde_json`-encoded `ProfileEvent`. JSON keeps
+    /// each record self-describing without pulling in a binary codec
+    /// dependency just for this.
+    pub fn dump_events(&self, path: impl AsRef<Path>) -> RagResult<()> {
+        let events = self.events();
+        let mut file = std::fs::File::create(path)?;
+        for event in &events {
+            let bytes = serde_json::to_vec(event)?;
+            file.write_all(&(bytes.len() as u32).to_le_bytes())?;
+            file.write_all(&bytes)?;
+        }
+        Ok(())
+    }
+
+    /// Read back a file written by `dump_events`.
+    pub fn load_events(path: impl AsRef<Path>) -> RagResult<Vec<ProfileEvent>> {
+        let mut file = std::fs::File::open(path)?;
+        let mut events = Vec::new();
+        loop {
+            let mut len_bytes = [0u8; 4];
+            match file.read_exact(&mut len_bytes) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(RagError::Io(e)),
+            }
+            let len = u32::from_le_bytes(len_bytes) as usize;
+            let mut buf = vec![0u8; len];
+            file.read_exact(&mut buf)?;
+            events.push(serde_json::from_slice(&buf)?);
+        }
+        Ok(events)
+    }
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Render `events` as Chrome's "Trace Event Format" JSON, loadable
+/// directly in `chrome://tracing` or Perfetto.
+pub fn events_to_chrome_trace(events: &[ProfileEvent]) -> RagResult<String> {
+    let trace_events: Vec<serde_json::Value> = events
+        .iter()
+        .map(|event| {
+            serde_json::json!({
+                "name": event.kind.as_str(),
+                "cat": "rag",
+                "ph": "X",
+                "ts": event.start_micros,
+                "dur": event.duration.as_micros() as u64,
+                "pid": 1,
+                "tid": 1,
+                "args": {
+                    "success": event.success,
+                    "correlation_id": event.correlation_id,
+                },
+            })
+        })
+        .collect();
+
+    Ok(serde_json::to_string(&serde_json::json!({ "traceEvents": trace_events }))?)
+}
+
+/// Render `events` as folded-stack lines (`stack_frame count`), the input
+/// format Brendan Gregg's `flamegraph.pl` and most flamegraph tooling
+/// expects. Each event becomes a single-frame stack weighted by its
+/// duration in microseconds.
+pub fn events_to_folded_stacks(events: &[ProfileEvent]) -> String {
+    let mut out = String::new();
+    for event in events {
+        out.push_str(event.kind.as_str());
+        out.push(' ');
+        out.push_str(&event.duration.as_micros().to_string());
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_profiler_records_nothing() {
+        let profiler = Profiler::new();
+        profiler.record(OperationKind::Retrieval, Duration::from_millis(10), true, None);
+        assert!(profiler.events().is_empty());
+    }
+
+    #[test]
+    fn enabled_profiler_respects_capacity() {
+        let profiler = Profiler::new();
+        profiler.enable(2, 1.0);
+
+        for _ in 0..5 {
+            profiler.record(OperationKind::VectorSearch, Duration::from_millis(1), true, None);
+        }
+
+        assert_eq!(profiler.events().len(), 2);
+    }
+
+    #[test]
+    fn dump_and_load_events_round_trips() {
+        let profiler = Profiler::new();
+        profiler.enable(10, 1.0);
+        profiler.record(OperationKind::Embedding, Duration::from_millis(5), true, Some("q-1".to_string()));
+        profiler.record(OperationKind::AgentCall, Duration::from_millis(15), false, None);
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("events.bin");
+        profiler.dump_events(&path).unwrap();
+
+        let loaded = Profiler::load_events(&path).unwrap();
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].correlation_id.as_deref(), Some("q-1"));
+        assert!(!loaded[1].success);
+    }
+
+    #[test]
+    fn chrome_trace_and_folded_stacks_cover_every_event() {
+        let events = vec![ProfileEvent {
+            kind: OperationKind::Retrieval,
+            start_micros: 100,
+            duration: Duration::from_micros(250),
+            success: true,
+            correlation_id: None,
+        }];
+
+        let trace = events_to_chrome_trace(&events).unwrap();
+        assert!(trace.contains("\"dur\":250"));
+
+        let folded = events_to_folded_stacks(&events);
+        assert_eq!(folded, "retrieval 250\n");
+    }
+}
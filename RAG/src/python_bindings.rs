@@ -1,14 +1,27 @@
 //! Python bindings for the RAG engine using PyO3 with async support
 
-use crate::config::RagConfig;
+use crate::config::{EmbeddingConfig, RagConfig};
 use crate::engine::RagEngine;
+use crate::histogram::{Histogram, DEFAULT_LATENCY_BUCKETS};
 use crate::types::{Document, RetrievalMode, RetrievalResult, RagStats};
+use pyo3::create_exception;
+use pyo3::exceptions::PyStopAsyncIteration;
 use pyo3::prelude::*;
-use pyo3::types::PyDict;
+use pyo3::types::{PyDict, PyList};
 use pyo3_asyncio::tokio::future_into_py;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use std::collections::HashMap;
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, RwLock};
+
+/// Raised instead of `PyRuntimeError` for failures `FaultInjector`
+/// manufactures, so a Python test suite can tell "the engine really
+/// failed" apart from "this is the fault-injection harness exercising my
+/// retry/rollback logic" with a plain `except InjectedFault`.
+create_exception!(melanie_rag, InjectedFault, pyo3::exceptions::PyRuntimeError);
 
 /// Python wrapper for the RAG engine with async support and token limit monitoring
 #[pyclass(name = "RagEngine")]
@@ -17,6 +30,8 @@ pub struct PyRagEngine {
     token_count: Arc<RwLock<usize>>,
     token_limit: usize,
     enable_user_prompts: bool,
+    metrics: Arc<PyEngineMetrics>,
+    fault_injector: Arc<FaultInjector>,
 }
 
 #[pymethods]
@@ -30,81 +45,131 @@ impl PyRagEngine {
             token_count: Arc::new(RwLock::new(0)),
             token_limit,
             enable_user_prompts,
+            metrics: Arc::new(PyEngineMetrics::new()),
+            fault_injector: Arc::new(FaultInjector::disabled()),
         })
     }
-    
+
     /// Initialize the RAG engine asynchronously
     fn initialize<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
         let engine_arc = self.engine.clone();
-        
+
         future_into_py(py, async move {
-            let engine = RagEngine::with_default_config().await
+            // Span capture has a small per-request overhead, but
+            // `metrics_text`/`metrics` need it on to report rerank
+            // latency, so it's on unconditionally for the Python binding
+            // rather than left off by default the way `RagConfig` is.
+            let mut config = RagConfig::default();
+            config.performance.enable_span_capture = true;
+            let engine = RagEngine::new(config).await
                 .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to create RAG engine: {}", e)))?;
-            
+
             let mut engine_guard = engine_arc.write().await;
             *engine_guard = Some(engine);
-            
+
             Ok(())
         })
     }
-    
-    /// Create a new RAG engine with custom configuration
+
+    /// Create a new RAG engine with custom configuration. `config_dict`'s
+    /// top-level RagConfig fields (chunking, embeddings, reranking, cache,
+    /// ...) are applied by `initialize_with_config`; only its optional
+    /// `"fault_injection"` sub-dict is read here, to configure
+    /// `FaultInjector` - see its doc comment for the accepted keys.
     #[staticmethod]
-    #[pyo3(signature = (_config_dict, token_limit = 500000, enable_user_prompts = true))]
-    fn with_config(_config_dict: &PyDict, token_limit: usize, enable_user_prompts: bool) -> PyResult<Self> {
+    #[pyo3(signature = (config_dict, token_limit = 500000, enable_user_prompts = true))]
+    fn with_config(config_dict: &PyDict, token_limit: usize, enable_user_prompts: bool) -> PyResult<Self> {
+        let fault_config = python_dict_to_fault_config(config_dict)?;
+
         Ok(Self {
             engine: Arc::new(RwLock::new(None)),
             token_count: Arc::new(RwLock::new(0)),
             token_limit,
             enable_user_prompts,
+            metrics: Arc::new(PyEngineMetrics::new()),
+            fault_injector: Arc::new(FaultInjector::new(fault_config)),
         })
     }
-    
-    /// Initialize the RAG engine with custom configuration asynchronously
-    fn initialize_with_config<'p>(&self, py: Python<'p>, config_dict: &PyDict) -> PyResult<&'p PyAny> {
+
+    /// Initialize the RAG engine with custom configuration asynchronously.
+    /// `config_dict` is recursively merged onto `RagConfig::default()` via
+    /// `python_dict_to_config` - see its doc comment for the accepted shape.
+    /// Unknown keys raise `PyValueError` unless `strict=False`.
+    #[pyo3(signature = (config_dict, strict = true))]
+    fn initialize_with_config<'p>(&self, py: Python<'p>, config_dict: &PyDict, strict: bool) -> PyResult<&'p PyAny> {
         let engine_arc = self.engine.clone();
-        let config = python_dict_to_config(config_dict)?;
-        
+        let mut config = python_dict_to_config(config_dict, strict)?;
+        config.performance.enable_span_capture = true;
+
         future_into_py(py, async move {
             let engine = RagEngine::new(config).await
                 .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to create RAG engine: {}", e)))?;
-            
+
             let mut engine_guard = engine_arc.write().await;
             *engine_guard = Some(engine);
-            
+
             Ok(())
         })
     }
-    
+
+    /// Read back the effective configuration of the initialized engine as a
+    /// `dict` matching the shape `initialize_with_config` accepts, so a
+    /// Python caller can inspect or re-serialize defaults it didn't
+    /// explicitly set.
+    fn config_to_dict<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
+        let engine_arc = self.engine.clone();
+
+        future_into_py(py, async move {
+            let engine_guard = engine_arc.read().await;
+            let engine = engine_guard.as_ref()
+                .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("RAG engine not initialized. Call initialize() first."))?;
+
+            let value = serde_json::to_value(engine.get_config())
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to serialize config: {}", e)))?;
+
+            Python::with_gil(|py| json_value_to_pyobject(py, &value))
+        })
+    }
+
     /// Ingest a document into the RAG system with token limit monitoring
     fn ingest_document<'p>(&self, py: Python<'p>, content: String, metadata: Option<&PyDict>) -> PyResult<&'p PyAny> {
         let engine_arc = self.engine.clone();
         let token_count_arc = self.token_count.clone();
         let token_limit = self.token_limit;
         let enable_prompts = self.enable_user_prompts;
-        
+        let metrics = self.metrics.clone();
+        let fault_injector = self.fault_injector.clone();
+
         let metadata_map = if let Some(meta) = metadata {
             python_dict_to_string_map(meta)?
         } else {
             HashMap::new()
         };
-        
+
         future_into_py(py, async move {
+            // Chaos mode: simulate contention for the engine lock before
+            // even trying to acquire it
+            if let Some(delay) = fault_injector.lock_contention_delay() {
+                tokio::time::sleep(delay).await;
+            }
+
             // Check if engine is initialized
             let engine_guard = engine_arc.read().await;
             let engine = engine_guard.as_ref()
                 .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("RAG engine not initialized. Call initialize() first."))?;
-            
-            // Estimate token count for the document (rough approximation: 1 token ≈ 4 characters)
-            let estimated_tokens = content.len() / 4;
-            
+
+            // Estimate token count for the document with the real tokenizer
+            let estimated_tokens = engine.count_tokens(&content)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to count tokens: {}", e)))?;
+
             // Check token limit before ingestion
             {
                 let mut current_count = token_count_arc.write().await;
                 if *current_count + estimated_tokens > token_limit {
+                    metrics.token_reservations_rejected.fetch_add(1, Ordering::Relaxed);
                     if enable_prompts {
                         return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
-                            format!("Token limit exceeded! Current: {}, Adding: {}, Limit: {}. Consider starting a new session or increasing the limit.", 
+                            format!("Token limit exceeded! Current: {}, Adding: {}, Limit: {}. Consider starting a new session or increasing the limit.",
                                    *current_count, estimated_tokens, token_limit)
                         ));
                     } else {
@@ -112,83 +177,302 @@ impl PyRagEngine {
                     }
                 }
                 *current_count += estimated_tokens;
+                metrics.token_reservations_granted.fetch_add(1, Ordering::Relaxed);
             }
-            
-            // Ingest the document
-            let document_id = engine.ingest_document(content, metadata_map).await
-                .map_err(|e| {
-                    // Rollback token count on failure
-                    tokio::spawn(async move {
-                        let mut current_count = token_count_arc.write().await;
-                        *current_count = current_count.saturating_sub(estimated_tokens);
-                    });
-                    PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to ingest document: {}", e))
-                })?;
-            
+
+            // Chaos mode: simulate a slow backend before the real call
+            if let Some(delay) = fault_injector.call_delay() {
+                tokio::time::sleep(delay).await;
+            }
+
+            // Ingest the document, or - at `ingest_failure_rate` - inject a
+            // synthetic failure here instead. Either way the failure runs
+            // through the same token-count rollback below, so a Python
+            // test suite can exercise that path deterministically without
+            // a real failing backend.
+            let ingest_result = if fault_injector.should_fail_ingest() {
+                Err(InjectedFault::new_err("fault injection: simulated ingest_document failure"))
+            } else {
+                engine.ingest_document(content, metadata_map).await
+                    .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to ingest document: {}", e)))
+            };
+
+            let document_id = ingest_result.map_err(|e| {
+                // Rollback token count on failure
+                tokio::spawn(async move {
+                    let mut current_count = token_count_arc.write().await;
+                    *current_count = current_count.saturating_sub(estimated_tokens);
+                });
+                e
+            })?;
+
+            metrics.ingest_total.fetch_add(1, Ordering::Relaxed);
             Ok(document_id.to_string())
         })
     }
-    
+
+    /// Ingest a batch of `(content, metadata)` pairs concurrently, bounded by
+    /// a semaphore, amortizing the per-call GIL/await overhead of calling
+    /// `ingest_document` once per item. Unlike `ingest_document`, a failure
+    /// on one item doesn't fail the batch: each item gets its own
+    /// `IngestOutcome` with either a `document_id` or an `error`. Token
+    /// accounting is transactional across the whole batch - the sum of
+    /// estimated tokens is reserved against `token_limit` atomically before
+    /// ingestion starts, then reconciled down to the actual total and with
+    /// failed items' reservations rolled back once every item finishes.
+    fn ingest_documents<'p>(
+        &self,
+        py: Python<'p>,
+        docs: Vec<(String, Option<&PyDict>)>,
+    ) -> PyResult<&'p PyAny> {
+        let engine_arc = self.engine.clone();
+        let token_count_arc = self.token_count.clone();
+        let token_limit = self.token_limit;
+        let enable_prompts = self.enable_user_prompts;
+
+        let items = docs
+            .into_iter()
+            .map(|(content, metadata)| {
+                let metadata_map = if let Some(meta) = metadata {
+                    python_dict_to_string_map(meta)?
+                } else {
+                    HashMap::new()
+                };
+                Ok((content, metadata_map))
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+
+        future_into_py(py, async move {
+            let items = {
+                let engine_guard = engine_arc.read().await;
+                let engine = engine_guard.as_ref()
+                    .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("RAG engine not initialized. Call initialize() first."))?;
+
+                items
+                    .into_iter()
+                    .map(|(content, metadata_map)| {
+                        let estimated_tokens = engine.count_tokens(&content)
+                            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to count tokens: {}", e)))?;
+                        Ok((content, metadata_map, estimated_tokens))
+                    })
+                    .collect::<PyResult<Vec<_>>>()?
+            };
+
+            let total_estimated: usize = items.iter().map(|(_, _, tokens)| tokens).sum();
+
+            {
+                let mut current_count = token_count_arc.write().await;
+                if *current_count + total_estimated > token_limit {
+                    if enable_prompts {
+                        return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                            format!("Token limit exceeded! Current: {}, Adding: {}, Limit: {}. Consider starting a new session or increasing the limit.",
+                                   *current_count, total_estimated, token_limit)
+                        ));
+                    } else {
+                        return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Token limit exceeded"));
+                    }
+                }
+                *current_count += total_estimated;
+            }
+
+            let semaphore = Arc::new(tokio::sync::Semaphore::new(8));
+            let tasks: Vec<_> = items
+                .into_iter()
+                .enumerate()
+                .map(|(index, (content, metadata_map, estimated_tokens))| {
+                    let engine_arc = engine_arc.clone();
+                    let semaphore = semaphore.clone();
+                    tokio::spawn(async move {
+                        let _permit = semaphore.acquire_owned().await.expect("ingest semaphore closed");
+
+                        let engine_guard = engine_arc.read().await;
+                        let engine = match engine_guard.as_ref() {
+                            Some(engine) => engine,
+                            None => {
+                                return PyIngestOutcome {
+                                    index,
+                                    document_id: None,
+                                    error: Some("RAG engine not initialized. Call initialize() first.".to_string()),
+                                    estimated_tokens,
+                                }
+                            }
+                        };
+
+                        match engine.ingest_document(content, metadata_map).await {
+                            Ok(document_id) => PyIngestOutcome {
+                                index,
+                                document_id: Some(document_id.to_string()),
+                                error: None,
+                                estimated_tokens,
+                            },
+                            Err(e) => PyIngestOutcome {
+                                index,
+                                document_id: None,
+                                error: Some(format!("Failed to ingest document: {}", e)),
+                                estimated_tokens,
+                            },
+                        }
+                    })
+                })
+                .collect();
+
+            let mut outcomes = Vec::with_capacity(tasks.len());
+            for task in tasks {
+                outcomes.push(
+                    task.await
+                        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Ingest task panicked: {}", e)))?,
+                );
+            }
+            outcomes.sort_by_key(|outcome| outcome.index);
+
+            // Reconcile the reservation down to what actually got ingested:
+            // roll back failed items' share and true up the rest to actual usage.
+            let actual_total: usize = outcomes
+                .iter()
+                .filter(|outcome| outcome.error.is_none())
+                .map(|outcome| outcome.estimated_tokens)
+                .sum();
+            {
+                let mut current_count = token_count_arc.write().await;
+                *current_count = current_count.saturating_sub(total_estimated) + actual_total;
+            }
+
+            Ok(outcomes)
+        })
+    }
+
     /// Retrieve relevant context for a query with General/Research modes
     fn retrieve_context<'p>(&self, py: Python<'p>, query: String, mode: Option<String>) -> PyResult<&'p PyAny> {
         let engine_arc = self.engine.clone();
         let token_count_arc = self.token_count.clone();
         let token_limit = self.token_limit;
         let enable_prompts = self.enable_user_prompts;
-        
+        let metrics = self.metrics.clone();
+        let fault_injector = self.fault_injector.clone();
+
         let retrieval_mode = match mode.as_deref() {
             Some("research") => RetrievalMode::Research,
             _ => RetrievalMode::General,
         };
-        
+
         future_into_py(py, async move {
+            // Chaos mode: simulate contention for the engine lock before
+            // even trying to acquire it
+            if let Some(delay) = fault_injector.lock_contention_delay() {
+                tokio::time::sleep(delay).await;
+            }
+
             // Check if engine is initialized
             let engine_guard = engine_arc.read().await;
             let engine = engine_guard.as_ref()
                 .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("RAG engine not initialized. Call initialize() first."))?;
-            
-            // Estimate tokens for the query
-            let query_tokens = query.len() / 4;
-            
+
+            // Estimate tokens for the query with the real tokenizer
+            let query_tokens = engine.count_tokens(&query)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to count tokens: {}", e)))?;
+
             // Estimate tokens that will be returned based on mode
             let estimated_return_tokens = match retrieval_mode {
                 RetrievalMode::General => 5000,   // 3k-5k tokens for general
                 RetrievalMode::Research => 20000, // 15k-25k tokens for research
+                RetrievalMode::Hybrid { .. } => 5000,
             };
-            
+
             // Check token limit before retrieval
             {
                 let current_count = token_count_arc.read().await;
                 if *current_count + query_tokens + estimated_return_tokens > token_limit {
+                    metrics.token_reservations_rejected.fetch_add(1, Ordering::Relaxed);
                     if enable_prompts {
                         return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
                             format!("Token limit would be exceeded! Current: {}, Query: {}, Expected return: {}, Limit: {}. \
-                                   Options: 1) Start new session, 2) Use 'general' mode instead of 'research', 3) Increase limit.", 
+                                   Options: 1) Start new session, 2) Use 'general' mode instead of 'research', 3) Increase limit.",
                                    *current_count, query_tokens, estimated_return_tokens, token_limit)
                         ));
                     } else {
                         return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("Token limit would be exceeded"));
                     }
                 }
+                metrics.token_reservations_granted.fetch_add(1, Ordering::Relaxed);
             }
-            
-            // Retrieve context
-            let results = engine.retrieve_context(&query, retrieval_mode).await
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to retrieve context: {}", e)))?;
-            
-            // Update token count with actual usage
+
+            // Chaos mode: simulate a slow backend before the real call
+            if let Some(delay) = fault_injector.call_delay() {
+                tokio::time::sleep(delay).await;
+            }
+
+            // Retrieve context, or - at `retrieve_failure_rate` - inject a
+            // synthetic failure here instead, so a Python test suite can
+            // exercise its retry logic without a real failing backend.
+            let retrieval_start = std::time::Instant::now();
+            let results = if fault_injector.should_fail_retrieve() {
+                return Err(InjectedFault::new_err("fault injection: simulated retrieve_context failure"));
+            } else {
+                engine.retrieve_context(&query, retrieval_mode).await
+                    .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to retrieve context: {}", e)))?
+            };
+            metrics.retrieval_latency.observe(retrieval_start.elapsed().as_secs_f64() * 1000.0);
+            metrics.record_retrieval_mode(&retrieval_mode);
+            metrics.record_reranking_stage(&engine.stage_timings()).await;
+
+            // Update token count with actual usage (chunks already carry a
+            // real token count from the chunker, no need to re-estimate)
             {
                 let mut current_count = token_count_arc.write().await;
                 let actual_tokens = results.iter()
-                    .map(|r| r.chunk.content.len() / 4)
+                    .map(|r| r.chunk.token_count)
                     .sum::<usize>();
                 *current_count += query_tokens + actual_tokens;
             }
-            
+
             Ok(results.into_iter().map(PyRetrievalResult::from).collect::<Vec<_>>())
         })
     }
-    
+
+    /// Stream relevant context for a query as an async iterator. Returns a
+    /// `PyContextStream` usable as `async for result in engine.stream_context(q)`.
+    /// A background task runs `retrieve_context` to completion and then
+    /// sends its results through the channel one at a time, so the first
+    /// result arrives only once the whole batch has been retrieved;
+    /// stopping early skips the remaining results but not their retrieval.
+    /// Unlike `retrieve_context`, no token-limit check is made and
+    /// `token_count` is not updated.
+    fn stream_context<'p>(&self, py: Python<'p>, query: String, mode: Option<String>) -> PyResult<&'p PyAny> {
+        let engine_arc = self.engine.clone();
+
+        let retrieval_mode = match mode.as_deref() {
+            Some("research") => RetrievalMode::Research,
+            _ => RetrievalMode::General,
+        };
+
+        future_into_py(py, async move {
+            let (tx, rx) = mpsc::channel(32);
+
+            tokio::spawn(async move {
+                let engine_guard = engine_arc.read().await;
+                let engine = match engine_guard.as_ref() {
+                    Some(engine) => engine,
+                    None => return,
+                };
+
+                match engine.retrieve_context(&query, retrieval_mode).await {
+                    Ok(results) => {
+                        for result in results {
+                            if tx.send(Ok(PyRetrievalResult::from(result))).await.is_err() {
+                                break;
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        let _ = tx.send(Err(format!("Failed to retrieve context: {}", e))).await;
+                    }
+                }
+            });
+
+            Ok(PyContextStream { receiver: Arc::new(RwLock::new(rx)) })
+        })
+    }
+
     /// Get current token count
     fn get_token_count<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
         let token_count_arc = self.token_count.clone();
@@ -316,15 +600,17 @@ impl PyRagEngine {
     /// Perform maintenance
     fn maintenance<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
         let engine_arc = self.engine.clone();
-        
+        let metrics = self.metrics.clone();
+
         future_into_py(py, async move {
             let engine_guard = engine_arc.read().await;
             let engine = engine_guard.as_ref()
                 .ok_or_else(|| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>("RAG engine not initialized. Call initialize() first."))?;
-            
+
             engine.maintenance().await
                 .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to perform maintenance: {}", e)))?;
-            
+
+            metrics.maintenance_total.fetch_add(1, Ordering::Relaxed);
             Ok(())
         })
     }
@@ -340,10 +626,194 @@ impl PyRagEngine {
             
             let health = engine.health_check().await
                 .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Health check failed: {}", e)))?;
-            
+
             Ok(health)
         })
     }
+
+    /// Render ingestion/retrieval/cache/token metrics as Prometheus text
+    /// exposition format, so an operator running the engine embedded in a
+    /// Python service can scrape it the same way they'd scrape any other
+    /// service instead of polling `get_stats()`/`get_token_count()` from
+    /// application code.
+    fn metrics_text<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
+        let engine_arc = self.engine.clone();
+        let token_count_arc = self.token_count.clone();
+        let token_limit = self.token_limit;
+        let metrics = self.metrics.clone();
+
+        future_into_py(py, async move {
+            let cache_hit_rate = {
+                let engine_guard = engine_arc.read().await;
+                match engine_guard.as_ref() {
+                    Some(engine) => engine.get_stats().await.cache_hit_rate,
+                    None => 0.0,
+                }
+            };
+            let token_count = *token_count_arc.read().await;
+
+            Ok(metrics.render_prometheus(cache_hit_rate, token_count, token_limit).await)
+        })
+    }
+
+    /// Structured equivalent of `metrics_text`, as a `dict` of metric name
+    /// to current value, for callers that want to inspect or re-export
+    /// metrics programmatically instead of parsing Prometheus text.
+    fn metrics<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
+        let engine_arc = self.engine.clone();
+        let token_count_arc = self.token_count.clone();
+        let token_limit = self.token_limit;
+        let metrics = self.metrics.clone();
+
+        future_into_py(py, async move {
+            let cache_hit_rate = {
+                let engine_guard = engine_arc.read().await;
+                match engine_guard.as_ref() {
+                    Some(engine) => engine.get_stats().await.cache_hit_rate,
+                    None => 0.0,
+                }
+            };
+            let token_count = *token_count_arc.read().await;
+
+            Ok(metrics.as_dict(cache_hit_rate, token_count, token_limit).await)
+        })
+    }
+}
+
+/// Counters and latency histograms backing `PyRagEngine::metrics_text`/
+/// `metrics`. Kept at the binding layer rather than inside `RagEngine`
+/// itself since these track calls through the Python surface (including
+/// the token-limit bookkeeping `PyRagEngine` already does), not engine
+/// internals.
+struct PyEngineMetrics {
+    ingest_total: AtomicU64,
+    retrieve_total_general: AtomicU64,
+    retrieve_total_research: AtomicU64,
+    retrieve_total_hybrid: AtomicU64,
+    maintenance_total: AtomicU64,
+    token_reservations_granted: AtomicU64,
+    token_reservations_rejected: AtomicU64,
+    retrieval_latency: Histogram,
+    /// Most recent `"reranking"` stage stats, refreshed after every
+    /// `retrieve_context` call from `RagEngine::stage_timings`. Only
+    /// populated once `PerformanceConfig::enable_span_capture` is on,
+    /// which `initialize`/`initialize_with_config` always set.
+    rerank_stage: RwLock<Option<crate::benchmark::StageStats>>,
+}
+
+impl PyEngineMetrics {
+    fn new() -> Self {
+        Self {
+            ingest_total: AtomicU64::new(0),
+            retrieve_total_general: AtomicU64::new(0),
+            retrieve_total_research: AtomicU64::new(0),
+            retrieve_total_hybrid: AtomicU64::new(0),
+            maintenance_total: AtomicU64::new(0),
+            token_reservations_granted: AtomicU64::new(0),
+            token_reservations_rejected: AtomicU64::new(0),
+            retrieval_latency: Histogram::new(
+                "rag_binding_retrieval_latency_ms",
+                "retrieve_context call latency as seen by the Python binding",
+                DEFAULT_LATENCY_BUCKETS,
+            ),
+            rerank_stage: RwLock::new(None),
+        }
+    }
+
+    fn record_retrieval_mode(&self, mode: &RetrievalMode) {
+        let counter = match mode {
+            RetrievalMode::General => &self.retrieve_total_general,
+            RetrievalMode::Research => &self.retrieve_total_research,
+            RetrievalMode::Hybrid { .. } => &self.retrieve_total_hybrid,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    async fn record_reranking_stage(&self, report: &crate::benchmark::StageReport) {
+        if let Some(stats) = report.stages.get("reranking") {
+            *self.rerank_stage.write().await = Some(stats.clone());
+        }
+    }
+
+    async fn render_prometheus(&self, cache_hit_rate: f32, token_count: usize, token_limit: usize) -> String {
+        let mut out = String::new();
+
+        push_counter(&mut out, "rag_binding_ingest_total", "Total ingest_document calls that completed successfully", self.ingest_total.load(Ordering::Relaxed));
+        push_counter(&mut out, "rag_binding_maintenance_total", "Total maintenance calls that completed successfully", self.maintenance_total.load(Ordering::Relaxed));
+        push_counter(&mut out, "rag_binding_token_reservations_granted_total", "Total token-limit reservations granted to ingest_document/retrieve_context", self.token_reservations_granted.load(Ordering::Relaxed));
+        push_counter(&mut out, "rag_binding_token_reservations_rejected_total", "Total token-limit reservations rejected at token_limit", self.token_reservations_rejected.load(Ordering::Relaxed));
+
+        out.push_str("# HELP rag_binding_retrieve_total Total retrieve_context calls that completed successfully, by mode\n");
+        out.push_str("# TYPE rag_binding_retrieve_total counter\n");
+        out.push_str(&format!("rag_binding_retrieve_total{{mode=\"general\"}} {}\n", self.retrieve_total_general.load(Ordering::Relaxed)));
+        out.push_str(&format!("rag_binding_retrieve_total{{mode=\"research\"}} {}\n", self.retrieve_total_research.load(Ordering::Relaxed)));
+        out.push_str(&format!("rag_binding_retrieve_total{{mode=\"hybrid\"}} {}\n", self.retrieve_total_hybrid.load(Ordering::Relaxed)));
+
+        push_gauge(&mut out, "rag_binding_cache_hit_rate", "Overall cache hit rate reported by RagEngine::get_stats", cache_hit_rate as f64);
+        push_gauge(&mut out, "rag_binding_token_count", "Current tracked token count against token_limit", token_count as f64);
+        push_gauge(&mut out, "rag_binding_token_limit", "Configured token_limit", token_limit as f64);
+
+        out.push_str(&self.retrieval_latency.render_prometheus());
+
+        if let Some(stats) = self.rerank_stage.read().await.as_ref() {
+            push_gauge(&mut out, "rag_binding_rerank_latency_p50_ms", "Most recent reranking stage p50 latency", stats.p50_ms);
+            push_gauge(&mut out, "rag_binding_rerank_latency_p95_ms", "Most recent reranking stage p95 latency", stats.p95_ms);
+            push_gauge(&mut out, "rag_binding_rerank_latency_count", "Samples behind the most recent reranking stage stats", stats.count as f64);
+        }
+
+        out
+    }
+
+    async fn as_dict(&self, cache_hit_rate: f32, token_count: usize, token_limit: usize) -> HashMap<String, f64> {
+        let mut metrics = HashMap::new();
+        metrics.insert("ingest_total".to_string(), self.ingest_total.load(Ordering::Relaxed) as f64);
+        metrics.insert("maintenance_total".to_string(), self.maintenance_total.load(Ordering::Relaxed) as f64);
+        metrics.insert("token_reservations_granted".to_string(), self.token_reservations_granted.load(Ordering::Relaxed) as f64);
+        metrics.insert("token_reservations_rejected".to_string(), self.token_reservations_rejected.load(Ordering::Relaxed) as f64);
+        metrics.insert("retrieve_total_general".to_string(), self.retrieve_total_general.load(Ordering::Relaxed) as f64);
+        metrics.insert("retrieve_total_research".to_string(), self.retrieve_total_research.load(Ordering::Relaxed) as f64);
+        metrics.insert("retrieve_total_hybrid".to_string(), self.retrieve_total_hybrid.load(Ordering::Relaxed) as f64);
+        metrics.insert("retrieval_latency_count".to_string(), self.retrieval_latency.count() as f64);
+        metrics.insert("retrieval_latency_sum_ms".to_string(), self.retrieval_latency.sum());
+        metrics.insert("cache_hit_rate".to_string(), cache_hit_rate as f64);
+        metrics.insert("token_count".to_string(), token_count as f64);
+        metrics.insert("token_limit".to_string(), token_limit as f64);
+
+        if let Some(stats) = self.rerank_stage.read().await.as_ref() {
+            metrics.insert("rerank_latency_p50_ms".to_string(), stats.p50_ms);
+            metrics.insert("rerank_latency_p95_ms".to_string(), stats.p95_ms);
+            metrics.insert("rerank_latency_count".to_string(), stats.count as f64);
+        }
+
+        metrics
+    }
+}
+
+/// Append one Prometheus counter's `HELP`/`TYPE`/sample lines to `out`
+fn push_counter(out: &mut String, name: &str, help: &str, value: u64) {
+    out.push_str(&format!("# HELP {} {}\n# TYPE {} counter\n{} {}\n", name, help, name, name, value));
+}
+
+/// Append one Prometheus gauge's `HELP`/`TYPE`/sample lines to `out`
+fn push_gauge(out: &mut String, name: &str, help: &str, value: f64) {
+    out.push_str(&format!("# HELP {} {}\n# TYPE {} gauge\n{} {}\n", name, help, name, name, value));
+}
+
+/// Outcome of one item in an `ingest_documents` batch call. Exactly one of
+/// `document_id`/`error` is set.
+#[pyclass(name = "IngestOutcome")]
+#[derive(Clone)]
+pub struct PyIngestOutcome {
+    /// Position of this item in the `docs` list passed to `ingest_documents`
+    #[pyo3(get)]
+    index: usize,
+    #[pyo3(get)]
+    document_id: Option<String>,
+    #[pyo3(get)]
+    error: Option<String>,
+    /// This item's share of the batch's token reservation
+    #[pyo3(get)]
+    estimated_tokens: usize,
 }
 
 /// Python wrapper for Document
@@ -439,6 +909,36 @@ impl From<RetrievalResult> for PyRetrievalResult {
     }
 }
 
+/// Async iterator over `PyRetrievalResult`s returned by
+/// `PyRagEngine::stream_context`, backed by an `mpsc::Receiver` fed by a
+/// background task driving the engine's retrieval pipeline. Implements
+/// PyO3's async-iterator protocol (`__aiter__`/`__anext__`) so Python can
+/// `async for result in stream: ...` and stop early without draining it.
+#[pyclass(name = "ContextStream")]
+pub struct PyContextStream {
+    receiver: Arc<RwLock<mpsc::Receiver<Result<PyRetrievalResult, String>>>>,
+}
+
+#[pymethods]
+impl PyContextStream {
+    fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __anext__<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
+        let receiver = self.receiver.clone();
+
+        future_into_py(py, async move {
+            let mut receiver = receiver.write().await;
+            match receiver.recv().await {
+                Some(Ok(result)) => Ok(result),
+                Some(Err(message)) => Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(message)),
+                None => Err(PyStopAsyncIteration::new_err(())),
+            }
+        })
+    }
+}
+
 /// Python wrapper for RagStats with token information
 #[pyclass(name = "RagStats")]
 #[derive(Clone)]
@@ -454,24 +954,30 @@ pub struct PyRagStats {
     #[pyo3(get)]
     cache_hit_rate: f32,
     #[pyo3(get)]
+    user_provided_embedding_count: usize,
+    #[pyo3(get)]
+    last_ingest_batch_size: usize,
+    #[pyo3(get)]
     last_updated: String,
     #[pyo3(get)]
     estimated_total_tokens: usize,
+    #[pyo3(get)]
+    deduplicated_count: usize,
 }
 
 impl From<RagStats> for PyRagStats {
     fn from(stats: RagStats) -> Self {
-        // Estimate total tokens based on chunk count and average size
-        let estimated_total_tokens = (stats.chunk_count as f32 * stats.avg_chunk_size) as usize;
-        
         Self {
             document_count: stats.document_count,
             chunk_count: stats.chunk_count,
             embedding_count: stats.embedding_count,
             avg_chunk_size: stats.avg_chunk_size,
             cache_hit_rate: stats.cache_hit_rate,
+            user_provided_embedding_count: stats.user_provided_embedding_count,
+            last_ingest_batch_size: stats.last_ingest_batch_size,
             last_updated: stats.last_updated.to_rfc3339(),
-            estimated_total_tokens,
+            estimated_total_tokens: stats.total_tokens,
+            deduplicated_count: stats.deduplicated_count,
         }
     }
 }
@@ -489,6 +995,38 @@ pub fn get_version() -> &'static str {
     env!("CARGO_PKG_VERSION")
 }
 
+/// Tokenizer shared by the standalone `count_tokens` pyfunction, built once
+/// on first use from the default chunking config rather than per call.
+static DEFAULT_COUNT_TOKENS_CHUNKER: tokio::sync::OnceCell<Arc<crate::chunker::SmartChunker>> =
+    tokio::sync::OnceCell::const_new();
+
+async fn default_count_tokens_chunker() -> crate::error::RagResult<Arc<crate::chunker::SmartChunker>> {
+    DEFAULT_COUNT_TOKENS_CHUNKER
+        .get_or_try_init(|| async {
+            crate::chunker::SmartChunker::with_default_tokenizer(crate::config::RagConfig::default().chunking)
+                .await
+                .map(Arc::new)
+        })
+        .await
+        .cloned()
+}
+
+/// Count tokens in `text` with the same tokenizer `PyRagEngine` uses, so
+/// Python callers can pre-check a budget before calling `ingest_document`/
+/// `retrieve_context` without spinning up a full engine
+#[pyfunction]
+pub fn count_tokens(py: Python, text: String) -> PyResult<&PyAny> {
+    future_into_py(py, async move {
+        let chunker = default_count_tokens_chunker()
+            .await
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to load tokenizer: {}", e)))?;
+
+        chunker
+            .count_tokens(&text)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(format!("Failed to count tokens: {}", e)))
+    })
+}
+
 /// Helper function to convert Python dict to HashMap<String, String>
 fn python_dict_to_string_map(dict: &PyDict) -> PyResult<HashMap<String, String>> {
     let mut map = HashMap::new();
@@ -502,11 +1040,272 @@ fn python_dict_to_string_map(dict: &PyDict) -> PyResult<HashMap<String, String>>
     Ok(map)
 }
 
-/// Helper function to convert Python dict to RagConfig
-fn python_dict_to_config(_dict: &PyDict) -> PyResult<RagConfig> {
-    // For now, return default config
-    // TODO: Implement proper conversion from Python dict to RagConfig
-    Ok(RagConfig::default())
+/// Recursively convert a Python value (dict/list/str/int/float/bool/None)
+/// into the equivalent `serde_json::Value`. Bool is checked before int,
+/// since Python `bool` is a subclass of `int` and would otherwise extract
+/// as `0`/`1`.
+fn pyobject_to_json_value(obj: &PyAny) -> PyResult<serde_json::Value> {
+    if obj.is_none() {
+        return Ok(serde_json::Value::Null);
+    }
+    if let Ok(dict) = obj.downcast::<PyDict>() {
+        let mut map = serde_json::Map::with_capacity(dict.len());
+        for (key, value) in dict.iter() {
+            let key = key.extract::<String>()
+                .map_err(|_| PyErr::new::<pyo3::exceptions::PyTypeError, _>("config dict keys must be strings"))?;
+            map.insert(key, pyobject_to_json_value(value)?);
+        }
+        return Ok(serde_json::Value::Object(map));
+    }
+    if let Ok(list) = obj.downcast::<PyList>() {
+        let mut items = Vec::with_capacity(list.len());
+        for item in list.iter() {
+            items.push(pyobject_to_json_value(item)?);
+        }
+        return Ok(serde_json::Value::Array(items));
+    }
+    if let Ok(value) = obj.extract::<bool>() {
+        return Ok(serde_json::Value::Bool(value));
+    }
+    if let Ok(value) = obj.extract::<i64>() {
+        return Ok(serde_json::Value::Number(value.into()));
+    }
+    if let Ok(value) = obj.extract::<f64>() {
+        return Ok(serde_json::json!(value));
+    }
+    if let Ok(value) = obj.extract::<String>() {
+        return Ok(serde_json::Value::String(value));
+    }
+    Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+        "unsupported config value type: {}",
+        obj.get_type().name()?
+    )))
+}
+
+/// The inverse of `pyobject_to_json_value`, used by `config_to_dict` to turn
+/// a serialized `RagConfig` back into a Python object.
+fn json_value_to_pyobject(py: Python, value: &serde_json::Value) -> PyResult<PyObject> {
+    Ok(match value {
+        serde_json::Value::Null => py.None(),
+        serde_json::Value::Bool(b) => b.into_py(py),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => i.into_py(py),
+            None => n.as_f64().unwrap_or(0.0).into_py(py),
+        },
+        serde_json::Value::String(s) => s.into_py(py),
+        serde_json::Value::Array(items) => {
+            let list = PyList::empty(py);
+            for item in items {
+                list.append(json_value_to_pyobject(py, item)?)?;
+            }
+            list.into_py(py)
+        }
+        serde_json::Value::Object(map) => {
+            let dict = PyDict::new(py);
+            for (key, value) in map {
+                dict.set_item(key, json_value_to_pyobject(py, value)?)?;
+            }
+            dict.into_py(py)
+        }
+    })
+}
+
+/// Recursively overlay `overlay` onto `template` (a `serde_json::to_value`
+/// of `RagConfig::default()`, or a sub-tree of it), tracking the dotted key
+/// path so an unknown key or type mismatch can name exactly where it went
+/// wrong. `embeddings.embedders` is a `HashMap<String, EmbeddingConfig>`
+/// rather than a fixed set of fields, so new embedder names are always
+/// accepted there, each validated against a fresh `EmbeddingConfig::default()`
+/// template rather than `template`'s (possibly differently-named) entries.
+fn merge_config_value(
+    template: &mut serde_json::Value,
+    overlay: &serde_json::Value,
+    path: &[String],
+    strict: bool,
+) -> PyResult<()> {
+    let (serde_json::Value::Object(template_map), serde_json::Value::Object(overlay_map)) = (&mut *template, overlay) else {
+        *template = overlay.clone();
+        return Ok(());
+    };
+
+    let is_embedders_map = path.len() == 2 && path[0] == "embeddings" && path[1] == "embedders";
+
+    for (key, overlay_value) in overlay_map {
+        let mut child_path = path.to_vec();
+        child_path.push(key.clone());
+
+        if let Some(template_value) = template_map.get_mut(key) {
+            merge_config_value(template_value, overlay_value, &child_path, strict)?;
+        } else if is_embedders_map {
+            let mut fresh = serde_json::to_value(EmbeddingConfig::default())
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+            merge_config_value(&mut fresh, overlay_value, &child_path, strict)?;
+            template_map.insert(key.clone(), fresh);
+        } else if strict {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "unknown config key: {}",
+                child_path.join(".")
+            )));
+        } else {
+            template_map.insert(key.clone(), overlay_value.clone());
+        }
+    }
+
+    Ok(())
+}
+
+/// Convert a Python dict into a `RagConfig` by recursively overlaying it
+/// onto `RagConfig::default()`'s own fields - chunking, vector store,
+/// embedders, reranking, hybrid search, generation, cache, performance, and
+/// dedup policy all accept the same nested shape `RagConfig` itself
+/// serializes to. Unknown keys raise `PyValueError` naming their full dotted
+/// path unless `strict` is false, in which case they're merged in verbatim
+/// and left for `serde`'s own deserialization to accept or reject. Once
+/// merged, the result is parsed back into `RagConfig` (surfacing any type
+/// mismatch as `PyValueError`) and run through `RagConfig::validate()` so
+/// out-of-range values are rejected the same way a hand-written config
+/// would be.
+fn python_dict_to_config(dict: &PyDict, strict: bool) -> PyResult<RagConfig> {
+    let mut template = serde_json::to_value(RagConfig::default())
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+    let mut overlay = pyobject_to_json_value(dict)?;
+
+    // "fault_injection" is consumed separately by `python_dict_to_fault_config`
+    // for `FaultInjector`, not a `RagConfig` field, so it's not part of this
+    // conversion's schema.
+    if let serde_json::Value::Object(map) = &mut overlay {
+        map.remove("fault_injection");
+    }
+
+    merge_config_value(&mut template, &overlay, &[], strict)?;
+
+    let config: RagConfig = serde_json::from_value(template)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("invalid config: {}", e)))?;
+
+    config.validate()
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("invalid config: {}", e)))?;
+
+    Ok(config)
+}
+
+/// Chaos-testing configuration for `PyRagEngine`, parsed from an optional
+/// `"fault_injection"` sub-dict passed to `with_config`:
+/// `{"enabled": bool, "seed": int, "ingest_failure_rate": float,
+/// "retrieve_failure_rate": float, "max_delay_ms": int,
+/// "max_lock_contention_ms": int}`. Every key is optional and defaults to
+/// off/zero, so a caller that doesn't pass `"fault_injection"` at all gets
+/// the same behavior as `PyRagEngine::new`.
+#[derive(Debug, Clone, Copy)]
+struct FaultInjectionConfig {
+    enabled: bool,
+    seed: u64,
+    ingest_failure_rate: f64,
+    retrieve_failure_rate: f64,
+    max_delay_ms: u64,
+    max_lock_contention_ms: u64,
+}
+
+impl Default for FaultInjectionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            seed: 0,
+            ingest_failure_rate: 0.0,
+            retrieve_failure_rate: 0.0,
+            max_delay_ms: 0,
+            max_lock_contention_ms: 0,
+        }
+    }
+}
+
+/// Extract `FaultInjectionConfig` from `dict["fault_injection"]`, if present.
+fn python_dict_to_fault_config(dict: &PyDict) -> PyResult<FaultInjectionConfig> {
+    let mut config = FaultInjectionConfig::default();
+
+    let Some(fault_dict) = dict.get_item("fault_injection")? else {
+        return Ok(config);
+    };
+    let fault_dict: &PyDict = fault_dict.downcast()
+        .map_err(|_| PyErr::new::<pyo3::exceptions::PyTypeError, _>("fault_injection must be a dict"))?;
+
+    if let Some(value) = fault_dict.get_item("enabled")? {
+        config.enabled = value.extract()?;
+    }
+    if let Some(value) = fault_dict.get_item("seed")? {
+        config.seed = value.extract()?;
+    }
+    if let Some(value) = fault_dict.get_item("ingest_failure_rate")? {
+        config.ingest_failure_rate = value.extract()?;
+    }
+    if let Some(value) = fault_dict.get_item("retrieve_failure_rate")? {
+        config.retrieve_failure_rate = value.extract()?;
+    }
+    if let Some(value) = fault_dict.get_item("max_delay_ms")? {
+        config.max_delay_ms = value.extract()?;
+    }
+    if let Some(value) = fault_dict.get_item("max_lock_contention_ms")? {
+        config.max_lock_contention_ms = value.extract()?;
+    }
+
+    Ok(config)
+}
+
+/// Seeded fault injector backing `PyRagEngine`'s chaos-testing mode.
+/// Disabled (`FaultInjector::disabled`) unless built via `with_config`'s
+/// `"fault_injection"` dict. The RNG is behind a `std::sync::Mutex` rather
+/// than the `tokio::sync::RwLock` used elsewhere in this file, following
+/// `RateLimiter::state`'s pattern of a synchronous lock that's only ever
+/// held across a `gen_*` call, never across an `.await`.
+struct FaultInjector {
+    config: FaultInjectionConfig,
+    rng: StdMutex<StdRng>,
+}
+
+impl FaultInjector {
+    fn new(config: FaultInjectionConfig) -> Self {
+        Self { rng: StdMutex::new(StdRng::seed_from_u64(config.seed)), config }
+    }
+
+    fn disabled() -> Self {
+        Self::new(FaultInjectionConfig::default())
+    }
+
+    /// Roll against `rate`; always `false` when fault injection is off.
+    fn should_fail(&self, rate: f64) -> bool {
+        if !self.config.enabled || rate <= 0.0 {
+            return false;
+        }
+        let mut rng = self.rng.lock().expect("fault injector rng mutex poisoned");
+        rng.gen_bool(rate.clamp(0.0, 1.0))
+    }
+
+    fn should_fail_ingest(&self) -> bool {
+        self.should_fail(self.config.ingest_failure_rate)
+    }
+
+    fn should_fail_retrieve(&self) -> bool {
+        self.should_fail(self.config.retrieve_failure_rate)
+    }
+
+    /// A simulated engine-lock-contention delay to sleep before acquiring
+    /// the real lock, or `None` when fault injection is off.
+    fn lock_contention_delay(&self) -> Option<Duration> {
+        self.random_delay(self.config.max_lock_contention_ms)
+    }
+
+    /// A simulated slow-backend delay to sleep before the real call, or
+    /// `None` when fault injection is off.
+    fn call_delay(&self) -> Option<Duration> {
+        self.random_delay(self.config.max_delay_ms)
+    }
+
+    fn random_delay(&self, max_ms: u64) -> Option<Duration> {
+        if !self.config.enabled || max_ms == 0 {
+            return None;
+        }
+        let mut rng = self.rng.lock().expect("fault injector rng mutex poisoned");
+        Some(Duration::from_millis(rng.gen_range(0..=max_ms)))
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,125 @@
+//! Token-bucket rate limiting for upstream embedding/rerank calls.
+//!
+//! `EmbeddingClient`/`RerankingClient` already retry on a 429 via
+//! `retry::wait_before_retry`, but that only recovers after a request has
+//! already tripped the provider's limit. `RateLimiter` lets a client
+//! reserve a permit up front and sleep until the bucket refills, so a large
+//! batch job smooths itself out in front of the limit instead of bursting
+//! into it and paying for the retry.
+
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Token-bucket settings for one `EmbeddingConfig`/`RerankingConfig`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    /// Maximum permits the bucket can hold at once, i.e. the largest burst
+    /// allowed before callers start waiting
+    pub capacity: f64,
+    /// Permits added back to the bucket per second
+    pub refill_per_sec: f64,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self { capacity: 10.0, refill_per_sec: 5.0 }
+    }
+}
+
+struct BucketState {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// A token bucket shared by every request a client makes. Starts full so
+/// the first burst up to `capacity` goes through immediately; every
+/// `acquire` after that either succeeds at once or sleeps for exactly as
+/// long as the bucket needs to refill enough permits.
+pub struct RateLimiter {
+    capacity: f64,
+    refill_per_sec: f64,
+    state: Mutex<BucketState>,
+}
+
+impl RateLimiter {
+    /// Create a bucket from `config`, starting full at `config.capacity`.
+    pub fn new(config: RateLimitConfig) -> Arc<Self> {
+        Arc::new(Self {
+            capacity: config.capacity,
+            refill_per_sec: config.refill_per_sec,
+            state: Mutex::new(BucketState { tokens: config.capacity, last_refill: Instant::now() }),
+        })
+    }
+
+    /// Block until `permits` tokens are available, consuming them before
+    /// returning. `permits` larger than `capacity` will never be satisfied
+    /// and wait forever, so callers should keep per-call permits at or
+    /// below the configured capacity.
+    pub async fn acquire(&self, permits: f64) {
+        loop {
+            let wait = {
+                let mut state = self.state.lock().unwrap();
+                self.refill(&mut state);
+                if state.tokens >= permits {
+                    state.tokens -= permits;
+                    None
+                } else {
+                    let deficit = permits - state.tokens;
+                    Some(Duration::from_secs_f64(deficit / self.refill_per_sec))
+                }
+            };
+
+            match wait {
+                None => return,
+                Some(duration) => tokio::time::sleep(duration).await,
+            }
+        }
+    }
+
+    /// Add back whatever has accrued since the last refill, capped at `capacity`.
+    fn refill(&self, state: &mut BucketState) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(state.last_refill).as_secs_f64();
+        state.tokens = (state.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        state.last_refill = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn acquire_does_not_block_within_capacity() {
+        let limiter = RateLimiter::new(RateLimitConfig { capacity: 5.0, refill_per_sec: 1.0 });
+        let start = Instant::now();
+        for _ in 0..5 {
+            limiter.acquire(1.0).await;
+        }
+        assert!(start.elapsed() < Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn acquire_waits_for_refill_once_the_bucket_is_empty() {
+        let limiter = RateLimiter::new(RateLimitConfig { capacity: 1.0, refill_per_sec: 20.0 });
+        limiter.acquire(1.0).await;
+
+        let start = Instant::now();
+        limiter.acquire(1.0).await;
+        // Needs ~50ms to refill one permit at 20/sec; allow scheduling slack.
+        assert!(start.elapsed() >= Duration::from_millis(30));
+    }
+
+    #[tokio::test]
+    async fn refill_never_exceeds_capacity() {
+        let limiter = RateLimiter::new(RateLimitConfig { capacity: 2.0, refill_per_sec: 1000.0 });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let tokens = {
+            let mut state = limiter.state.lock().unwrap();
+            limiter.refill(&mut state);
+            state.tokens
+        };
+        assert!(tokens <= 2.0);
+    }
+}
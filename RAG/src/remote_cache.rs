@@ -0,0 +1,160 @@
+//! Pluggable remote/shared cache backends for `RagCache`.
+//!
+//! Each RAG worker behind a load balancer otherwise maintains its own
+//! in-process cache, so the same query gets embedded and retrieved
+//! repeatedly across nodes. A `CacheBackend` lets `RagCache` optionally
+//! fall through to a store shared by every worker, keyed by the same
+//! `CacheKey` used by the in-memory tiers.
+
+use crate::error::{RagError, RagResult};
+use lru::LruCache;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+/// Which tier actually answered a `CacheBackend::get`, so callers can
+/// distinguish a free in-process hit from one that required a round trip
+/// to a shared store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackendTier {
+    Memory,
+    Redis,
+}
+
+/// The bytes a `CacheBackend::get` found, plus which tier served them
+#[derive(Debug, Clone)]
+pub struct BackendHit {
+    pub bytes: Vec<u8>,
+    pub tier: BackendTier,
+}
+
+/// A byte-oriented cache store `RagCache` can fall through to once its own
+/// in-memory and disk tiers miss. `kind` namespaces entries the same way
+/// the disk tier's Sled trees do ("embeddings", "reranking", "retrieval");
+/// `key` is the same `CacheKey` hash the in-memory tiers use.
+#[async_trait::async_trait]
+pub trait CacheBackend: Send + Sync {
+    async fn get(&self, kind: &str, key: u64) -> Option<BackendHit>;
+
+    async fn put(&self, kind: &str, key: u64, bytes: Vec<u8>, ttl: Duration) -> RagResult<()>;
+}
+
+fn entry_key(kind: &str, key: u64) -> (String, u64) {
+    (kind.to_string(), key)
+}
+
+/// In-process byte store, bounded by an LRU. Used both as the hot tier in
+/// front of `RedisBackend` (`LayeredBackend`) and as a standalone backend
+/// for tests/local development without a Redis instance.
+pub struct MemoryBackend {
+    entries: RwLock<LruCache<(String, u64), (Vec<u8>, Instant, Duration)>>,
+}
+
+impl MemoryBackend {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::new(1).unwrap());
+        Self {
+            entries: RwLock::new(LruCache::new(capacity)),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl CacheBackend for MemoryBackend {
+    async fn get(&self, kind: &str, key: u64) -> Option<BackendHit> {
+        let mut entries = self.entries.write().await;
+        let (bytes, created_at, ttl) = entries.get(&entry_key(kind, key))?;
+        if created_at.elapsed() > *ttl {
+            entries.pop(&entry_key(kind, key));
+            return None;
+        }
+        Some(BackendHit { bytes: bytes.clone(), tier: BackendTier::Memory })
+    }
+
+    async fn put(&self, kind: &str, key: u64, bytes: Vec<u8>, ttl: Duration) -> RagResult<()> {
+        self.entries.write().await.put(entry_key(kind, key), (bytes, Instant::now(), ttl));
+        Ok(())
+    }
+}
+
+/// Redis-backed shared cache. Values are stored under
+/// `rag:cache:{kind}:{key}` (the `u64` key rendered as a decimal string)
+/// with the entry's `ttl` set as the key's Redis expiry, so a cold entry
+/// disappears from the shared store on its own without a background
+/// sweep.
+#[cfg(feature = "redis-cache")]
+pub struct RedisBackend {
+    manager: redis::aio::ConnectionManager,
+}
+
+#[cfg(feature = "redis-cache")]
+impl RedisBackend {
+    pub async fn connect(redis_url: &str) -> RagResult<Self> {
+        let client = redis::Client::open(redis_url)
+            .map_err(|e| RagError::cache(format!("invalid redis_url: {}", e)))?;
+        let manager = client
+            .get_connection_manager()
+            .await
+            .map_err(|e| RagError::cache(format!("failed to connect to redis: {}", e)))?;
+        Ok(Self { manager })
+    }
+
+    fn redis_key(kind: &str, key: u64) -> String {
+        format!("rag:cache:{}:{}", kind, key)
+    }
+}
+
+#[cfg(feature = "redis-cache")]
+#[async_trait::async_trait]
+impl CacheBackend for RedisBackend {
+    async fn get(&self, kind: &str, key: u64) -> Option<BackendHit> {
+        use redis::AsyncCommands;
+        let mut conn = self.manager.clone();
+        let bytes: Option<Vec<u8>> = conn.get(Self::redis_key(kind, key)).await.ok()?;
+        bytes.map(|bytes| BackendHit { bytes, tier: BackendTier::Redis })
+    }
+
+    async fn put(&self, kind: &str, key: u64, bytes: Vec<u8>, ttl: Duration) -> RagResult<()> {
+        use redis::AsyncCommands;
+        let mut conn = self.manager.clone();
+        let ttl_secs = ttl.as_secs().max(1);
+        conn.set_ex::<_, _, ()>(Self::redis_key(kind, key), bytes, ttl_secs)
+            .await
+            .map_err(|e| RagError::cache(format!("redis SET failed: {}", e)))
+    }
+}
+
+/// Checks `memory` first and only falls through to `remote` (typically
+/// `RedisBackend`) on a miss, backfilling `memory` so the next lookup on
+/// this node stays local. This is what `RemoteCacheMode::MemoryOverRedis`
+/// builds: hot items stay in-process, cold items fall through to the
+/// store shared by every worker.
+pub struct LayeredBackend {
+    memory: MemoryBackend,
+    remote: Arc<dyn CacheBackend>,
+}
+
+impl LayeredBackend {
+    pub fn new(local_capacity: usize, remote: Arc<dyn CacheBackend>) -> Self {
+        Self { memory: MemoryBackend::new(local_capacity), remote }
+    }
+}
+
+#[async_trait::async_trait]
+impl CacheBackend for LayeredBackend {
+    async fn get(&self, kind: &str, key: u64) -> Option<BackendHit> {
+        if let Some(hit) = self.memory.get(kind, key).await {
+            return Some(hit);
+        }
+
+        let hit = self.remote.get(kind, key).await?;
+        let _ = self.memory.put(kind, key, hit.bytes.clone(), Duration::from_secs(60)).await;
+        Some(hit)
+    }
+
+    async fn put(&self, kind: &str, key: u64, bytes: Vec<u8>, ttl: Duration) -> RagResult<()> {
+        self.memory.put(kind, key, bytes.clone(), ttl).await?;
+        self.remote.put(kind, key, bytes, ttl).await
+    }
+}
@@ -1,7 +1,9 @@
 //! Reranking client for scoring and filtering retrieved chunks
 
-use crate::config::RerankingConfig;
+use crate::config::{RerankProvider, RerankingConfig, ScoreNormalization};
 use crate::error::{RagError, RagResult};
+use crate::rate_limiter::RateLimiter;
+use crate::retry::RetryOutcome;
 use crate::types::{RetrievalResult, SubChunk};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -36,12 +38,82 @@ struct Usage {
     total_tokens: usize,
 }
 
+/// Voyage AI's rerank response nests results under `data` rather than
+/// `results`, otherwise matching the `RerankingResult` shape
+#[derive(Debug, Deserialize)]
+struct VoyageRerankingResponse {
+    data: Vec<RerankingResult>,
+}
+
+impl RerankProvider {
+    /// Build the outgoing request body for this provider, renaming/dropping
+    /// fields `make_reranking_request`'s single `RerankingRequest` can't
+    /// express for every backend (e.g. `top_k` vs. `top_n`)
+    fn build_request_body(&self, request: &RerankingRequest) -> serde_json::Value {
+        match self {
+            RerankProvider::Ernie | RerankProvider::Voyage => serde_json::json!({
+                "query": request.query,
+                "documents": request.documents,
+                "model": request.model,
+                "top_k": request.top_k,
+            }),
+            RerankProvider::Cohere | RerankProvider::Jina => serde_json::json!({
+                "query": request.query,
+                "documents": request.documents,
+                "model": request.model,
+                "top_n": request.top_k,
+            }),
+        }
+    }
+
+    /// Parse a provider's JSON response into per-document scores, ordered
+    /// back to the original document index rather than the order the
+    /// provider returned them in
+    fn parse_response(&self, body: &serde_json::Value, num_documents: usize) -> RagResult<Vec<f32>> {
+        let results = match self {
+            RerankProvider::Voyage => {
+                let response: VoyageRerankingResponse = serde_json::from_value(body.clone())
+                    .map_err(|e| RagError::reranking(format!("Failed to parse Voyage response: {}", e)))?;
+                response.data
+            }
+            RerankProvider::Ernie | RerankProvider::Cohere | RerankProvider::Jina => {
+                let response: RerankingResponse = serde_json::from_value(body.clone())
+                    .map_err(|e| RagError::reranking(format!("Failed to parse reranking response: {}", e)))?;
+                response.results
+            }
+        };
+
+        let mut scores = vec![0.0f32; num_documents];
+        for result in results {
+            if result.index < scores.len() {
+                scores[result.index] = result.relevance_score;
+            }
+        }
+        Ok(scores)
+    }
+}
+
+/// A local, in-process cross-encoder scorer, e.g. an ONNX session loaded
+/// once and reused across requests, so `RerankingClient` doesn't pay
+/// per-request model-load cost or depend on a particular runtime
+pub trait CrossEncoderBackend: Send + Sync {
+    /// Score each `(query, document)` pair strictly by position; the
+    /// returned `Vec<f32>` is the same length and order as `pairs`
+    fn score(&self, pairs: &[(String, String)]) -> RagResult<Vec<f32>>;
+}
+
 /// Client for reranking operations
 pub struct RerankingClient {
     /// HTTP client
     client: Client,
     /// Configuration
     config: RerankingConfig,
+    /// Local cross-encoder backend, used instead of the HTTP/Python paths
+    /// when set (see `endpoint = "local://..."`)
+    local_cross_encoder: Option<std::sync::Arc<dyn CrossEncoderBackend>>,
+    /// Built from `config.rate_limit`, if set, and acquired once per
+    /// request attempt before it goes out
+    rate_limiter: Option<std::sync::Arc<RateLimiter>>,
 }
 
 impl RerankingClient {
@@ -51,10 +123,21 @@ impl RerankingClient {
             .timeout(Duration::from_secs(config.timeout))
             .build()
             .map_err(|e| RagError::reranking(format!("Failed to create HTTP client: {}", e)))?;
-        
-        Ok(Self { client, config })
+
+        let rate_limiter = config.rate_limit.map(RateLimiter::new);
+
+        Ok(Self { client, config, local_cross_encoder: None, rate_limiter })
     }
-    
+
+    /// Attach a local cross-encoder backend. Takes effect once
+    /// `config.endpoint` starts with `local://`, routing requests to
+    /// `backend.score` in-process instead of over HTTP or a Python
+    /// subprocess.
+    pub fn with_local_cross_encoder(mut self, backend: std::sync::Arc<dyn CrossEncoderBackend>) -> Self {
+        self.local_cross_encoder = Some(backend);
+        self
+    }
+
     /// Rerank sub-chunks based on query relevance
     pub async fn rerank_sub_chunks(
         &self,
@@ -123,16 +206,35 @@ impl RerankingClient {
         
         // Split into batches if necessary
         let mut all_scores = Vec::new();
-        
+
         for batch in documents.chunks(self.config.max_candidates) {
             let batch_scores = self.rerank_batch_internal(query, batch).await?;
             all_scores.extend(batch_scores);
         }
-        
-        Ok(all_scores)
+
+        Ok(Self::normalize_scores(all_scores, self.config.normalization))
+    }
+
+    /// Rescale provider-specific scores into a common range before they
+    /// reach `set_rerank_score`/threshold comparisons, so a threshold tuned
+    /// for one model doesn't silently mis-filter another
+    fn normalize_scores(scores: Vec<f32>, mode: ScoreNormalization) -> Vec<f32> {
+        match mode {
+            ScoreNormalization::None => scores,
+            ScoreNormalization::Sigmoid => scores.into_iter().map(|s| 1.0 / (1.0 + (-s).exp())).collect(),
+            ScoreNormalization::MinMax => {
+                let min = scores.iter().copied().fold(f32::INFINITY, f32::min);
+                let max = scores.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+                let range = (max - min).max(f32::EPSILON);
+                scores.into_iter().map(|s| (s - min) / range).collect()
+            }
+        }
     }
     
-    /// Internal batch reranking with retries
+    /// Internal batch reranking with retries. On a retryable failure (rate
+    /// limiting or a transient transport/server error), honors a
+    /// provider-supplied `Retry-After` delay when present, otherwise backs
+    /// off with full jitter, retrying up to `config.max_retries` times.
     async fn rerank_batch_internal(&self, query: &str, documents: &[String]) -> RagResult<Vec<f32>> {
         let request = RerankingRequest {
             query: query.to_string(),
@@ -140,70 +242,88 @@ impl RerankingClient {
             model: self.config.model.clone(),
             top_k: Some(documents.len()), // Return scores for all documents
         };
-        
+
         let mut last_error = None;
-        
+
         for attempt in 0..=self.config.max_retries {
+            if let Some(limiter) = &self.rate_limiter {
+                limiter.acquire(1.0).await;
+            }
+
             match self.make_reranking_request(&request).await {
                 Ok(scores) => return Ok(scores),
-                Err(e) => {
-                    last_error = Some(e);
+                Err(RetryOutcome::Fatal(error)) => return Err(error),
+                Err(RetryOutcome::Retryable { error, retry_after }) => {
                     if attempt < self.config.max_retries {
-                        // Exponential backoff
-                        let delay = Duration::from_millis(100 * (2_u64.pow(attempt as u32)));
-                        tokio::time::sleep(delay).await;
+                        crate::retry::wait_before_retry(attempt as u32, retry_after).await;
                     }
+                    last_error = Some(error);
                 }
             }
         }
-        
+
         Err(last_error.unwrap_or_else(|| RagError::reranking("Unknown error during reranking")))
     }
-    
+
     /// Make the actual HTTP request for reranking
-    async fn make_reranking_request(&self, request: &RerankingRequest) -> RagResult<Vec<f32>> {
+    async fn make_reranking_request(&self, request: &RerankingRequest) -> Result<Vec<f32>, RetryOutcome> {
+        // Local in-process cross-encoder, no subprocess or network involved
+        if self.config.endpoint.starts_with("local://") {
+            let backend = self.local_cross_encoder.as_ref().ok_or_else(|| {
+                RetryOutcome::Fatal(RagError::reranking(
+                    "endpoint is local:// but no CrossEncoderBackend was attached via with_local_cross_encoder",
+                ))
+            })?;
+            let pairs: Vec<(String, String)> = request
+                .documents
+                .iter()
+                .map(|doc| (request.query.clone(), doc.clone()))
+                .collect();
+            return backend.score(&pairs).map_err(RetryOutcome::Fatal);
+        }
+
         // Check if we should use Python integration client
         if self.config.endpoint.contains("python://") {
-            return self.call_python_reranking_client(request).await;
+            return self.call_python_reranking_client(request).await.map_err(RetryOutcome::Fatal);
         }
-        
+
+        let body = self.config.provider.build_request_body(request);
+
         let mut req_builder = self.client
             .post(&self.config.endpoint)
-            .json(request);
-        
+            .json(&body);
+
         // Add API key if configured
         if let Some(api_key) = &self.config.api_key {
             req_builder = req_builder.bearer_auth(api_key);
         }
-        
+
         let response = timeout(
             Duration::from_secs(self.config.timeout),
             req_builder.send()
         ).await
-        .map_err(|_| RagError::timeout("Reranking request timed out"))?
-        .map_err(|e| RagError::reranking(format!("HTTP request failed: {}", e)))?;
-        
+        .map_err(|_| RetryOutcome::Retryable { error: RagError::timeout("Reranking request timed out"), retry_after: None })?
+        .map_err(|e| RetryOutcome::Retryable { error: RagError::reranking(format!("HTTP request failed: {}", e)), retry_after: None })?;
+
         if !response.status().is_success() {
             let status = response.status();
+            let (retryable, retry_after) = crate::retry::classify_response(&response);
             let error_text = response.text().await
                 .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(RagError::reranking(format!(
+            let error = RagError::reranking(format!(
                 "Reranking API returned error {}: {}", status, error_text
-            )));
+            ));
+            return Err(if retryable {
+                RetryOutcome::Retryable { error, retry_after }
+            } else {
+                RetryOutcome::Fatal(error)
+            });
         }
-        
-        let reranking_response: RerankingResponse = response.json().await
-            .map_err(|e| RagError::reranking(format!("Failed to parse response: {}", e)))?;
-        
-        // Sort by index to maintain order
-        let mut results = reranking_response.results;
-        results.sort_by_key(|r| r.index);
-        
-        let scores: Vec<f32> = results.into_iter()
-            .map(|r| r.relevance_score)
-            .collect();
-        
-        Ok(scores)
+
+        let response_body: serde_json::Value = response.json().await
+            .map_err(|e| RetryOutcome::Fatal(RagError::reranking(format!("Failed to parse response: {}", e))))?;
+
+        self.config.provider.parse_response(&response_body, request.documents.len()).map_err(RetryOutcome::Fatal)
     }
     
     /// Call Python reranking client for integration
@@ -299,6 +419,29 @@ if __name__ == '__main__':
             .cloned()
             .collect()
     }
+
+    /// Keep only results whose final score is within `config.relative_threshold_ratio`
+    /// of the top score in `results` (i.e. `>= top_score * ratio`), so results
+    /// far below the best match are dropped. The cut is relative only: an
+    /// empty `results` returns nothing, but with `ratio <= 1.0` the top
+    /// result itself always passes when its score is non-negative, however
+    /// low that score is. A no-op, returning `results` unchanged, when the
+    /// ratio is unset.
+    pub fn filter_by_relative_threshold(&self, results: &[RetrievalResult]) -> Vec<RetrievalResult> {
+        let Some(ratio) = self.config.relative_threshold_ratio else {
+            return results.to_vec();
+        };
+
+        let top_score = results.iter().map(|r| r.final_score).fold(f32::NEG_INFINITY, f32::max);
+        if !top_score.is_finite() {
+            return Vec::new();
+        }
+
+        results.iter()
+            .filter(|result| result.final_score >= top_score * ratio)
+            .cloned()
+            .collect()
+    }
     
     /// Calculate diversity score between two texts (simple implementation)
     pub fn calculate_diversity(&self, text1: &str, text2: &str) -> f32 {
@@ -345,6 +488,97 @@ if __name__ == '__main__':
         diverse_results
     }
     
+    /// Re-order `results` by Maximal Marginal Relevance instead of
+    /// `ensure_diversity`'s hard similarity cutoff, so a highly relevant but
+    /// slightly similar chunk can still be kept rather than dropped outright.
+    /// Each pick maximizes `lambda * rel(d) - (1 - lambda) * max_sim(d, selected)`,
+    /// where `rel(d)` is `d.final_score` normalized to `[0, 1]` across the
+    /// candidate set and `sim` is Jaccard similarity (`1.0 - calculate_diversity`).
+    /// `lambda = 1.0` reduces to pure relevance ordering, `lambda = 0.0` to pure
+    /// diversity. Selection stops once `top_k` results are chosen or candidates
+    /// run out.
+    pub fn mmr_rerank(&self, results: &[RetrievalResult], lambda: f32, top_k: usize) -> Vec<RetrievalResult> {
+        if results.is_empty() || top_k == 0 {
+            return Vec::new();
+        }
+
+        let min_score = results.iter().map(|r| r.final_score).fold(f32::INFINITY, f32::min);
+        let max_score = results.iter().map(|r| r.final_score).fold(f32::NEG_INFINITY, f32::max);
+        let range = (max_score - min_score).max(f32::EPSILON);
+        let relevance = |score: f32| (score - min_score) / range;
+
+        let mut remaining: Vec<usize> = (0..results.len()).collect();
+        let mut selected = Vec::new();
+
+        let first = remaining
+            .iter()
+            .copied()
+            .max_by(|&a, &b| {
+                results[a]
+                    .final_score
+                    .partial_cmp(&results[b].final_score)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .expect("remaining is non-empty");
+        remaining.retain(|&i| i != first);
+        selected.push(first);
+
+        while selected.len() < top_k && !remaining.is_empty() {
+            let (pick_pos, _) = remaining
+                .iter()
+                .enumerate()
+                .map(|(pos, &candidate)| {
+                    let rel = relevance(results[candidate].final_score);
+                    let max_sim = selected
+                        .iter()
+                        .map(|&s| 1.0 - self.calculate_diversity(&results[candidate].chunk.content, &results[s].chunk.content))
+                        .fold(f32::NEG_INFINITY, f32::max);
+                    (pos, lambda * rel - (1.0 - lambda) * max_sim)
+                })
+                .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+                .expect("remaining is non-empty");
+
+            selected.push(remaining.remove(pick_pos));
+        }
+
+        selected.into_iter().map(|i| results[i].clone()).collect()
+    }
+
+    /// Merge multiple ranked result lists via Reciprocal Rank Fusion, so a
+    /// dense vector ranking and a lexical/BM25 ranking can be combined into
+    /// one candidate set before `rerank_documents` runs, without needing the
+    /// raw scores of either list to be on the same scale. Each chunk's
+    /// contribution from a list is `1.0 / (k + rank)`, where `rank` is its
+    /// 0-based position in that list; contributions are summed across lists
+    /// (deduplicated by chunk id), and the sum is written into `final_score`.
+    /// `k` is typically `60.0`.
+    pub fn fuse_rankings(&self, ranked_lists: &[Vec<RetrievalResult>], k: f32) -> Vec<RetrievalResult> {
+        use std::collections::HashMap;
+
+        let mut fused: HashMap<crate::types::ChunkId, (RetrievalResult, f32)> = HashMap::new();
+
+        for list in ranked_lists {
+            for (rank, result) in list.iter().enumerate() {
+                let contribution = 1.0 / (k + rank as f32);
+                fused
+                    .entry(result.chunk.id)
+                    .and_modify(|(_, score)| *score += contribution)
+                    .or_insert_with(|| (result.clone(), contribution));
+            }
+        }
+
+        let mut combined: Vec<RetrievalResult> = fused
+            .into_values()
+            .map(|(mut result, score)| {
+                result.final_score = score;
+                result
+            })
+            .collect();
+
+        combined.sort_by(|a, b| b.final_score.partial_cmp(&a.final_score).unwrap_or(std::cmp::Ordering::Equal));
+        combined
+    }
+
     /// Get reranking statistics
     pub fn get_stats(&self) -> RerankingConfig {
         self.config.clone()
@@ -0,0 +1,145 @@
+//! Shared retry/backoff helper for HTTP calls to embedding and reranking
+//! providers. Mirrors how most LLM SDKs handle provider rate limiting:
+//! honor a `Retry-After` header when the provider sends one, otherwise back
+//! off exponentially with full jitter so a thundering herd of retries
+//! doesn't immediately re-trigger the same rate limit.
+
+use crate::error::RagError;
+use rand::Rng;
+use reqwest::Response;
+use std::time::Duration;
+
+const BASE_DELAY: Duration = Duration::from_millis(500);
+const MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// How a failed HTTP attempt made by an embedding or reranking client
+/// should be handled: either the caller's retry loop should try again
+/// (optionally after a provider-supplied delay), or the failure is
+/// terminal and should be propagated immediately. Used as the `Err` side
+/// of the per-attempt `Result` so `?` still works for transport-level
+/// errors that can't be classified as one or the other.
+pub enum RetryOutcome {
+    /// Worth retrying, e.g. a 429 or 5xx response, or a transient transport error
+    Retryable { error: RagError, retry_after: Option<Duration> },
+    /// Not worth retrying, e.g. a 4xx other than 429, or a response parse failure
+    Fatal(RagError),
+}
+
+/// Inspect a non-success HTTP response and decide whether it is worth
+/// retrying: 429 (rate limited) and 5xx (transient provider/server errors)
+/// are retryable, everything else is not. A `Retry-After` header (seconds
+/// form) is honored over our own backoff computation when present.
+pub fn classify_response(response: &Response) -> (bool, Option<Duration>) {
+    let status = response.status();
+    let retryable = status.as_u16() == 429 || status.is_server_error();
+
+    let retry_after = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(Duration::from_secs);
+
+    (retryable, retry_after)
+}
+
+/// Full-jitter exponential backoff delay for a zero-based attempt number:
+/// `random(0, min(cap, base * 2^attempt))`, base 500ms, cap 30s.
+fn backoff_delay(attempt: u32) -> Duration {
+    let capped_millis = BASE_DELAY
+        .as_millis()
+        .saturating_mul(1u128 << attempt.min(16))
+        .min(MAX_DELAY.as_millis());
+    let millis = capped_millis as u64;
+    let jittered = if millis == 0 { 0 } else { rand::thread_rng().gen_range(0..=millis) };
+    Duration::from_millis(jittered)
+}
+
+/// Sleep before the next retry attempt: honor an explicit delay from the
+/// provider (e.g. `Retry-After`) when given, otherwise fall back to
+/// full-jitter exponential backoff.
+pub async fn wait_before_retry(attempt: u32, retry_after: Option<Duration>) {
+    let delay = retry_after.unwrap_or_else(|| backoff_delay(attempt)).min(MAX_DELAY);
+    tokio::time::sleep(delay).await;
+}
+
+/// Retry an arbitrary fallible async operation based on
+/// `RagError::is_retryable()` rather than HTTP-status classification. Meant
+/// for callers that only have a final `RagError` to inspect - e.g. an
+/// `EmbeddingProvider` backend that doesn't build a `RetryOutcome` per
+/// attempt the way `EmbeddingClient`/`RerankingClient` do. Backs off with
+/// full jitter between attempts; once `max_retries` is exhausted, the last
+/// retryable error is wrapped in `RagError::timeout` with the accumulated
+/// context, while a fatal (non-retryable) error is returned immediately.
+pub async fn retry_with_backoff<T, F, Fut>(max_retries: u32, mut f: F) -> Result<T, RagError>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, RagError>>,
+{
+    let mut last_error: Option<RagError> = None;
+
+    for attempt in 0..=max_retries {
+        match f().await {
+            Ok(value) => return Ok(value),
+            Err(error) if error.is_retryable() => {
+                if attempt < max_retries {
+                    wait_before_retry(attempt, None).await;
+                }
+                last_error = Some(error);
+            }
+            Err(error) => return Err(error),
+        }
+    }
+
+    Err(RagError::timeout(format!(
+        "operation timed out after {} retries: {}",
+        max_retries,
+        last_error.map(|e| e.to_string()).unwrap_or_else(|| "unknown error".to_string())
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test]
+    async fn retry_with_backoff_succeeds_after_transient_failures() {
+        let attempts = AtomicU32::new(0);
+        let result = retry_with_backoff(3, || async {
+            if attempts.fetch_add(1, Ordering::SeqCst) < 2 {
+                Err(RagError::timeout("transient"))
+            } else {
+                Ok(42)
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn retry_with_backoff_returns_fatal_errors_immediately() {
+        let attempts = AtomicU32::new(0);
+        let result: Result<(), RagError> = retry_with_backoff(3, || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(RagError::invalid_input("never retryable"))
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn retry_with_backoff_wraps_exhausted_retries_as_timeout() {
+        let result: Result<(), RagError> =
+            retry_with_backoff(2, || async { Err(RagError::timeout("still failing")) }).await;
+
+        match result {
+            Err(RagError::Timeout(_)) => {}
+            other => panic!("expected a wrapped Timeout error, got {:?}", other.err().map(|e| e.to_string())),
+        }
+    }
+}
@@ -0,0 +1,97 @@
+//! Tokio scheduler health sampling, to tell "we're CPU-bound in vector
+//! ops" apart from "the scheduler itself is starved".
+//!
+//! A retrieval latency spike with high `parallel_efficiency` usually
+//! means the async runtime is contended, not that the vector math got
+//! slower. This samples `tokio::runtime::Handle::metrics()` - only
+//! available when the crate is built with `--cfg tokio_unstable` - on an
+//! interval and folds mean task poll time into an exponentially-weighted
+//! moving average, the same `new = alpha*sample + (1-alpha)*old` shape
+//! used for `AgentMetrics::avg_response_time_ms`.
+
+use crate::performance::PerformanceMonitor;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// A point-in-time view of Tokio scheduler health, surfaced on
+/// `PerformanceHealth::runtime`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct RuntimeHealth {
+    /// Exponentially-weighted moving average of mean task poll time, in
+    /// milliseconds, across all worker threads
+    pub poll_time_ewma_ms: f64,
+    /// Total worker park events since the runtime started
+    pub parks: u64,
+    /// Total worker unpark events since the runtime started
+    pub unparks: u64,
+    /// Tasks queued on the global injection queue, summed at sample time
+    pub injection_queue_depth: usize,
+    /// Tasks queued on per-worker local queues, summed at sample time
+    pub local_queue_depth: usize,
+}
+
+/// Handle to the background task started by `RuntimeMetricsTracker::start`.
+/// Stops sampling when dropped.
+pub struct RuntimeMetricsHandle {
+    task: JoinHandle<()>,
+}
+
+impl Drop for RuntimeMetricsHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Samples `tokio::runtime::Handle::metrics()` and reports a `RuntimeHealth`
+/// EWMA into a `PerformanceMonitor`.
+pub struct RuntimeMetricsTracker;
+
+impl RuntimeMetricsTracker {
+    /// Spawn a background task that samples runtime metrics every
+    /// `interval` and folds them into `monitor`'s `RuntimeHealth` EWMA with
+    /// smoothing factor `alpha` (e.g. `0.2`: a new sample counts for 20% of
+    /// the running average). Requires the binary to be built with `--cfg
+    /// tokio_unstable`; without it, `Handle::metrics()` isn't available and
+    /// this is a no-op task that exits immediately.
+    #[cfg(tokio_unstable)]
+    pub fn start(monitor: Arc<PerformanceMonitor>, interval: Duration, alpha: f64) -> RuntimeMetricsHandle {
+        let task = tokio::spawn(async move {
+            loop {
+                let metrics = tokio::runtime::Handle::current().metrics();
+                let num_workers = metrics.num_workers();
+
+                let mut poll_time_total_ms = 0.0;
+                let mut parks = 0u64;
+                let mut unparks = 0u64;
+                let mut local_queue_depth = 0usize;
+
+                for worker in 0..num_workers {
+                    poll_time_total_ms += metrics.worker_mean_poll_time(worker).as_secs_f64() * 1000.0;
+                    parks += metrics.worker_park_count(worker);
+                    unparks += metrics.worker_unpark_count(worker);
+                    local_queue_depth += metrics.worker_local_queue_depth(worker);
+                }
+
+                let mean_poll_time_ms = if num_workers > 0 { poll_time_total_ms / num_workers as f64 } else { 0.0 };
+                let injection_queue_depth = metrics.injection_queue_depth();
+
+                monitor
+                    .update_runtime_health(mean_poll_time_ms, parks, unparks, injection_queue_depth, local_queue_depth, alpha)
+                    .await;
+
+                tokio::time::sleep(interval).await;
+            }
+        });
+        RuntimeMetricsHandle { task }
+    }
+
+    /// No-op fallback when the crate isn't built with `--cfg
+    /// tokio_unstable`: `Handle::metrics()` doesn't exist, so there's
+    /// nothing to sample.
+    #[cfg(not(tokio_unstable))]
+    pub fn start(_monitor: Arc<PerformanceMonitor>, _interval: Duration, _alpha: f64) -> RuntimeMetricsHandle {
+        let task = tokio::spawn(async {});
+        RuntimeMetricsHandle { task }
+    }
+}
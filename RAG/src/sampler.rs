@@ -0,0 +1,104 @@
+//! Background host-stats sampler for `PerformanceMetrics::system`.
+//!
+//! `PerformanceMonitor::update_system_metrics` otherwise requires the
+//! embedding application to probe CPU/memory/disk/network itself and push
+//! the numbers in by hand, so `SystemMetrics` sits empty unless someone
+//! wires that up. `SystemSampler` spawns a `tokio` task that samples real
+//! host stats on an interval via `sysinfo` and calls
+//! `update_system_metrics` automatically.
+
+use crate::performance::PerformanceMonitor;
+use std::sync::Arc;
+use std::time::Duration;
+use sysinfo::{Disks, Networks, System};
+use tokio::task::JoinHandle;
+use tracing::debug;
+
+/// Handle to a running `SystemSampler` background task. Stops the task when
+/// dropped, so the sampler's lifetime can be tied to whatever owns the
+/// handle instead of requiring an explicit shutdown call.
+pub struct SystemSamplerHandle {
+    task: JoinHandle<()>,
+}
+
+impl Drop for SystemSamplerHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Samples host CPU, memory, disk, and network stats on an interval and
+/// feeds them into a `PerformanceMonitor`.
+pub struct SystemSampler;
+
+impl SystemSampler {
+    /// Spawn the sampling task, pushing a fresh sample into `monitor` every
+    /// `interval`. `available_disk_mb` is the most free space reported by
+    /// any mounted disk, which in single-volume deployments is the
+    /// partition backing the vector store.
+    pub fn spawn(monitor: Arc<PerformanceMonitor>, interval: Duration) -> SystemSamplerHandle {
+        let task = tokio::spawn(async move {
+            let mut system = System::new_all();
+            let mut last_disk_io_bytes: u64 = 0;
+            let mut last_network_bytes: u64 = 0;
+
+            loop {
+                system.refresh_cpu_usage();
+                system.refresh_memory();
+
+                let cpu_usage = system.cpus().iter().map(|cpu| cpu.cpu_usage() as f64).sum::<f64>()
+                    / system.cpus().len().max(1) as f64;
+                let memory_usage = if system.total_memory() > 0 {
+                    system.used_memory() as f64 / system.total_memory() as f64 * 100.0
+                } else {
+                    0.0
+                };
+
+                let disks = Disks::new_with_refreshed_list();
+                let available_disk_mb = disks
+                    .iter()
+                    .map(|disk| disk.available_space())
+                    .max()
+                    .unwrap_or(0) as f64
+                    / (1024.0 * 1024.0);
+
+                let networks = Networks::new_with_refreshed_list();
+                let network_bytes: u64 = networks
+                    .iter()
+                    .map(|(_, data)| data.total_received() + data.total_transmitted())
+                    .sum();
+                let network_io_rate = network_bytes.saturating_sub(last_network_bytes) as f64
+                    / (1024.0 * 1024.0)
+                    / interval.as_secs_f64().max(1.0);
+                last_network_bytes = network_bytes;
+
+                // `sysinfo` doesn't expose disk I/O throughput directly on every
+                // platform, so approximate it from process-level read+write
+                // bytes, which is consistently available cross-platform.
+                system.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+                let disk_io_bytes: u64 = system
+                    .processes()
+                    .values()
+                    .map(|p| p.disk_usage().read_bytes + p.disk_usage().written_bytes)
+                    .sum();
+                let disk_io_rate = disk_io_bytes.saturating_sub(last_disk_io_bytes) as f64
+                    / (1024.0 * 1024.0)
+                    / interval.as_secs_f64().max(1.0);
+                last_disk_io_bytes = disk_io_bytes;
+
+                monitor
+                    .update_system_metrics(cpu_usage, memory_usage, disk_io_rate, network_io_rate, available_disk_mb)
+                    .await;
+
+                debug!(
+                    "Sampled host stats: CPU={:.1}%, Memory={:.1}%, disk free={:.0}MB",
+                    cpu_usage, memory_usage, available_disk_mb
+                );
+
+                tokio::time::sleep(interval).await;
+            }
+        });
+
+        SystemSamplerHandle { task }
+    }
+}
@@ -0,0 +1,285 @@
+//! Streaming ingestion sources for keeping the RAG index synced with an
+//! external feed (a message bus, a change-data-capture stream, etc.).
+//!
+//! A `DocumentSource` yields offset-tagged records; `SourceSync` drives one
+//! into `RagEngine::ingest_document`, persisting the last successfully
+//! *indexed* offset to a `CheckpointStore` only after
+//! `RagEngine::await_indexed` confirms the vector-store write landed. A
+//! restart replays from that checkpoint, falling back to a configurable
+//! `ResetPolicy` the first time a source is synced. This mirrors Quickwit's
+//! Kafka source checkpoint / `auto.offset.reset` behavior and gives
+//! at-least-once indexing: a crash between ingest and checkpoint just
+//! re-ingests the same record next time.
+
+use crate::engine::RagEngine;
+use crate::error::{RagError, RagResult};
+use crate::types::IndexingStatus;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// A single record read from a `DocumentSource`.
+#[derive(Debug, Clone)]
+pub struct SourceRecord {
+    /// Source-defined offset. Must increase monotonically within a source.
+    pub offset: u64,
+    /// Document text to ingest.
+    pub content: String,
+    /// Document metadata to ingest alongside `content`.
+    pub metadata: HashMap<String, String>,
+}
+
+/// Where to start consuming a source when no checkpoint has been saved yet,
+/// mirroring Kafka/Quickwit's `auto.offset.reset`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResetPolicy {
+    /// Start from the beginning of the source.
+    Earliest,
+    /// Start from the most recently produced record, skipping backlog.
+    Latest,
+}
+
+/// A resumable feed of documents to ingest, analogous to a Kafka/Quickwit
+/// source.
+#[async_trait]
+pub trait DocumentSource: Send + Sync {
+    /// Stable identifier for this source, used as the checkpoint key. Must
+    /// stay the same across restarts for resume to find the right offset.
+    fn source_id(&self) -> &str;
+
+    /// Fetch the next batch of records after `from_offset` (`None` means
+    /// replay from the very beginning of the source). Returns an empty
+    /// vec, without erroring, when no new records are currently available.
+    async fn poll(&self, from_offset: Option<u64>) -> RagResult<Vec<SourceRecord>>;
+
+    /// The offset of the most recently produced record, if the source can
+    /// report one without consuming it. Used to honor `ResetPolicy::Latest`
+    /// when no checkpoint exists yet. Sources that can't seek to the tail
+    /// return `Ok(None)`, which falls back to `ResetPolicy::Earliest`.
+    async fn latest_offset(&self) -> RagResult<Option<u64>> {
+        Ok(None)
+    }
+}
+
+/// Persists the last successfully-indexed offset per source so a restart
+/// resumes instead of re-ingesting or silently skipping documents.
+pub struct CheckpointStore {
+    db: sled::Db,
+}
+
+impl CheckpointStore {
+    /// Open (or create) a checkpoint store backed by a sled database at `path`.
+    pub fn open(path: impl AsRef<Path>) -> RagResult<Self> {
+        let db = sled::open(path)?;
+        Ok(Self { db })
+    }
+
+    /// Load the last checkpointed offset for `source_id`, if any.
+    pub fn load(&self, source_id: &str) -> RagResult<Option<u64>> {
+        match self.db.get(source_id.as_bytes())? {
+            Some(bytes) => {
+                let raw: [u8; 8] = bytes
+                    .as_ref()
+                    .try_into()
+                    .map_err(|_| RagError::generic("corrupt checkpoint offset"))?;
+                Ok(Some(u64::from_be_bytes(raw)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Persist `offset` as the last successfully-indexed position for `source_id`.
+    pub fn save(&self, source_id: &str, offset: u64) -> RagResult<()> {
+        self.db.insert(source_id.as_bytes(), &offset.to_be_bytes())?;
+        Ok(())
+    }
+}
+
+/// Drives a `DocumentSource` into a `RagEngine`, checkpointing progress so a
+/// restart resumes rather than re-ingesting or losing documents.
+pub struct SourceSync {
+    checkpoints: CheckpointStore,
+    reset_policy: ResetPolicy,
+}
+
+impl SourceSync {
+    /// Create a sync driver backed by `checkpoints`, using `reset_policy`
+    /// the first time a given source is synced (i.e. when no checkpoint
+    /// has been saved for it yet).
+    pub fn new(checkpoints: CheckpointStore, reset_policy: ResetPolicy) -> Self {
+        Self { checkpoints, reset_policy }
+    }
+
+    /// Poll `source` once and ingest every returned record into `engine` in
+    /// order, committing the checkpoint for each record only after
+    /// `await_indexed` confirms its vector-store write succeeded. Returns
+    /// the number of records ingested. A failed or crashed ingest leaves
+    /// the checkpoint at the last committed offset, so the next call
+    /// retries that record rather than skipping it.
+    pub async fn sync_once(&self, engine: &RagEngine, source: &dyn DocumentSource) -> RagResult<usize> {
+        let source_id = source.source_id();
+        let checkpoint = self.checkpoints.load(source_id)?;
+
+        let from_offset = if checkpoint.is_some() {
+            checkpoint
+        } else {
+            match self.reset_policy {
+                ResetPolicy::Earliest => None,
+                ResetPolicy::Latest => source.latest_offset().await?,
+            }
+        };
+
+        let records = source.poll(from_offset).await?;
+        let mut ingested = 0;
+        for record in records {
+            let offset = record.offset;
+            let document_id = engine.ingest_document(record.content, record.metadata).await?;
+            match engine.await_indexed(document_id).await? {
+                IndexingStatus::Done => {
+                    self.checkpoints.save(source_id, offset)?;
+                    ingested += 1;
+                }
+                IndexingStatus::Failed(reason) => {
+                    return Err(RagError::generic(format!(
+                        "indexing failed for source '{}' offset {}, checkpoint not advanced: {}",
+                        source_id, offset, reason
+                    )));
+                }
+                IndexingStatus::Pending => unreachable!("await_indexed only returns once Pending resolves"),
+            }
+        }
+
+        Ok(ingested)
+    }
+
+    /// Continuously poll `source`, sleeping `idle_interval` between polls
+    /// that yield no new records. Intended to be driven from its own
+    /// `tokio::spawn`ed task for the lifetime of the engine.
+    pub async fn run(
+        &self,
+        engine: &RagEngine,
+        source: &dyn DocumentSource,
+        idle_interval: std::time::Duration,
+    ) -> RagResult<()> {
+        loop {
+            if self.sync_once(engine, source).await? == 0 {
+                tokio::time::sleep(idle_interval).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn temp_checkpoint_store() -> (CheckpointStore, TempDir) {
+        let dir = TempDir::new().unwrap();
+        let store = CheckpointStore::open(dir.path().join("checkpoints")).unwrap();
+        (store, dir)
+    }
+
+    async fn create_test_engine() -> (RagEngine, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let mut config = crate::config::RagConfig::default();
+        config.vector_store.db_path = temp_dir.path().to_path_buf();
+
+        let engine = RagEngine::new(config).await.unwrap();
+        (engine, temp_dir)
+    }
+
+    #[test]
+    fn checkpoint_round_trips_and_is_per_source() {
+        let (store, _dir) = temp_checkpoint_store();
+        assert_eq!(store.load("feed-a").unwrap(), None);
+
+        store.save("feed-a", 42).unwrap();
+        store.save("feed-b", 7).unwrap();
+
+        assert_eq!(store.load("feed-a").unwrap(), Some(42));
+        assert_eq!(store.load("feed-b").unwrap(), Some(7));
+    }
+
+    /// An in-memory source over a fixed record list, used to exercise
+    /// `SourceSync` without a real message bus.
+    struct FixedSource {
+        id: &'static str,
+        records: Vec<SourceRecord>,
+    }
+
+    #[async_trait]
+    impl DocumentSource for FixedSource {
+        fn source_id(&self) -> &str {
+            self.id
+        }
+
+        async fn poll(&self, from_offset: Option<u64>) -> RagResult<Vec<SourceRecord>> {
+            Ok(self
+                .records
+                .iter()
+                .filter(|r| from_offset.map_or(true, |after| r.offset > after))
+                .cloned()
+                .collect())
+        }
+    }
+
+    fn record(offset: u64, content: &str) -> SourceRecord {
+        SourceRecord { offset, content: content.to_string(), metadata: HashMap::new() }
+    }
+
+    #[tokio::test]
+    async fn sync_once_ingests_and_checkpoints_in_order() {
+        let (engine, _engine_dir) = create_test_engine().await;
+        let (checkpoints, _dir) = temp_checkpoint_store();
+        let sync = SourceSync::new(checkpoints, ResetPolicy::Earliest);
+
+        let source = FixedSource {
+            id: "feed",
+            records: vec![record(1, "first document"), record(2, "second document")],
+        };
+
+        let ingested = sync.sync_once(&engine, &source).await.unwrap();
+        assert_eq!(ingested, 2);
+        assert_eq!(sync.checkpoints.load("feed").unwrap(), Some(2));
+
+        // A subsequent poll resumes after the checkpoint and finds nothing new.
+        let ingested_again = sync.sync_once(&engine, &source).await.unwrap();
+        assert_eq!(ingested_again, 0);
+    }
+
+    #[tokio::test]
+    async fn latest_reset_policy_skips_backlog_on_first_sync() {
+        let (engine, _engine_dir) = create_test_engine().await;
+        let (checkpoints, _dir) = temp_checkpoint_store();
+        let sync = SourceSync::new(checkpoints, ResetPolicy::Latest);
+
+        struct TailSource {
+            records: Vec<SourceRecord>,
+        }
+
+        #[async_trait]
+        impl DocumentSource for TailSource {
+            fn source_id(&self) -> &str {
+                "feed"
+            }
+
+            async fn poll(&self, from_offset: Option<u64>) -> RagResult<Vec<SourceRecord>> {
+                Ok(self
+                    .records
+                    .iter()
+                    .filter(|r| from_offset.map_or(true, |after| r.offset > after))
+                    .cloned()
+                    .collect())
+            }
+
+            async fn latest_offset(&self) -> RagResult<Option<u64>> {
+                Ok(self.records.iter().map(|r| r.offset).max())
+            }
+        }
+
+        let source = TailSource { records: vec![record(1, "old backlog"), record(2, "newest")] };
+        let ingested = sync.sync_once(&engine, &source).await.unwrap();
+        assert_eq!(ingested, 0, "latest reset policy should skip records at or before the tail offset");
+    }
+}
@@ -0,0 +1,92 @@
+//! Prompt templates for rendering chunk text before it is sent to the
+//! embedding model, e.g. `{{ title }}\n\n{{ text }}`.
+
+use crate::error::{RagError, RagResult};
+use std::collections::HashMap;
+
+/// A parsed template segment: either literal text or a field reference
+#[derive(Debug, Clone)]
+enum TemplateToken {
+    Literal(String),
+    Field(String),
+}
+
+/// Reserved field name bound to the chunk's own content
+const TEXT_FIELD: &str = "text";
+
+/// Parse a `{{ field }}` template into tokens, rejecting unclosed or malformed placeholders
+fn parse_template(template: &str) -> RagResult<Vec<TemplateToken>> {
+    let mut tokens = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            tokens.push(TemplateToken::Literal(rest[..start].to_string()));
+        }
+
+        let after_open = &rest[start + 2..];
+        let end = after_open.find("}}").ok_or_else(|| {
+            RagError::configuration("Embedding template has an unclosed '{{' placeholder")
+        })?;
+
+        let field = after_open[..end].trim();
+        if !is_valid_field(field) {
+            return Err(RagError::configuration(format!(
+                "Embedding template references an unknown or invalid field '{{{{ {} }}}}'",
+                field
+            )));
+        }
+
+        tokens.push(TemplateToken::Field(field.to_string()));
+        rest = &after_open[end + 2..];
+    }
+
+    if !rest.is_empty() {
+        tokens.push(TemplateToken::Literal(rest.to_string()));
+    }
+
+    Ok(tokens)
+}
+
+/// A field is a plain identifier: letters, digits, underscores, not starting with a digit
+fn is_valid_field(field: &str) -> bool {
+    let mut chars = field.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_alphabetic() || c == '_' => {}
+        _ => return false,
+    }
+    chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Render parsed tokens against chunk text and metadata. Unresolved metadata
+/// fields render as an empty string, since metadata is user-defined per document.
+fn render_tokens(tokens: &[TemplateToken], text: &str, metadata: &HashMap<String, String>) -> String {
+    let mut out = String::new();
+    for token in tokens {
+        match token {
+            TemplateToken::Literal(literal) => out.push_str(literal),
+            TemplateToken::Field(field) if field == TEXT_FIELD => out.push_str(text),
+            TemplateToken::Field(field) => {
+                if let Some(value) = metadata.get(field) {
+                    out.push_str(value);
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Compile a template against a dummy context, failing at config-load time
+/// rather than mid-ingest if the syntax is invalid.
+pub fn validate_template(template: &str) -> RagResult<()> {
+    let tokens = parse_template(template)?;
+    let _ = render_tokens(&tokens, "", &HashMap::new());
+    Ok(())
+}
+
+/// Render a template for a single chunk, substituting `text` for the chunk's
+/// content and all other fields from its metadata
+pub fn render_template(template: &str, text: &str, metadata: &HashMap<String, String>) -> RagResult<String> {
+    let tokens = parse_template(template)?;
+    Ok(render_tokens(&tokens, text, metadata))
+}
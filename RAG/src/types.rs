@@ -14,13 +14,27 @@ pub type ChunkId = Uuid;
 /// Vector embedding type
 pub type Embedding = Vec<f32>;
 
+/// SHA-1 digest of a chunk's content, used to recognize identical-content
+/// chunks so they can share a single embedding instead of each paying for
+/// its own embedder call
+pub type ContentDigest = [u8; 20];
+
 /// Retrieval mode for different use cases
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum RetrievalMode {
     /// General queries - top 20 chunks, 3k-5k tokens
     General,
     /// Research queries - top 100 chunks, 15k-25k tokens
     Research,
+    /// General-sized retrieval that blends dense vector similarity with BM25
+    /// keyword scoring for this query specifically, overriding
+    /// `RagConfig::hybrid_search` for the call. `semantic_ratio` weights the
+    /// dense score (`1.0` = pure vector, `0.0` = pure keyword) and only
+    /// applies when the fusion mode is `FusionMode::Convex`.
+    Hybrid {
+        /// Weight given to the dense vector score vs. the BM25 score
+        semantic_ratio: f32,
+    },
 }
 
 impl RetrievalMode {
@@ -29,14 +43,16 @@ impl RetrievalMode {
         match self {
             RetrievalMode::General => 20,
             RetrievalMode::Research => 100,
+            RetrievalMode::Hybrid { .. } => 20,
         }
     }
-    
+
     /// Get the target token range for this mode
     pub fn token_range(&self) -> (usize, usize) {
         match self {
             RetrievalMode::General => (3000, 5000),
             RetrievalMode::Research => (15000, 25000),
+            RetrievalMode::Hybrid { .. } => (3000, 5000),
         }
     }
 }
@@ -100,6 +116,9 @@ pub struct Chunk {
     pub metadata: HashMap<String, String>,
     /// When the chunk was created
     pub created_at: DateTime<Utc>,
+    /// Name of the embedder that produced `embedding`, if any
+    #[serde(default)]
+    pub embedder: Option<String>,
 }
 
 impl Chunk {
@@ -121,13 +140,19 @@ impl Chunk {
             token_count,
             metadata: HashMap::new(),
             created_at: Utc::now(),
+            embedder: None,
         }
     }
-    
+
     /// Set the embedding for this chunk
     pub fn set_embedding(&mut self, embedding: Embedding) {
         self.embedding = Some(embedding);
     }
+
+    /// Set the name of the embedder that produced this chunk's embedding
+    pub fn set_embedder(&mut self, embedder: impl Into<String>) {
+        self.embedder = Some(embedder.into());
+    }
     
     /// Check if this chunk has an embedding
     pub fn has_embedding(&self) -> bool {
@@ -217,6 +242,37 @@ pub struct ChunkingConfig {
     pub min_chunk_size: usize,
     /// Maximum chunk size in tokens
     pub max_chunk_size: usize,
+    /// Strategy used to decide chunk boundaries
+    #[serde(default)]
+    pub strategy: ChunkingStrategy,
+    /// Hard cap on a single chunk's token count, independent of
+    /// `max_chunk_size`: `chunk_document` re-splits any produced chunk
+    /// that exceeds this on sentence boundaries. A single sentence longer
+    /// than the cap is not split further and can still exceed it. `0`
+    /// disables the cap.
+    #[serde(default = "default_max_input_tokens")]
+    pub max_input_tokens: usize,
+    /// Upper bound on how many chunks a caller should embed concurrently,
+    /// exposed via `SmartChunker::max_concurrent_chunks`
+    #[serde(default = "default_max_concurrent_chunks")]
+    pub max_concurrent_chunks: usize,
+    /// Strategy used to cut sub-chunks for reranking
+    #[serde(default)]
+    pub sub_chunk_strategy: SubChunkStrategy,
+    /// Path to a serialized Hugging Face `tokenizers` JSON file (BPE,
+    /// Unigram, or WordPiece) to load via `SmartChunker::from_tokenizer_file`
+    /// instead of the toy WordPiece vocabulary `with_default_tokenizer`
+    /// builds, so `count_tokens` matches a real model's encoding
+    #[serde(default)]
+    pub tokenizer_path: Option<String>,
+}
+
+fn default_max_input_tokens() -> usize {
+    8191
+}
+
+fn default_max_concurrent_chunks() -> usize {
+    8
 }
 
 impl Default for ChunkingConfig {
@@ -226,6 +282,183 @@ impl Default for ChunkingConfig {
             overlap: 50,
             min_chunk_size: 100,
             max_chunk_size: 600,
+            strategy: ChunkingStrategy::default(),
+            max_input_tokens: default_max_input_tokens(),
+            max_concurrent_chunks: default_max_concurrent_chunks(),
+            sub_chunk_strategy: SubChunkStrategy::default(),
+            tokenizer_path: None,
+        }
+    }
+}
+
+/// Per-model chunking defaults keyed by embedding model ID, used by
+/// `ChunkingConfig::for_model` so chunk sizing tracks the model's real
+/// input token limit instead of `ChunkingConfig::default()`'s guess
+fn model_input_limit(model_id: &str) -> Option<usize> {
+    match model_id {
+        "text-embedding-3-small" | "text-embedding-3-large" | "text-embedding-ada-002" => Some(8191),
+        "voyage-2" | "voyage-large-2" | "voyage-code-2" => Some(16000),
+        "all-MiniLM-L6-v2" | "all-mpnet-base-v2" => Some(512),
+        "bge-small-en" | "bge-base-en" | "bge-large-en" => Some(512),
+        "cohere-embed-v3" => Some(512),
+        _ => None,
+    }
+}
+
+/// Per-embedding-model profile: how big to make chunks, how many tokens
+/// the model accepts, and how many chunks may embed concurrently against
+/// it. Looked up via `embedding_model_profile`.
+#[derive(Debug, Clone, Copy)]
+pub struct EmbeddingModelProfile {
+    /// The model's real input token limit
+    pub max_input_tokens: usize,
+    /// Target chunk size for this model (80% of `max_input_tokens`, leaving
+    /// headroom for overlap)
+    pub default_chunk_size: usize,
+    /// Upper bound on chunks that may be embedded against this model at once
+    pub max_concurrent_chunks: usize,
+}
+
+/// Look up the known profile for `model_id`, or `None` for a model not in
+/// the registry
+pub fn embedding_model_profile(model_id: &str) -> Option<EmbeddingModelProfile> {
+    let max_input_tokens = model_input_limit(model_id)?;
+    Some(EmbeddingModelProfile {
+        max_input_tokens,
+        default_chunk_size: (max_input_tokens * 4 / 5).max(1),
+        max_concurrent_chunks: default_max_concurrent_chunks(),
+    })
+}
+
+impl ChunkingConfig {
+    /// Derive a config sized for `model_id`'s input token limit:
+    /// `chunk_size` targets 80% of the limit (leaving headroom for
+    /// overlap), `max_chunk_size`/`max_input_tokens` are capped at the
+    /// limit itself. Falls back to `ChunkingConfig::default()` for a
+    /// model not in the registry.
+    pub fn for_model(model_id: &str) -> Self {
+        let Some(profile) = embedding_model_profile(model_id) else {
+            return Self::default();
+        };
+
+        let chunk_size = profile.default_chunk_size;
+        let overlap = (chunk_size / 9).max(1);
+
+        Self {
+            chunk_size,
+            overlap,
+            min_chunk_size: (chunk_size / 4).max(1),
+            max_chunk_size: profile.max_input_tokens,
+            strategy: ChunkingStrategy::default(),
+            max_input_tokens: profile.max_input_tokens,
+            max_concurrent_chunks: profile.max_concurrent_chunks,
+            sub_chunk_strategy: SubChunkStrategy::default(),
+            tokenizer_path: None,
+        }
+    }
+}
+
+/// Strategy used to decide where chunk boundaries fall
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ChunkingStrategy {
+    /// Fixed token-count windows with sentence-aware overlap (default)
+    Fixed,
+    /// FastCDC content-defined chunking for deduplication-friendly,
+    /// boundary-stable chunks across document revisions
+    ContentDefined {
+        /// Minimum chunk size in bytes
+        min_size: usize,
+        /// Target average chunk size in bytes
+        avg_size: usize,
+        /// Maximum chunk size in bytes
+        max_size: usize,
+    },
+    /// AST-aware chunking via tree-sitter: boundaries fall on line breaks
+    /// and are chosen to minimize how deeply nested they are in the
+    /// syntax tree, so source files split between statements/items
+    /// instead of mid-construct. Falls back to `Fixed` when no parser is
+    /// available for `language`.
+    Syntactic {
+        /// Source language tree-sitter should parse the document as
+        language: SourceLanguage,
+    },
+    /// Sentence-boundary chunking that packs sentences into chunks of
+    /// roughly equal token length (`~total_tokens / chunk_count`) instead
+    /// of greedily filling to `chunk_size` and leaving a near-empty
+    /// trailing chunk
+    Sentence,
+}
+
+impl Default for ChunkingStrategy {
+    fn default() -> Self {
+        ChunkingStrategy::Fixed
+    }
+}
+
+/// A source language `ChunkingStrategy::Syntactic` can request a
+/// tree-sitter grammar for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SourceLanguage {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+}
+
+/// Strategy used to cut sub-chunks for reranking out of an oversized chunk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SubChunkStrategy {
+    /// Cut purely on sentence boundaries within the 150-250 token window
+    /// (default)
+    Sentence,
+    /// Cut on phrase-chunk boundaries decided by a beam-searched sequence
+    /// tagger, so splits avoid breaking a noun/verb phrase in half
+    Phrase,
+}
+
+impl Default for SubChunkStrategy {
+    fn default() -> Self {
+        SubChunkStrategy::Sentence
+    }
+}
+
+/// What a `SmartChunker` does when a document exceeds
+/// `ValidationConfig::max_total_tokens`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OverLengthAction {
+    /// Reject the document with `RagError::InputTooLong`
+    Error,
+    /// Cut the document at the token boundary nearest the limit, then
+    /// chunk normally
+    Truncate,
+    /// Chunk the full document normally, tagging chunks past the ceiling
+    /// in their metadata instead of dropping anything
+    Split,
+}
+
+impl Default for OverLengthAction {
+    fn default() -> Self {
+        OverLengthAction::Error
+    }
+}
+
+/// Document-level validation a `SmartChunker` can enforce before chunking
+/// begins, mirroring a router-style input validator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationConfig {
+    /// Ceiling on total tokens across the whole document. `0` disables the check.
+    #[serde(default)]
+    pub max_total_tokens: usize,
+    /// What to do when `max_total_tokens` is exceeded
+    #[serde(default)]
+    pub over_length_action: OverLengthAction,
+}
+
+impl Default for ValidationConfig {
+    fn default() -> Self {
+        Self {
+            max_total_tokens: 0,
+            over_length_action: OverLengthAction::default(),
         }
     }
 }
@@ -243,6 +476,23 @@ pub struct RagStats {
     pub avg_chunk_size: f32,
     /// Cache hit rate
     pub cache_hit_rate: f32,
+    /// Number of documents ingested with caller-supplied embeddings via
+    /// `RagEngine::ingest_document_with_embeddings` /
+    /// `ingest_chunks_with_embeddings`, rather than the configured embedder
+    pub user_provided_embedding_count: usize,
+    /// Per-shard embedding batch size (chunks per `embed_batch` call) that
+    /// `RagEngine::ingest_document` last computed for a large document or
+    /// bulk ingest, sized from the document's chunk count and
+    /// `available_parallelism()`. Zero until the sharded ingest path has
+    /// run at least once.
+    pub last_ingest_batch_size: usize,
+    /// Sum of `Chunk::token_count` across every currently-stored chunk, from
+    /// the real tokenizer rather than `chunk_count * avg_chunk_size`
+    pub total_tokens: usize,
+    /// Number of `ingest_document` calls short-circuited by
+    /// `RagConfig::dedup_policy` because their content hash already
+    /// matched a stored document, rather than being chunked/embedded again
+    pub deduplicated_count: usize,
     /// Last update timestamp
     pub last_updated: DateTime<Utc>,
 }
@@ -255,7 +505,24 @@ impl Default for RagStats {
             embedding_count: 0,
             avg_chunk_size: 0.0,
             cache_hit_rate: 0.0,
+            user_provided_embedding_count: 0,
+            last_ingest_batch_size: 0,
+            total_tokens: 0,
+            deduplicated_count: 0,
             last_updated: Utc::now(),
         }
     }
+}
+
+/// Progress of a document handed to the background indexing worker via
+/// `RagEngine::ingest_document`, queryable through `RagEngine::await_indexed`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum IndexingStatus {
+    /// Chunked and queued, waiting for (or currently in) an embedding batch
+    Pending,
+    /// Embedded, written to the vector store, and visible to retrieval
+    Done,
+    /// The embedding batch or vector-store write failed; the document was
+    /// not added, so it is safe to retry by ingesting it again
+    Failed(String),
 }
\ No newline at end of file
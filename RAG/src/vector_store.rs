@@ -1,17 +1,245 @@
 //! Vector storage implementations for the RAG engine
 
 use crate::cache::RagCache;
-use crate::config::VectorStoreConfig;
+use crate::config::{DistanceMetric, HnswParams, QuantizationMode, VectorStoreConfig};
 use crate::error::{RagError, RagResult};
-use crate::types::{Chunk, ChunkId, Embedding, RetrievalResult};
+use crate::types::{Chunk, ChunkId, ContentDigest, Embedding, RetrievalResult};
 use async_trait::async_trait;
+use rand::Rng;
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
 use sled::Db;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Instant;
 
+/// SHA-1 digest of `content`, used to recognize identical-content chunks so
+/// they can reuse an already-computed embedding instead of each paying for
+/// its own embedder call — the same span-digest reuse pattern code-indexing
+/// engines use to skip re-embedding unchanged spans
+fn content_digest(content: &str) -> ContentDigest {
+    let mut hasher = Sha1::new();
+    hasher.update(content.as_bytes());
+    hasher.finalize().into()
+}
+
+/// L2 norm of an embedding, precomputed once per stored vector
+/// (`VectorIndex.norms`) and once per query in `parallel_similarity_search`
+/// so `DistanceMetric::Cosine` never has to recompute it on the hot path.
+fn vector_norm(embedding: &Embedding) -> f32 {
+    embedding.iter().map(|x| x * x).sum::<f32>().sqrt()
+}
+
+/// Score `embedding` against `query_embedding` under the configured
+/// `DistanceMetric`. `query_norm`/`embedding_norm` are precomputed L2 norms,
+/// used by `Cosine` and ignored by the other metrics. `Euclidean` distances
+/// are converted to a descending-sortable score (`1/(1+d^2)`) so callers can
+/// keep sorting/truncating/`min_score`-filtering exactly as they do for the
+/// other two metrics.
+fn score_by_metric(
+    metric: DistanceMetric,
+    query_embedding: &Embedding,
+    query_norm: f32,
+    embedding: &Embedding,
+    embedding_norm: f32,
+) -> f32 {
+    if query_embedding.len() != embedding.len() {
+        return 0.0;
+    }
+
+    match metric {
+        DistanceMetric::Cosine => {
+            if query_norm == 0.0 || embedding_norm == 0.0 {
+                return 0.0;
+            }
+            let dot_product: f32 = query_embedding.iter().zip(embedding.iter()).map(|(x, y)| x * y).sum();
+            dot_product / (query_norm * embedding_norm)
+        }
+        DistanceMetric::DotProduct => {
+            query_embedding.iter().zip(embedding.iter()).map(|(x, y)| x * y).sum()
+        }
+        DistanceMetric::Euclidean => {
+            let squared_distance: f32 = query_embedding
+                .iter()
+                .zip(embedding.iter())
+                .map(|(x, y)| (x - y) * (x - y))
+                .sum();
+            1.0 / (1.0 + squared_distance)
+        }
+    }
+}
+
+/// Canonicalize a float's bit pattern before hashing so logically-equal
+/// queries (`0.0` vs `-0.0`, any two `NaN`s) hash identically in
+/// `cache_key`.
+fn normalize_f32_for_hash(x: f32) -> f32 {
+    if x.is_nan() {
+        f32::NAN
+    } else if x == 0.0 {
+        0.0
+    } else {
+        x
+    }
+}
+
+/// Stable, content-addressed cache key for a `search_similar_advanced` call,
+/// shared by `SledVectorStore` and `FaissVectorStore` so both backends hit
+/// the same `RagCache` entry for the same query. Hashes the raw
+/// little-endian bytes of `query` plus `top_k` and `min_score` with blake3
+/// instead of Debug-formatting the whole embedding on every lookup.
+fn cache_key(query: &Embedding, top_k: usize, min_score: Option<f32>) -> String {
+    let mut hasher = blake3::Hasher::new();
+    for component in query {
+        hasher.update(&normalize_f32_for_hash(*component).to_le_bytes());
+    }
+    hasher.update(&top_k.to_le_bytes());
+    match min_score {
+        Some(score) => {
+            hasher.update(&[1u8]);
+            hasher.update(&normalize_f32_for_hash(score).to_le_bytes());
+        }
+        None => {
+            hasher.update(&[0u8]);
+        }
+    }
+    hasher.finalize().to_hex().to_string()
+}
+
+/// BM25 tuning constants shared by `bm25_scores` and the incrementally
+/// maintained `SparseIndex`
+const BM25_K1: f32 = 1.2;
+const BM25_B: f32 = 0.75;
+
+/// Lowercase, punctuation-stripped whitespace tokenization shared by every
+/// BM25 scorer in this module
+fn tokenize(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// Default number of quantized candidates pulled per requested result before
+/// full-precision rescoring, when `rescore_multiplier` is not configured
+const DEFAULT_RESCORE_MULTIPLIER: usize = 4;
+
+/// Per-dimension (min, max) bounds used to linearly quantize floats to u8
+struct ScalarBounds {
+    min: Vec<f32>,
+    max: Vec<f32>,
+}
+
+fn scalar8_bounds<'a>(embeddings: impl Iterator<Item = &'a Embedding>) -> ScalarBounds {
+    let mut min: Vec<f32> = Vec::new();
+    let mut max: Vec<f32> = Vec::new();
+
+    for embedding in embeddings {
+        if min.is_empty() {
+            min = embedding.clone();
+            max = embedding.clone();
+            continue;
+        }
+        for (i, value) in embedding.iter().enumerate() {
+            if *value < min[i] {
+                min[i] = *value;
+            }
+            if *value > max[i] {
+                max[i] = *value;
+            }
+        }
+    }
+
+    ScalarBounds { min, max }
+}
+
+/// Linearly quantize an embedding to u8 per dimension using `bounds`
+fn quantize_scalar8(embedding: &Embedding, bounds: &ScalarBounds) -> Vec<u8> {
+    embedding
+        .iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let min = bounds.min.get(i).copied().unwrap_or(0.0);
+            let max = bounds.max.get(i).copied().unwrap_or(0.0);
+            let range = (max - min).max(f32::EPSILON);
+            (((value - min) / range) * 255.0).round().clamp(0.0, 255.0) as u8
+        })
+        .collect()
+}
+
+/// Squared Euclidean distance between two scalar-quantized codes
+fn scalar8_distance(a: &[u8], b: &[u8]) -> i64 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(x, y)| {
+            let diff = *x as i64 - *y as i64;
+            diff * diff
+        })
+        .sum()
+}
+
+/// Per-dimension bounds plus every embedding's scalar-quantized u8 code,
+/// built wholesale in `SledVectorStore::optimize` instead of being
+/// recomputed from scratch (as the transient `QuantizationMode::Scalar8`
+/// branch of `parallel_similarity_search` does) on every single query.
+/// Rebuilt whole rather than incrementally on `store_chunk`/`delete_chunk`,
+/// so a chunk ingested after the last `optimize()` call has no `codes`
+/// entry yet; callers (`similarity_search_with_hnsw`) must confirm `covers`
+/// the live embedding set and fall back to the unquantized path whenever
+/// they've drifted apart, rather than silently searching a cache that no
+/// longer covers every chunk.
+struct QuantizedIndex {
+    bounds: ScalarBounds,
+    codes: HashMap<ChunkId, Vec<u8>>,
+}
+
+impl QuantizedIndex {
+    fn build(embeddings: &HashMap<ChunkId, Embedding>) -> Self {
+        let bounds = scalar8_bounds(embeddings.values());
+        let codes = embeddings
+            .iter()
+            .map(|(id, embedding)| (*id, quantize_scalar8(embedding, &bounds)))
+            .collect();
+        Self { bounds, codes }
+    }
+
+    /// Approximate resident size of the codes plus bounds, in bytes —
+    /// roughly 4x smaller than the `embedding_count * dim * 4` full-
+    /// precision estimate `get_stats` otherwise uses, since each component
+    /// is one byte instead of four.
+    fn size_bytes(&self) -> usize {
+        let code_bytes: usize = self.codes.values().map(|code| code.len()).sum();
+        let bounds_bytes = (self.bounds.min.len() + self.bounds.max.len()) * std::mem::size_of::<f32>();
+        code_bytes + bounds_bytes
+    }
+
+    /// Whether `codes` has an entry for every chunk id currently in
+    /// `embeddings` — not just the same count. A delete+insert cycle that
+    /// nets to the same chunk count would pass a plain length comparison
+    /// while `codes` still held stale ids and was missing the new ones.
+    fn covers(&self, embeddings: &HashMap<ChunkId, Embedding>) -> bool {
+        self.codes.len() == embeddings.len() && embeddings.keys().all(|id| self.codes.contains_key(id))
+    }
+}
+
+/// Bit-pack an embedding into per-dimension sign bits: each dimension becomes
+/// one bit, the sign of the value after centering on the vector's own mean
+fn quantize_binary(embedding: &Embedding) -> Vec<u64> {
+    let mean: f32 = embedding.iter().sum::<f32>() / embedding.len().max(1) as f32;
+    let mut words = vec![0u64; embedding.len().div_ceil(64)];
+    for (i, value) in embedding.iter().enumerate() {
+        if *value > mean {
+            words[i / 64] |= 1u64 << (i % 64);
+        }
+    }
+    words
+}
+
+/// Hamming distance between two bit-packed codes (lower = more similar)
+fn hamming_distance(a: &[u64], b: &[u64]) -> u32 {
+    a.iter().zip(b.iter()).map(|(x, y)| (x ^ y).count_ones()).sum()
+}
+
 /// Trait for vector storage backends
 #[async_trait]
 pub trait VectorStore: Send + Sync {
@@ -46,7 +274,19 @@ pub trait VectorStore: Send + Sync {
         query_embeddings: &[Embedding],
         top_k: usize,
     ) -> RagResult<Vec<Vec<(ChunkId, f32)>>>;
-    
+
+    /// Lexical (BM25-style) keyword search over stored chunk content, used
+    /// alongside dense vector search for hybrid retrieval
+    async fn keyword_search(&self, query: &str, top_k: usize) -> RagResult<Vec<(ChunkId, f32)>>;
+
+    /// Look up already-computed embeddings for a batch of content digests,
+    /// so a batch caller can tell which spans already have an embedding on
+    /// file before asking the embedder for the rest
+    async fn embeddings_for_digests(
+        &self,
+        digests: &[ContentDigest],
+    ) -> RagResult<HashMap<ContentDigest, Embedding>>;
+
     /// Delete a chunk
     async fn delete_chunk(&self, id: ChunkId) -> RagResult<()>;
     
@@ -89,15 +329,48 @@ pub struct SledVectorStore {
     cache: Option<Arc<RagCache>>,
     /// Performance statistics
     stats: Arc<tokio::sync::RwLock<VectorStoreStats>>,
+    /// Optional HNSW fast path, built when `config.hnsw` is set. Persisted
+    /// to its own Sled tree so it survives restarts instead of being
+    /// rebuilt from scratch in `load_index`.
+    hnsw: Option<Arc<tokio::sync::RwLock<HnswState>>>,
+    /// Incrementally-maintained BM25 postings backing `keyword_search`, the
+    /// sparse half of `RagEngine::retrieve_context`'s hybrid fusion.
+    /// Persisted to its own Sled tree, updated on every
+    /// `store_chunk`/`store_chunks`/`delete_chunk`/`delete_chunks` rather
+    /// than rebuilt from a full scan per query.
+    sparse: Arc<tokio::sync::RwLock<SparseIndex>>,
+    /// Cached scalar-quantized codes, rebuilt wholesale by `optimize()`
+    /// when `config.quantization` is `Scalar8`. `None` until the first
+    /// `optimize()` call, during which searches fall back to the
+    /// unquantized brute-force path in `parallel_similarity_search`.
+    quantized: Arc<tokio::sync::RwLock<Option<QuantizedIndex>>>,
 }
 
+/// Sled tree name and key the HNSW graph is persisted under
+const HNSW_TREE: &str = "hnsw_index";
+const HNSW_KEY: &[u8] = b"state";
+
+/// Sled tree name and key the sparse BM25 postings are persisted under
+const SPARSE_TREE: &str = "sparse_index";
+const SPARSE_KEY: &[u8] = b"state";
+
 /// In-memory vector index for fast similarity search
 #[derive(Debug, Clone)]
 struct VectorIndex {
     /// Mapping from chunk ID to embedding
     embeddings: HashMap<ChunkId, Embedding>,
+    /// Mapping from chunk ID to its embedding's precomputed L2 norm, kept in
+    /// lockstep with `embeddings` so `DistanceMetric::Cosine` doesn't have
+    /// to recompute `norm_b` for every stored vector on every query
+    norms: HashMap<ChunkId, f32>,
     /// Mapping from chunk ID to metadata
     metadata: HashMap<ChunkId, ChunkMetadata>,
+    /// Mapping from content digest to an already-computed embedding,
+    /// derived from `metadata`/`embeddings` (rebuilt in `load_index`, kept
+    /// incrementally up to date on every insert). Lets a chunk that arrives
+    /// without an embedding but with already-seen content reuse the stored
+    /// vector instead of requiring a fresh embedder call.
+    digest_embeddings: HashMap<ContentDigest, Embedding>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -106,6 +379,7 @@ struct ChunkMetadata {
     content_length: usize,
     token_count: usize,
     created_at: chrono::DateTime<chrono::Utc>,
+    content_digest: ContentDigest,
 }
 
 impl SledVectorStore {
@@ -126,11 +400,15 @@ impl SledVectorStore {
         let db = sled::open(&config.db_path)
             .map_err(|e| RagError::vector_store(format!("Failed to open database: {}", e)))?;
         
+        let hnsw = config.hnsw.clone().map(|params| Arc::new(tokio::sync::RwLock::new(HnswState::new(params))));
+
         let store = Self {
             db: Arc::new(db),
             index: Arc::new(tokio::sync::RwLock::new(VectorIndex {
                 embeddings: HashMap::new(),
+                norms: HashMap::new(),
                 metadata: HashMap::new(),
+                digest_embeddings: HashMap::new(),
             })),
             config,
             cache,
@@ -142,48 +420,144 @@ impl SledVectorStore {
                 cache_hit_rate: 0.0,
                 last_optimization: None,
             })),
+            hnsw,
+            sparse: Arc::new(tokio::sync::RwLock::new(SparseIndex::default())),
+            quantized: Arc::new(tokio::sync::RwLock::new(None)),
         };
-        
+
         // Load existing data into memory index
         store.load_index().await?;
-        
+
         Ok(store)
     }
-    
+
     /// Load existing data into the in-memory index
     async fn load_index(&self) -> RagResult<()> {
         let mut index = self.index.write().await;
-        
+        let mut sparse_entries: Vec<(ChunkId, String)> = Vec::new();
+
         // Load chunks from database
         for result in self.db.iter() {
             let (key, value) = result
                 .map_err(|e| RagError::vector_store(format!("Database iteration error: {}", e)))?;
-            
+
             // Parse chunk ID from key
             let chunk_id_str = String::from_utf8(key.to_vec())
                 .map_err(|e| RagError::vector_store(format!("Invalid key format: {}", e)))?;
             let chunk_id: ChunkId = chunk_id_str.parse()
                 .map_err(|e| RagError::vector_store(format!("Invalid chunk ID: {}", e)))?;
-            
+
             // Deserialize chunk
             let chunk: Chunk = serde_json::from_slice(&value)
                 .map_err(|e| RagError::vector_store(format!("Failed to deserialize chunk: {}", e)))?;
-            
+
             // Add to index if chunk has embedding
             if let Some(embedding) = &chunk.embedding {
+                let digest = content_digest(&chunk.content);
                 index.embeddings.insert(chunk_id, embedding.clone());
+                index.norms.insert(chunk_id, vector_norm(embedding));
                 index.metadata.insert(chunk_id, ChunkMetadata {
                     document_id: chunk.document_id,
                     content_length: chunk.content.len(),
                     token_count: chunk.token_count,
                     created_at: chunk.created_at,
+                    content_digest: digest,
                 });
+                index.digest_embeddings.entry(digest).or_insert_with(|| embedding.clone());
             }
+
+            sparse_entries.push((chunk_id, chunk.content));
         }
-        
+
+        // Rebuild the sparse BM25 postings: prefer the persisted snapshot,
+        // falling back to reindexing every loaded chunk's content if none
+        // was persisted yet
+        if let Some(persisted) = self.load_persisted_sparse()? {
+            *self.sparse.write().await = persisted;
+        } else {
+            let mut sparse = self.sparse.write().await;
+            for (chunk_id, content) in sparse_entries {
+                sparse.upsert(chunk_id, &content);
+            }
+        }
+
+        // Rebuild the HNSW graph: prefer the persisted snapshot (keeps
+        // graph structure, including any deletions, intact across
+        // restarts), falling back to reinserting every loaded embedding if
+        // none was persisted yet (e.g. `config.hnsw` was just turned on).
+        if let Some(hnsw) = &self.hnsw {
+            let mut state = hnsw.write().await;
+            if let Some(persisted) = self.load_persisted_hnsw()? {
+                *state = persisted;
+            } else {
+                for (&chunk_id, embedding) in index.embeddings.iter() {
+                    state.insert(chunk_id, embedding.clone());
+                }
+            }
+        }
+
         Ok(())
     }
-    
+
+    /// Read and deserialize the persisted HNSW graph from its Sled tree, if
+    /// one exists yet
+    fn load_persisted_hnsw(&self) -> RagResult<Option<HnswState>> {
+        let tree = self.db.open_tree(HNSW_TREE)
+            .map_err(|e| RagError::vector_store(format!("Failed to open hnsw tree: {}", e)))?;
+        let Some(bytes) = tree.get(HNSW_KEY)
+            .map_err(|e| RagError::vector_store(format!("Failed to read hnsw state: {}", e)))?
+        else {
+            return Ok(None);
+        };
+        let persisted: PersistedHnswState = serde_json::from_slice(&bytes)
+            .map_err(|e| RagError::vector_store(format!("Failed to deserialize hnsw state: {}", e)))?;
+        Ok(Some(HnswState::from_persisted(persisted)))
+    }
+
+    /// Persist the current HNSW graph to its Sled tree so it survives a
+    /// restart without having to be rebuilt from every stored embedding
+    async fn persist_hnsw(&self) -> RagResult<()> {
+        let Some(hnsw) = &self.hnsw else {
+            return Ok(());
+        };
+        let state = hnsw.read().await;
+        let bytes = serde_json::to_vec(&state.to_persisted())
+            .map_err(|e| RagError::vector_store(format!("Failed to serialize hnsw state: {}", e)))?;
+        let tree = self.db.open_tree(HNSW_TREE)
+            .map_err(|e| RagError::vector_store(format!("Failed to open hnsw tree: {}", e)))?;
+        tree.insert(HNSW_KEY, bytes)
+            .map_err(|e| RagError::vector_store(format!("Failed to persist hnsw state: {}", e)))?;
+        Ok(())
+    }
+
+    /// Read and deserialize the persisted sparse BM25 postings from their
+    /// Sled tree, if they exist yet
+    fn load_persisted_sparse(&self) -> RagResult<Option<SparseIndex>> {
+        let tree = self.db.open_tree(SPARSE_TREE)
+            .map_err(|e| RagError::vector_store(format!("Failed to open sparse tree: {}", e)))?;
+        let Some(bytes) = tree.get(SPARSE_KEY)
+            .map_err(|e| RagError::vector_store(format!("Failed to read sparse state: {}", e)))?
+        else {
+            return Ok(None);
+        };
+        let sparse: SparseIndex = serde_json::from_slice(&bytes)
+            .map_err(|e| RagError::vector_store(format!("Failed to deserialize sparse state: {}", e)))?;
+        Ok(Some(sparse))
+    }
+
+    /// Persist the current sparse BM25 postings to their Sled tree so they
+    /// survive a restart without having to be rebuilt from every chunk
+    async fn persist_sparse(&self) -> RagResult<()> {
+        let sparse = self.sparse.read().await;
+        let bytes = serde_json::to_vec(&*sparse)
+            .map_err(|e| RagError::vector_store(format!("Failed to serialize sparse state: {}", e)))?;
+        let tree = self.db.open_tree(SPARSE_TREE)
+            .map_err(|e| RagError::vector_store(format!("Failed to open sparse tree: {}", e)))?;
+        tree.insert(SPARSE_KEY, bytes)
+            .map_err(|e| RagError::vector_store(format!("Failed to persist sparse state: {}", e)))?;
+        Ok(())
+    }
+
     /// Calculate cosine similarity between embeddings
     pub fn cosine_similarity(a: &Embedding, b: &Embedding) -> f32 {
         if a.len() != b.len() {
@@ -201,18 +575,63 @@ impl SledVectorStore {
         dot_product / (norm_a * norm_b)
     }
     
-    /// Parallel similarity calculation for multiple embeddings
+    /// Parallel similarity calculation for multiple embeddings, scored with
+    /// the configured `metric`. When `quantization` is not `None`, candidates
+    /// are first narrowed down using quantized distances, then rescored with
+    /// full-precision similarity over the top `top_k * rescore_multiplier`
+    /// candidates. `norms` supplies precomputed L2 norms for `metric`'s
+    /// `Cosine` case; an embedding missing from it falls back to computing
+    /// its norm on the spot.
     pub fn parallel_similarity_search(
         query_embedding: &Embedding,
         embeddings: &HashMap<ChunkId, Embedding>,
+        norms: &HashMap<ChunkId, f32>,
         top_k: usize,
         min_score: Option<f32>,
+        quantization: QuantizationMode,
+        rescore_multiplier: Option<usize>,
+        metric: DistanceMetric,
     ) -> Vec<(ChunkId, f32)> {
-        let mut similarities: Vec<(ChunkId, f32)> = embeddings
+        let candidate_count = top_k.saturating_mul(rescore_multiplier.unwrap_or(DEFAULT_RESCORE_MULTIPLIER)).max(top_k);
+
+        let candidates: Vec<(&ChunkId, &Embedding)> = match quantization {
+            QuantizationMode::None => embeddings.iter().collect(),
+            QuantizationMode::Binary => {
+                let query_code = quantize_binary(query_embedding);
+                let mut scored: Vec<(&ChunkId, &Embedding, u32)> = embeddings
+                    .par_iter()
+                    .map(|(chunk_id, embedding)| {
+                        let distance = hamming_distance(&query_code, &quantize_binary(embedding));
+                        (chunk_id, embedding, distance)
+                    })
+                    .collect();
+                scored.sort_by_key(|(_, _, distance)| *distance);
+                scored.truncate(candidate_count);
+                scored.into_iter().map(|(id, embedding, _)| (id, embedding)).collect()
+            }
+            QuantizationMode::Scalar8 => {
+                let bounds = scalar8_bounds(embeddings.values());
+                let query_code = quantize_scalar8(query_embedding, &bounds);
+                let mut scored: Vec<(&ChunkId, &Embedding, i64)> = embeddings
+                    .par_iter()
+                    .map(|(chunk_id, embedding)| {
+                        let distance = scalar8_distance(&query_code, &quantize_scalar8(embedding, &bounds));
+                        (chunk_id, embedding, distance)
+                    })
+                    .collect();
+                scored.sort_by_key(|(_, _, distance)| *distance);
+                scored.truncate(candidate_count);
+                scored.into_iter().map(|(id, embedding, _)| (id, embedding)).collect()
+            }
+        };
+
+        let query_norm = vector_norm(query_embedding);
+        let mut similarities: Vec<(ChunkId, f32)> = candidates
             .par_iter()
             .map(|(chunk_id, embedding)| {
-                let similarity = Self::cosine_similarity(query_embedding, embedding);
-                (*chunk_id, similarity)
+                let embedding_norm = norms.get(*chunk_id).copied().unwrap_or_else(|| vector_norm(embedding));
+                let score = score_by_metric(metric, query_embedding, query_norm, embedding, embedding_norm);
+                (**chunk_id, score)
             })
             .filter(|(_, score)| {
                 if let Some(min) = min_score {
@@ -222,110 +641,265 @@ impl SledVectorStore {
                 }
             })
             .collect();
-        
+
         // Sort by similarity (descending) and take top k
         similarities.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
         similarities.truncate(top_k);
-        
+
         similarities
     }
-    
+
+    /// Same two-stage shape as `parallel_similarity_search`'s `Scalar8`
+    /// branch — narrow to `top_k * rescore_multiplier` candidates by
+    /// quantized distance, then rescore the survivors with full-precision
+    /// similarity under `metric` — but using `quantized`'s already-built
+    /// codes instead of requantizing every embedding for this one query.
+    fn search_similar_quantized(
+        quantized: &QuantizedIndex,
+        embeddings: &HashMap<ChunkId, Embedding>,
+        norms: &HashMap<ChunkId, f32>,
+        query_embedding: &Embedding,
+        top_k: usize,
+        min_score: Option<f32>,
+        rescore_multiplier: Option<usize>,
+        metric: DistanceMetric,
+    ) -> Vec<(ChunkId, f32)> {
+        let candidate_count = top_k.saturating_mul(rescore_multiplier.unwrap_or(DEFAULT_RESCORE_MULTIPLIER)).max(top_k);
+        let query_code = quantize_scalar8(query_embedding, &quantized.bounds);
+        let query_norm = vector_norm(query_embedding);
+
+        let mut scored: Vec<(ChunkId, i64)> = quantized
+            .codes
+            .par_iter()
+            .map(|(chunk_id, code)| (*chunk_id, scalar8_distance(&query_code, code)))
+            .collect();
+        scored.sort_by_key(|(_, distance)| *distance);
+        scored.truncate(candidate_count);
+
+        // Exact rerank: candidates are only known by id at this point, so
+        // look their full-precision vectors back up before scoring them.
+        let mut similarities: Vec<(ChunkId, f32)> = scored
+            .into_iter()
+            .filter_map(|(chunk_id, _)| {
+                embeddings.get(&chunk_id).map(|embedding| {
+                    let embedding_norm = norms.get(&chunk_id).copied().unwrap_or_else(|| vector_norm(embedding));
+                    let score = score_by_metric(metric, query_embedding, query_norm, embedding, embedding_norm);
+                    (chunk_id, score)
+                })
+            })
+            .filter(|(_, score)| min_score.map(|min| *score >= min).unwrap_or(true))
+            .collect();
+
+        similarities.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        similarities.truncate(top_k);
+        similarities
+    }
+
     /// Update statistics after search operation
     async fn update_search_stats(&self, search_time_ms: f64) {
         let mut stats = self.stats.write().await;
-        
+
         // Update average search time using exponential moving average
         if stats.avg_search_time_ms == 0.0 {
             stats.avg_search_time_ms = search_time_ms;
         } else {
             stats.avg_search_time_ms = stats.avg_search_time_ms * 0.9 + search_time_ms * 0.1;
         }
-        
+
         // Update cache hit rate if cache is available
         if let Some(cache) = &self.cache {
             let cache_stats = cache.get_stats().await;
             stats.cache_hit_rate = cache_stats.overall_hit_rate();
         }
     }
+
+    /// Score a corpus of chunks against a query using BM25 (k1=1.2, b=0.75)
+    pub fn bm25_scores(query: &str, corpus: &[(ChunkId, String)], top_k: usize) -> Vec<(ChunkId, f32)> {
+        if corpus.is_empty() {
+            return Vec::new();
+        }
+
+        let query_terms = tokenize(query);
+        if query_terms.is_empty() {
+            return Vec::new();
+        }
+
+        let doc_terms: Vec<(ChunkId, Vec<String>)> = corpus
+            .iter()
+            .map(|(id, text)| (*id, tokenize(text)))
+            .collect();
+
+        let avg_doc_len: f32 = doc_terms.iter().map(|(_, t)| t.len() as f32).sum::<f32>()
+            / doc_terms.len() as f32;
+
+        let mut doc_freq: HashMap<&str, usize> = HashMap::new();
+        for term in &query_terms {
+            let df = doc_terms
+                .iter()
+                .filter(|(_, terms)| terms.iter().any(|t| t == term))
+                .count();
+            doc_freq.insert(term.as_str(), df);
+        }
+
+        let n = doc_terms.len() as f32;
+        let mut scores: Vec<(ChunkId, f32)> = doc_terms
+            .iter()
+            .map(|(id, terms)| {
+                let doc_len = terms.len() as f32;
+                let mut score = 0.0f32;
+                for term in &query_terms {
+                    let df = *doc_freq.get(term.as_str()).unwrap_or(&0) as f32;
+                    if df == 0.0 {
+                        continue;
+                    }
+                    let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
+                    let tf = terms.iter().filter(|t| *t == term).count() as f32;
+                    let denom = tf + BM25_K1 * (1.0 - BM25_B + BM25_B * (doc_len / avg_doc_len.max(1.0)));
+                    if denom > 0.0 {
+                        score += idf * (tf * (BM25_K1 + 1.0)) / denom;
+                    }
+                }
+                (*id, score)
+            })
+            .filter(|(_, score)| *score > 0.0)
+            .collect();
+
+        scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scores.truncate(top_k);
+        scores
+    }
 }
 
 #[async_trait]
 impl VectorStore for SledVectorStore {
     async fn store_chunk(&self, chunk: &Chunk) -> RagResult<()> {
+        let digest = content_digest(&chunk.content);
+
+        // A chunk that arrives without an embedding but whose content was
+        // already seen reuses the stored vector instead of requiring a
+        // fresh embedder call
+        let mut chunk = chunk.clone();
+        if chunk.embedding.is_none() {
+            let index = self.index.read().await;
+            if let Some(existing) = index.digest_embeddings.get(&digest) {
+                chunk.embedding = Some(existing.clone());
+            }
+        }
+
         // Serialize chunk
-        let chunk_data = serde_json::to_vec(chunk)
+        let chunk_data = serde_json::to_vec(&chunk)
             .map_err(|e| RagError::vector_store(format!("Failed to serialize chunk: {}", e)))?;
-        
+
         // Store in database
         let key = chunk.id.to_string();
         self.db.insert(key.as_bytes(), chunk_data)
             .map_err(|e| RagError::vector_store(format!("Failed to store chunk: {}", e)))?;
-        
+
         // Update in-memory index if chunk has embedding
         if let Some(embedding) = &chunk.embedding {
             let mut index = self.index.write().await;
             index.embeddings.insert(chunk.id, embedding.clone());
+            index.norms.insert(chunk.id, vector_norm(embedding));
             index.metadata.insert(chunk.id, ChunkMetadata {
                 document_id: chunk.document_id,
                 content_length: chunk.content.len(),
                 token_count: chunk.token_count,
                 created_at: chunk.created_at,
+                content_digest: digest,
             });
+            index.digest_embeddings.entry(digest).or_insert_with(|| embedding.clone());
+
+            if let Some(hnsw) = &self.hnsw {
+                hnsw.write().await.insert(chunk.id, embedding.clone());
+                self.persist_hnsw().await?;
+            }
         }
-        
+
+        self.sparse.write().await.upsert(chunk.id, &chunk.content);
+        self.persist_sparse().await?;
+
         Ok(())
     }
-    
+
     async fn store_chunks(&self, chunks: &[Chunk]) -> RagResult<()> {
         if chunks.is_empty() {
             return Ok(());
         }
-        
-        // Prepare batch operations
+
+        // Resolve digest-reuse and prepare batch operations up front, under
+        // one index write lock, so two identical-content chunks within the
+        // same batch dedup against each other too
         let mut batch_ops = Vec::new();
         let mut index_updates = Vec::new();
-        
-        for chunk in chunks {
-            // Serialize chunk
-            let chunk_data = serde_json::to_vec(chunk)
-                .map_err(|e| RagError::vector_store(format!("Failed to serialize chunk: {}", e)))?;
-            
-            let key = chunk.id.to_string();
-            batch_ops.push((key.as_bytes().to_vec(), chunk_data));
-            
-            // Prepare index update if chunk has embedding
-            if let Some(embedding) = &chunk.embedding {
-                index_updates.push((chunk.id, embedding.clone(), ChunkMetadata {
-                    document_id: chunk.document_id,
-                    content_length: chunk.content.len(),
-                    token_count: chunk.token_count,
-                    created_at: chunk.created_at,
-                }));
+        {
+            let mut index = self.index.write().await;
+            for chunk in chunks {
+                let digest = content_digest(&chunk.content);
+                let mut chunk = chunk.clone();
+                if chunk.embedding.is_none() {
+                    if let Some(existing) = index.digest_embeddings.get(&digest) {
+                        chunk.embedding = Some(existing.clone());
+                    }
+                }
+
+                let chunk_data = serde_json::to_vec(&chunk)
+                    .map_err(|e| RagError::vector_store(format!("Failed to serialize chunk: {}", e)))?;
+                let key = chunk.id.to_string();
+                batch_ops.push((key.as_bytes().to_vec(), chunk_data));
+
+                if let Some(embedding) = &chunk.embedding {
+                    index.digest_embeddings.entry(digest).or_insert_with(|| embedding.clone());
+                    index_updates.push((chunk.id, embedding.clone(), ChunkMetadata {
+                        document_id: chunk.document_id,
+                        content_length: chunk.content.len(),
+                        token_count: chunk.token_count,
+                        created_at: chunk.created_at,
+                        content_digest: digest,
+                    }));
+                }
             }
         }
-        
+
         // Execute batch operations
         let mut batch = sled::Batch::default();
         for (key, value) in batch_ops {
             batch.insert(key, value);
         }
-        
+
         self.db.apply_batch(batch)
             .map_err(|e| RagError::vector_store(format!("Failed to store batch: {}", e)))?;
-        
+
         // Update in-memory index
         let mut index = self.index.write().await;
+        let mut hnsw_inserted = false;
         for (chunk_id, embedding, metadata) in index_updates {
-            index.embeddings.insert(chunk_id, embedding);
+            index.norms.insert(chunk_id, vector_norm(&embedding));
+            index.embeddings.insert(chunk_id, embedding.clone());
             index.metadata.insert(chunk_id, metadata);
+
+            if let Some(hnsw) = &self.hnsw {
+                hnsw.write().await.insert(chunk_id, embedding);
+                hnsw_inserted = true;
+            }
         }
-        
+        if hnsw_inserted {
+            self.persist_hnsw().await?;
+        }
+
+        {
+            let mut sparse = self.sparse.write().await;
+            for chunk in chunks {
+                sparse.upsert(chunk.id, &chunk.content);
+            }
+        }
+        self.persist_sparse().await?;
+
         Ok(())
     }
-    
+
     async fn get_chunk(&self, id: ChunkId) -> RagResult<Option<Chunk>> {
         let key = id.to_string();
-        
+
         match self.db.get(key.as_bytes()) {
             Ok(Some(data)) => {
                 let chunk: Chunk = serde_json::from_slice(&data)
@@ -348,23 +922,30 @@ impl VectorStore for SledVectorStore {
         if index.embeddings.is_empty() {
             return Ok(Vec::new());
         }
-        
-        // Use parallel similarity search
-        let similarities = Self::parallel_similarity_search(
+
+        // Use the HNSW fast path when it's enabled and large enough,
+        // falling back to the exact brute-force scan otherwise
+        let similarities = similarity_search_with_hnsw(
+            &self.hnsw,
+            &Some(self.quantized.clone()),
             query_embedding,
             &index.embeddings,
+            &index.norms,
             top_k,
             None,
-        );
-        
+            self.config.quantization,
+            self.config.rescore_multiplier,
+            self.config.distance_metric,
+        ).await;
+
         // Update statistics
         let search_time_ms = start_time.elapsed().as_millis() as f64;
         drop(index); // Release read lock before updating stats
         self.update_search_stats(search_time_ms).await;
-        
+
         Ok(similarities)
     }
-    
+
     async fn search_similar_advanced(
         &self,
         query_embedding: &Embedding,
@@ -373,31 +954,38 @@ impl VectorStore for SledVectorStore {
         use_cache: bool,
     ) -> RagResult<Vec<RetrievalResult>> {
         let start_time = Instant::now();
-        
+
         // Check cache first if enabled
         if use_cache {
             if let Some(cache) = &self.cache {
-                let query_key = format!("{:?}_{}__{:?}", query_embedding, top_k, min_score);
+                let query_key = cache_key(query_embedding, top_k, min_score);
                 if let Some(cached_results) = cache.get_retrieval(&query_key).await {
                     return Ok(cached_results);
                 }
             }
         }
-        
+
         let index = self.index.read().await;
-        
+
         if index.embeddings.is_empty() {
             return Ok(Vec::new());
         }
-        
-        // Use parallel similarity search with filtering
-        let similarities = Self::parallel_similarity_search(
+
+        // Use the HNSW fast path when it's enabled and large enough,
+        // falling back to the exact brute-force scan otherwise
+        let similarities = similarity_search_with_hnsw(
+            &self.hnsw,
+            &Some(self.quantized.clone()),
             query_embedding,
             &index.embeddings,
+            &index.norms,
             top_k,
             min_score,
-        );
-        
+            self.config.quantization,
+            self.config.rescore_multiplier,
+            self.config.distance_metric,
+        ).await;
+
         // Convert to RetrievalResult and fetch chunks
         let mut results = Vec::new();
         for (chunk_id, similarity_score) in similarities {
@@ -407,23 +995,23 @@ impl VectorStore for SledVectorStore {
                 results.push(result);
             }
         }
-        
+
         // Cache results if enabled
         if use_cache {
             if let Some(cache) = &self.cache {
-                let query_key = format!("{:?}_{}__{:?}", query_embedding, top_k, min_score);
+                let query_key = cache_key(query_embedding, top_k, min_score);
                 let _ = cache.cache_retrieval(&query_key, &results).await;
             }
         }
-        
+
         // Update statistics
         let search_time_ms = start_time.elapsed().as_millis() as f64;
         drop(index); // Release read lock before updating stats
         self.update_search_stats(search_time_ms).await;
-        
+
         Ok(results)
     }
-    
+
     async fn batch_search_similar(
         &self,
         query_embeddings: &[Embedding],
@@ -431,35 +1019,58 @@ impl VectorStore for SledVectorStore {
     ) -> RagResult<Vec<Vec<(ChunkId, f32)>>> {
         let start_time = Instant::now();
         let index = self.index.read().await;
-        
+
         if index.embeddings.is_empty() {
             return Ok(vec![Vec::new(); query_embeddings.len()]);
         }
-        
-        // Process queries in parallel
-        let results: Vec<Vec<(ChunkId, f32)>> = query_embeddings
-            .par_iter()
-            .map(|query_embedding| {
-                Self::parallel_similarity_search(
+
+        // Each query independently chooses the HNSW fast path or the
+        // brute-force fallback
+        let mut results = Vec::with_capacity(query_embeddings.len());
+        for query_embedding in query_embeddings {
+            results.push(
+                similarity_search_with_hnsw(
+                    &self.hnsw,
+                    &Some(self.quantized.clone()),
                     query_embedding,
                     &index.embeddings,
+                    &index.norms,
                     top_k,
                     None,
+                    self.config.quantization,
+                    self.config.rescore_multiplier,
+                    self.config.distance_metric,
                 )
-            })
-            .collect();
-        
+                .await,
+            );
+        }
+
         // Update statistics
         let search_time_ms = start_time.elapsed().as_millis() as f64;
         drop(index); // Release read lock before updating stats
         self.update_search_stats(search_time_ms).await;
-        
+
         Ok(results)
     }
-    
+
+    async fn keyword_search(&self, query: &str, top_k: usize) -> RagResult<Vec<(ChunkId, f32)>> {
+        Ok(self.sparse.read().await.score(query, top_k))
+    }
+
+    async fn embeddings_for_digests(
+        &self,
+        digests: &[ContentDigest],
+    ) -> RagResult<HashMap<ContentDigest, Embedding>> {
+        let index = self.index.read().await;
+        Ok(digests
+            .iter()
+            .filter_map(|digest| index.digest_embeddings.get(digest).map(|embedding| (*digest, embedding.clone())))
+            .collect())
+    }
+
     async fn delete_chunk(&self, id: ChunkId) -> RagResult<()> {
         let key = id.to_string();
-        
+
         // Remove from database
         self.db.remove(key.as_bytes())
             .map_err(|e| RagError::vector_store(format!("Failed to delete chunk: {}", e)))?;
@@ -467,49 +1078,76 @@ impl VectorStore for SledVectorStore {
         // Remove from in-memory index
         let mut index = self.index.write().await;
         index.embeddings.remove(&id);
+        index.norms.remove(&id);
         index.metadata.remove(&id);
-        
+
+        if let Some(hnsw) = &self.hnsw {
+            hnsw.write().await.remove(id);
+            self.persist_hnsw().await?;
+        }
+
+        self.sparse.write().await.remove(id);
+        self.persist_sparse().await?;
+
         // Update statistics
         let mut stats = self.stats.write().await;
         stats.total_chunks = stats.total_chunks.saturating_sub(1);
         if index.embeddings.len() < stats.total_embeddings {
             stats.total_embeddings = index.embeddings.len();
         }
-        
+
         Ok(())
     }
-    
+
     async fn delete_chunks(&self, ids: &[ChunkId]) -> RagResult<()> {
         if ids.is_empty() {
             return Ok(());
         }
-        
+
         // Prepare batch operations
         let mut batch = sled::Batch::default();
         for id in ids {
             let key = id.to_string();
             batch.remove(key.as_bytes());
         }
-        
+
         // Execute batch delete
         self.db.apply_batch(batch)
             .map_err(|e| RagError::vector_store(format!("Failed to delete batch: {}", e)))?;
-        
+
         // Remove from in-memory index
         let mut index = self.index.write().await;
         for id in ids {
             index.embeddings.remove(id);
+            index.norms.remove(id);
             index.metadata.remove(id);
         }
-        
+
+        if let Some(hnsw) = &self.hnsw {
+            let mut state = hnsw.write().await;
+            for id in ids {
+                state.remove(*id);
+            }
+            drop(state);
+            self.persist_hnsw().await?;
+        }
+
+        {
+            let mut sparse = self.sparse.write().await;
+            for id in ids {
+                sparse.remove(*id);
+            }
+        }
+        self.persist_sparse().await?;
+
         // Update statistics
         let mut stats = self.stats.write().await;
         stats.total_chunks = stats.total_chunks.saturating_sub(ids.len());
         stats.total_embeddings = index.embeddings.len();
-        
+
         Ok(())
     }
-    
+
     async fn count(&self) -> RagResult<usize> {
         Ok(self.db.len())
     }
@@ -517,16 +1155,26 @@ impl VectorStore for SledVectorStore {
     async fn get_stats(&self) -> RagResult<VectorStoreStats> {
         let stats = self.stats.read().await;
         let index = self.index.read().await;
-        
-        // Calculate approximate index size in MB
+
+        // Calculate approximate index size in MB. When scalar quantization
+        // is active and `optimize()` has built the cache, report the size
+        // of the u8 codes actually used for search instead of the full
+        // f32 vectors still held in memory for exact reranking.
         let embedding_count = index.embeddings.len();
-        let avg_embedding_size = if embedding_count > 0 {
-            index.embeddings.values().next().map(|e| e.len()).unwrap_or(0)
-        } else {
-            0
+        let index_size_mb = match self.quantized.read().await.as_ref() {
+            Some(quantized) if self.config.quantization == QuantizationMode::Scalar8 => {
+                quantized.size_bytes() as f64 / (1024.0 * 1024.0)
+            }
+            _ => {
+                let avg_embedding_size = if embedding_count > 0 {
+                    index.embeddings.values().next().map(|e| e.len()).unwrap_or(0)
+                } else {
+                    0
+                };
+                (embedding_count * avg_embedding_size * 4) as f64 / (1024.0 * 1024.0)
+            }
         };
-        let index_size_mb = (embedding_count * avg_embedding_size * 4) as f64 / (1024.0 * 1024.0);
-        
+
         Ok(VectorStoreStats {
             total_chunks: self.db.len(),
             total_embeddings: embedding_count,
@@ -536,35 +1184,57 @@ impl VectorStore for SledVectorStore {
             last_optimization: stats.last_optimization,
         })
     }
-    
+
     async fn clear(&self) -> RagResult<()> {
         // Clear database
         self.db.clear()
             .map_err(|e| RagError::vector_store(format!("Failed to clear database: {}", e)))?;
-        
+
         // Clear in-memory index
         let mut index = self.index.write().await;
         index.embeddings.clear();
+        index.norms.clear();
         index.metadata.clear();
-        
+        index.digest_embeddings.clear();
+
+        if let Some(hnsw) = &self.hnsw {
+            hnsw.write().await.clear(self.config.hnsw.clone().unwrap_or_default());
+            self.persist_hnsw().await?;
+        }
+
+        *self.sparse.write().await = SparseIndex::default();
+        self.persist_sparse().await?;
+
+        *self.quantized.write().await = None;
+
         // Reset statistics
         let mut stats = self.stats.write().await;
         stats.total_chunks = 0;
         stats.total_embeddings = 0;
         stats.index_size_mb = 0.0;
-        
+
         Ok(())
     }
-    
+
     async fn optimize(&self) -> RagResult<()> {
         // For Sled, we can flush and compact the database
         self.db.flush_async().await
             .map_err(|e| RagError::vector_store(format!("Failed to flush database: {}", e)))?;
-        
+
+        // Rebuild the scalar-quantized code cache wholesale from the
+        // current embeddings, so the next search's candidate-narrowing
+        // pass doesn't have to requantize everything itself
+        if self.config.quantization == QuantizationMode::Scalar8 {
+            let index = self.index.read().await;
+            *self.quantized.write().await = Some(QuantizedIndex::build(&index.embeddings));
+        } else {
+            *self.quantized.write().await = None;
+        }
+
         // Update optimization timestamp
         let mut stats = self.stats.write().await;
         stats.last_optimization = Some(chrono::Utc::now());
-        
+
         Ok(())
     }
 }
@@ -582,6 +1252,15 @@ pub struct FaissVectorStore {
     cache: Option<Arc<RagCache>>,
     /// Performance statistics
     stats: Arc<tokio::sync::RwLock<VectorStoreStats>>,
+    /// Optional HNSW fast path, built when `config.hnsw` is set. Unlike
+    /// `SledVectorStore`, there's no database to persist it to — it's
+    /// rebuilt from scratch (empty) every time a `FaissVectorStore` starts,
+    /// consistent with the rest of this backend's in-memory-only nature.
+    hnsw: Option<Arc<tokio::sync::RwLock<HnswState>>>,
+    /// BM25 postings backing `keyword_search`, the sparse half of
+    /// `RagEngine::retrieve_context`'s hybrid fusion. In-memory only, like
+    /// the rest of this backend.
+    sparse: Arc<tokio::sync::RwLock<SparseIndex>>,
 }
 
 impl FaissVectorStore {
@@ -589,14 +1268,18 @@ impl FaissVectorStore {
     pub async fn new(config: VectorStoreConfig) -> RagResult<Self> {
         Self::new_with_cache(config, None).await
     }
-    
+
     /// Create a new FAISS-like vector store with cache
     pub async fn new_with_cache(config: VectorStoreConfig, cache: Option<Arc<RagCache>>) -> RagResult<Self> {
+        let hnsw = config.hnsw.clone().map(|params| Arc::new(tokio::sync::RwLock::new(HnswState::new(params))));
+
         Ok(Self {
             chunks: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
             index: Arc::new(tokio::sync::RwLock::new(VectorIndex {
                 embeddings: HashMap::new(),
+                norms: HashMap::new(),
                 metadata: HashMap::new(),
+                digest_embeddings: HashMap::new(),
             })),
             config,
             cache,
@@ -608,20 +1291,22 @@ impl FaissVectorStore {
                 cache_hit_rate: 0.0,
                 last_optimization: None,
             })),
+            hnsw,
+            sparse: Arc::new(tokio::sync::RwLock::new(SparseIndex::default())),
         })
     }
-    
+
     /// Update statistics after search operation
     async fn update_search_stats(&self, search_time_ms: f64) {
         let mut stats = self.stats.write().await;
-        
+
         // Update average search time using exponential moving average
         if stats.avg_search_time_ms == 0.0 {
             stats.avg_search_time_ms = search_time_ms;
         } else {
             stats.avg_search_time_ms = stats.avg_search_time_ms * 0.9 + search_time_ms * 0.1;
         }
-        
+
         // Update cache hit rate if cache is available
         if let Some(cache) = &self.cache {
             let cache_stats = cache.get_stats().await;
@@ -633,58 +1318,97 @@ impl FaissVectorStore {
 #[async_trait]
 impl VectorStore for FaissVectorStore {
     async fn store_chunk(&self, chunk: &Chunk) -> RagResult<()> {
+        let digest = content_digest(&chunk.content);
+
+        // A chunk that arrives without an embedding but whose content was
+        // already seen reuses the stored vector instead of requiring a
+        // fresh embedder call
+        let mut chunk = chunk.clone();
+        if chunk.embedding.is_none() {
+            let index = self.index.read().await;
+            if let Some(existing) = index.digest_embeddings.get(&digest) {
+                chunk.embedding = Some(existing.clone());
+            }
+        }
+
         // Store chunk
         let mut chunks = self.chunks.write().await;
         chunks.insert(chunk.id, chunk.clone());
-        
+
         // Update index if chunk has embedding
         if let Some(embedding) = &chunk.embedding {
             let mut index = self.index.write().await;
             index.embeddings.insert(chunk.id, embedding.clone());
+            index.norms.insert(chunk.id, vector_norm(embedding));
             index.metadata.insert(chunk.id, ChunkMetadata {
                 document_id: chunk.document_id,
                 content_length: chunk.content.len(),
                 token_count: chunk.token_count,
                 created_at: chunk.created_at,
+                content_digest: digest,
             });
+            index.digest_embeddings.entry(digest).or_insert_with(|| embedding.clone());
+
+            if let Some(hnsw) = &self.hnsw {
+                hnsw.write().await.insert(chunk.id, embedding.clone());
+            }
         }
-        
+
+        self.sparse.write().await.upsert(chunk.id, &chunk.content);
+
         // Update statistics
         let mut stats = self.stats.write().await;
         let index_read = self.index.read().await;
         stats.total_chunks = chunks.len();
         stats.total_embeddings = index_read.embeddings.len();
-        
+
         Ok(())
     }
-    
+
     async fn store_chunks(&self, chunks: &[Chunk]) -> RagResult<()> {
         if chunks.is_empty() {
             return Ok(());
         }
-        
+
         let mut chunk_store = self.chunks.write().await;
         let mut index = self.index.write().await;
-        
+
         for chunk in chunks {
+            let digest = content_digest(&chunk.content);
+            let mut chunk = chunk.clone();
+            if chunk.embedding.is_none() {
+                if let Some(existing) = index.digest_embeddings.get(&digest) {
+                    chunk.embedding = Some(existing.clone());
+                }
+            }
+
             chunk_store.insert(chunk.id, chunk.clone());
-            
+
             if let Some(embedding) = &chunk.embedding {
                 index.embeddings.insert(chunk.id, embedding.clone());
+                index.norms.insert(chunk.id, vector_norm(embedding));
                 index.metadata.insert(chunk.id, ChunkMetadata {
                     document_id: chunk.document_id,
                     content_length: chunk.content.len(),
                     token_count: chunk.token_count,
                     created_at: chunk.created_at,
+                    content_digest: digest,
                 });
+                index.digest_embeddings.entry(digest).or_insert_with(|| embedding.clone());
+
+                if let Some(hnsw) = &self.hnsw {
+                    hnsw.write().await.insert(chunk.id, embedding.clone());
+                }
             }
+
+            self.sparse.write().await.upsert(chunk.id, &chunk.content);
         }
-        
+
         // Update statistics
         let mut stats = self.stats.write().await;
         stats.total_chunks = chunk_store.len();
         stats.total_embeddings = index.embeddings.len();
-        
+
         Ok(())
     }
     
@@ -704,23 +1428,30 @@ impl VectorStore for FaissVectorStore {
         if index.embeddings.is_empty() {
             return Ok(Vec::new());
         }
-        
-        // Use parallel similarity search
-        let similarities = SledVectorStore::parallel_similarity_search(
+
+        // Use the HNSW fast path when it's enabled and large enough,
+        // falling back to the exact brute-force scan otherwise
+        let similarities = similarity_search_with_hnsw(
+            &self.hnsw,
+            &None,
             query_embedding,
             &index.embeddings,
+            &index.norms,
             top_k,
             None,
-        );
-        
+            self.config.quantization,
+            self.config.rescore_multiplier,
+            self.config.distance_metric,
+        ).await;
+
         // Update statistics
         let search_time_ms = start_time.elapsed().as_millis() as f64;
         drop(index);
         self.update_search_stats(search_time_ms).await;
-        
+
         Ok(similarities)
     }
-    
+
     async fn search_similar_advanced(
         &self,
         query_embedding: &Embedding,
@@ -729,31 +1460,38 @@ impl VectorStore for FaissVectorStore {
         use_cache: bool,
     ) -> RagResult<Vec<RetrievalResult>> {
         let start_time = Instant::now();
-        
+
         // Check cache first if enabled
         if use_cache {
             if let Some(cache) = &self.cache {
-                let query_key = format!("{:?}_{}__{:?}", query_embedding, top_k, min_score);
+                let query_key = cache_key(query_embedding, top_k, min_score);
                 if let Some(cached_results) = cache.get_retrieval(&query_key).await {
                     return Ok(cached_results);
                 }
             }
         }
-        
+
         let index = self.index.read().await;
-        
+
         if index.embeddings.is_empty() {
             return Ok(Vec::new());
         }
-        
-        // Use parallel similarity search with filtering
-        let similarities = SledVectorStore::parallel_similarity_search(
+
+        // Use the HNSW fast path when it's enabled and large enough,
+        // falling back to the exact brute-force scan otherwise
+        let similarities = similarity_search_with_hnsw(
+            &self.hnsw,
+            &None,
             query_embedding,
             &index.embeddings,
+            &index.norms,
             top_k,
             min_score,
-        );
-        
+            self.config.quantization,
+            self.config.rescore_multiplier,
+            self.config.distance_metric,
+        ).await;
+
         // Convert to RetrievalResult and fetch chunks
         let mut results = Vec::new();
         for (chunk_id, similarity_score) in similarities {
@@ -767,7 +1505,7 @@ impl VectorStore for FaissVectorStore {
         // Cache results if enabled
         if use_cache {
             if let Some(cache) = &self.cache {
-                let query_key = format!("{:?}_{}__{:?}", query_embedding, top_k, min_score);
+                let query_key = cache_key(query_embedding, top_k, min_score);
                 let _ = cache.cache_retrieval(&query_key, &results).await;
             }
         }
@@ -791,119 +1529,724 @@ impl VectorStore for FaissVectorStore {
         if index.embeddings.is_empty() {
             return Ok(vec![Vec::new(); query_embeddings.len()]);
         }
-        
-        // Process queries in parallel
-        let results: Vec<Vec<(ChunkId, f32)>> = query_embeddings
-            .par_iter()
-            .map(|query_embedding| {
-                SledVectorStore::parallel_similarity_search(
+
+        // Each query independently chooses the HNSW fast path or the
+        // brute-force fallback
+        let mut results = Vec::with_capacity(query_embeddings.len());
+        for query_embedding in query_embeddings {
+            results.push(
+                similarity_search_with_hnsw(
+                    &self.hnsw,
+                    &None,
                     query_embedding,
                     &index.embeddings,
+                    &index.norms,
                     top_k,
                     None,
+                    self.config.quantization,
+                    self.config.rescore_multiplier,
+                    self.config.distance_metric,
                 )
-            })
-            .collect();
-        
+                .await,
+            );
+        }
+
         // Update statistics
         let search_time_ms = start_time.elapsed().as_millis() as f64;
         drop(index);
         self.update_search_stats(search_time_ms).await;
-        
+
         Ok(results)
     }
-    
-    async fn delete_chunk(&self, id: ChunkId) -> RagResult<()> {
-        let mut chunks = self.chunks.write().await;
-        let mut index = self.index.write().await;
-        
-        chunks.remove(&id);
-        index.embeddings.remove(&id);
-        index.metadata.remove(&id);
-        
-        // Update statistics
-        let mut stats = self.stats.write().await;
-        stats.total_chunks = chunks.len();
-        stats.total_embeddings = index.embeddings.len();
-        
-        Ok(())
+
+    async fn keyword_search(&self, query: &str, top_k: usize) -> RagResult<Vec<(ChunkId, f32)>> {
+        Ok(self.sparse.read().await.score(query, top_k))
+    }
+
+    async fn embeddings_for_digests(
+        &self,
+        digests: &[ContentDigest],
+    ) -> RagResult<HashMap<ContentDigest, Embedding>> {
+        let index = self.index.read().await;
+        Ok(digests
+            .iter()
+            .filter_map(|digest| index.digest_embeddings.get(digest).map(|embedding| (*digest, embedding.clone())))
+            .collect())
+    }
+
+    async fn delete_chunk(&self, id: ChunkId) -> RagResult<()> {
+        let mut chunks = self.chunks.write().await;
+        let mut index = self.index.write().await;
+
+        chunks.remove(&id);
+        index.embeddings.remove(&id);
+        index.norms.remove(&id);
+        index.metadata.remove(&id);
+
+        if let Some(hnsw) = &self.hnsw {
+            hnsw.write().await.remove(id);
+        }
+
+        self.sparse.write().await.remove(id);
+
+        // Update statistics
+        let mut stats = self.stats.write().await;
+        stats.total_chunks = chunks.len();
+        stats.total_embeddings = index.embeddings.len();
+
+        Ok(())
+    }
+
+    async fn delete_chunks(&self, ids: &[ChunkId]) -> RagResult<()> {
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        let mut chunks = self.chunks.write().await;
+        let mut index = self.index.write().await;
+
+        for id in ids {
+            chunks.remove(id);
+            index.embeddings.remove(id);
+            index.norms.remove(id);
+            index.metadata.remove(id);
+        }
+
+        if let Some(hnsw) = &self.hnsw {
+            let mut state = hnsw.write().await;
+            for id in ids {
+                state.remove(*id);
+            }
+        }
+
+        {
+            let mut sparse = self.sparse.write().await;
+            for id in ids {
+                sparse.remove(*id);
+            }
+        }
+
+        // Update statistics
+        let mut stats = self.stats.write().await;
+        stats.total_chunks = chunks.len();
+        stats.total_embeddings = index.embeddings.len();
+
+        Ok(())
+    }
+    
+    async fn count(&self) -> RagResult<usize> {
+        let chunks = self.chunks.read().await;
+        Ok(chunks.len())
+    }
+    
+    async fn get_stats(&self) -> RagResult<VectorStoreStats> {
+        let stats = self.stats.read().await;
+        let index = self.index.read().await;
+        
+        // Calculate approximate index size in MB
+        let embedding_count = index.embeddings.len();
+        let avg_embedding_size = if embedding_count > 0 {
+            index.embeddings.values().next().map(|e| e.len()).unwrap_or(0)
+        } else {
+            0
+        };
+        let index_size_mb = (embedding_count * avg_embedding_size * 4) as f64 / (1024.0 * 1024.0);
+        
+        Ok(VectorStoreStats {
+            total_chunks: self.chunks.read().await.len(),
+            total_embeddings: embedding_count,
+            index_size_mb,
+            avg_search_time_ms: stats.avg_search_time_ms,
+            cache_hit_rate: stats.cache_hit_rate,
+            last_optimization: stats.last_optimization,
+        })
+    }
+    
+    async fn clear(&self) -> RagResult<()> {
+        let mut chunks = self.chunks.write().await;
+        let mut index = self.index.write().await;
+        
+        chunks.clear();
+        index.embeddings.clear();
+        index.norms.clear();
+        index.metadata.clear();
+        index.digest_embeddings.clear();
+
+        if let Some(hnsw) = &self.hnsw {
+            hnsw.write().await.clear(self.config.hnsw.clone().unwrap_or_default());
+        }
+
+        *self.sparse.write().await = SparseIndex::default();
+
+        // Reset statistics
+        let mut stats = self.stats.write().await;
+        stats.total_chunks = 0;
+        stats.total_embeddings = 0;
+        stats.index_size_mb = 0.0;
+
+        Ok(())
+    }
+
+    async fn optimize(&self) -> RagResult<()> {
+        // For in-memory store, optimization is a no-op
+        // Update optimization timestamp
+        let mut stats = self.stats.write().await;
+        stats.last_optimization = Some(chrono::Utc::now());
+        
+        Ok(())
+    }
+}
+
+/// A node's links within one layer of the HNSW graph: `(neighbor, distance)`
+type Neighbors = Vec<(usize, f32)>;
+
+/// Incrementally-built HNSW (Hierarchical Navigable Small World) index: a
+/// layered proximity graph that answers nearest-neighbor queries in
+/// sub-linear time, unlike `EmbeddingClient::find_similar`'s O(n) scan.
+/// Each inserted node is assigned a top layer at random, with an
+/// exponentially-decaying distribution (level multiplier `1 / ln(m)`, so a
+/// node reaches layer `l` with probability `m^-l`), so
+/// layer 0 holds every node and each layer above holds exponentially
+/// fewer. A query descends greedily, one layer at a time, from the entry
+/// point down to layer 1, then runs an `ef`-wide beam search at layer 0 to
+/// gather the nearest candidates.
+///
+/// Distances here are `1 - cosine_similarity`, kept private to this type
+/// so it doesn't couple to `SledVectorStore`'s own similarity helpers.
+pub struct HnswIndex {
+    params: HnswParams,
+    /// Stored vectors, indexed by internal node id
+    vectors: Vec<Embedding>,
+    /// Caller-supplied id per internal node id, decoupling external
+    /// identity (e.g. a mailbox message UID) from graph-internal indices
+    external_ids: Vec<u64>,
+    /// `layers[l]` holds the neighbor list of every node present at layer
+    /// `l`; layer 0 holds every inserted node
+    layers: Vec<HashMap<usize, Neighbors>>,
+    /// Node currently at the highest layer, where search descent starts
+    entry_point: Option<usize>,
+    /// Nodes unlinked by `remove()`. Their slot and vector stay in place
+    /// (every other node's indices must stay stable), but they're excluded
+    /// from `entry_point` selection and filtered out of `search` results
+    removed: HashSet<usize>,
+}
+
+/// Serializable projection of an `HnswIndex`, produced by `to_snapshot`/
+/// consumed by `from_snapshot`. `layers` is stored as `Vec<(node,
+/// neighbors)>` per layer rather than `HashMap<usize, _>` since JSON (what
+/// `SledVectorStore` persists with) only supports string map keys.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HnswSnapshot {
+    params: HnswParams,
+    vectors: Vec<Embedding>,
+    external_ids: Vec<u64>,
+    layers: Vec<Vec<(usize, Neighbors)>>,
+    entry_point: Option<usize>,
+    removed: Vec<usize>,
+}
+
+impl HnswIndex {
+    pub fn new(params: HnswParams) -> Self {
+        Self {
+            params,
+            vectors: Vec::new(),
+            external_ids: Vec::new(),
+            layers: Vec::new(),
+            entry_point: None,
+            removed: HashSet::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.vectors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vectors.is_empty()
+    }
+
+    /// Insert `embedding` under `external_id`: assign it a random top
+    /// layer, greedily descend from the current entry point to one layer
+    /// above it, then beam-search and bidirectionally link it at every
+    /// layer from there down to 0, truncating neighbor lists to `m`.
+    pub fn insert(&mut self, external_id: u64, embedding: Embedding) {
+        let node = self.vectors.len();
+        let level = self.random_level();
+        let prev_top_level = self.layers.len().saturating_sub(1);
+
+        self.vectors.push(embedding);
+        self.external_ids.push(external_id);
+        while self.layers.len() <= level {
+            self.layers.push(HashMap::new());
+        }
+        for layer in self.layers.iter_mut().take(level + 1) {
+            layer.insert(node, Vec::new());
+        }
+
+        let Some(mut current) = self.entry_point else {
+            self.entry_point = Some(node);
+            return;
+        };
+
+        let query = self.vectors[node].clone();
+        for l in (level + 1..=prev_top_level).rev() {
+            current = self.greedy_search_layer(current, &query, l);
+        }
+
+        for l in (0..=level.min(prev_top_level)).rev() {
+            let candidates = self.search_layer(&query, current, l, self.params.ef_construction);
+            let mut nearest = candidates.clone();
+            nearest.truncate(self.params.m.max(1));
+            self.layers[l].insert(node, nearest.clone());
+            for &(neighbor, dist) in &nearest {
+                self.link(neighbor, node, dist, l);
+            }
+            if let Some(&(closest, _)) = candidates.first() {
+                current = closest;
+            }
+        }
+
+        if level > prev_top_level {
+            self.entry_point = Some(node);
+        }
+    }
+
+    /// Find the `top_k` nodes nearest `query`: greedy descent from the
+    /// entry point down to layer 1, then an `ef_search`-wide beam search
+    /// at layer 0, returned as `(external_id, cosine_similarity)`.
+    pub fn search(&self, query: &Embedding, top_k: usize) -> Vec<(u64, f32)> {
+        let Some(entry) = self.entry_point else {
+            return Vec::new();
+        };
+        let top_layer = self.layers.len().saturating_sub(1);
+
+        let mut current = entry;
+        for l in (1..=top_layer).rev() {
+            current = self.greedy_search_layer(current, query, l);
+        }
+
+        let ef = self.params.ef_search.max(top_k);
+        self.search_layer(query, current, 0, ef)
+            .into_iter()
+            .filter(|(node, _)| !self.removed.contains(node))
+            .take(top_k)
+            .map(|(node, dist)| (self.external_ids[node], 1.0 - dist))
+            .collect()
+    }
+
+    /// Tombstone `external_id` so `search` stops returning it. Every other
+    /// node's reference to it is stripped from their neighbor lists and the
+    /// entry point moves off it, so no later traversal reaches it; its own
+    /// neighbor list, slot and vector are left in place, since every other
+    /// node's indices into `vectors`/`layers` must stay stable. Nodes that
+    /// were reachable only through it are not relinked.
+    pub fn remove(&mut self, external_id: u64) {
+        let Some(node) = self.external_ids.iter().position(|&id| id == external_id) else {
+            return;
+        };
+        self.removed.insert(node);
+        for layer in &mut self.layers {
+            for (&other, neighbors) in layer.iter_mut() {
+                if other != node {
+                    neighbors.retain(|&(n, _)| n != node);
+                }
+            }
+        }
+        if self.entry_point == Some(node) {
+            self.entry_point = self
+                .layers
+                .iter()
+                .rev()
+                .flat_map(|layer| layer.keys())
+                .find(|n| !self.removed.contains(n))
+                .copied();
+        }
+    }
+
+    /// Snapshot the graph into a form `serde` can (de)serialize: `layers`
+    /// uses `HashMap<usize, _>`, but JSON requires string map keys, so the
+    /// snapshot stores each layer as a `(node, neighbors)` pair list instead
+    pub fn to_snapshot(&self) -> HnswSnapshot {
+        HnswSnapshot {
+            params: self.params.clone(),
+            vectors: self.vectors.clone(),
+            external_ids: self.external_ids.clone(),
+            layers: self
+                .layers
+                .iter()
+                .map(|layer| layer.iter().map(|(&node, neighbors)| (node, neighbors.clone())).collect())
+                .collect(),
+            entry_point: self.entry_point,
+            removed: self.removed.iter().copied().collect(),
+        }
+    }
+
+    pub fn from_snapshot(snapshot: HnswSnapshot) -> Self {
+        Self {
+            params: snapshot.params,
+            vectors: snapshot.vectors,
+            external_ids: snapshot.external_ids,
+            layers: snapshot.layers.into_iter().map(|pairs| pairs.into_iter().collect()).collect(),
+            entry_point: snapshot.entry_point,
+            removed: snapshot.removed.into_iter().collect(),
+        }
+    }
+
+    /// Hill-climb within a single `layer`, starting at `start`: repeatedly
+    /// step to whichever neighbor is closer to `query` than the current
+    /// node, stopping at the first local optimum
+    fn greedy_search_layer(&self, start: usize, query: &Embedding, layer: usize) -> usize {
+        let mut current = start;
+        let mut current_dist = cosine_distance(&self.vectors[current], query);
+        loop {
+            let mut stepped = false;
+            if let Some(neighbors) = self.layers[layer].get(&current) {
+                for &(neighbor, _) in neighbors {
+                    let dist = cosine_distance(&self.vectors[neighbor], query);
+                    if dist < current_dist {
+                        current = neighbor;
+                        current_dist = dist;
+                        stepped = true;
+                    }
+                }
+            }
+            if !stepped {
+                return current;
+            }
+        }
+    }
+
+    /// Beam-search `layer` from `entry`, keeping the `ef` closest nodes to
+    /// `query` found so far and expanding the closest unexpanded candidate
+    /// until none remains better than the worst kept result. Returned
+    /// sorted nearest-first.
+    fn search_layer(&self, query: &Embedding, entry: usize, layer: usize, ef: usize) -> Vec<(usize, f32)> {
+        let ef = ef.max(1);
+        let entry_dist = cosine_distance(&self.vectors[entry], query);
+
+        let mut visited: HashSet<usize> = HashSet::new();
+        visited.insert(entry);
+        let mut candidates: Vec<(usize, f32)> = vec![(entry, entry_dist)];
+        let mut found: Vec<(usize, f32)> = vec![(entry, entry_dist)];
+
+        while !candidates.is_empty() {
+            let (current, current_dist) = candidates.remove(0);
+            let worst_found = found.last().map(|&(_, d)| d).unwrap_or(f32::MAX);
+            if found.len() >= ef && current_dist > worst_found {
+                break;
+            }
+
+            let Some(neighbors) = self.layers[layer].get(&current) else {
+                continue;
+            };
+            for &(neighbor, _) in neighbors {
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+                let dist = cosine_distance(&self.vectors[neighbor], query);
+                found.push((neighbor, dist));
+                found.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+                found.truncate(ef);
+                candidates.push((neighbor, dist));
+                candidates.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+            }
+        }
+
+        found
+    }
+
+    /// Link `to` into `from`'s neighbor list at `layer` (if not already
+    /// linked), truncating back to the `m` nearest afterward
+    fn link(&mut self, from: usize, to: usize, dist: f32, layer: usize) {
+        let neighbors = self.layers[layer].entry(from).or_insert_with(Vec::new);
+        if neighbors.iter().any(|&(n, _)| n == to) {
+            return;
+        }
+        neighbors.push((to, dist));
+        neighbors.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        neighbors.truncate(self.params.m.max(1));
+    }
+
+    /// Random top layer for a newly inserted node, drawn from the
+    /// exponential distribution HNSW uses so each layer above 0 holds
+    /// roughly `1/m` as many nodes as the one below it
+    fn random_level(&self) -> usize {
+        let m = (self.params.m.max(2)) as f64;
+        let lambda = 1.0 / m.ln();
+        let r: f64 = rand::thread_rng().gen::<f64>().max(f64::EPSILON);
+        (-r.ln() * lambda).floor() as usize
+    }
+}
+
+fn cosine_distance(a: &Embedding, b: &Embedding) -> f32 {
+    1.0 - cosine_similarity(a, b)
+}
+
+fn cosine_similarity(a: &Embedding, b: &Embedding) -> f32 {
+    if a.len() != b.len() || a.is_empty() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b.iter()).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+/// Below this many indexed vectors, brute-force `parallel_similarity_search`
+/// is both exact and cheap enough that the approximate HNSW graph isn't
+/// worth the recall hit
+const HNSW_MIN_INDEX_SIZE: usize = 1_000;
+
+/// Bookkeeping for the optional HNSW fast path: the graph itself plus the
+/// bidirectional mapping between `ChunkId` (what the rest of the store uses)
+/// and the small sequential `u64` ids `HnswIndex` expects, following the
+/// same "incrementing counter + reverse map" convention used to bridge
+/// `HnswIndex` into `Email/src-tauri`'s `MailboxIndex`.
+struct HnswState {
+    index: HnswIndex,
+    next_id: u64,
+    id_for_chunk: HashMap<ChunkId, u64>,
+    chunk_for_id: HashMap<u64, ChunkId>,
+}
+
+/// Serializable projection of `HnswState`. `chunk_for_id` is rebuilt from
+/// `id_for_chunk` on load rather than persisted, since a `HashMap<u64, _>`
+/// can't round-trip through JSON's string-keyed objects.
+#[derive(Serialize, Deserialize)]
+struct PersistedHnswState {
+    snapshot: HnswSnapshot,
+    next_id: u64,
+    id_for_chunk: HashMap<ChunkId, u64>,
+}
+
+impl HnswState {
+    fn new(params: HnswParams) -> Self {
+        Self {
+            index: HnswIndex::new(params),
+            next_id: 0,
+            id_for_chunk: HashMap::new(),
+            chunk_for_id: HashMap::new(),
+        }
+    }
+
+    /// Insert `embedding` under `chunk_id`, assigning it a fresh internal
+    /// id. A chunk that's already indexed is left untouched: `HnswIndex` has
+    /// no update-in-place support, so a re-indexed chunk keeps its original
+    /// vector until deleted and re-inserted.
+    fn insert(&mut self, chunk_id: ChunkId, embedding: Embedding) {
+        if self.id_for_chunk.contains_key(&chunk_id) {
+            return;
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        self.index.insert(id, embedding);
+        self.id_for_chunk.insert(chunk_id, id);
+        self.chunk_for_id.insert(id, chunk_id);
     }
-    
-    async fn delete_chunks(&self, ids: &[ChunkId]) -> RagResult<()> {
-        if ids.is_empty() {
-            return Ok(());
+
+    fn remove(&mut self, chunk_id: ChunkId) {
+        if let Some(id) = self.id_for_chunk.remove(&chunk_id) {
+            self.chunk_for_id.remove(&id);
+            self.index.remove(id);
         }
-        
-        let mut chunks = self.chunks.write().await;
-        let mut index = self.index.write().await;
-        
-        for id in ids {
-            chunks.remove(id);
-            index.embeddings.remove(id);
-            index.metadata.remove(id);
+    }
+
+    fn clear(&mut self, params: HnswParams) {
+        *self = Self::new(params);
+    }
+
+    fn search(&self, query: &Embedding, top_k: usize) -> Vec<(ChunkId, f32)> {
+        self.index
+            .search(query, top_k)
+            .into_iter()
+            .filter_map(|(id, score)| self.chunk_for_id.get(&id).map(|chunk_id| (*chunk_id, score)))
+            .collect()
+    }
+
+    fn to_persisted(&self) -> PersistedHnswState {
+        PersistedHnswState {
+            snapshot: self.index.to_snapshot(),
+            next_id: self.next_id,
+            id_for_chunk: self.id_for_chunk.clone(),
         }
-        
-        // Update statistics
-        let mut stats = self.stats.write().await;
-        stats.total_chunks = chunks.len();
-        stats.total_embeddings = index.embeddings.len();
-        
-        Ok(())
     }
-    
-    async fn count(&self) -> RagResult<usize> {
-        let chunks = self.chunks.read().await;
-        Ok(chunks.len())
+
+    fn from_persisted(persisted: PersistedHnswState) -> Self {
+        let chunk_for_id = persisted.id_for_chunk.iter().map(|(&chunk_id, &id)| (id, chunk_id)).collect();
+        Self {
+            index: HnswIndex::from_snapshot(persisted.snapshot),
+            next_id: persisted.next_id,
+            id_for_chunk: persisted.id_for_chunk,
+            chunk_for_id,
+        }
     }
-    
-    async fn get_stats(&self) -> RagResult<VectorStoreStats> {
-        let stats = self.stats.read().await;
-        let index = self.index.read().await;
-        
-        // Calculate approximate index size in MB
-        let embedding_count = index.embeddings.len();
-        let avg_embedding_size = if embedding_count > 0 {
-            index.embeddings.values().next().map(|e| e.len()).unwrap_or(0)
-        } else {
-            0
-        };
-        let index_size_mb = (embedding_count * avg_embedding_size * 4) as f64 / (1024.0 * 1024.0);
-        
-        Ok(VectorStoreStats {
-            total_chunks: self.chunks.read().await.len(),
-            total_embeddings: embedding_count,
-            index_size_mb,
-            avg_search_time_ms: stats.avg_search_time_ms,
-            cache_hit_rate: stats.cache_hit_rate,
-            last_optimization: stats.last_optimization,
-        })
+}
+
+/// Incrementally-maintained BM25 postings, kept up to date by `upsert`/
+/// `remove` in `store_chunk`/`delete_chunk` rather than rescanning every
+/// stored chunk on each `keyword_search`. Scored with the same BM25
+/// constants (`BM25_K1`/`BM25_B`) as `SledVectorStore::bm25_scores`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SparseIndex {
+    /// term -> chunk id -> term frequency within that chunk
+    postings: HashMap<String, HashMap<ChunkId, usize>>,
+    /// chunk id -> number of tokens, for the BM25 length-normalization term
+    doc_lengths: HashMap<ChunkId, usize>,
+    /// chunk id -> its unique terms, so `remove` knows which postings to
+    /// clean up without rescanning every term in the index
+    doc_terms: HashMap<ChunkId, Vec<String>>,
+}
+
+impl SparseIndex {
+    /// Index (or re-index) `chunk_id`'s content, replacing any previous
+    /// entry for it first so re-storing an existing chunk doesn't leave
+    /// stale postings behind
+    fn upsert(&mut self, chunk_id: ChunkId, content: &str) {
+        self.remove(chunk_id);
+
+        let terms = tokenize(content);
+        self.doc_lengths.insert(chunk_id, terms.len());
+
+        let mut term_freqs: HashMap<String, usize> = HashMap::new();
+        for term in terms {
+            *term_freqs.entry(term).or_insert(0) += 1;
+        }
+
+        let unique_terms: Vec<String> = term_freqs.keys().cloned().collect();
+        for (term, tf) in term_freqs {
+            self.postings.entry(term).or_default().insert(chunk_id, tf);
+        }
+        self.doc_terms.insert(chunk_id, unique_terms);
     }
-    
-    async fn clear(&self) -> RagResult<()> {
-        let mut chunks = self.chunks.write().await;
-        let mut index = self.index.write().await;
-        
-        chunks.clear();
-        index.embeddings.clear();
-        index.metadata.clear();
-        
-        // Reset statistics
-        let mut stats = self.stats.write().await;
-        stats.total_chunks = 0;
-        stats.total_embeddings = 0;
-        stats.index_size_mb = 0.0;
-        
-        Ok(())
+
+    fn remove(&mut self, chunk_id: ChunkId) {
+        if let Some(terms) = self.doc_terms.remove(&chunk_id) {
+            for term in terms {
+                if let Some(postings) = self.postings.get_mut(&term) {
+                    postings.remove(&chunk_id);
+                    if postings.is_empty() {
+                        self.postings.remove(&term);
+                    }
+                }
+            }
+        }
+        self.doc_lengths.remove(&chunk_id);
     }
-    
-    async fn optimize(&self) -> RagResult<()> {
-        // For in-memory store, optimization is a no-op
-        // Update optimization timestamp
-        let mut stats = self.stats.write().await;
-        stats.last_optimization = Some(chrono::Utc::now());
-        
-        Ok(())
+
+    /// Score every indexed chunk against `query` using BM25, same formula
+    /// as `SledVectorStore::bm25_scores` but sourcing term/document
+    /// frequencies from the maintained postings instead of a fresh scan
+    fn score(&self, query: &str, top_k: usize) -> Vec<(ChunkId, f32)> {
+        if self.doc_lengths.is_empty() {
+            return Vec::new();
+        }
+
+        let query_terms = tokenize(query);
+        if query_terms.is_empty() {
+            return Vec::new();
+        }
+
+        let n = self.doc_lengths.len() as f32;
+        let avg_doc_len: f32 =
+            self.doc_lengths.values().sum::<usize>() as f32 / n;
+
+        let mut scores: HashMap<ChunkId, f32> = HashMap::new();
+        for term in &query_terms {
+            let Some(postings) = self.postings.get(term) else {
+                continue;
+            };
+            let df = postings.len() as f32;
+            let idf = ((n - df + 0.5) / (df + 0.5) + 1.0).ln();
+            for (&chunk_id, &tf) in postings {
+                let tf = tf as f32;
+                let doc_len = self.doc_lengths.get(&chunk_id).copied().unwrap_or(0) as f32;
+                let denom = tf + BM25_K1 * (1.0 - BM25_B + BM25_B * (doc_len / avg_doc_len.max(1.0)));
+                if denom > 0.0 {
+                    *scores.entry(chunk_id).or_insert(0.0) += idf * (tf * (BM25_K1 + 1.0)) / denom;
+                }
+            }
+        }
+
+        let mut scores: Vec<(ChunkId, f32)> = scores.into_iter().filter(|(_, score)| *score > 0.0).collect();
+        scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scores.truncate(top_k);
+        scores
+    }
+}
+
+/// Answer a similarity query using the optional HNSW index when it's
+/// enabled and holds enough vectors to be worth it; otherwise fall back to
+/// the exact brute-force `parallel_similarity_search`, which also backstops
+/// an HNSW lookup that (due to approximate search) returns fewer than
+/// `top_k` candidates.
+async fn similarity_search_with_hnsw(
+    hnsw: &Option<Arc<tokio::sync::RwLock<HnswState>>>,
+    quantized: &Option<Arc<tokio::sync::RwLock<Option<QuantizedIndex>>>>,
+    query_embedding: &Embedding,
+    embeddings: &HashMap<ChunkId, Embedding>,
+    norms: &HashMap<ChunkId, f32>,
+    top_k: usize,
+    min_score: Option<f32>,
+    quantization: QuantizationMode,
+    rescore_multiplier: Option<usize>,
+    metric: DistanceMetric,
+) -> Vec<(ChunkId, f32)> {
+    // The HNSW graph is only ever built from cosine distances, so skip it
+    // for any other metric and fall through to the brute-force path below.
+    if metric == DistanceMetric::Cosine {
+        if let Some(hnsw) = hnsw {
+            let state = hnsw.read().await;
+            if state.index.len() >= HNSW_MIN_INDEX_SIZE {
+                let mut results = state.search(query_embedding, top_k);
+                if let Some(min) = min_score {
+                    results.retain(|(_, score)| *score >= min);
+                }
+                if results.len() >= top_k.min(state.index.len()) {
+                    return results;
+                }
+            }
+        }
+    }
+
+    if quantization == QuantizationMode::Scalar8 {
+        if let Some(quantized) = quantized {
+            if let Some(quantized) = quantized.read().await.as_ref() {
+                // `codes` is only rebuilt by `optimize()`, so any chunk
+                // stored or deleted since then leaves it stale — fall
+                // through to the brute-force path instead of silently
+                // excluding or misrepresenting chunks until the next
+                // `optimize()`. A delete+insert cycle can net back to the
+                // same chunk count, so this checks id membership, not just
+                // `len()`.
+                if quantized.covers(embeddings) {
+                    return SledVectorStore::search_similar_quantized(
+                        quantized,
+                        embeddings,
+                        norms,
+                        query_embedding,
+                        top_k,
+                        min_score,
+                        rescore_multiplier,
+                        metric,
+                    );
+                }
+            }
+        }
     }
+
+    SledVectorStore::parallel_similarity_search(
+        query_embedding,
+        embeddings,
+        norms,
+        top_k,
+        min_score,
+        quantization,
+        rescore_multiplier,
+        metric,
+    )
 }
 
 /// Factory function to create vector store based on configuration
@@ -967,7 +2310,7 @@ mod tests {
     
     async fn create_test_store_with_cache() -> (Box<dyn VectorStore>, Arc<RagCache>) {
         let cache_config = CacheConfig::default();
-        let cache = Arc::new(RagCache::new(cache_config).unwrap());
+        let cache = Arc::new(RagCache::new(cache_config).await.unwrap());
         
         let config = VectorStoreConfig {
             backend: crate::config::VectorStoreBackend::Faiss,
@@ -1178,7 +2521,187 @@ mod tests {
             assert!(store.get_chunk(id).await.unwrap().is_none());
         }
     }
-    
+
+    #[tokio::test]
+    async fn test_content_digest_dedup_reuses_embedding() {
+        let store = create_test_faiss_store().await;
+
+        let document_id = Uuid::new_v4();
+        let mut embedded = Chunk::new(document_id, "duplicate content".to_string(), 0, 17, 2);
+        embedded.set_embedding(vec![1.0, 2.0, 3.0]);
+        store.store_chunk(&embedded).await.unwrap();
+
+        // Same content, no embedding supplied: should reuse the stored vector
+        let unembedded = Chunk::new(document_id, "duplicate content".to_string(), 17, 34, 2);
+        store.store_chunk(&unembedded).await.unwrap();
+
+        let retrieved = store.get_chunk(unembedded.id).await.unwrap().unwrap();
+        assert_eq!(retrieved.embedding, embedded.embedding);
+
+        let digest = content_digest(&embedded.content);
+        let found = store.embeddings_for_digests(&[digest]).await.unwrap();
+        assert_eq!(found.get(&digest), embedded.embedding.as_ref());
+    }
+
+    #[tokio::test]
+    async fn test_content_digest_dedup_within_same_batch() {
+        let store = create_test_faiss_store().await;
+
+        let document_id = Uuid::new_v4();
+        let mut embedded = Chunk::new(document_id, "batch duplicate".to_string(), 0, 15, 2);
+        embedded.set_embedding(vec![4.0, 5.0, 6.0]);
+        let unembedded = Chunk::new(document_id, "batch duplicate".to_string(), 15, 30, 2);
+
+        store.store_chunks(&[embedded.clone(), unembedded.clone()]).await.unwrap();
+
+        let retrieved = store.get_chunk(unembedded.id).await.unwrap().unwrap();
+        assert_eq!(retrieved.embedding, embedded.embedding);
+    }
+
+    #[tokio::test]
+    async fn test_scalar8_quantization_optimize_and_search() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = VectorStoreConfig {
+            backend: crate::config::VectorStoreBackend::Sled,
+            db_path: temp_dir.path().to_path_buf(),
+            quantization: QuantizationMode::Scalar8,
+            ..Default::default()
+        };
+        let store = SledVectorStore::new(config).await.unwrap();
+
+        let document_id = Uuid::new_v4();
+        let mut close = Chunk::new(document_id, "close".to_string(), 0, 5, 1);
+        close.set_embedding(vec![1.0, 0.0, 0.0]);
+        let mut far = Chunk::new(document_id, "far".to_string(), 5, 8, 1);
+        far.set_embedding(vec![-1.0, 0.0, 0.0]);
+        store.store_chunks(&[close.clone(), far.clone()]).await.unwrap();
+
+        // Before optimize() builds the quantized cache, search still works
+        // via the unquantized brute-force fallback.
+        let pre_optimize = store.search_similar(&vec![1.0, 0.0, 0.0], 1).await.unwrap();
+        assert_eq!(pre_optimize[0].0, close.id);
+
+        store.optimize().await.unwrap();
+
+        let post_optimize = store.search_similar(&vec![1.0, 0.0, 0.0], 1).await.unwrap();
+        assert_eq!(post_optimize[0].0, close.id);
+
+        let unquantized_stats_size = {
+            let dim = 3usize;
+            (2 * dim * 4) as f64 / (1024.0 * 1024.0)
+        };
+        let stats = store.get_stats().await.unwrap();
+        assert!(stats.index_size_mb < unquantized_stats_size);
+    }
+
+    #[tokio::test]
+    async fn test_scalar8_quantization_falls_back_for_chunks_added_after_optimize() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = VectorStoreConfig {
+            backend: crate::config::VectorStoreBackend::Sled,
+            db_path: temp_dir.path().to_path_buf(),
+            quantization: QuantizationMode::Scalar8,
+            ..Default::default()
+        };
+        let store = SledVectorStore::new(config).await.unwrap();
+
+        let document_id = Uuid::new_v4();
+        let mut close = Chunk::new(document_id, "close".to_string(), 0, 5, 1);
+        close.set_embedding(vec![1.0, 0.0, 0.0]);
+        store.store_chunks(&[close.clone()]).await.unwrap();
+        store.optimize().await.unwrap();
+
+        // Stored after the quantized cache was built, so it has no entry in
+        // `QuantizedIndex.codes` yet — it must still be found instead of
+        // being silently excluded until the next `optimize()`.
+        let mut late = Chunk::new(document_id, "late".to_string(), 5, 9, 1);
+        late.set_embedding(vec![0.0, 1.0, 0.0]);
+        store.store_chunks(&[late.clone()]).await.unwrap();
+
+        let results = store.search_similar(&vec![0.0, 1.0, 0.0], 1).await.unwrap();
+        assert_eq!(results[0].0, late.id);
+    }
+
+    #[tokio::test]
+    async fn test_scalar8_quantization_falls_back_after_delete_and_insert_nets_same_count() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = VectorStoreConfig {
+            backend: crate::config::VectorStoreBackend::Sled,
+            db_path: temp_dir.path().to_path_buf(),
+            quantization: QuantizationMode::Scalar8,
+            ..Default::default()
+        };
+        let store = SledVectorStore::new(config).await.unwrap();
+
+        let document_id = Uuid::new_v4();
+        let mut old = Chunk::new(document_id, "old".to_string(), 0, 3, 1);
+        old.set_embedding(vec![1.0, 0.0, 0.0]);
+        store.store_chunks(&[old.clone()]).await.unwrap();
+        store.optimize().await.unwrap();
+
+        // Net chunk count is unchanged (one deleted, one inserted), so a
+        // plain `codes.len() == embeddings.len()` check would wrongly see
+        // the stale quantized cache as still valid.
+        store.delete_chunk(old.id).await.unwrap();
+        let mut replacement = Chunk::new(document_id, "replacement".to_string(), 3, 7, 1);
+        replacement.set_embedding(vec![0.0, 1.0, 0.0]);
+        store.store_chunks(&[replacement.clone()]).await.unwrap();
+
+        let results = store.search_similar(&vec![0.0, 1.0, 0.0], 1).await.unwrap();
+        assert_eq!(results[0].0, replacement.id);
+    }
+
+    #[tokio::test]
+    async fn test_distance_metric_euclidean_and_dot_product() {
+        let document_id = Uuid::new_v4();
+        let mut close = Chunk::new(document_id, "close".to_string(), 0, 5, 1);
+        close.set_embedding(vec![1.0, 0.0, 0.0]);
+        let mut far = Chunk::new(document_id, "far".to_string(), 5, 8, 1);
+        far.set_embedding(vec![5.0, 5.0, 5.0]);
+        let query = vec![1.1, 0.1, 0.1];
+
+        let temp_dir = TempDir::new().unwrap();
+        let euclidean_config = VectorStoreConfig {
+            backend: crate::config::VectorStoreBackend::Sled,
+            db_path: temp_dir.path().to_path_buf(),
+            distance_metric: DistanceMetric::Euclidean,
+            ..Default::default()
+        };
+        let euclidean_store = SledVectorStore::new(euclidean_config).await.unwrap();
+        euclidean_store.store_chunks(&[close.clone(), far.clone()]).await.unwrap();
+        let euclidean_results = euclidean_store.search_similar(&query, 1).await.unwrap();
+        assert_eq!(euclidean_results[0].0, close.id);
+
+        let temp_dir = TempDir::new().unwrap();
+        let dot_product_config = VectorStoreConfig {
+            backend: crate::config::VectorStoreBackend::Sled,
+            db_path: temp_dir.path().to_path_buf(),
+            distance_metric: DistanceMetric::DotProduct,
+            ..Default::default()
+        };
+        let dot_product_store = SledVectorStore::new(dot_product_config).await.unwrap();
+        dot_product_store.store_chunks(&[close.clone(), far.clone()]).await.unwrap();
+        let dot_product_results = dot_product_store.search_similar(&query, 1).await.unwrap();
+        assert_eq!(dot_product_results[0].0, far.id);
+    }
+
+    #[tokio::test]
+    async fn test_sparse_index_updated_on_delete() {
+        let store = create_test_faiss_store().await;
+
+        let document_id = Uuid::new_v4();
+        let chunk = Chunk::new(document_id, "unique needle term".to_string(), 0, 18, 2);
+        store.store_chunk(&chunk).await.unwrap();
+
+        let before = store.keyword_search("needle", 5).await.unwrap();
+        assert_eq!(before.len(), 1);
+
+        store.delete_chunk(chunk.id).await.unwrap();
+
+        let after = store.keyword_search("needle", 5).await.unwrap();
+        assert!(after.is_empty());
+    }
+
     #[tokio::test]
     async fn test_statistics() {
         let store = create_test_faiss_store().await;
@@ -1228,7 +2751,21 @@ mod tests {
         let cache_stats = cache.get_stats().await;
         assert!(cache_stats.retrieval_hits > 0 || cache_stats.retrieval_misses > 0);
     }
-    
+
+    #[test]
+    fn test_cache_key_normalizes_negative_zero_and_nan() {
+        let zero_key = cache_key(&vec![0.0, 1.0], 5, Some(0.0));
+        let negative_zero_key = cache_key(&vec![-0.0, 1.0], 5, Some(-0.0));
+        assert_eq!(zero_key, negative_zero_key);
+
+        let nan_key = cache_key(&vec![f32::NAN, 1.0], 5, None);
+        let other_nan_key = cache_key(&vec![-f32::NAN, 1.0], 5, None);
+        assert_eq!(nan_key, other_nan_key);
+
+        let different_top_k_key = cache_key(&vec![0.0, 1.0], 6, Some(0.0));
+        assert_ne!(zero_key, different_top_k_key);
+    }
+
     #[tokio::test]
     async fn test_optimization() {
         let store = create_test_faiss_store().await;
@@ -1280,7 +2817,7 @@ mod tests {
         
         // Test with cache
         let cache_config = CacheConfig::default();
-        let cache = Arc::new(RagCache::new(cache_config).unwrap());
+        let cache = Arc::new(RagCache::new(cache_config).await.unwrap());
         
         let config_with_cache = VectorStoreConfig {
             backend: crate::config::VectorStoreBackend::Faiss,
@@ -1288,4 +2825,100 @@ mod tests {
         };
         let _store_with_cache = create_vector_store_with_cache(config_with_cache, Some(cache)).await.unwrap();
     }
+
+    #[test]
+    fn test_hnsw_index_search() {
+        // `m` comfortably exceeds the candidate count, so every node ends up
+        // linked to every other at layer 0 and the beam search is exhaustive.
+        let mut index = HnswIndex::new(HnswParams::default());
+        index.insert(1, vec![1.0, 0.0, 0.0]);
+        index.insert(2, vec![0.9, 0.1, 0.0]);
+        index.insert(3, vec![0.0, 1.0, 0.0]);
+        index.insert(4, vec![0.0, 0.0, 1.0]);
+
+        assert_eq!(index.len(), 4);
+
+        let results = index.search(&vec![1.0, 0.0, 0.0], 2);
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0, 1);
+        assert_eq!(results[1].0, 2);
+        assert!(results[0].1 > results[1].1);
+    }
+
+    #[test]
+    fn test_hnsw_index_remove_unlinks_node() {
+        let mut index = HnswIndex::new(HnswParams::default());
+        index.insert(1, vec![1.0, 0.0, 0.0]);
+        index.insert(2, vec![0.9, 0.1, 0.0]);
+        index.insert(3, vec![0.0, 1.0, 0.0]);
+
+        index.remove(2);
+
+        let results = index.search(&vec![1.0, 0.0, 0.0], 3);
+        assert!(!results.iter().any(|(id, _)| *id == 2));
+
+        // Round-tripping through a snapshot preserves the tombstone
+        let snapshot = index.to_snapshot();
+        let restored = HnswIndex::from_snapshot(snapshot);
+        let restored_results = restored.search(&vec![1.0, 0.0, 0.0], 3);
+        assert!(!restored_results.iter().any(|(id, _)| *id == 2));
+    }
+
+    #[test]
+    fn test_hnsw_index_remove_keeps_outgoing_edges_for_routing() {
+        let mut index = HnswIndex::new(HnswParams { m: 4, ef_construction: 20, ef_search: 20 });
+        index.insert(1, vec![1.0, 0.0, 0.0]);
+        index.insert(2, vec![0.9, 0.1, 0.0]);
+        index.insert(3, vec![0.5, 0.5, 0.0]);
+        index.insert(4, vec![0.1, 0.9, 0.0]);
+        index.insert(5, vec![0.0, 1.0, 0.0]);
+
+        let node = index.external_ids.iter().position(|&id| id == 3).unwrap();
+        index.remove(3);
+
+        // The tombstoned node's own outgoing edges survive; only other
+        // nodes' incoming references to it are stripped, so no traversal
+        // passes through it any more.
+        assert!(
+            index.layers[0].contains_key(&node),
+            "remove() must not delete the tombstoned node's own adjacency list"
+        );
+        for (&other, neighbors) in index.layers[0].iter() {
+            if other != node {
+                assert!(
+                    !neighbors.iter().any(|&(n, _)| n == node),
+                    "other nodes must drop their edges into a removed node"
+                );
+            }
+        }
+
+        // It must still never be surfaced as a search result.
+        let results = index.search(&vec![0.5, 0.5, 0.0], 5);
+        assert!(!results.iter().any(|(id, _)| *id == 3));
+    }
+
+    #[test]
+    fn test_hnsw_state_maps_chunk_ids_and_ignores_reinsert() {
+        let mut state = HnswState::new(HnswParams::default());
+        let chunk_a = Uuid::new_v4();
+        let chunk_b = Uuid::new_v4();
+
+        state.insert(chunk_a, vec![1.0, 0.0, 0.0]);
+        state.insert(chunk_b, vec![0.0, 1.0, 0.0]);
+        // `HnswIndex` has no update-in-place support, so a re-insert under
+        // an already-indexed chunk id is a no-op
+        state.insert(chunk_a, vec![0.0, 0.0, 1.0]);
+        assert_eq!(state.index.len(), 2);
+
+        let results = state.search(&vec![1.0, 0.0, 0.0], 1);
+        assert_eq!(results[0].0, chunk_a);
+
+        state.remove(chunk_a);
+        let results = state.search(&vec![1.0, 0.0, 0.0], 2);
+        assert!(!results.iter().any(|(id, _)| *id == chunk_a));
+
+        let persisted = state.to_persisted();
+        let restored = HnswState::from_persisted(persisted);
+        assert_eq!(restored.chunk_for_id.get(&restored.id_for_chunk[&chunk_b]), Some(&chunk_b));
+    }
 }
\ No newline at end of file